@@ -81,5 +81,6 @@ publish hashing
 publish execution_engine
 publish json_rpc
 publish node
+publish smart_contracts/contract_derive
 publish smart_contracts/contract
 publish execution_engine_testing/test_support
@@ -47,6 +47,8 @@ pub const DEFAULT_REFUND_HANDLING: RefundHandling = RefundHandling::Refund {
 };
 /// Default fee handling.
 pub const DEFAULT_FEE_HANDLING: FeeHandling = FeeHandling::PayToProposer;
+/// Default value for the maximum amount of gas an account's deploy validator can use.
+pub const DEFAULT_DEPLOY_VALIDATOR_GAS_LIMIT: u64 = 1_000_000_000;
 
 /// The runtime configuration of the execution engine
 #[derive(Debug, Clone)]
@@ -81,6 +83,8 @@ pub struct EngineConfig {
     pub(crate) refund_handling: RefundHandling,
     /// Fee handling.
     pub(crate) fee_handling: FeeHandling,
+    /// The maximum amount of gas an account's deploy validator can use when authorizing a deploy.
+    deploy_validator_gas_limit: u64,
 }
 
 impl Default for EngineConfig {
@@ -100,6 +104,7 @@ impl Default for EngineConfig {
             allow_unrestricted_transfers: DEFAULT_ALLOW_UNRESTRICTED_TRANSFERS,
             refund_handling: DEFAULT_REFUND_HANDLING,
             fee_handling: DEFAULT_FEE_HANDLING,
+            deploy_validator_gas_limit: DEFAULT_DEPLOY_VALIDATOR_GAS_LIMIT,
         }
     }
 }
@@ -140,6 +145,7 @@ impl EngineConfig {
             allow_unrestricted_transfers: DEFAULT_ALLOW_UNRESTRICTED_TRANSFERS,
             refund_handling: DEFAULT_REFUND_HANDLING,
             fee_handling: DEFAULT_FEE_HANDLING,
+            deploy_validator_gas_limit: DEFAULT_DEPLOY_VALIDATOR_GAS_LIMIT,
         }
     }
 
@@ -212,6 +218,11 @@ impl EngineConfig {
     pub fn fee_handling(&self) -> FeeHandling {
         self.fee_handling
     }
+
+    /// Returns the maximum amount of gas an account's deploy validator can use.
+    pub fn deploy_validator_gas_limit(&self) -> u64 {
+        self.deploy_validator_gas_limit
+    }
 }
 
 /// A builder for an [`EngineConfig`].
@@ -234,6 +245,7 @@ pub struct EngineConfigBuilder {
     allow_unrestricted_transfers: Option<bool>,
     refund_handling: Option<RefundHandling>,
     fee_handling: Option<FeeHandling>,
+    deploy_validator_gas_limit: Option<u64>,
 }
 
 impl EngineConfigBuilder {
@@ -348,6 +360,12 @@ impl EngineConfigBuilder {
         self
     }
 
+    /// Sets the deploy validator gas limit config option.
+    pub fn with_deploy_validator_gas_limit(mut self, deploy_validator_gas_limit: u64) -> Self {
+        self.deploy_validator_gas_limit = Some(deploy_validator_gas_limit);
+        self
+    }
+
     /// Builds a new [`EngineConfig`] object.
     pub fn build(self) -> EngineConfig {
         let max_query_depth = self.max_query_depth.unwrap_or(DEFAULT_MAX_QUERY_DEPTH);
@@ -377,6 +395,9 @@ impl EngineConfigBuilder {
             .unwrap_or(DEFAULT_ALLOW_UNRESTRICTED_TRANSFERS);
         let refund_handling = self.refund_handling.unwrap_or(DEFAULT_REFUND_HANDLING);
         let fee_handling = self.fee_handling.unwrap_or(DEFAULT_FEE_HANDLING);
+        let deploy_validator_gas_limit = self
+            .deploy_validator_gas_limit
+            .unwrap_or(DEFAULT_DEPLOY_VALIDATOR_GAS_LIMIT);
 
         let strict_argument_checking = self
             .strict_argument_checking
@@ -398,6 +419,7 @@ impl EngineConfigBuilder {
             allow_unrestricted_transfers,
             refund_handling,
             fee_handling,
+            deploy_validator_gas_limit,
             strict_argument_checking,
             vesting_schedule_period_millis,
             max_delegators_per_validator,
@@ -106,6 +106,9 @@ pub enum Error {
     /// Failed to retrieve accumulation purse from handle payment system contract.
     #[error("Failed to retrieve accumulation purse from the handle payment contract")]
     FailedToRetrieveAccumulationPurse,
+    /// The account's deploy validator rejected the deploy.
+    #[error("Deploy rejected by the account's deploy validator: {0}")]
+    DeployValidatorRejected(String),
 }
 
 impl Error {
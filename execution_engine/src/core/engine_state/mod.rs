@@ -16,6 +16,7 @@ pub mod op;
 mod prune;
 pub mod query;
 pub mod run_genesis_request;
+pub mod snapshot;
 pub mod step;
 pub mod system_contract_registry;
 mod transfer;
@@ -23,6 +24,7 @@ pub mod upgrade;
 
 use std::{
     cell::RefCell,
+    cmp,
     collections::{btree_map::Entry, BTreeMap, BTreeSet},
     convert::TryFrom,
     rc::Rc,
@@ -35,7 +37,10 @@ use tracing::{debug, error, trace, warn};
 
 use casper_hashing::Digest;
 use casper_types::{
-    account::{Account, AccountHash},
+    account::{
+        Account, AccountHash, ARG_AUTHORIZATION_KEYS, ARG_DEPLOY_HASH,
+        DEPLOY_VALIDATOR_ENTRY_POINT, DEPLOY_VALIDATOR_KEY,
+    },
     bytesrepr::ToBytes,
     contracts::NamedKeys,
     system::{
@@ -73,6 +78,7 @@ pub use self::{
     prune::{PruneConfig, PruneResult},
     query::{QueryRequest, QueryResult},
     run_genesis_request::RunGenesisRequest,
+    snapshot::{SnapshotError, SnapshotHeader},
     step::{RewardItem, SlashItem, StepError, StepRequest, StepSuccess},
     system_contract_registry::SystemContractRegistry,
     transfer::{TransferArgs, TransferRuntimeArgsBuilder, TransferTargetMode},
@@ -88,14 +94,20 @@ use crate::{
             upgrade::{ProtocolUpgradeError, SystemUpgrader},
         },
         execution::{self, DirectSystemContractCall, Executor},
-        runtime::RuntimeStack,
+        runtime::{
+            observer::ExecutionObserver,
+            profile::{ExecutionProfiler, GasProfile},
+            trace::{ExecutionTrace, ExecutionTracer},
+            RuntimeStack,
+        },
         tracking_copy::{TrackingCopy, TrackingCopyExt},
     },
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
     storage::{
         global_state::{
-            lmdb::LmdbGlobalState, scratch::ScratchGlobalState, CommitProvider, StateProvider,
-            StateReader,
+            lmdb::{GarbageCollectionResult, LmdbGlobalState},
+            scratch::ScratchGlobalState,
+            CommitProvider, StateProvider, StateReader,
         },
         trie::{merkle_proof::TrieMerkleProof, TrieRaw},
         trie_store::operations::DeleteResult,
@@ -140,6 +152,15 @@ impl EngineState<LmdbGlobalState> {
         &self.state
     }
 
+    /// Deletes every trie in global state which is not reachable from any of `retained_roots`.
+    pub fn collect_garbage(
+        &self,
+        correlation_id: CorrelationId,
+        retained_roots: &[Digest],
+    ) -> Result<GarbageCollectionResult, Error> {
+        Ok(self.state.collect_garbage(correlation_id, retained_roots)?)
+    }
+
     /// Flushes the LMDB environment to disk when manual sync is enabled in the config.toml.
     pub fn flush_environment(&self) -> Result<(), lmdb::Error> {
         if self.state.environment.is_manual_sync_enabled() {
@@ -562,26 +583,8 @@ where
         let mut results = ExecutionResults::with_capacity(deploys.len());
 
         for deploy_item in deploys {
-            let result = match deploy_item.session {
-                ExecutableDeployItem::Transfer { .. } => self.transfer(
-                    correlation_id,
-                    &executor,
-                    exec_request.protocol_version,
-                    exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
-                    deploy_item,
-                    exec_request.proposer.clone(),
-                ),
-                _ => self.deploy(
-                    correlation_id,
-                    &executor,
-                    exec_request.protocol_version,
-                    exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
-                    deploy_item,
-                    exec_request.proposer.clone(),
-                ),
-            };
+            let result =
+                self.execute_deploy_item(correlation_id, &executor, &exec_request, deploy_item);
             match result {
                 Ok(result) => results.push_back(result),
                 Err(error) => {
@@ -593,13 +596,141 @@ where
         Ok(results)
     }
 
+    /// Runs a deploy execution request, tracing the execution of each deploy.
+    ///
+    /// Returns the execution results along with the trace of each deploy, in the same order.
+    pub fn run_execute_with_trace(
+        &self,
+        correlation_id: CorrelationId,
+        mut exec_request: ExecuteRequest,
+    ) -> Result<(ExecutionResults, Vec<ExecutionTrace>), Error> {
+        self.run_execute_observed(
+            correlation_id,
+            exec_request,
+            ExecutionTracer::default(),
+            ExecutionTracer::take_trace,
+        )
+    }
+
+    /// Runs a deploy execution request, profiling the gas used by each deploy.
+    ///
+    /// Returns the execution results along with the gas profile of each deploy, in the same order.
+    pub fn run_execute_with_profile(
+        &self,
+        correlation_id: CorrelationId,
+        mut exec_request: ExecuteRequest,
+    ) -> Result<(ExecutionResults, Vec<GasProfile>), Error> {
+        self.run_execute_observed(
+            correlation_id,
+            exec_request,
+            ExecutionProfiler::default(),
+            ExecutionProfiler::take_profile,
+        )
+    }
+
+    /// Runs a deploy execution request with each deploy observed by `observer`.
+    ///
+    /// Returns the execution results along with what `take_observation` took from the observer
+    /// after each deploy, in the same order.
+    fn run_execute_observed<O, T>(
+        &self,
+        correlation_id: CorrelationId,
+        mut exec_request: ExecuteRequest,
+        observer: O,
+        take_observation: fn(&mut O) -> T,
+    ) -> Result<(ExecutionResults, Vec<T>), Error>
+    where
+        O: ExecutionObserver + 'static,
+    {
+        let observer = Rc::new(RefCell::new(observer));
+        let executor = Executor::new_with_observer(self.config().clone(), observer.clone());
+
+        let deploys = exec_request.take_deploys();
+        let mut results = ExecutionResults::with_capacity(deploys.len());
+        let mut observations = Vec::with_capacity(deploys.len());
+
+        for deploy_item in deploys {
+            let result =
+                self.execute_deploy_item(correlation_id, &executor, &exec_request, deploy_item)?;
+            results.push_back(result);
+            observations.push(take_observation(&mut observer.borrow_mut()));
+        }
+
+        Ok((results, observations))
+    }
+
+    /// Executes a single deploy of an execution request, taking the native transfer shortcut if
+    /// applicable.
+    fn execute_deploy_item(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        exec_request: &ExecuteRequest,
+        deploy_item: DeployItem,
+    ) -> Result<ExecutionResult, Error> {
+        match deploy_item.session {
+            ExecutableDeployItem::Transfer { .. } => self.transfer(
+                correlation_id,
+                executor,
+                exec_request.protocol_version,
+                exec_request.parent_state_hash,
+                BlockTime::new(exec_request.block_time),
+                deploy_item,
+                exec_request.proposer.clone(),
+            ),
+            _ => self.deploy(
+                correlation_id,
+                executor,
+                exec_request.protocol_version,
+                exec_request.parent_state_hash,
+                BlockTime::new(exec_request.block_time),
+                deploy_item,
+                exec_request.proposer.clone(),
+            ),
+        }
+    }
+
+    /// Executes the deploys of an [`ExecuteRequest`] for a dry run, without checking their
+    /// approvals and without running their payment code.
+    ///
+    /// See [`EngineState::dry_run`] for details.
+    ///
+    /// Return execution results which contains results from each deploy ran.
+    pub fn run_dry_run(
+        &self,
+        correlation_id: CorrelationId,
+        mut exec_request: ExecuteRequest,
+    ) -> Result<ExecutionResults, Error> {
+        let executor = Executor::new(self.config().clone());
+
+        let deploys = exec_request.take_deploys();
+        let mut results = ExecutionResults::with_capacity(deploys.len());
+
+        for deploy_item in deploys {
+            let result = self.dry_run(
+                correlation_id,
+                &executor,
+                exec_request.protocol_version,
+                exec_request.parent_state_hash,
+                BlockTime::new(exec_request.block_time),
+                deploy_item,
+                exec_request.proposer.clone(),
+            )?;
+            results.push_back(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Returns the account the deploy is authorized for, along with the hash of the deploy
+    /// validator which still has to approve the deploy if the account installed one.
     fn get_authorized_account(
         &self,
         correlation_id: CorrelationId,
         account_hash: AccountHash,
         authorization_keys: &BTreeSet<AccountHash>,
         tracking_copy: Rc<RefCell<TrackingCopy<<S as StateProvider>::Reader>>>,
-    ) -> Result<Account, Error> {
+    ) -> Result<(Account, Option<ContractHash>), Error> {
         let account: Account = match tracking_copy
             .borrow_mut()
             .get_account(correlation_id, account_hash)
@@ -614,7 +745,7 @@ where
 
         if !admin_set.is_empty() && admin_set.intersection(authorization_keys).next().is_some() {
             // Exit early if there's at least a single signature coming from an admin.
-            return Ok(account);
+            return Ok((account, None));
         }
 
         // Authorize using provided authorization keys
@@ -622,12 +753,121 @@ where
             return Err(error::Error::Authorization);
         }
 
+        // An installed deploy validator decides in place of the deploy threshold, once the deploy
+        // is paid for.  Keys meeting the key management threshold could remove the validator
+        // anyway, so they aren't subject to it: this way a validator rejecting every deploy can't
+        // lock the account out.  A validator which can't be loaded falls back to the deploy
+        // threshold for the same reason.
+        if let Some(validator_key) = account.named_keys().get(DEPLOY_VALIDATOR_KEY) {
+            if !account.can_manage_keys_with(authorization_keys) {
+                let maybe_validator_hash =
+                    self.get_deploy_validator(correlation_id, *validator_key, &tracking_copy);
+                if maybe_validator_hash.is_some() {
+                    return Ok((account, maybe_validator_hash));
+                }
+            }
+        }
+
         // Check total key weight against deploy threshold
         if !account.can_deploy_with(authorization_keys) {
             return Err(execution::Error::DeploymentAuthorizationFailure.into());
         }
 
-        Ok(account)
+        Ok((account, None))
+    }
+
+    /// Returns the hash of the deploy validator contract installed under `validator_key`, or `None`
+    /// if it doesn't refer to a stored contract with a [`DEPLOY_VALIDATOR_ENTRY_POINT`] entry
+    /// point.
+    fn get_deploy_validator(
+        &self,
+        correlation_id: CorrelationId,
+        validator_key: Key,
+        tracking_copy: &Rc<RefCell<TrackingCopy<<S as StateProvider>::Reader>>>,
+    ) -> Option<ContractHash> {
+        let validator_hash = ContractHash::new(validator_key.into_hash()?);
+        let mut tracking_copy = tracking_copy.borrow_mut();
+        let contract = tracking_copy
+            .get_contract(correlation_id, validator_hash)
+            .ok()?;
+        if !contract.has_entry_point(DEPLOY_VALIDATOR_ENTRY_POINT) {
+            return None;
+        }
+        tracking_copy
+            .get_contract_wasm(correlation_id, contract.contract_wasm_hash())
+            .ok()?;
+        Some(validator_hash)
+    }
+
+    /// Calls the deploy validator installed by `account` under [`DEPLOY_VALIDATOR_KEY`] to decide
+    /// whether the deploy is authorized.
+    ///
+    /// The validator is executed with the lower of `gas_limit` and the gas limit set in the engine
+    /// config, against a fork of the global state so that any effects it has are discarded.
+    /// Returns the outcome of the validation along with the gas used by the validator, which is
+    /// for the caller to charge to the account whatever the outcome.
+    #[allow(clippy::too_many_arguments)]
+    fn validate_deploy(
+        &self,
+        correlation_id: CorrelationId,
+        account: &Account,
+        validator_hash: ContractHash,
+        authorization_keys: &BTreeSet<AccountHash>,
+        protocol_version: ProtocolVersion,
+        blocktime: BlockTime,
+        deploy_hash: DeployHash,
+        gas_limit: Gas,
+        tracking_copy: &Rc<RefCell<TrackingCopy<<S as StateProvider>::Reader>>>,
+    ) -> (Result<(), Error>, Gas) {
+        let maybe_args = RuntimeArgs::try_new(|args| {
+            args.insert(ARG_AUTHORIZATION_KEYS, authorization_keys.clone())?;
+            args.insert(ARG_DEPLOY_HASH, deploy_hash.value())?;
+            Ok(())
+        });
+        let args = match maybe_args {
+            Ok(args) => args,
+            Err(error) => return (Err(ExecError::from(error).into()), Gas::default()),
+        };
+
+        let parent_tracking_copy = tracking_copy.borrow();
+        let validator_tracking_copy = Rc::new(RefCell::new(parent_tracking_copy.fork()));
+
+        let stack = RuntimeStack::from_account_hash(
+            account.account_hash(),
+            self.config.max_runtime_call_stack_height() as usize,
+        );
+        let gas_limit = cmp::min(
+            gas_limit,
+            Gas::new(U512::from(self.config.deploy_validator_gas_limit())),
+        );
+        let mut named_keys = account.named_keys().clone();
+
+        // A dedicated executor keeps the validation out of any trace or profile of the deploy.
+        let executor = Executor::new(self.config().clone());
+        let result = executor.exec(
+            ExecutionKind::new_contract(validator_hash, DEPLOY_VALIDATOR_ENTRY_POINT.to_string()),
+            args,
+            account,
+            &mut named_keys,
+            account.extract_access_rights(),
+            authorization_keys.clone(),
+            blocktime,
+            deploy_hash,
+            gas_limit,
+            protocol_version,
+            correlation_id,
+            validator_tracking_copy,
+            Phase::Session,
+            stack,
+        );
+        log_execution_result("deploy validator result", &result);
+
+        match result {
+            ExecutionResult::Success { cost, .. } => (Ok(()), cost),
+            ExecutionResult::Failure { error, cost, .. } => {
+                (Err(Error::DeployValidatorRejected(error.to_string())), cost)
+            }
+        }
     }
 
     /// Get the balance of a passed purse referenced by its [`URef`].
@@ -677,13 +917,13 @@ where
 
         let authorization_keys = deploy_item.authorization_keys;
 
-        let account = match self.get_authorized_account(
+        let (account, maybe_validator_hash) = match self.get_authorized_account(
             correlation_id,
             account_hash,
             &authorization_keys,
             Rc::clone(&tracking_copy),
         ) {
-            Ok(account) => account,
+            Ok(authorized) => authorized,
             Err(e) => return Ok(ExecutionResult::precondition_failure(e)),
         };
 
@@ -779,6 +1019,40 @@ where
             ));
         }
 
+        // The deploy validator of the account, if any, can use no more gas than the main purse
+        // can pay for on top of the fixed cost of the transfer, and its gas is added to that cost.
+        let mut validation_result = Ok(());
+        let (wasmless_transfer_gas_cost, wasmless_transfer_motes) = match maybe_validator_hash {
+            Some(validator_hash) => {
+                let affordable_gas = account_main_purse_balance
+                    .checked_sub(wasmless_transfer_motes)
+                    .and_then(|motes| Gas::from_motes(motes, WASMLESS_TRANSFER_FIXED_GAS_PRICE))
+                    .unwrap_or_default();
+                let (result, validator_gas) = self.validate_deploy(
+                    correlation_id,
+                    &account,
+                    validator_hash,
+                    &authorization_keys,
+                    protocol_version,
+                    blocktime,
+                    deploy_item.deploy_hash,
+                    affordable_gas,
+                    &tracking_copy,
+                );
+                validation_result = result;
+                let total_gas_cost = wasmless_transfer_gas_cost + validator_gas;
+                match Motes::from_gas(total_gas_cost, WASMLESS_TRANSFER_FIXED_GAS_PRICE) {
+                    Some(total_motes) => (total_gas_cost, total_motes),
+                    None => {
+                        return Ok(ExecutionResult::precondition_failure(
+                            Error::GasConversionOverflow,
+                        ))
+                    }
+                }
+            }
+            None => (wasmless_transfer_gas_cost, wasmless_transfer_motes),
+        };
+
         // Function below creates an ExecutionResult with precomputed effects of "finalize_payment".
         let make_charged_execution_failure = |error| match ExecutionResult::new_payment_code_error(
             error,
@@ -795,6 +1069,10 @@ where
         // All wasmless transfer preconditions are met.
         // Any error that occurs in logic below this point would result in a charge for user error.
 
+        if let Err(error) = validation_result {
+            return Ok(make_charged_execution_failure(error));
+        }
+
         let mut runtime_args_builder =
             TransferRuntimeArgsBuilder::new(deploy_item.session.args().clone());
 
@@ -1138,7 +1416,7 @@ where
                     Phase::FinalizePayment,
                     finalize_payment_stack,
                     // Spending limit is cost of wasmless execution.
-                    U512::from(self.config().system_config().wasmless_transfer_cost()),
+                    wasmless_transfer_motes.value(),
                 );
 
             finalize_result
@@ -1216,7 +1494,7 @@ where
 
         // Get account from tracking copy
         // validation_spec_3: account validity
-        let account = {
+        let (account, maybe_validator_hash) = {
             let account_hash = deploy_item.address;
             match self.get_authorized_account(
                 correlation_id,
@@ -1224,7 +1502,7 @@ where
                 &authorization_keys,
                 Rc::clone(&tracking_copy),
             ) {
-                Ok(account) => account,
+                Ok(authorized) => authorized,
                 Err(e) => return Ok(ExecutionResult::precondition_failure(e)),
             }
         };
@@ -1519,6 +1797,46 @@ where
             }
         };
 
+        // The deploy validator of the account, if any, runs once the deploy is paid for, and the
+        // gas it uses is charged as part of the payment.
+        let (payment_result, payment_result_cost) = match maybe_validator_hash {
+            Some(validator_hash) => {
+                let available_gas = Gas::from_motes(payment_purse_balance, deploy_item.gas_price)
+                    .and_then(|gas| gas.checked_sub(payment_result_cost))
+                    .unwrap_or_default();
+                let (validation_result, validator_gas) = self.validate_deploy(
+                    correlation_id,
+                    &account,
+                    validator_hash,
+                    &authorization_keys,
+                    protocol_version,
+                    blocktime,
+                    deploy_hash,
+                    available_gas,
+                    &tracking_copy,
+                );
+                let payment_result_cost = payment_result_cost + validator_gas;
+                if let Err(error) = validation_result {
+                    match ExecutionResult::new_payment_code_error(
+                        error,
+                        max_payment_cost,
+                        account_main_purse_balance,
+                        payment_result_cost,
+                        account_main_purse_balance_key,
+                        rewards_target_purse_balance_key,
+                    ) {
+                        Ok(execution_result) => return Ok(execution_result),
+                        Err(error) => return Ok(ExecutionResult::precondition_failure(error)),
+                    }
+                }
+                (
+                    payment_result.with_cost(payment_result_cost),
+                    payment_result_cost,
+                )
+            }
+            None => (payment_result, payment_result_cost),
+        };
+
         // Transfer the contents of the rewards purse to block proposer
         execution_result_builder.set_payment_execution_result(payment_result);
 
@@ -1724,6 +2042,119 @@ where
         Ok(ret)
     }
 
+    /// Executes a deploy for a dry run, i.e. without checking its approvals and without running its
+    /// payment code.
+    ///
+    /// The deploy is authorized as if it was approved by all of the account's associated keys.
+    /// Native transfers are then executed as usual, including the charge of their fixed cost.  For
+    /// any other deploy only the session code is executed, with a gas limit derived from the
+    /// `amount` argument of the payment code, or from [`MAX_PAYMENT`] if there is no such argument.
+    /// No payment is taken, and the returned result holds the effects of the session code only.
+    ///
+    /// Returns [`ExecutionResult`], or an error condition.
+    #[allow(clippy::too_many_arguments)]
+    pub fn dry_run(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        protocol_version: ProtocolVersion,
+        prestate_hash: Digest,
+        blocktime: BlockTime,
+        mut deploy_item: DeployItem,
+        proposer: PublicKey,
+    ) -> Result<ExecutionResult, Error> {
+        let tracking_copy = match self.tracking_copy(prestate_hash) {
+            Err(error) => return Ok(ExecutionResult::precondition_failure(error)),
+            Ok(None) => return Err(Error::RootNotFound(prestate_hash)),
+            Ok(Some(tracking_copy)) => Rc::new(RefCell::new(tracking_copy)),
+        };
+
+        let account: Account = match tracking_copy
+            .borrow_mut()
+            .get_account(correlation_id, deploy_item.address)
+        {
+            Ok(account) => account,
+            Err(_) => return Ok(ExecutionResult::precondition_failure(Error::Authorization)),
+        };
+        deploy_item.authorization_keys = account
+            .associated_keys()
+            .iter()
+            .map(|(account_hash, _weight)| *account_hash)
+            .collect();
+
+        if deploy_item.session.is_transfer() {
+            return self.transfer(
+                correlation_id,
+                executor,
+                protocol_version,
+                prestate_hash,
+                blocktime,
+                deploy_item,
+                proposer,
+            );
+        }
+
+        let session_gas_limit = match deploy_item
+            .payment
+            .payment_amount(deploy_item.gas_price)
+            .or_else(|| Gas::from_motes(Motes::new(*MAX_PAYMENT), deploy_item.gas_price))
+        {
+            Some(gas) => gas,
+            None => {
+                return Ok(ExecutionResult::precondition_failure(
+                    Error::GasConversionOverflow,
+                ))
+            }
+        };
+
+        let session = deploy_item.session;
+        let session_args = session.args().clone();
+        let session_execution_kind = match ExecutionKind::new(
+            Rc::clone(&tracking_copy),
+            account.named_keys(),
+            session,
+            correlation_id,
+            &protocol_version,
+            Phase::Session,
+        ) {
+            Ok(execution_kind) => execution_kind,
+            Err(error) => {
+                return Ok(ExecutionResult::precondition_failure(error));
+            }
+        };
+
+        let session_stack = RuntimeStack::from_account_hash(
+            deploy_item.address,
+            self.config.max_runtime_call_stack_height() as usize,
+        );
+        let session_access_rights = account.extract_access_rights();
+        let mut session_named_keys = account.named_keys().clone();
+
+        let session_result = executor.exec(
+            session_execution_kind,
+            session_args,
+            &account,
+            &mut session_named_keys,
+            session_access_rights,
+            deploy_item.authorization_keys,
+            blocktime,
+            deploy_item.deploy_hash,
+            session_gas_limit,
+            protocol_version,
+            correlation_id,
+            Rc::clone(&tracking_copy),
+            Phase::Session,
+            session_stack,
+        );
+        log_execution_result("dry run session result", &session_result);
+
+        if session_result.is_failure() {
+            return Ok(session_result);
+        }
+        let execution_journal = tracking_copy.borrow().execution_journal();
+        Ok(session_result.with_journal(execution_journal))
+    }
+
     fn get_rewards_purse(
         &self,
         correlation_id: CorrelationId,
@@ -2439,11 +2870,13 @@ fn log_execution_result(preamble: &'static str, result: &ExecutionResult) {
             transfers,
             cost,
             execution_journal,
+            events,
         } => {
             debug!(
                 %cost,
                 transfer_count=%transfers.len(),
                 journal_entries=%execution_journal.len(),
+                event_count=%events.len(),
                 "{}: execution success",
                 preamble
             );
@@ -2522,7 +2955,8 @@ fn should_charge_for_errors_in_wasm(execution_result: &ExecutionResult) -> bool
                 | ExecError::ValueTooLarge
                 | ExecError::MissingRuntimeStack
                 | ExecError::DisabledContract(_)
-                | ExecError::DisabledUnrestrictedTransfers => false,
+                | ExecError::DisabledUnrestrictedTransfers
+                | ExecError::DeployValidatorPermissionDenied => false,
             },
             Error::WasmPreprocessing(_) => true,
             Error::WasmSerialization(_) => true,
@@ -2551,7 +2985,8 @@ fn should_charge_for_errors_in_wasm(execution_result: &ExecutionResult) -> bool
             | Error::FailedToRetrieveUnbondingDelay
             | Error::FailedToRetrieveEraId
             | Error::MissingTrieNodeChildren(_)
-            | Error::FailedToRetrieveAccumulationPurse => false,
+            | Error::FailedToRetrieveAccumulationPurse
+            | Error::DeployValidatorRejected(_) => false,
         },
         ExecutionResult::Success { .. } => false,
     }
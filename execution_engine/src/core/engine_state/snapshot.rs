@@ -0,0 +1,471 @@
+//! Support for exporting the global state under a state root hash to a portable snapshot, and for
+//! importing such a snapshot into another global state.
+//!
+//! A snapshot consists of a header followed by a sequence of chunks:
+//!
+//! * the header holds the [`SNAPSHOT_MAGIC`] bytes, the snapshot format version, the protocol
+//!   version of the exporting node and the state root hash,
+//! * each chunk holds its length and number of tries, the tries themselves and a checksum of the
+//!   length, number of tries and tries,
+//! * a chunk holding no tries marks the end of the snapshot.
+//!
+//! Tries are written children first, so that every trie can be imported via
+//! [`EngineState::put_trie_if_all_children_present`].
+use std::{
+    convert::TryFrom,
+    io::{self, Read, Write},
+};
+
+use thiserror::Error;
+use tracing::{debug, info};
+
+use casper_hashing::Digest;
+use casper_types::{
+    bytesrepr::{self, Bytes, FromBytes, ToBytes, U32_SERIALIZED_LENGTH},
+    Key, ProtocolVersion, StoredValue,
+};
+
+use super::{EngineState, Error};
+use crate::{
+    core::execution,
+    shared::newtypes::CorrelationId,
+    storage::{
+        global_state::{CommitProvider, StateProvider},
+        trie::{lazy_trie_tag, Trie, TrieTag},
+    },
+};
+
+/// The bytes at the start of every global state snapshot.
+pub const SNAPSHOT_MAGIC: [u8; 8] = *b"CSPRSNAP";
+/// The version of the snapshot format written by this version of the execution engine.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+/// The size of the tries held by a snapshot chunk above which the chunk is written out.
+pub const SNAPSHOT_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Length of the fixed-size fields at the start of each chunk: the length of the chunk's tries and
+/// the number of tries.
+const CHUNK_PREFIX_LENGTH: usize = 2 * U32_SERIALIZED_LENGTH;
+
+/// An error while exporting or importing a global state snapshot.
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    /// I/O error while reading or writing the snapshot.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// Error reading from or writing to global state.
+    #[error(transparent)]
+    Engine(#[from] Error),
+    /// Serialization/deserialization error.
+    #[error("Bytesrepr error: {0}")]
+    Bytesrepr(bytesrepr::Error),
+    /// The input does not start with the snapshot magic bytes.
+    #[error("Input is not a global state snapshot")]
+    InvalidMagic,
+    /// The snapshot was written in an unsupported format version.
+    #[error("Unsupported snapshot format version: {0}")]
+    UnsupportedFormatVersion(u32),
+    /// The checksum of a chunk does not match its contents.
+    #[error("Checksum mismatch in snapshot chunk {0}")]
+    ChecksumMismatch(u64),
+    /// The state root hash to export is not in global state.
+    #[error("Root not found: {0}")]
+    RootNotFound(Digest),
+    /// A trie below the state root hash to export is not in global state.
+    #[error("Trie not found: {0}")]
+    MissingTrie(Digest),
+    /// The snapshot ended without providing the trie under its state root hash.
+    #[error("Snapshot is missing its root trie {0}")]
+    IncompleteSnapshot(Digest),
+}
+
+impl From<bytesrepr::Error> for SnapshotError {
+    fn from(error: bytesrepr::Error) -> Self {
+        SnapshotError::Bytesrepr(error)
+    }
+}
+
+/// The header of a global state snapshot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SnapshotHeader {
+    protocol_version: ProtocolVersion,
+    state_root_hash: Digest,
+}
+
+impl SnapshotHeader {
+    /// Creates a new snapshot header.
+    pub fn new(protocol_version: ProtocolVersion, state_root_hash: Digest) -> Self {
+        SnapshotHeader {
+            protocol_version,
+            state_root_hash,
+        }
+    }
+
+    /// Returns the protocol version of the node which exported the snapshot.
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        self.protocol_version
+    }
+
+    /// Returns the state root hash of the global state held by the snapshot.
+    pub fn state_root_hash(&self) -> Digest {
+        self.state_root_hash
+    }
+
+    fn write<W: Write>(&self, writer: &mut W) -> Result<(), SnapshotError> {
+        writer.write_all(&SNAPSHOT_MAGIC)?;
+        writer.write_all(&SNAPSHOT_FORMAT_VERSION.to_bytes()?)?;
+        writer.write_all(&self.protocol_version.to_bytes()?)?;
+        writer.write_all(&self.state_root_hash.to_bytes()?)?;
+        Ok(())
+    }
+
+    fn read<R: Read>(reader: &mut R) -> Result<Self, SnapshotError> {
+        let mut magic = [0; SNAPSHOT_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::InvalidMagic);
+        }
+
+        let format_version: u32 = read_value(reader, U32_SERIALIZED_LENGTH)?;
+        if format_version != SNAPSHOT_FORMAT_VERSION {
+            return Err(SnapshotError::UnsupportedFormatVersion(format_version));
+        }
+
+        let protocol_version = read_value(reader, ProtocolVersion::V1_0_0.serialized_length())?;
+        let state_root_hash = read_value(reader, Digest::LENGTH)?;
+        Ok(SnapshotHeader::new(protocol_version, state_root_hash))
+    }
+}
+
+/// Reads a value of the given serialized length.
+fn read_value<R: Read, T: FromBytes>(reader: &mut R, length: usize) -> Result<T, SnapshotError> {
+    let mut buffer = vec![0; length];
+    reader.read_exact(&mut buffer)?;
+    Ok(bytesrepr::deserialize(buffer)?)
+}
+
+/// Writes tries to a snapshot, grouped into checksummed chunks.
+struct ChunkWriter<W> {
+    writer: W,
+    tries: Vec<u8>,
+    trie_count: u32,
+    total_trie_count: u64,
+}
+
+impl<W: Write> ChunkWriter<W> {
+    fn new(writer: W) -> Self {
+        ChunkWriter {
+            writer,
+            tries: vec![],
+            trie_count: 0,
+            total_trie_count: 0,
+        }
+    }
+
+    fn push(&mut self, trie: Bytes) -> Result<(), SnapshotError> {
+        trie.write_bytes(&mut self.tries)?;
+        self.trie_count += 1;
+        self.total_trie_count += 1;
+        if self.tries.len() >= SNAPSHOT_CHUNK_SIZE {
+            self.write_chunk()?;
+        }
+        Ok(())
+    }
+
+    fn write_chunk(&mut self) -> Result<(), SnapshotError> {
+        let length = u32::try_from(self.tries.len()).map_err(|_| bytesrepr::Error::OutOfMemory)?;
+        let mut prefix = length.to_bytes()?;
+        self.trie_count.write_bytes(&mut prefix)?;
+        self.writer.write_all(&prefix)?;
+        self.writer.write_all(&self.tries)?;
+        self.writer
+            .write_all(&Digest::hash_pair(&prefix, &self.tries).to_bytes()?)?;
+        self.tries.clear();
+        self.trie_count = 0;
+        Ok(())
+    }
+
+    /// Writes out any buffered tries followed by the empty chunk marking the end of the snapshot,
+    /// returning the number of tries written.
+    fn finish(mut self) -> Result<u64, SnapshotError> {
+        if self.trie_count > 0 {
+            self.write_chunk()?;
+        }
+        self.write_chunk()?;
+        self.writer.flush()?;
+        Ok(self.total_trie_count)
+    }
+}
+
+/// Reads the next chunk of a snapshot, returning its tries.
+fn read_chunk<R: Read>(reader: &mut R, chunk_index: u64) -> Result<Vec<Bytes>, SnapshotError> {
+    let mut prefix = [0; CHUNK_PREFIX_LENGTH];
+    reader.read_exact(&mut prefix)?;
+    let (length, remainder) = u32::from_bytes(&prefix)?;
+    let (trie_count, _) = u32::from_bytes(remainder)?;
+
+    // Read via `take` rather than allocating the declared length up front, so a corrupt length
+    // can't cause a huge allocation.
+    let mut tries = vec![];
+    reader
+        .by_ref()
+        .take(u64::from(length))
+        .read_to_end(&mut tries)?;
+    if tries.len() != length as usize {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+    let checksum: Digest = read_value(reader, Digest::LENGTH)?;
+    if checksum != Digest::hash_pair(prefix, &tries) {
+        return Err(SnapshotError::ChecksumMismatch(chunk_index));
+    }
+
+    // The trie count is not used to size the result up front, as even a checksummed chunk isn't
+    // necessarily from a trusted source.
+    let mut remainder = tries.as_slice();
+    let mut result = vec![];
+    for _ in 0..trie_count {
+        let (trie, rem) = Bytes::from_bytes(remainder)?;
+        result.push(trie);
+        remainder = rem;
+    }
+    if !remainder.is_empty() {
+        return Err(bytesrepr::Error::LeftOverBytes.into());
+    }
+    Ok(result)
+}
+
+/// Returns the hashes of the direct descendants of the given serialized trie.
+fn trie_children(trie_raw: &[u8]) -> Result<Vec<Digest>, SnapshotError> {
+    // Optimization: Don't deserialize leaves as they have no descendants.
+    if lazy_trie_tag(trie_raw) == Some(TrieTag::Leaf) {
+        return Ok(vec![]);
+    }
+    let trie: Trie<Key, StoredValue> = bytesrepr::deserialize_from_slice(trie_raw)?;
+    Ok(trie.iter_children().collect())
+}
+
+/// A trie waiting to be written to a snapshot.
+enum PendingTrie {
+    /// The trie's descendants have not been visited yet.
+    Unvisited(Digest),
+    /// The trie's descendants have all been written.
+    Visited(Bytes),
+}
+
+impl<S> EngineState<S>
+where
+    S: StateProvider + CommitProvider,
+    S::Error: Into<execution::Error>,
+    Error: From<S::Error>,
+{
+    /// Writes all the tries below `state_root_hash` to `writer` as a global state snapshot.
+    ///
+    /// Returns the number of tries written.
+    pub fn export_snapshot<W: Write>(
+        &self,
+        correlation_id: CorrelationId,
+        protocol_version: ProtocolVersion,
+        state_root_hash: Digest,
+        mut writer: W,
+    ) -> Result<u64, SnapshotError> {
+        if self
+            .get_trie_full(correlation_id, state_root_hash)?
+            .is_none()
+        {
+            return Err(SnapshotError::RootNotFound(state_root_hash));
+        }
+
+        SnapshotHeader::new(protocol_version, state_root_hash).write(&mut writer)?;
+        let mut chunk_writer = ChunkWriter::new(writer);
+
+        // Depth-first traversal, writing each trie once all of its descendants have been written.
+        let mut stack = vec![PendingTrie::Unvisited(state_root_hash)];
+        while let Some(pending_trie) = stack.pop() {
+            match pending_trie {
+                PendingTrie::Unvisited(trie_key) => {
+                    let trie_raw = self
+                        .get_trie_full(correlation_id, trie_key)?
+                        .ok_or(SnapshotError::MissingTrie(trie_key))?
+                        .into_inner();
+                    let children = trie_children(&trie_raw)?;
+                    stack.push(PendingTrie::Visited(trie_raw));
+                    stack.extend(children.into_iter().map(PendingTrie::Unvisited));
+                }
+                PendingTrie::Visited(trie_raw) => chunk_writer.push(trie_raw)?,
+            }
+        }
+
+        let trie_count = chunk_writer.finish()?;
+        info!(%state_root_hash, trie_count, "exported global state snapshot");
+        Ok(trie_count)
+    }
+
+    /// Reads a global state snapshot from `reader`, writing its tries to global state.
+    ///
+    /// Every chunk is verified against its checksum before its tries are written, and every trie is
+    /// only written once all of its descendants are present in global state.  Returns the header of
+    /// the snapshot.
+    pub fn import_snapshot<R: Read>(
+        &self,
+        correlation_id: CorrelationId,
+        mut reader: R,
+    ) -> Result<SnapshotHeader, SnapshotError> {
+        let header = SnapshotHeader::read(&mut reader)?;
+
+        let mut trie_count: u64 = 0;
+        for chunk_index in 0.. {
+            let tries = read_chunk(&mut reader, chunk_index)?;
+            if tries.is_empty() {
+                break;
+            }
+            for trie in tries {
+                let _ = self.put_trie_if_all_children_present(correlation_id, &trie)?;
+                trie_count += 1;
+            }
+            debug!(
+                chunk_index,
+                trie_count, "imported global state snapshot chunk"
+            );
+        }
+
+        if self
+            .get_trie_full(correlation_id, header.state_root_hash())?
+            .is_none()
+        {
+            return Err(SnapshotError::IncompleteSnapshot(header.state_root_hash()));
+        }
+
+        info!(
+            state_root_hash = %header.state_root_hash(),
+            trie_count,
+            "imported global state snapshot"
+        );
+        Ok(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{AccessRights, CLValue, URef};
+
+    use super::*;
+    use crate::{
+        core::engine_state::EngineConfig,
+        storage::global_state::{in_memory::InMemoryGlobalState, StateReader},
+    };
+
+    fn engine_state_with_values(
+        count: u8,
+    ) -> (
+        EngineState<InMemoryGlobalState>,
+        Digest,
+        Vec<(Key, StoredValue)>,
+    ) {
+        let pairs: Vec<(Key, StoredValue)> = (0..count)
+            .map(|index| {
+                let key = Key::URef(URef::new([index; 32], AccessRights::READ_ADD_WRITE));
+                let value = StoredValue::CLValue(CLValue::from_t(u64::from(index)).unwrap());
+                (key, value)
+            })
+            .collect();
+        let (global_state, state_root_hash) =
+            InMemoryGlobalState::from_pairs(CorrelationId::new(), &pairs).unwrap();
+        (
+            EngineState::new(global_state, EngineConfig::default()),
+            state_root_hash,
+            pairs,
+        )
+    }
+
+    fn export(engine_state: &EngineState<InMemoryGlobalState>, state_root_hash: Digest) -> Vec<u8> {
+        let mut snapshot = vec![];
+        let trie_count = engine_state
+            .export_snapshot(
+                CorrelationId::new(),
+                ProtocolVersion::V1_0_0,
+                state_root_hash,
+                &mut snapshot,
+            )
+            .unwrap();
+        assert!(trie_count > 0);
+        snapshot
+    }
+
+    #[test]
+    fn should_round_trip_snapshot() {
+        let (source, state_root_hash, pairs) = engine_state_with_values(50);
+        let snapshot = export(&source, state_root_hash);
+
+        let target = EngineState::new(InMemoryGlobalState::empty().unwrap(), Default::default());
+        let header = target
+            .import_snapshot(CorrelationId::new(), snapshot.as_slice())
+            .unwrap();
+        assert_eq!(
+            header,
+            SnapshotHeader::new(ProtocolVersion::V1_0_0, state_root_hash)
+        );
+
+        let reader = target.state.checkout(state_root_hash).unwrap().unwrap();
+        for (key, value) in pairs {
+            assert_eq!(
+                reader.read(CorrelationId::new(), &key).unwrap(),
+                Some(value)
+            );
+        }
+    }
+
+    #[test]
+    fn should_fail_to_export_missing_root() {
+        let (source, _, _) = engine_state_with_values(1);
+        let result = source.export_snapshot(
+            CorrelationId::new(),
+            ProtocolVersion::V1_0_0,
+            Digest::hash([1; 32]),
+            vec![],
+        );
+        assert!(matches!(result, Err(SnapshotError::RootNotFound(_))));
+    }
+
+    #[test]
+    fn should_reject_corrupted_snapshot() {
+        let (source, state_root_hash, _) = engine_state_with_values(10);
+        let snapshot = export(&source, state_root_hash);
+        let target = EngineState::new(InMemoryGlobalState::empty().unwrap(), Default::default());
+
+        let mut bad_magic = snapshot.clone();
+        bad_magic[0] ^= 1;
+        assert!(matches!(
+            target.import_snapshot(CorrelationId::new(), bad_magic.as_slice()),
+            Err(SnapshotError::InvalidMagic)
+        ));
+
+        // Flip a byte in the tries of the first chunk, just past the header and chunk prefix.
+        let header_length = SNAPSHOT_MAGIC.len()
+            + U32_SERIALIZED_LENGTH
+            + ProtocolVersion::V1_0_0.serialized_length()
+            + Digest::LENGTH;
+        let mut bad_tries = snapshot.clone();
+        bad_tries[header_length + CHUNK_PREFIX_LENGTH + 10] ^= 1;
+        assert!(matches!(
+            target.import_snapshot(CorrelationId::new(), bad_tries.as_slice()),
+            Err(SnapshotError::ChecksumMismatch(0))
+        ));
+        assert!(target
+            .get_trie_full(CorrelationId::new(), state_root_hash)
+            .unwrap()
+            .is_none());
+
+        // Flip a byte in the trie count of the first chunk, which is covered by the checksum too.
+        let mut bad_trie_count = snapshot.clone();
+        bad_trie_count[header_length + U32_SERIALIZED_LENGTH] ^= 1;
+        assert!(matches!(
+            target.import_snapshot(CorrelationId::new(), bad_trie_count.as_slice()),
+            Err(SnapshotError::ChecksumMismatch(0))
+        ));
+
+        let truncated = &snapshot[..header_length + 4];
+        assert!(matches!(
+            target.import_snapshot(CorrelationId::new(), truncated),
+            Err(SnapshotError::Io(_))
+        ));
+    }
+}
@@ -176,6 +176,10 @@ pub enum Error {
     /// Failed to transfer tokens on a private chain.
     #[error("Failed to transfer with unrestricted transfers disabled")]
     DisabledUnrestrictedTransfers,
+    /// An attempt to install or remove an account's deploy validator without the weight required
+    /// to manage the account's keys.
+    #[error("Changing the deploy validator requires the key management threshold")]
+    DeployValidatorPermissionDenied,
 }
 
 impl From<wasm_prep::PreprocessingError> for Error {
@@ -16,7 +16,7 @@ use crate::{
             ExecError,
         },
         execution::{address_generator::AddressGenerator, Error},
-        runtime::{Runtime, RuntimeStack},
+        runtime::{observer::ExecutionObserver, trace::FrameKind, Runtime, RuntimeStack},
         runtime_context::RuntimeContext,
         tracking_copy::{TrackingCopy, TrackingCopyExt},
     },
@@ -35,12 +35,40 @@ fn try_get_amount(runtime_args: &RuntimeArgs) -> Result<U512, ExecError> {
 /// Executor object deals with execution of WASM modules.
 pub struct Executor {
     config: EngineConfig,
+    observer: Option<Rc<RefCell<dyn ExecutionObserver>>>,
 }
 
 impl Executor {
     /// Creates new executor object.
     pub fn new(config: EngineConfig) -> Self {
-        Executor { config }
+        Executor {
+            config,
+            observer: None,
+        }
+    }
+
+    /// Creates new executor object whose executions are observed by `observer`.
+    pub(crate) fn new_with_observer(
+        config: EngineConfig,
+        observer: Rc<RefCell<dyn ExecutionObserver>>,
+    ) -> Self {
+        Executor {
+            config,
+            observer: Some(observer),
+        }
+    }
+
+    /// Runs `f` within a new outermost frame of the given kind, if the executions are observed.
+    fn traced(&self, kind: FrameKind, f: impl FnOnce() -> ExecutionResult) -> ExecutionResult {
+        let observer = match &self.observer {
+            Some(observer) => observer,
+            None => return f(),
+        };
+        observer.borrow_mut().enter_frame(&kind, Gas::default());
+        let result = f();
+        let error = result.as_error().map(ToString::to_string);
+        observer.borrow_mut().exit_frame(result.cost(), error);
+        result
     }
 
     /// Executes a WASM module.
@@ -65,6 +93,52 @@ impl Executor {
         phase: Phase,
         stack: RuntimeStack,
     ) -> ExecutionResult
+    where
+        R: StateReader<Key, StoredValue>,
+        R::Error: Into<Error>,
+    {
+        let kind = match phase {
+            Phase::Payment => FrameKind::Payment,
+            _ => FrameKind::Session,
+        };
+        self.traced(kind, || {
+            self.exec_untraced(
+                execution_kind,
+                args,
+                account,
+                named_keys,
+                access_rights,
+                authorization_keys,
+                blocktime,
+                deploy_hash,
+                gas_limit,
+                protocol_version,
+                correlation_id,
+                tracking_copy,
+                phase,
+                stack,
+            )
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn exec_untraced<R>(
+        &self,
+        execution_kind: ExecutionKind,
+        args: RuntimeArgs,
+        account: &Account,
+        named_keys: &mut NamedKeys,
+        access_rights: ContextAccessRights,
+        authorization_keys: BTreeSet<AccountHash>,
+        blocktime: BlockTime,
+        deploy_hash: DeployHash,
+        gas_limit: Gas,
+        protocol_version: ProtocolVersion,
+        correlation_id: CorrelationId,
+        tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
+        phase: Phase,
+        stack: RuntimeStack,
+    ) -> ExecutionResult
     where
         R: StateReader<Key, StoredValue>,
         R::Error: Into<Error>,
@@ -122,6 +196,7 @@ impl Executor {
                 execution_journal: runtime.context().execution_journal(),
                 transfers: runtime.context().transfers().to_owned(),
                 cost: runtime.context().gas_counter(),
+                events: runtime.context().events().to_owned(),
             },
             Err(error) => ExecutionResult::Failure {
                 error: error.into(),
@@ -151,6 +226,48 @@ impl Executor {
         phase: Phase,
         stack: RuntimeStack,
     ) -> ExecutionResult
+    where
+        R: StateReader<Key, StoredValue>,
+        R::Error: Into<Error>,
+    {
+        self.traced(FrameKind::Payment, || {
+            self.exec_standard_payment_untraced(
+                payment_args,
+                payment_base_key,
+                account,
+                payment_named_keys,
+                access_rights,
+                authorization_keys,
+                blocktime,
+                deploy_hash,
+                payment_gas_limit,
+                protocol_version,
+                correlation_id,
+                tracking_copy,
+                phase,
+                stack,
+            )
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn exec_standard_payment_untraced<R>(
+        &self,
+        payment_args: RuntimeArgs,
+        payment_base_key: Key,
+        account: &Account,
+        payment_named_keys: &mut NamedKeys,
+        access_rights: ContextAccessRights,
+        authorization_keys: BTreeSet<AccountHash>,
+        blocktime: BlockTime,
+        deploy_hash: DeployHash,
+        payment_gas_limit: Gas,
+        protocol_version: ProtocolVersion,
+        correlation_id: CorrelationId,
+        tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
+        phase: Phase,
+        stack: RuntimeStack,
+    ) -> ExecutionResult
     where
         R: StateReader<Key, StoredValue>,
         R::Error: Into<Error>,
@@ -197,6 +314,7 @@ impl Executor {
                 execution_journal: runtime.context().execution_journal(),
                 transfers: runtime.context().transfers().to_owned(),
                 cost: runtime.context().gas_counter(),
+                events: runtime.context().events().to_owned(),
             },
             Err(error) => ExecutionResult::Failure {
                 execution_journal,
@@ -322,6 +440,7 @@ impl Executor {
                     execution_journal: runtime.context().execution_journal(),
                     transfers: runtime.context().transfers().to_owned(),
                     cost: runtime.context().gas_counter(),
+                    events: runtime.context().events().to_owned(),
                 }
                 .take_with_ret(ret),
                 Err(error) => ExecutionResult::Failure {
@@ -370,7 +489,7 @@ impl Executor {
         let gas_counter = Gas::default();
         let transfers = Vec::default();
 
-        RuntimeContext::new(
+        let mut runtime_context = RuntimeContext::new(
             tracking_copy,
             entry_point_type,
             named_keys,
@@ -390,7 +509,9 @@ impl Executor {
             self.config.clone(),
             transfers,
             remaining_spending_limit,
-        )
+        );
+        runtime_context.set_observer(self.observer.clone());
+        runtime_context
     }
 }
 
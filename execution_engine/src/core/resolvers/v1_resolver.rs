@@ -245,6 +245,18 @@ impl ModuleImportResolver for RuntimeModuleImportResolver {
                 Signature::new(&[ValueType::I32; 4][..], Some(ValueType::I32)),
                 FunctionIndex::EnableContractVersion.into(),
             ),
+            "casper_verify_signature" => FuncInstance::alloc_host(
+                Signature::new(&[ValueType::I32; 6][..], Some(ValueType::I32)),
+                FunctionIndex::VerifySignature.into(),
+            ),
+            "casper_emit_event" => FuncInstance::alloc_host(
+                Signature::new(&[ValueType::I32; 4][..], None),
+                FunctionIndex::EmitEvent.into(),
+            ),
+            "casper_add_contract_version_with_schema" => FuncInstance::alloc_host(
+                Signature::new(&[ValueType::I32; 12][..], Some(ValueType::I32)),
+                FunctionIndex::AddContractVersionWithSchema.into(),
+            ),
             _ => {
                 return Err(InterpreterError::Function(format!(
                     "host module doesn't export function with name {}",
@@ -9,8 +9,8 @@ use casper_types::{
     contracts::{ContractPackageStatus, EntryPoints, NamedKeys},
     crypto,
     system::auction::EraInfo,
-    ApiError, ContractHash, ContractPackageHash, ContractVersion, EraId, Gas, Group, Key,
-    StoredValue, URef, U512, UREF_SERIALIZED_LENGTH,
+    ApiError, ContractHash, ContractPackageHash, ContractSchema, ContractVersion, EraId, Group,
+    Key, PublicKey, Signature, StoredValue, URef, U512, UREF_SERIALIZED_LENGTH,
 };
 
 use super::{args::Args, Error, Runtime};
@@ -32,6 +32,34 @@ where
     ) -> Result<Option<RuntimeValue>, Trap> {
         let func = FunctionIndex::try_from(index).expect("unknown function index");
 
+        // The `gas` function is called by the instrumented code of every basic block, so it isn't
+        // traced; the gas it charges is accounted for in the enclosing frame.
+        if func == FunctionIndex::GasFuncIndex || !self.context.is_observed() {
+            return self.invoke_host_function(func, args);
+        }
+
+        let gas_counter = self.context.gas_counter();
+        self.context.observe(|observer| {
+            observer.enter_host_function(func.name(), args.as_ref(), gas_counter)
+        });
+        let result = self.invoke_host_function(func, args);
+        let gas_counter = self.context.gas_counter();
+        self.context
+            .observe(|observer| observer.exit_host_function(gas_counter));
+        result
+    }
+}
+
+impl<'a, R> Runtime<'a, R>
+where
+    R: StateReader<Key, StoredValue>,
+    R::Error: Into<Error>,
+{
+    fn invoke_host_function(
+        &mut self,
+        func: FunctionIndex,
+        args: RuntimeArgs,
+    ) -> Result<Option<RuntimeValue>, Trap> {
         let host_function_costs = self.config.wasm_config().take_host_function_costs();
 
         match func {
@@ -189,7 +217,7 @@ where
                 let (gas_arg,): (u32,) = Args::parse(args)?;
                 // Gas is special cased internal host function and for accounting purposes it isn't
                 // represented in protocol data.
-                self.gas(Gas::new(gas_arg.into()))?;
+                self.metered_block_gas(gas_arg)?;
                 Ok(None)
             }
 
@@ -639,6 +667,7 @@ where
                     contract_package_hash,
                     entry_points,
                     named_keys,
+                    None,
                     output_ptr,
                     output_size as usize,
                     bytes_written_ptr,
@@ -1095,6 +1124,122 @@ where
 
                 Ok(Some(RuntimeValue::I32(api_error::i32_from(result))))
             }
+
+            FunctionIndex::VerifySignature => {
+                // args(0) = pointer to message in wasm memory
+                // args(1) = size of message in wasm memory
+                // args(2) = pointer to serialized signature in wasm memory
+                // args(3) = size of serialized signature in wasm memory
+                // args(4) = pointer to serialized public key in wasm memory
+                // args(5) = size of serialized public key in wasm memory
+                let (
+                    message_ptr,
+                    message_size,
+                    signature_ptr,
+                    signature_size,
+                    public_key_ptr,
+                    public_key_size,
+                ) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.verify_signature,
+                    [
+                        message_ptr,
+                        message_size,
+                        signature_ptr,
+                        signature_size,
+                        public_key_ptr,
+                        public_key_size,
+                    ],
+                )?;
+                let message = self.bytes_from_mem(message_ptr, message_size as usize)?;
+                let signature: Signature = self.t_from_mem(signature_ptr, signature_size)?;
+                let public_key: PublicKey = self.t_from_mem(public_key_ptr, public_key_size)?;
+
+                // Zero indicates a valid signature, and one an invalid signature.
+                let result = match crypto::verify(message, &signature, &public_key) {
+                    Ok(()) => 0,
+                    Err(_) => 1,
+                };
+                Ok(Some(RuntimeValue::I32(result)))
+            }
+
+            FunctionIndex::EmitEvent => {
+                // args(0) = pointer to topic in Wasm memory
+                // args(1) = size of topic
+                // args(2) = pointer to serialized payload in Wasm memory
+                // args(3) = size of serialized payload
+                let (topic_ptr, topic_size, payload_ptr, payload_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.emit_event,
+                    [topic_ptr, topic_size, payload_ptr, payload_size],
+                )?;
+                self.emit_event(topic_ptr, topic_size, payload_ptr, payload_size)?;
+                Ok(None)
+            }
+
+            FunctionIndex::AddContractVersionWithSchema => {
+                // args(0) = pointer to package key in wasm memory
+                // args(1) = size of package key in wasm memory
+                // args(2) = pointer to output location for the new version
+                // args(3) = pointer to entrypoints in wasm memory
+                // args(4) = size of entrypoints in wasm memory
+                // args(5) = pointer to named keys in wasm memory
+                // args(6) = size of named keys in wasm memory
+                // args(7) = pointer to schema in wasm memory
+                // args(8) = size of schema in wasm memory
+                // args(9) = pointer to output buffer for serialized key
+                // args(10) = size of output buffer
+                // args(11) = pointer to bytes written
+                let (
+                    contract_package_hash_ptr,
+                    contract_package_hash_size,
+                    version_ptr,
+                    entry_points_ptr,
+                    entry_points_size,
+                    named_keys_ptr,
+                    named_keys_size,
+                    schema_ptr,
+                    schema_size,
+                    output_ptr,
+                    output_size,
+                    bytes_written_ptr,
+                ) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.add_contract_version_with_schema,
+                    [
+                        contract_package_hash_ptr,
+                        contract_package_hash_size,
+                        version_ptr,
+                        entry_points_ptr,
+                        entry_points_size,
+                        named_keys_ptr,
+                        named_keys_size,
+                        schema_ptr,
+                        schema_size,
+                        output_ptr,
+                        output_size,
+                        bytes_written_ptr,
+                    ],
+                )?;
+
+                let contract_package_hash: ContractPackageHash =
+                    self.t_from_mem(contract_package_hash_ptr, contract_package_hash_size)?;
+                let entry_points: EntryPoints =
+                    self.t_from_mem(entry_points_ptr, entry_points_size)?;
+                let named_keys: NamedKeys = self.t_from_mem(named_keys_ptr, named_keys_size)?;
+                let schema: ContractSchema = self.t_from_mem(schema_ptr, schema_size)?;
+                let ret = self.add_contract_version(
+                    contract_package_hash,
+                    entry_points,
+                    named_keys,
+                    Some(schema),
+                    output_ptr,
+                    output_size as usize,
+                    bytes_written_ptr,
+                    version_ptr,
+                )?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
         }
     }
 }
@@ -5,9 +5,12 @@ mod externals;
 mod handle_payment_internal;
 mod host_function_flag;
 mod mint_internal;
+pub(crate) mod observer;
+pub mod profile;
 pub mod stack;
 mod standard_payment_internal;
-mod utils;
+pub mod trace;
+pub(crate) mod utils;
 
 use std::{
     cmp,
@@ -23,7 +26,7 @@ use wasmi::{MemoryRef, Trap, TrapKind};
 use casper_types::{
     account::{
         Account, AccountHash, ActionType, AddKeyFailure, RemoveKeyFailure, SetThresholdFailure,
-        UpdateKeyFailure, Weight,
+        UpdateKeyFailure, Weight, DEPLOY_VALIDATOR_KEY,
     },
     bytesrepr::{self, Bytes, FromBytes, ToBytes},
     contracts::{
@@ -38,16 +41,17 @@ use casper_types::{
         HANDLE_PAYMENT, MINT, STANDARD_PAYMENT,
     },
     AccessRights, ApiError, CLTyped, CLValue, ContextAccessRights, ContractHash,
-    ContractPackageHash, ContractVersionKey, ContractWasm, DeployHash, EntryPointType, Gas,
-    GrantedAccess, Key, NamedArg, Parameter, Phase, PublicKey, RuntimeArgs, StoredValue, Transfer,
-    TransferResult, TransferredTo, URef, DICTIONARY_ITEM_KEY_MAX_LENGTH, U512,
+    ContractPackageHash, ContractSchema, ContractVersionKey, ContractWasm, DeployHash,
+    EntryPointType, Gas, GrantedAccess, Key, NamedArg, Parameter, Phase, PublicKey, RuntimeArgs,
+    StoredValue, Transfer, TransferResult, TransferredTo, URef, DICTIONARY_ITEM_KEY_MAX_LENGTH,
+    U512,
 };
 
 use crate::{
     core::{
         engine_state::EngineConfig,
         execution::{self, Error},
-        runtime::host_function_flag::HostFunctionFlag,
+        runtime::{host_function_flag::HostFunctionFlag, trace::FrameKind},
         runtime_context::{self, RuntimeContext},
         tracking_copy::TrackingCopyExt,
     },
@@ -163,6 +167,31 @@ where
         self.context.charge_gas(amount)
     }
 
+    /// Charges the gas of a metered block of Wasm code, identified by the argument of a call of the
+    /// gas function, reporting it to the observer of the execution if any.
+    fn metered_block_gas(&mut self, gas_arg: u32) -> Result<(), Error> {
+        let amount = self
+            .context
+            .observe(|observer| observer.metered_block_cost(gas_arg))
+            .unwrap_or_else(|| Gas::new(gas_arg.into()));
+        self.gas(amount)?;
+        self.context
+            .observe(|observer| observer.record_metered_block(gas_arg));
+        Ok(())
+    }
+
+    /// Returns the module to instantiate for executing `module`.
+    ///
+    /// This is a copy of `module` unless the observer of the execution provides a module to execute
+    /// in its place, which must never be stored.
+    fn module_to_instantiate(&self, module: &Module) -> Module {
+        let opcode_costs = self.config.wasm_config().opcode_costs();
+        self.context
+            .observe(|observer| observer.prepare_module(module, &opcode_costs))
+            .flatten()
+            .unwrap_or_else(|| module.clone())
+    }
+
     /// Returns current gas counter.
     fn gas_counter(&self) -> Gas {
         self.context.gas_counter()
@@ -306,15 +335,42 @@ where
     ) -> Result<(), Trap> {
         let name = self.string_from_mem(name_ptr, name_size)?;
         let key = self.key_from_mem(key_ptr, key_size)?;
+        self.check_deploy_validator_change(&name)?;
         self.context.put_key(name, key).map_err(Into::into)
     }
 
     fn remove_key(&mut self, name_ptr: u32, name_size: u32) -> Result<(), Trap> {
         let name = self.string_from_mem(name_ptr, name_size)?;
+        self.check_deploy_validator_change(&name)?;
         self.context.remove_key(&name)?;
         Ok(())
     }
 
+    /// Checks that the account's deploy validator, which takes the place of its deploy threshold,
+    /// is only installed or removed by a deploy able to manage the account's keys.
+    fn check_deploy_validator_change(&self, name: &str) -> Result<(), Error> {
+        if name == DEPLOY_VALIDATOR_KEY
+            && self.context.base_key() == Key::Account(self.context.account().account_hash())
+            && !self.can_manage_keys()
+        {
+            return Err(Error::DeployValidatorPermissionDenied);
+        }
+        Ok(())
+    }
+
+    /// Records an event with the given topic and payload, emitted by the current context.
+    fn emit_event(
+        &mut self,
+        topic_ptr: u32,
+        topic_size: u32,
+        payload_ptr: u32,
+        payload_size: u32,
+    ) -> Result<(), Trap> {
+        let topic = self.string_from_mem(topic_ptr, topic_size)?;
+        let payload = self.cl_value_from_mem(payload_ptr, payload_size)?;
+        self.context.emit_event(topic, payload).map_err(Into::into)
+    }
+
     /// Writes runtime context's account main purse to dest_ptr in the Wasm memory.
     fn get_main_purse(&mut self, dest_ptr: u32) -> Result<(), Trap> {
         let purse = self.context.get_main_purse()?;
@@ -1032,8 +1088,11 @@ where
         let protocol_version = self.context.protocol_version();
         let engine_config = self.config.clone();
         let module = wasm_prep::preprocess(*engine_config.wasm_config(), module_bytes)?;
-        let (instance, memory) =
-            utils::instance_and_memory(module.clone(), protocol_version, &engine_config)?;
+        let (instance, memory) = utils::instance_and_memory(
+            self.module_to_instantiate(&module),
+            protocol_version,
+            &engine_config,
+        )?;
         self.memory = Some(memory);
         self.module = Some(module);
         self.stack = Some(stack);
@@ -1144,6 +1203,30 @@ where
         identifier: CallContractIdentifier,
         entry_point_name: &str,
         args: RuntimeArgs,
+    ) -> Result<CLValue, Error> {
+        if !self.context.is_observed() {
+            return self.execute_contract_inner(identifier, entry_point_name, args);
+        }
+
+        let kind = FrameKind::Unresolved {
+            entry_point: entry_point_name.to_string(),
+        };
+        let gas_counter = self.context.gas_counter();
+        self.context
+            .observe(|observer| observer.enter_frame(&kind, gas_counter));
+        let result = self.execute_contract_inner(identifier, entry_point_name, args);
+        let gas_counter = self.context.gas_counter();
+        let error = result.as_ref().err().map(ToString::to_string);
+        self.context
+            .observe(|observer| observer.exit_frame(gas_counter, error));
+        result
+    }
+
+    fn execute_contract_inner(
+        &mut self,
+        identifier: CallContractIdentifier,
+        entry_point_name: &str,
+        args: RuntimeArgs,
     ) -> Result<CLValue, Error> {
         let (contract, contract_hash, contract_package) = match identifier {
             CallContractIdentifier::Contract { contract_hash } => {
@@ -1281,6 +1364,8 @@ where
                     contract_hash,
                 ),
             };
+            self.context
+                .observe(|observer| observer.resolve_frame(&call_stack_element));
             stack.push(call_stack_element)?;
 
             stack
@@ -1362,8 +1447,11 @@ where
             context_args,
         );
         let protocol_version = self.context.protocol_version();
-        let (instance, memory) =
-            utils::instance_and_memory(module.clone(), protocol_version, &self.config)?;
+        let (instance, memory) = utils::instance_and_memory(
+            self.module_to_instantiate(&module),
+            protocol_version,
+            &self.config,
+        )?;
         let runtime = &mut Runtime::new_invocation_runtime(self, context, module, memory, stack);
 
         let result = instance.invoke_export(entry_point.name(), &[], runtime);
@@ -1378,6 +1466,11 @@ where
             *transfers = runtime.context.transfers().to_owned();
         }
 
+        {
+            let events = self.context.events_mut();
+            *events = runtime.context.events().to_owned();
+        }
+
         let error = match result {
             Err(error) => error,
             // If `Ok` and the `host_buffer` is `None`, the contract's execution succeeded but did
@@ -1656,6 +1749,7 @@ where
         contract_package_hash: ContractPackageHash,
         entry_points: EntryPoints,
         mut named_keys: NamedKeys,
+        schema: Option<ContractSchema>,
         output_ptr: u32,
         output_size: usize,
         bytes_written_ptr: u32,
@@ -1675,6 +1769,12 @@ where
             return Err(Error::LockedContract(contract_package_hash));
         }
 
+        if let Some(schema) = schema.as_ref() {
+            if let Err(error) = schema.validate(&entry_points) {
+                return Ok(Err(error.into()));
+            }
+        }
+
         let contract_wasm_hash = self.context.new_hash_address()?;
         let contract_wasm = {
             let module_bytes = self.get_module_from_entry_points(&entry_points)?;
@@ -1712,6 +1812,12 @@ where
             .metered_write_gs_unsafe(Key::Hash(contract_hash), contract)?;
         self.context
             .metered_write_gs_unsafe(contract_package_hash, contract_package)?;
+        if let Some(schema) = schema {
+            self.context.metered_write_gs_unsafe(
+                Key::ContractSchema(contract_hash),
+                CLValue::from_t(schema)?,
+            )?;
+        }
 
         // return contract key to caller
         {
@@ -2776,6 +2882,8 @@ where
     {
         let cost = host_function.calculate_gas_cost(weights);
         self.gas(cost)?;
+        self.context
+            .observe(|observer| observer.record_host_function_cost(cost));
         Ok(())
     }
 
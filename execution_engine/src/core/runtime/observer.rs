@@ -0,0 +1,176 @@
+//! Opt-in observation of the execution of a deploy as it happens.
+//!
+//! The executor and the runtime report the frames and host function calls they enter and exit, the
+//! gas they charge and the global state they access to the single [`ExecutionObserver`] of the
+//! execution, if any.  Tracing and gas profiling are both implemented as observers.
+use parity_wasm::elements::Module;
+use wasmi::RuntimeValue;
+
+use casper_types::{system::CallStackElement, Gas, Key};
+
+use super::trace::FrameKind;
+use crate::shared::opcode_costs::OpcodeCosts;
+
+/// An observer of an execution.
+///
+/// A single observer is shared by all the runtimes and runtime contexts of an execution.  Every
+/// method does nothing by default, so an observer only needs to implement those it is interested
+/// in.
+pub(crate) trait ExecutionObserver {
+    /// Called when a frame is entered, nested in the innermost open frame or host function call if
+    /// any.
+    fn enter_frame(&mut self, _kind: &FrameKind, _gas_counter: Gas) {}
+
+    /// Called once the contract called by the innermost open frame has been resolved.
+    fn resolve_frame(&mut self, _call_stack_element: &CallStackElement) {}
+
+    /// Called with the Wasm module about to be executed by the innermost open frame.
+    ///
+    /// Returns the module to execute in its place, if any.  Such a module must never be stored.
+    fn prepare_module(&mut self, _module: &Module, _opcode_costs: &OpcodeCosts) -> Option<Module> {
+        None
+    }
+
+    /// Called when the innermost open frame is exited, with the error it failed with if any.
+    fn exit_frame(&mut self, _gas_counter: Gas, _error: Option<String>) {}
+
+    /// Called when a host function is called from the innermost open frame.
+    fn enter_host_function(
+        &mut self,
+        _name: &'static str,
+        _args: &[RuntimeValue],
+        _gas_counter: Gas,
+    ) {
+    }
+
+    /// Called when the innermost open host function call returns.
+    fn exit_host_function(&mut self, _gas_counter: Gas) {}
+
+    /// Returns the gas to charge for the metered block of Wasm code identified by the argument of
+    /// a call of the gas function.
+    fn metered_block_cost(&self, gas_arg: u32) -> Gas {
+        Gas::new(gas_arg.into())
+    }
+
+    /// Called once the gas for the metered block identified by `gas_arg` has been charged.
+    fn record_metered_block(&mut self, _gas_arg: u32) {}
+
+    /// Called once the gas for the innermost open host function call itself has been charged.
+    fn record_host_function_cost(&mut self, _cost: Gas) {}
+
+    /// Called once the gas for writing `bytes_count` bytes to global state has been charged.
+    fn record_storage(&mut self, _bytes_count: usize, _cost: Gas) {}
+
+    /// Called once the gas for a call of a system contract entry point has been charged.
+    fn record_system_contract_call(&mut self, _cost: Gas) {}
+
+    /// Called when the value under `key` is read from global state.
+    fn record_read(&mut self, _key: &Key) {}
+
+    /// Called when a value is written under `key` in global state.
+    fn record_write(&mut self, _key: &Key) {}
+
+    /// Called when a value is added to the value under `key` in global state.
+    fn record_add(&mut self, _key: &Key) {}
+}
+
+/// A frame or host function call which has been entered but not yet exited, along with the state
+/// an observer keeps for it.
+pub(crate) enum OpenNode<F, H> {
+    Frame(F),
+    HostFunction(H),
+}
+
+/// The frames and host function calls which have been entered but not yet exited, innermost last.
+pub(crate) struct OpenNodes<F, H>(Vec<OpenNode<F, H>>);
+
+impl<F, H> Default for OpenNodes<F, H> {
+    fn default() -> Self {
+        OpenNodes(vec![])
+    }
+}
+
+impl<F, H> OpenNodes<F, H> {
+    /// Opens a new frame.
+    pub(crate) fn push_frame(&mut self, frame: F) {
+        self.0.push(OpenNode::Frame(frame));
+    }
+
+    /// Opens a new host function call.
+    pub(crate) fn push_host_function(&mut self, host_function: H) {
+        self.0.push(OpenNode::HostFunction(host_function));
+    }
+
+    /// Closes the innermost open node, which is expected to be a frame.
+    pub(crate) fn pop_frame(&mut self) -> Option<F> {
+        match self.0.pop() {
+            Some(OpenNode::Frame(frame)) => Some(frame),
+            Some(node) => {
+                debug_assert!(false, "exiting a frame while in a host function call");
+                self.0.push(node);
+                None
+            }
+            None => {
+                debug_assert!(false, "exiting a frame which was never entered");
+                None
+            }
+        }
+    }
+
+    /// Closes the innermost open node, which is expected to be a host function call.
+    pub(crate) fn pop_host_function(&mut self) -> Option<H> {
+        match self.0.pop() {
+            Some(OpenNode::HostFunction(host_function)) => Some(host_function),
+            Some(node) => {
+                debug_assert!(false, "exiting a host function call while in a frame");
+                self.0.push(node);
+                None
+            }
+            None => {
+                debug_assert!(
+                    false,
+                    "exiting a host function call which was never entered"
+                );
+                None
+            }
+        }
+    }
+
+    /// Returns the innermost open node.
+    pub(crate) fn last(&self) -> Option<&OpenNode<F, H>> {
+        self.0.last()
+    }
+
+    /// Returns the innermost open node mutably.
+    pub(crate) fn last_mut(&mut self) -> Option<&mut OpenNode<F, H>> {
+        self.0.last_mut()
+    }
+
+    /// Returns the open node enclosing the innermost one.
+    pub(crate) fn enclosing(&self) -> Option<&OpenNode<F, H>> {
+        self.0.len().checked_sub(2).map(|index| &self.0[index])
+    }
+
+    /// Returns the innermost open frame.
+    pub(crate) fn innermost_frame(&self) -> Option<&F> {
+        self.0.iter().rev().find_map(|node| match node {
+            OpenNode::Frame(frame) => Some(frame),
+            OpenNode::HostFunction(_) => None,
+        })
+    }
+
+    /// Returns the number of open nodes.
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no node is open.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Closes all open nodes.
+    pub(crate) fn clear(&mut self) {
+        self.0.clear()
+    }
+}
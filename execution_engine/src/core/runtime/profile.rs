@@ -0,0 +1,597 @@
+//! Opt-in gas profiling of the execution of a deploy.
+//!
+//! When enabled, every gas charge made during the execution is attributed to the stack of frames
+//! (the payment and session code of the deploy, and every stored contract or stored session entry
+//! point called from them) and host function calls it was made in, and to one of the following:
+//!
+//! * a class of Wasm opcodes, as defined by the [`OpcodeCosts`] table,
+//! * the own cost of a host function, as defined by the
+//!   [`HostFunctionCosts`](crate::shared::host_function_costs::HostFunctionCosts) table,
+//! * the bytes written to global state, as charged via the
+//!   [`StorageCosts`](crate::shared::storage_costs::StorageCosts) table, or
+//! * a call of a system contract entry point.
+//!
+//! Wasm code is charged a whole metered block at a time, so the gas charged for a metered block is
+//! split across opcode classes in proportion to the costs of the instructions it covers.  The
+//! instructions covered by each metered block are determined statically, and the split is an
+//! estimate; the total charged for each metered block is exact.
+//!
+//! The profile is recorded by an `ExecutionProfiler` observing the execution.
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+};
+
+use parity_wasm::elements::{External, Instruction, Module};
+use serde::{Deserialize, Serialize};
+use wasmi::RuntimeValue;
+
+use casper_types::{system::CallStackElement, Gas, U512};
+
+use super::{
+    observer::{ExecutionObserver, OpenNode, OpenNodes},
+    trace::FrameKind,
+};
+use crate::shared::{
+    opcode_costs::{OpcodeClass, OpcodeCosts},
+    wasm_prep::{DEFAULT_GAS_MODULE_NAME, INTERNAL_GAS_FUNCTION_NAME},
+};
+
+/// The bit set in the argument of the gas function by metering calls tagged for profiling.
+const METERED_BLOCK_TAG: u32 = 1 << 31;
+
+/// The gas profile of the execution of a single deploy.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GasProfile {
+    opcodes: BTreeMap<String, U512>,
+    host_functions: BTreeMap<String, HostFunctionProfile>,
+    storage: StorageProfile,
+    system_contract_calls: U512,
+    entry_points: BTreeMap<String, EntryPointProfile>,
+    stacks: BTreeMap<String, U512>,
+}
+
+impl GasProfile {
+    /// Returns the gas charged for executing Wasm code, by opcode class.
+    pub fn opcodes(&self) -> &BTreeMap<String, U512> {
+        &self.opcodes
+    }
+
+    /// Returns the number of calls and own gas cost of each called host function, by the name
+    /// under which it is imported by Wasm modules.
+    pub fn host_functions(&self) -> &BTreeMap<String, HostFunctionProfile> {
+        &self.host_functions
+    }
+
+    /// Returns the bytes written to global state and the gas charged for them.
+    pub fn storage(&self) -> &StorageProfile {
+        &self.storage
+    }
+
+    /// Returns the gas charged for calls of system contract entry points.
+    pub fn system_contract_calls(&self) -> U512 {
+        self.system_contract_calls
+    }
+
+    /// Returns the number of calls and gas used by each executed frame, by frame name.
+    ///
+    /// Frames are named `payment` and `session` for the payment and session code of the deploy,
+    /// and `<contract hash>::<entry point>` for stored contracts and stored session code.
+    pub fn entry_points(&self) -> &BTreeMap<String, EntryPointProfile> {
+        &self.entry_points
+    }
+
+    /// Returns the total gas attributed by the profile.
+    pub fn total_gas(&self) -> U512 {
+        self.stacks
+            .values()
+            .fold(U512::zero(), |total, gas| total.saturating_add(*gas))
+    }
+
+    /// Returns the profile in the folded stacks format, as consumed by flamegraph tools.
+    ///
+    /// Each line holds a `;`-separated stack of frames and host function calls, ending in the
+    /// opcode class, host function, storage or system contract call charged, followed by the gas
+    /// charged.
+    pub fn to_folded_stacks(&self) -> String {
+        let mut folded_stacks = String::new();
+        for (stack, gas) in &self.stacks {
+            let _ = writeln!(folded_stacks, "{} {}", stack, gas);
+        }
+        folded_stacks
+    }
+}
+
+/// The profile of the calls of a single host function.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HostFunctionProfile {
+    calls: u64,
+    gas: U512,
+}
+
+impl HostFunctionProfile {
+    /// Returns the number of calls of the host function.
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// Returns the gas charged for the host function itself, excluding any storage costs and the
+    /// gas used by any nested frames.
+    pub fn gas(&self) -> U512 {
+        self.gas
+    }
+}
+
+/// The profile of the bytes written to global state.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StorageProfile {
+    bytes: u64,
+    gas: U512,
+}
+
+impl StorageProfile {
+    /// Returns the number of bytes charged for.
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Returns the gas charged for the bytes written.
+    pub fn gas(&self) -> U512 {
+        self.gas
+    }
+}
+
+/// The profile of the executions of a single frame.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EntryPointProfile {
+    calls: u64,
+    gas: U512,
+}
+
+impl EntryPointProfile {
+    /// Returns the number of executions of the frame.
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// Returns the gas used by the executions of the frame, including the gas used by any nested
+    /// frames.
+    pub fn gas(&self) -> U512 {
+        self.gas
+    }
+}
+
+/// A metered block of Wasm code, with its cost split across opcode classes.
+struct MeteredBlock {
+    cost: u32,
+    classes: Vec<(OpcodeClass, u32)>,
+}
+
+/// The metered blocks of a Wasm module tagged for profiling, indexed by their tag.
+struct MeteredBlocks(Vec<MeteredBlock>);
+
+/// Returns a copy of the given instrumented module in which the argument of every metering call is
+/// replaced by a tag identifying the metered block, along with the metered blocks by tag.
+///
+/// The tagged module charges exactly the same gas as the given one when executed by a profiling
+/// runtime, but must never be stored.
+fn tag_metered_blocks(mut module: Module, opcode_costs: &OpcodeCosts) -> (Module, MeteredBlocks) {
+    let mut blocks = vec![];
+    let gas_function_index = match gas_function_index(&module) {
+        Some(index) => index,
+        None => return (module, MeteredBlocks(blocks)),
+    };
+
+    if let Some(code_section) = module.code_section_mut() {
+        for body in code_section.bodies_mut() {
+            let instructions = body.code_mut().elements_mut();
+            let mut estimates: Vec<BTreeMap<OpcodeClass, u64>> = vec![];
+            let mut current_block = None;
+            let mut enclosing_blocks = vec![];
+            let mut cursor = 0;
+            while cursor < instructions.len() {
+                let next = instructions.get(cursor + 1);
+                if let (Instruction::I32Const(cost), Some(Instruction::Call(index))) =
+                    (&instructions[cursor], next)
+                {
+                    let tag = blocks.len() as u32;
+                    if *index == gas_function_index && tag & METERED_BLOCK_TAG == 0 {
+                        current_block = Some(estimates.len());
+                        estimates.push(BTreeMap::new());
+                        blocks.push(MeteredBlock {
+                            cost: *cost as u32,
+                            classes: vec![],
+                        });
+                        instructions[cursor] =
+                            Instruction::I32Const((tag | METERED_BLOCK_TAG) as i32);
+                        cursor += 2;
+                        continue;
+                    }
+                }
+
+                let instruction = &instructions[cursor];
+                if let (Some(block), Some((class, cost))) =
+                    (current_block, opcode_costs.instruction_class(instruction))
+                {
+                    *estimates[block].entry(class).or_default() += u64::from(cost);
+                }
+                // Instructions following a nested control block are covered by the metered block
+                // enclosing it, unless a new metered block begins.
+                match instruction {
+                    Instruction::Block(_) | Instruction::Loop(_) | Instruction::If(_) => {
+                        enclosing_blocks.push(current_block)
+                    }
+                    Instruction::End => {
+                        if let Some(block) = enclosing_blocks.pop() {
+                            current_block = block;
+                        }
+                    }
+                    _ => {}
+                }
+                cursor += 1;
+            }
+
+            let first_block = blocks.len() - estimates.len();
+            for (block, estimate) in blocks[first_block..].iter_mut().zip(estimates) {
+                block.classes = split_cost(block.cost, estimate);
+            }
+        }
+    }
+
+    (module, MeteredBlocks(blocks))
+}
+
+/// Returns the index of the gas function imported by the given instrumented module.
+fn gas_function_index(module: &Module) -> Option<u32> {
+    module
+        .import_section()?
+        .entries()
+        .iter()
+        .filter(|entry| matches!(entry.external(), External::Function(_)))
+        .position(|entry| {
+            entry.module() == DEFAULT_GAS_MODULE_NAME && entry.field() == INTERNAL_GAS_FUNCTION_NAME
+        })
+        .map(|index| index as u32)
+}
+
+/// Splits `cost` across opcode classes in proportion to the estimated costs of each class.
+fn split_cost(cost: u32, estimate: BTreeMap<OpcodeClass, u64>) -> Vec<(OpcodeClass, u32)> {
+    let estimated_total: u64 = estimate.values().sum();
+    if estimated_total == 0 {
+        // Every metered block covers at least one branching instruction.
+        return vec![(OpcodeClass::ControlFlow, cost)];
+    }
+
+    let mut remaining = cost;
+    let mut classes: Vec<(OpcodeClass, u32)> = estimate
+        .into_iter()
+        .map(|(class, estimated_cost)| {
+            let share = (u64::from(cost) * estimated_cost / estimated_total) as u32;
+            remaining -= share;
+            (class, share)
+        })
+        .collect();
+    // Rounding leaves at most one unit per class, which is added to the costliest class.
+    if let Some((_class, share)) = classes.iter_mut().max_by_key(|(_class, share)| *share) {
+        *share += remaining;
+    }
+    classes
+}
+
+/// The part of an execution a gas charge is attributed to, within its stack.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Charge {
+    Opcodes(OpcodeClass),
+    HostFunction,
+    Storage,
+    SystemContractCall,
+}
+
+/// A frame which has been entered but not yet exited.
+struct OpenFrame {
+    name: String,
+    stack: usize,
+    start_gas: Gas,
+    metered_blocks: Option<MeteredBlocks>,
+}
+
+/// A host function call which has been entered but not yet exited.
+struct OpenHostFunction {
+    name: &'static str,
+    stack: usize,
+}
+
+/// Returns the index of the stack of the given open node.
+fn stack_of(node: &OpenNode<OpenFrame, OpenHostFunction>) -> usize {
+    match node {
+        OpenNode::Frame(OpenFrame { stack, .. })
+        | OpenNode::HostFunction(OpenHostFunction { stack, .. }) => *stack,
+    }
+}
+
+/// Returns the name of a frame of the given kind in the profile.
+fn frame_name(kind: &FrameKind) -> String {
+    match kind {
+        FrameKind::Payment => "payment".to_string(),
+        FrameKind::Session => "session".to_string(),
+        FrameKind::StoredSession {
+            contract_hash,
+            entry_point,
+            ..
+        }
+        | FrameKind::StoredContract {
+            contract_hash,
+            entry_point,
+            ..
+        } => format!("{}::{}", contract_hash.to_formatted_string(), entry_point),
+        FrameKind::Unresolved { entry_point } => entry_point.clone(),
+    }
+}
+
+/// Records the gas profile of an execution as it happens.
+#[derive(Default)]
+pub(crate) struct ExecutionProfiler {
+    open_nodes: OpenNodes<OpenFrame, OpenHostFunction>,
+    /// The distinct stacks of open nodes seen so far, along with their index in `stacks`.
+    stacks: Vec<String>,
+    stack_indices: HashMap<String, usize>,
+    charges: BTreeMap<(usize, Charge), U512>,
+    host_functions: BTreeMap<&'static str, HostFunctionProfile>,
+    storage: StorageProfile,
+    entry_points: BTreeMap<String, EntryPointProfile>,
+}
+
+impl ExecutionProfiler {
+    /// Returns the profile recorded so far, leaving the profiler empty.
+    pub(crate) fn take_profile(&mut self) -> GasProfile {
+        debug_assert!(self.open_nodes.is_empty(), "taking profile of open frames");
+        let profiler = std::mem::take(self);
+
+        let mut profile = GasProfile {
+            storage: profiler.storage,
+            entry_points: profiler.entry_points,
+            host_functions: profiler
+                .host_functions
+                .into_iter()
+                .map(|(name, host_function)| (name.to_string(), host_function))
+                .collect(),
+            ..GasProfile::default()
+        };
+        for ((stack, charge), gas) in profiler.charges {
+            let leaf = match charge {
+                Charge::Opcodes(class) => {
+                    let opcodes = profile.opcodes.entry(class.name().to_string()).or_default();
+                    *opcodes = opcodes.saturating_add(gas);
+                    format!("{};wasm:{}", profiler.stacks[stack], class.name())
+                }
+                Charge::HostFunction => profiler.stacks[stack].clone(),
+                Charge::Storage => format!("{};storage", profiler.stacks[stack]),
+                Charge::SystemContractCall => {
+                    profile.system_contract_calls =
+                        profile.system_contract_calls.saturating_add(gas);
+                    format!("{};system_contract_call", profiler.stacks[stack])
+                }
+            };
+            let _ = profile.stacks.insert(leaf, gas);
+        }
+        profile
+    }
+
+    fn metered_block(&self, gas_arg: u32) -> Option<&MeteredBlock> {
+        if gas_arg & METERED_BLOCK_TAG == 0 {
+            return None;
+        }
+        let metered_blocks = self.open_nodes.innermost_frame()?.metered_blocks.as_ref()?;
+        metered_blocks
+            .0
+            .get((gas_arg & !METERED_BLOCK_TAG) as usize)
+    }
+
+    fn record(&mut self, charge: Charge, cost: Gas) {
+        // Charges outside of any frame aren't part of the profile.
+        if let Some(node) = self.open_nodes.last() {
+            let gas = self.charges.entry((stack_of(node), charge)).or_default();
+            *gas = gas.saturating_add(cost.value());
+        }
+    }
+
+    /// Returns the index of the stack made of the open nodes followed by `name`.
+    fn nested_stack(&mut self, name: &str) -> usize {
+        let stack = match self.open_nodes.last() {
+            Some(node) => format!("{};{}", self.stacks[stack_of(node)], name),
+            None => name.to_string(),
+        };
+        self.intern_stack(stack)
+    }
+
+    /// Returns the index of the stack made of the open nodes except the innermost, followed by
+    /// `name`.
+    fn enclosing_stack(&mut self, name: &str) -> usize {
+        let stack = match self.open_nodes.enclosing() {
+            Some(node) => format!("{};{}", self.stacks[stack_of(node)], name),
+            None => name.to_string(),
+        };
+        self.intern_stack(stack)
+    }
+
+    fn intern_stack(&mut self, stack: String) -> usize {
+        if let Some(index) = self.stack_indices.get(&stack) {
+            return *index;
+        }
+        let index = self.stacks.len();
+        self.stacks.push(stack.clone());
+        let _ = self.stack_indices.insert(stack, index);
+        index
+    }
+}
+
+impl ExecutionObserver for ExecutionProfiler {
+    fn enter_frame(&mut self, kind: &FrameKind, gas_counter: Gas) {
+        let name = frame_name(kind);
+        let stack = self.nested_stack(&name);
+        self.open_nodes.push_frame(OpenFrame {
+            name,
+            stack,
+            start_gas: gas_counter,
+            metered_blocks: None,
+        });
+    }
+
+    /// Prefixes the name of the innermost open frame with the hash of the called contract.
+    fn resolve_frame(&mut self, call_stack_element: &CallStackElement) {
+        let contract_hash = match call_stack_element {
+            CallStackElement::StoredSession { contract_hash, .. }
+            | CallStackElement::StoredContract { contract_hash, .. } => *contract_hash,
+            CallStackElement::Session { .. } => return,
+        };
+        let name = match self.open_nodes.last() {
+            Some(OpenNode::Frame(OpenFrame { name, .. })) => {
+                format!("{}::{}", contract_hash.to_formatted_string(), name)
+            }
+            _ => return,
+        };
+        let stack = self.enclosing_stack(&name);
+        if let Some(OpenNode::Frame(frame)) = self.open_nodes.last_mut() {
+            frame.name = name;
+            frame.stack = stack;
+        }
+    }
+
+    /// Returns a copy of `module` with its metered blocks tagged, keeping the metered blocks for
+    /// the innermost open frame.
+    fn prepare_module(&mut self, module: &Module, opcode_costs: &OpcodeCosts) -> Option<Module> {
+        let (tagged_module, blocks) = tag_metered_blocks(module.clone(), opcode_costs);
+        if let Some(OpenNode::Frame(frame)) = self.open_nodes.last_mut() {
+            frame.metered_blocks = Some(blocks);
+        }
+        Some(tagged_module)
+    }
+
+    fn exit_frame(&mut self, gas_counter: Gas, _error: Option<String>) {
+        if let Some(OpenFrame {
+            name, start_gas, ..
+        }) = self.open_nodes.pop_frame()
+        {
+            let entry_point = self.entry_points.entry(name).or_default();
+            entry_point.calls += 1;
+            entry_point.gas = entry_point
+                .gas
+                .saturating_add(gas_counter.value().saturating_sub(start_gas.value()));
+        }
+    }
+
+    fn enter_host_function(
+        &mut self,
+        name: &'static str,
+        _args: &[RuntimeValue],
+        _gas_counter: Gas,
+    ) {
+        let stack = self.nested_stack(&format!("host:{}", name));
+        self.open_nodes
+            .push_host_function(OpenHostFunction { name, stack });
+        self.host_functions.entry(name).or_default().calls += 1;
+    }
+
+    fn exit_host_function(&mut self, _gas_counter: Gas) {
+        let _ = self.open_nodes.pop_host_function();
+    }
+
+    fn metered_block_cost(&self, gas_arg: u32) -> Gas {
+        let cost = self
+            .metered_block(gas_arg)
+            .map_or(gas_arg, |metered_block| metered_block.cost);
+        Gas::new(cost.into())
+    }
+
+    fn record_metered_block(&mut self, gas_arg: u32) {
+        let classes = match self.metered_block(gas_arg) {
+            Some(metered_block) => metered_block.classes.clone(),
+            // Untagged calls are made by the function instrumenting `memory.grow`.
+            None => vec![(OpcodeClass::GrowMemory, gas_arg)],
+        };
+        for (class, cost) in classes {
+            self.record(Charge::Opcodes(class), Gas::new(cost.into()));
+        }
+    }
+
+    fn record_host_function_cost(&mut self, cost: Gas) {
+        if let Some(OpenNode::HostFunction(OpenHostFunction { name, .. })) = self.open_nodes.last()
+        {
+            let host_function = self.host_functions.entry(name).or_default();
+            host_function.gas = host_function.gas.saturating_add(cost.value());
+        }
+        self.record(Charge::HostFunction, cost);
+    }
+
+    fn record_storage(&mut self, bytes_count: usize, cost: Gas) {
+        self.storage.bytes = self.storage.bytes.saturating_add(bytes_count as u64);
+        self.storage.gas = self.storage.gas.saturating_add(cost.value());
+        self.record(Charge::Storage, cost);
+    }
+
+    fn record_system_contract_call(&mut self, cost: Gas) {
+        self.record(Charge::SystemContractCall, cost);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_split_cost_in_proportion_to_estimate() {
+        let estimate = vec![
+            (OpcodeClass::Local, 300),
+            (OpcodeClass::Add, 100),
+            (OpcodeClass::ControlFlow, 200),
+        ]
+        .into_iter()
+        .collect();
+        let classes = split_cost(601, estimate);
+        assert_eq!(
+            classes,
+            vec![
+                (OpcodeClass::Add, 100),
+                (OpcodeClass::Local, 301),
+                (OpcodeClass::ControlFlow, 200),
+            ]
+        );
+        assert_eq!(classes.iter().map(|(_class, cost)| cost).sum::<u32>(), 601);
+    }
+
+    #[test]
+    fn should_attribute_charges_to_stacks() {
+        let mut profiler = ExecutionProfiler::default();
+        profiler.enter_frame(&FrameKind::Session, Gas::new(U512::from(0)));
+        profiler.record_metered_block(5);
+        profiler.enter_host_function("casper_write", &[], Gas::new(U512::from(5)));
+        profiler.record_host_function_cost(Gas::new(U512::from(10)));
+        profiler.record_storage(4, Gas::new(U512::from(20)));
+        profiler.exit_host_function(Gas::new(U512::from(35)));
+        profiler.enter_host_function("casper_write", &[], Gas::new(U512::from(35)));
+        profiler.record_host_function_cost(Gas::new(U512::from(10)));
+        profiler.exit_host_function(Gas::new(U512::from(45)));
+        profiler.exit_frame(Gas::new(U512::from(45)), None);
+
+        let profile = profiler.take_profile();
+        assert_eq!(profile.total_gas(), U512::from(45));
+        assert_eq!(profile.opcodes()["grow_memory"], U512::from(5));
+        assert_eq!(profile.host_functions()["casper_write"].calls(), 2);
+        assert_eq!(
+            profile.host_functions()["casper_write"].gas(),
+            U512::from(20)
+        );
+        assert_eq!(profile.storage().bytes(), 4);
+        assert_eq!(profile.entry_points()["session"].gas(), U512::from(45));
+        assert_eq!(
+            profile.to_folded_stacks(),
+            "session;host:casper_write 20\n\
+             session;host:casper_write;storage 20\n\
+             session;wasm:grow_memory 5\n"
+        );
+    }
+}
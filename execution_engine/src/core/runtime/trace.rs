@@ -0,0 +1,510 @@
+//! Opt-in tracing of the execution of a deploy.
+//!
+//! When enabled, every frame of the execution (the payment and session code of the deploy, and
+//! every stored contract or stored session called from them) is recorded as a [`TraceFrame`],
+//! along with the host functions it called, the global state keys it accessed and the gas it
+//! used.  Nested calls appear as [`TraceEvent::Call`] events of the calling frame, in the order
+//! they were made.
+//!
+//! The trace is recorded by an `ExecutionTracer` observing the execution.  At most
+//! [`MAX_TRACE_EVENTS`] events are recorded, nested at most [`MAX_TRACE_DEPTH`] frames and host
+//! function calls deep.  Anything beyond that is left out, and the trace is marked as truncated.
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use wasmi::RuntimeValue;
+
+use casper_types::{system::CallStackElement, ContractHash, ContractPackageHash, Gas, Key, U512};
+
+use super::observer::{ExecutionObserver, OpenNode, OpenNodes};
+
+/// The maximum number of events recorded in a trace, including frames and host function calls.
+pub const MAX_TRACE_EVENTS: usize = 10_000;
+
+/// The maximum depth of nested frames and host function calls recorded in a trace.
+pub const MAX_TRACE_DEPTH: usize = 64;
+
+/// The trace of the execution of a single deploy.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExecutionTrace {
+    frames: Vec<TraceFrame>,
+    truncated: bool,
+}
+
+impl ExecutionTrace {
+    /// Returns the outermost frames of the execution, in the order they were executed.
+    pub fn frames(&self) -> &[TraceFrame] {
+        &self.frames
+    }
+
+    /// Returns `true` if events were left out of the trace because it exceeded
+    /// [`MAX_TRACE_EVENTS`] or [`MAX_TRACE_DEPTH`].
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Returns `true` if nothing was traced.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+/// The code executed by a [`TraceFrame`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum FrameKind {
+    /// The payment code of the deploy.
+    Payment,
+    /// The session code of the deploy.
+    Session,
+    /// Stored session code, executed in the context of the calling account.
+    StoredSession {
+        /// The hash of the contract package holding the stored session code.
+        contract_package_hash: ContractPackageHash,
+        /// The hash of the contract holding the stored session code.
+        contract_hash: ContractHash,
+        /// The name of the called entry point.
+        entry_point: String,
+    },
+    /// A stored contract, executed in its own context.
+    StoredContract {
+        /// The hash of the contract package holding the contract.
+        contract_package_hash: ContractPackageHash,
+        /// The hash of the contract.
+        contract_hash: ContractHash,
+        /// The name of the called entry point.
+        entry_point: String,
+    },
+    /// A call of a stored contract or stored session code which failed before the called contract
+    /// was resolved.
+    Unresolved {
+        /// The name of the called entry point.
+        entry_point: String,
+    },
+}
+
+/// A single frame of the execution, i.e. the execution of one piece of code with its own entry on
+/// the runtime call stack.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TraceFrame {
+    kind: FrameKind,
+    gas_used: U512,
+    error: Option<String>,
+    events: Vec<TraceEvent>,
+}
+
+impl TraceFrame {
+    /// Returns the code executed by this frame.
+    pub fn kind(&self) -> &FrameKind {
+        &self.kind
+    }
+
+    /// Returns the gas used by this frame, including the gas used by any nested frames.
+    pub fn gas_used(&self) -> U512 {
+        self.gas_used
+    }
+
+    /// Returns the error the frame failed with, if any.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Returns the events recorded within this frame, in the order they happened.
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Returns the frames called directly from this frame, including those called from within its
+    /// host function calls.
+    pub fn calls(&self) -> Vec<&TraceFrame> {
+        let mut calls = vec![];
+        collect_calls(&self.events, &mut calls);
+        calls
+    }
+}
+
+fn collect_calls<'a>(events: &'a [TraceEvent], calls: &mut Vec<&'a TraceFrame>) {
+    for event in events {
+        match event {
+            TraceEvent::Call(frame) => calls.push(frame),
+            TraceEvent::HostFunction(host_function_call) => {
+                collect_calls(&host_function_call.events, calls)
+            }
+            TraceEvent::Read { .. } | TraceEvent::Write { .. } | TraceEvent::Add { .. } => {}
+        }
+    }
+}
+
+/// A call of a host function.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HostFunctionCall {
+    name: String,
+    args: Vec<i64>,
+    gas_used: U512,
+    events: Vec<TraceEvent>,
+}
+
+impl HostFunctionCall {
+    /// Returns the name under which the host function is imported by Wasm modules.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the raw arguments passed to the host function.
+    pub fn args(&self) -> &[i64] {
+        &self.args
+    }
+
+    /// Returns the gas used by the call, including the gas used by any nested frames.
+    pub fn gas_used(&self) -> U512 {
+        self.gas_used
+    }
+
+    /// Returns the events recorded within this call, in the order they happened.
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+}
+
+/// An event recorded within a [`TraceFrame`] or a [`HostFunctionCall`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum TraceEvent {
+    /// A host function was called.
+    HostFunction(HostFunctionCall),
+    /// A value was read from global state.
+    Read {
+        /// The formatted key read.
+        key: String,
+    },
+    /// A value was written to global state.
+    Write {
+        /// The formatted key written.
+        key: String,
+    },
+    /// A value was added to a value in global state.
+    Add {
+        /// The formatted key added to.
+        key: String,
+    },
+    /// A stored contract or stored session code was called.
+    Call(TraceFrame),
+}
+
+/// A frame which has been entered but not yet exited.
+struct OpenFrame {
+    kind: FrameKind,
+    start_gas: Gas,
+    events: Vec<TraceEvent>,
+}
+
+/// A host function call which has been entered but not yet exited.
+struct OpenHostFunction {
+    name: &'static str,
+    args: Vec<i64>,
+    start_gas: Gas,
+    events: Vec<TraceEvent>,
+}
+
+/// Records the trace of an execution as it happens.
+#[derive(Default)]
+pub(crate) struct ExecutionTracer {
+    frames: Vec<TraceFrame>,
+    open_nodes: OpenNodes<OpenFrame, OpenHostFunction>,
+    /// The number of events recorded so far.
+    event_count: usize,
+    /// The number of open frames and host function calls which are left out of the trace.
+    skipped_depth: usize,
+    truncated: bool,
+}
+
+impl ExecutionTracer {
+    /// Returns the trace recorded so far, leaving the tracer empty.
+    pub(crate) fn take_trace(&mut self) -> ExecutionTrace {
+        debug_assert!(
+            self.open_nodes.is_empty() && self.skipped_depth == 0,
+            "taking trace of open frames"
+        );
+        self.open_nodes.clear();
+        self.event_count = 0;
+        self.skipped_depth = 0;
+        ExecutionTrace {
+            frames: std::mem::take(&mut self.frames),
+            truncated: std::mem::take(&mut self.truncated),
+        }
+    }
+
+    /// Counts a new event, or marks the trace as truncated if the event has to be left out.
+    fn admit_event(&mut self) -> bool {
+        if self.skipped_depth > 0 || self.event_count >= MAX_TRACE_EVENTS {
+            self.truncated = true;
+            return false;
+        }
+        self.event_count += 1;
+        true
+    }
+
+    /// Counts a new frame or host function call, or marks it as left out of the trace along with
+    /// everything nested in it.
+    fn admit_node(&mut self) -> bool {
+        if self.open_nodes.len() >= MAX_TRACE_DEPTH || !self.admit_event() {
+            self.truncated = true;
+            self.skipped_depth += 1;
+            return false;
+        }
+        true
+    }
+
+    /// Closes a frame or host function call which was left out of the trace, returning `false` if
+    /// the innermost one was recorded.
+    fn exit_skipped_node(&mut self) -> bool {
+        if self.skipped_depth == 0 {
+            return false;
+        }
+        self.skipped_depth -= 1;
+        true
+    }
+
+    fn record(&mut self, event: TraceEvent) {
+        // Events outside of any frame, e.g. reads made while preparing the execution, aren't part
+        // of the trace.
+        if self.open_nodes.is_empty() || !self.admit_event() {
+            return;
+        }
+        self.attach(event);
+    }
+
+    /// Adds the event to the innermost open frame or host function call.
+    fn attach(&mut self, event: TraceEvent) {
+        match self.open_nodes.last_mut() {
+            Some(OpenNode::Frame(frame)) => frame.events.push(event),
+            Some(OpenNode::HostFunction(host_function)) => host_function.events.push(event),
+            None => {}
+        }
+    }
+}
+
+impl ExecutionObserver for ExecutionTracer {
+    fn enter_frame(&mut self, kind: &FrameKind, gas_counter: Gas) {
+        if !self.admit_node() {
+            return;
+        }
+        self.open_nodes.push_frame(OpenFrame {
+            kind: kind.clone(),
+            start_gas: gas_counter,
+            events: vec![],
+        });
+    }
+
+    fn resolve_frame(&mut self, call_stack_element: &CallStackElement) {
+        if self.skipped_depth > 0 {
+            return;
+        }
+        if let Some(OpenNode::Frame(OpenFrame { kind, .. })) = self.open_nodes.last_mut() {
+            let entry_point = match kind {
+                FrameKind::Unresolved { entry_point } => entry_point.clone(),
+                _ => return,
+            };
+            *kind = match *call_stack_element {
+                CallStackElement::Session { .. } => FrameKind::Session,
+                CallStackElement::StoredSession {
+                    contract_package_hash,
+                    contract_hash,
+                    ..
+                } => FrameKind::StoredSession {
+                    contract_package_hash,
+                    contract_hash,
+                    entry_point,
+                },
+                CallStackElement::StoredContract {
+                    contract_package_hash,
+                    contract_hash,
+                } => FrameKind::StoredContract {
+                    contract_package_hash,
+                    contract_hash,
+                    entry_point,
+                },
+            };
+        }
+    }
+
+    fn exit_frame(&mut self, gas_counter: Gas, error: Option<String>) {
+        if self.exit_skipped_node() {
+            return;
+        }
+        let OpenFrame {
+            kind,
+            start_gas,
+            events,
+        } = match self.open_nodes.pop_frame() {
+            Some(open_frame) => open_frame,
+            None => return,
+        };
+        let frame = TraceFrame {
+            kind,
+            gas_used: gas_used(start_gas, gas_counter),
+            error,
+            events,
+        };
+        if self.open_nodes.last().is_some() {
+            self.attach(TraceEvent::Call(frame));
+        } else {
+            self.frames.push(frame);
+        }
+    }
+
+    fn enter_host_function(&mut self, name: &'static str, args: &[RuntimeValue], gas_counter: Gas) {
+        if !self.admit_node() {
+            return;
+        }
+        self.open_nodes.push_host_function(OpenHostFunction {
+            name,
+            args: args.iter().map(arg_value).collect(),
+            start_gas: gas_counter,
+            events: vec![],
+        });
+    }
+
+    fn exit_host_function(&mut self, gas_counter: Gas) {
+        if self.exit_skipped_node() {
+            return;
+        }
+        if let Some(OpenHostFunction {
+            name,
+            args,
+            start_gas,
+            events,
+        }) = self.open_nodes.pop_host_function()
+        {
+            self.attach(TraceEvent::HostFunction(HostFunctionCall {
+                name: name.to_string(),
+                args,
+                gas_used: gas_used(start_gas, gas_counter),
+                events,
+            }));
+        }
+    }
+
+    fn record_read(&mut self, key: &Key) {
+        self.record(TraceEvent::Read {
+            key: key.to_formatted_string(),
+        });
+    }
+
+    fn record_write(&mut self, key: &Key) {
+        self.record(TraceEvent::Write {
+            key: key.to_formatted_string(),
+        });
+    }
+
+    fn record_add(&mut self, key: &Key) {
+        self.record(TraceEvent::Add {
+            key: key.to_formatted_string(),
+        });
+    }
+}
+
+fn gas_used(start: Gas, end: Gas) -> U512 {
+    end.value().saturating_sub(start.value())
+}
+
+fn arg_value(value: &RuntimeValue) -> i64 {
+    match *value {
+        RuntimeValue::I32(value) => value.into(),
+        RuntimeValue::I64(value) => value,
+        RuntimeValue::F32(value) => value.to_bits().into(),
+        RuntimeValue::F64(value) => value.to_bits() as i64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gas(value: u64) -> Gas {
+        Gas::new(U512::from(value))
+    }
+
+    fn event_count(events: &[TraceEvent]) -> usize {
+        events
+            .iter()
+            .map(|event| match event {
+                TraceEvent::HostFunction(host_function_call) => {
+                    1 + event_count(host_function_call.events())
+                }
+                TraceEvent::Call(frame) => 1 + event_count(frame.events()),
+                TraceEvent::Read { .. } | TraceEvent::Write { .. } | TraceEvent::Add { .. } => 1,
+            })
+            .sum()
+    }
+
+    #[test]
+    fn should_not_truncate_small_trace() {
+        let mut tracer = ExecutionTracer::default();
+        tracer.enter_frame(&FrameKind::Session, gas(0));
+        tracer.enter_host_function("casper_read_value", &[], gas(1));
+        tracer.record_read(&Key::Hash([1; 32]));
+        tracer.exit_host_function(gas(2));
+        tracer.exit_frame(gas(3), None);
+
+        let trace = tracer.take_trace();
+        assert!(!trace.is_truncated());
+        assert_eq!(trace.frames().len(), 1);
+        assert_eq!(event_count(trace.frames()[0].events()), 2);
+    }
+
+    #[test]
+    fn should_cap_number_of_events() {
+        let mut tracer = ExecutionTracer::default();
+        tracer.enter_frame(&FrameKind::Session, gas(0));
+        for _ in 0..MAX_TRACE_EVENTS {
+            tracer.enter_host_function("casper_write", &[], gas(0));
+            tracer.record_write(&Key::Hash([1; 32]));
+            tracer.exit_host_function(gas(0));
+        }
+        tracer.exit_frame(gas(0), None);
+
+        let trace = tracer.take_trace();
+        assert!(trace.is_truncated());
+        assert_eq!(
+            1 + event_count(trace.frames()[0].events()),
+            MAX_TRACE_EVENTS
+        );
+
+        // The tracer starts afresh for the next trace.
+        tracer.enter_frame(&FrameKind::Session, gas(0));
+        tracer.exit_frame(gas(0), None);
+        assert!(!tracer.take_trace().is_truncated());
+    }
+
+    #[test]
+    fn should_cap_depth_of_nested_calls() {
+        let mut tracer = ExecutionTracer::default();
+        let depth = 2 * MAX_TRACE_DEPTH;
+        tracer.enter_frame(&FrameKind::Session, gas(0));
+        for _ in 1..depth {
+            tracer.enter_host_function("casper_call_contract", &[], gas(0));
+            tracer.record_read(&Key::Hash([1; 32]));
+        }
+        for _ in 1..depth {
+            tracer.exit_host_function(gas(1));
+        }
+        tracer.exit_frame(gas(1), None);
+
+        let trace = tracer.take_trace();
+        assert!(trace.is_truncated());
+        let mut recorded_depth = 1;
+        let mut events = trace.frames()[0].events();
+        while let Some(host_function_call) = events.iter().find_map(|event| match event {
+            TraceEvent::HostFunction(host_function_call) => Some(host_function_call),
+            _ => None,
+        }) {
+            assert_eq!(host_function_call.gas_used(), U512::from(1));
+            recorded_depth += 1;
+            events = host_function_call.events();
+        }
+        assert_eq!(recorded_depth, MAX_TRACE_DEPTH);
+    }
+}
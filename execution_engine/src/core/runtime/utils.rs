@@ -4,8 +4,12 @@ use parity_wasm::elements::Module;
 use wasmi::{ImportsBuilder, MemoryRef, ModuleInstance, ModuleRef};
 
 use casper_types::{
-    contracts::NamedKeys, AccessRights, CLType, CLValue, Key, ProtocolVersion, PublicKey,
-    RuntimeArgs, URef, URefAddr, U128, U256, U512,
+    bytesrepr::{
+        self, FromBytes, ToBytes, OPTION_NONE_TAG, OPTION_SOME_TAG, RESULT_ERR_TAG, RESULT_OK_TAG,
+    },
+    contracts::NamedKeys,
+    AccessRights, CLType, CLValue, Key, ProtocolVersion, PublicKey, RuntimeArgs, URef, URefAddr,
+    U128, U256, U512,
 };
 
 use crate::core::{
@@ -59,7 +63,7 @@ pub(super) fn attenuate_uref_in_args(
 }
 
 /// Extracts a copy of every uref able to be deserialized from `cl_value`.
-pub(super) fn extract_urefs(cl_value: &CLValue) -> Result<Vec<URef>, Error> {
+pub(crate) fn extract_urefs(cl_value: &CLValue) -> Result<Vec<URef>, Error> {
     let mut vec: Vec<URef> = Vec::new();
     rewrite_urefs(cl_value.clone(), |uref| {
         vec.push(*uref);
@@ -1155,6 +1159,19 @@ fn rewrite_urefs(cl_value: CLValue, mut func: impl FnMut(&mut URef)) -> Result<C
         },
         // TODO: nested matches for Tuple3?
         CLType::Tuple3(_) => cl_value,
+        CLType::Struct { .. } | CLType::Enum { .. } => {
+            let mut bytes = Vec::with_capacity(cl_value.inner_bytes().len());
+            let remainder = rewrite_urefs_in_bytes(
+                cl_value.cl_type(),
+                cl_value.inner_bytes(),
+                &mut bytes,
+                &mut func,
+            )?;
+            if !remainder.is_empty() {
+                return Err(bytesrepr::Error::LeftOverBytes.into());
+            }
+            CLValue::from_components(cl_value.cl_type().clone(), bytes)
+        }
         CLType::Key => {
             let mut key: Key = cl_value.to_owned().into_t()?; // TODO: optimize?
             key.as_uref_mut().iter_mut().for_each(|v| func(v));
@@ -1169,6 +1186,130 @@ fn rewrite_urefs(cl_value: CLValue, mut func: impl FnMut(&mut URef)) -> Result<C
     Ok(ret)
 }
 
+/// Executes `func` on every uref held in the value of type `cl_type` serialized at the start of
+/// `bytes`, whether directly or within a key, and appends the re-serialized value to `output`.
+///
+/// The value is walked using its type description, as `casper_types::cl_value_to_json` does, so
+/// urefs are found at any depth.  Returns the remainder of `bytes`.
+fn rewrite_urefs_in_bytes<'a, F: FnMut(&mut URef)>(
+    cl_type: &CLType,
+    bytes: &'a [u8],
+    output: &mut Vec<u8>,
+    func: &mut F,
+) -> Result<&'a [u8], bytesrepr::Error> {
+    match cl_type {
+        CLType::Bool => copy_serialized::<bool>(bytes, output),
+        CLType::I32 => copy_serialized::<i32>(bytes, output),
+        CLType::I64 => copy_serialized::<i64>(bytes, output),
+        CLType::U8 => copy_serialized::<u8>(bytes, output),
+        CLType::U32 => copy_serialized::<u32>(bytes, output),
+        CLType::U64 => copy_serialized::<u64>(bytes, output),
+        CLType::U128 => copy_serialized::<U128>(bytes, output),
+        CLType::U256 => copy_serialized::<U256>(bytes, output),
+        CLType::U512 => copy_serialized::<U512>(bytes, output),
+        CLType::Unit => copy_serialized::<()>(bytes, output),
+        CLType::String => copy_serialized::<String>(bytes, output),
+        CLType::PublicKey => copy_serialized::<PublicKey>(bytes, output),
+        CLType::Key => {
+            let (mut key, remainder) = Key::from_bytes(bytes)?;
+            if let Some(uref) = key.as_uref_mut() {
+                func(uref);
+            }
+            output.append(&mut key.to_bytes()?);
+            Ok(remainder)
+        }
+        CLType::URef => {
+            let (mut uref, remainder) = URef::from_bytes(bytes)?;
+            func(&mut uref);
+            output.append(&mut uref.to_bytes()?);
+            Ok(remainder)
+        }
+        CLType::Option(inner_cl_type) => {
+            let (tag, remainder) = u8::from_bytes(bytes)?;
+            output.push(tag);
+            match tag {
+                OPTION_NONE_TAG => Ok(remainder),
+                OPTION_SOME_TAG => rewrite_urefs_in_bytes(inner_cl_type, remainder, output, func),
+                _ => Err(bytesrepr::Error::Formatting),
+            }
+        }
+        CLType::List(inner_cl_type) => {
+            let (count, mut stream) = u32::from_bytes(bytes)?;
+            output.append(&mut count.to_bytes()?);
+            for _ in 0..count {
+                stream = rewrite_urefs_in_bytes(inner_cl_type, stream, output, func)?;
+            }
+            Ok(stream)
+        }
+        CLType::ByteArray(length) => {
+            let length = *length as usize;
+            if bytes.len() < length {
+                return Err(bytesrepr::Error::EarlyEndOfStream);
+            }
+            let (array, remainder) = bytes.split_at(length);
+            output.extend_from_slice(array);
+            Ok(remainder)
+        }
+        CLType::Result { ok, err } => {
+            let (tag, remainder) = u8::from_bytes(bytes)?;
+            output.push(tag);
+            match tag {
+                RESULT_ERR_TAG => rewrite_urefs_in_bytes(err, remainder, output, func),
+                RESULT_OK_TAG => rewrite_urefs_in_bytes(ok, remainder, output, func),
+                _ => Err(bytesrepr::Error::Formatting),
+            }
+        }
+        CLType::Map { key, value } => {
+            let (count, mut stream) = u32::from_bytes(bytes)?;
+            output.append(&mut count.to_bytes()?);
+            for _ in 0..count {
+                stream = rewrite_urefs_in_bytes(key, stream, output, func)?;
+                stream = rewrite_urefs_in_bytes(value, stream, output, func)?;
+            }
+            Ok(stream)
+        }
+        CLType::Tuple1(cl_types) => rewrite_urefs_in_bytes(&cl_types[0], bytes, output, func),
+        CLType::Tuple2(cl_types) => {
+            let remainder = rewrite_urefs_in_bytes(&cl_types[0], bytes, output, func)?;
+            rewrite_urefs_in_bytes(&cl_types[1], remainder, output, func)
+        }
+        CLType::Tuple3(cl_types) => {
+            let remainder = rewrite_urefs_in_bytes(&cl_types[0], bytes, output, func)?;
+            let remainder = rewrite_urefs_in_bytes(&cl_types[1], remainder, output, func)?;
+            rewrite_urefs_in_bytes(&cl_types[2], remainder, output, func)
+        }
+        // The extent of a value of unspecified type is unknown, so any urefs following it couldn't
+        // be found.
+        CLType::Any => Err(bytesrepr::Error::Formatting),
+        CLType::Struct { fields, .. } => {
+            let mut stream = bytes;
+            for field in fields {
+                stream = rewrite_urefs_in_bytes(&field.cl_type, stream, output, func)?;
+            }
+            Ok(stream)
+        }
+        CLType::Enum { variants, .. } => {
+            let (index, remainder) = u8::from_bytes(bytes)?;
+            let variant = variants
+                .get(index as usize)
+                .ok_or(bytesrepr::Error::Formatting)?;
+            output.push(index);
+            rewrite_urefs_in_bytes(&variant.cl_type, remainder, output, func)
+        }
+    }
+}
+
+/// Appends the value of type `T` serialized at the start of `bytes` to `output`, and returns the
+/// remainder of `bytes`.
+fn copy_serialized<'a, T: FromBytes>(
+    bytes: &'a [u8],
+    output: &mut Vec<u8>,
+) -> Result<&'a [u8], bytesrepr::Error> {
+    let (_value, remainder) = T::from_bytes(bytes)?;
+    output.extend_from_slice(&bytes[..bytes.len() - remainder.len()]);
+    Ok(remainder)
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
@@ -1216,7 +1357,9 @@ mod tests {
                 | CLType::Tuple2(_)
                 | CLType::Tuple3(_)
                 | CLType::PublicKey
-                | CLType::Any => (),
+                | CLType::Any
+                | CLType::Struct { .. }
+                | CLType::Enum { .. } => (),
             }
         };
 
@@ -1428,4 +1571,88 @@ mod tests {
         ];
         assert_eq!(lhs, rhs);
     }
+
+    /// Returns a struct holding the given uref, an optional key, and an enum holding a list of
+    /// urefs, along with its serialization.
+    fn struct_with_urefs(uref: URef, maybe_key: Option<Key>, urefs: Vec<URef>) -> CLValue {
+        let enum_cl_type = CLType::new_enum(
+            "Holder",
+            vec![
+                ("Nothing", CLType::Unit),
+                ("Urefs", CLType::List(Box::new(CLType::URef))),
+            ],
+        );
+        let cl_type = CLType::new_struct(
+            "Purses",
+            vec![
+                ("name", CLType::String),
+                ("main", CLType::URef),
+                ("key", CLType::Option(Box::new(CLType::Key))),
+                ("others", enum_cl_type),
+            ],
+        );
+        let mut bytes = "purses".to_bytes().unwrap();
+        bytes.append(&mut uref.to_bytes().unwrap());
+        bytes.append(&mut maybe_key.to_bytes().unwrap());
+        bytes.push(1);
+        bytes.append(&mut urefs.to_bytes().unwrap());
+        CLValue::from_components(cl_type, bytes)
+    }
+
+    #[test]
+    fn should_extract_urefs_from_structs_and_enums() {
+        let uref_1 = URef::new([1; 32], AccessRights::READ_ADD_WRITE);
+        let uref_2 = URef::new([2; 32], AccessRights::READ);
+        let uref_3 = URef::new([3; 32], AccessRights::ADD);
+
+        let cl_value = struct_with_urefs(uref_1, Some(Key::from(uref_2)), vec![uref_3, uref_1]);
+        assert_eq!(
+            extract_urefs(&cl_value).unwrap(),
+            vec![uref_1, uref_2, uref_3, uref_1]
+        );
+
+        let cl_value = struct_with_urefs(uref_1, Some(Key::Hash([2; 32])), vec![]);
+        assert_eq!(extract_urefs(&cl_value).unwrap(), vec![uref_1]);
+    }
+
+    #[test]
+    fn should_modify_urefs_in_structs_and_enums() {
+        let uref_1 = URef::new([1; 32], AccessRights::READ_ADD_WRITE);
+        let uref_2 = URef::new([2; 32], AccessRights::READ_ADD_WRITE);
+        let attenuated_uref_1 = uref_1.with_access_rights(AccessRights::READ_ADD);
+
+        let mut args = RuntimeArgs::new();
+        args.insert_cl_value(
+            "purses",
+            struct_with_urefs(uref_1, Some(Key::from(uref_1)), vec![uref_2, uref_1]),
+        );
+        let args = attenuate_uref_in_args(args, uref_1.addr(), AccessRights::WRITE).unwrap();
+
+        assert_eq!(
+            args.get("purses").unwrap(),
+            &struct_with_urefs(
+                attenuated_uref_1,
+                Some(Key::from(attenuated_uref_1)),
+                vec![uref_2, attenuated_uref_1]
+            )
+        );
+    }
+
+    #[test]
+    fn should_fail_to_extract_urefs_from_malformed_structs() {
+        let cl_type = CLType::new_struct("Opaque", vec![("value", CLType::Any)]);
+        let cl_value = CLValue::from_components(cl_type, vec![]);
+        assert!(extract_urefs(&cl_value).is_err());
+
+        let cl_type = CLType::new_enum("Empty", Vec::<(String, CLType)>::new());
+        let cl_value = CLValue::from_components(cl_type, vec![0]);
+        assert!(extract_urefs(&cl_value).is_err());
+
+        let uref = URef::new([1; 32], AccessRights::READ);
+        let mut cl_value = struct_with_urefs(uref, None, vec![]);
+        let mut bytes = cl_value.inner_bytes().clone();
+        bytes.push(0);
+        cl_value = CLValue::from_components(cl_value.cl_type().clone(), bytes);
+        assert!(extract_urefs(&cl_value).is_err());
+    }
 }
@@ -17,16 +17,18 @@ use casper_types::{
     bytesrepr::ToBytes,
     contracts::NamedKeys,
     system::auction::EraInfo,
-    AccessRights, BlockTime, CLType, CLValue, ContextAccessRights, Contract, ContractHash,
-    ContractPackage, ContractPackageHash, DeployHash, DeployInfo, EntryPointAccess, EntryPointType,
-    Gas, GrantedAccess, Key, KeyTag, Phase, ProtocolVersion, PublicKey, RuntimeArgs, StoredValue,
-    Transfer, TransferAddr, URef, URefAddr, DICTIONARY_ITEM_KEY_MAX_LENGTH, KEY_HASH_LENGTH, U512,
+    AccessRights, BlockTime, CLType, CLValue, ContextAccessRights, Contract, ContractEvent,
+    ContractHash, ContractPackage, ContractPackageHash, DeployHash, DeployInfo, EntryPointAccess,
+    EntryPointType, Gas, GrantedAccess, Key, KeyTag, Phase, ProtocolVersion, PublicKey,
+    RuntimeArgs, StoredValue, Transfer, TransferAddr, URef, URefAddr,
+    DICTIONARY_ITEM_KEY_MAX_LENGTH, KEY_HASH_LENGTH, U512,
 };
 
 use crate::{
     core::{
         engine_state::{execution_effect::ExecutionEffect, EngineConfig, SystemContractRegistry},
         execution::{AddressGenerator, Error},
+        runtime::{observer::ExecutionObserver, utils},
         runtime_context::dictionary::DictionaryValue,
         tracking_copy::{AddResult, TrackingCopy, TrackingCopyExt},
     },
@@ -104,6 +106,10 @@ pub struct RuntimeContext<'a, R> {
     entry_point_type: EntryPointType,
     transfers: Vec<TransferAddr>,
     remaining_spending_limit: U512,
+    // Events emitted by contracts so far during the execution
+    events: Vec<ContractEvent>,
+    // Observes the execution, e.g. to trace or profile it
+    observer: Option<Rc<RefCell<dyn ExecutionObserver>>>,
 }
 
 impl<'a, R> RuntimeContext<'a, R>
@@ -156,6 +162,8 @@ where
             engine_config,
             transfers,
             remaining_spending_limit,
+            events: Vec::new(),
+            observer: None,
         }
     }
 
@@ -184,6 +192,8 @@ where
         let engine_config = self.engine_config.clone();
         let transfers = self.transfers.clone();
         let remaining_spending_limit = self.remaining_spending_limit();
+        let events = self.events.clone();
+        let observer = self.observer.clone();
 
         RuntimeContext {
             tracking_copy,
@@ -205,9 +215,31 @@ where
             engine_config,
             transfers,
             remaining_spending_limit,
+            events,
+            observer,
         }
     }
 
+    /// Sets the observer of the execution.
+    pub(crate) fn set_observer(&mut self, observer: Option<Rc<RefCell<dyn ExecutionObserver>>>) {
+        self.observer = observer;
+    }
+
+    /// Returns `true` if the execution is being observed.
+    pub(crate) fn is_observed(&self) -> bool {
+        self.observer.is_some()
+    }
+
+    /// Calls `f` with the observer of the execution, if any.
+    pub(crate) fn observe<F, T>(&self, f: F) -> Option<T>
+    where
+        F: FnOnce(&mut dyn ExecutionObserver) -> T,
+    {
+        self.observer
+            .as_ref()
+            .map(|observer| f(&mut *observer.borrow_mut()))
+    }
+
     /// Returns all authorization keys for this deploy.
     pub fn authorization_keys(&self) -> &BTreeSet<AccountHash> {
         &self.authorization_keys
@@ -338,6 +370,10 @@ where
                 error!("should not remove the checksum registry key");
                 Err(Error::RemoveKeyFailure(RemoveKeyFailure::PermissionDenied))
             }
+            Key::ContractSchema(_) => {
+                self.named_keys.remove(name);
+                Ok(())
+            }
         }
     }
 
@@ -500,6 +536,7 @@ where
     pub fn read_gs(&mut self, key: &Key) -> Result<Option<StoredValue>, Error> {
         self.validate_readable(key)?;
         self.validate_key(key)?;
+        self.observe(|observer| observer.record_read(key));
 
         let maybe_stored_value = self
             .tracking_copy
@@ -522,6 +559,7 @@ where
     /// DO NOT EXPOSE THIS VIA THE FFI - This function bypasses security checks and should be used
     /// with caution.
     pub fn read_gs_direct(&mut self, key: &Key) -> Result<Option<StoredValue>, Error> {
+        self.observe(|observer| observer.record_read(key));
         self.tracking_copy
             .borrow_mut()
             .read(self.correlation_id, key)
@@ -562,6 +600,7 @@ where
     pub fn read_account(&mut self, key: &Key) -> Result<Option<StoredValue>, Error> {
         if let Key::Account(_) = key {
             self.validate_key(key)?;
+            self.observe(|observer| observer.record_read(key));
             self.tracking_copy
                 .borrow_mut()
                 .read(self.correlation_id, key)
@@ -587,6 +626,7 @@ where
     pub fn write_transfer(&mut self, key: Key, value: Transfer) {
         if let Key::Transfer(_) = key {
             // Writing a `Transfer` will not exceed write size limit.
+            self.observe(|observer| observer.record_write(&key));
             self.tracking_copy
                 .borrow_mut()
                 .write(key, StoredValue::Transfer(value));
@@ -599,6 +639,7 @@ where
     pub fn write_era_info(&mut self, key: Key, value: EraInfo) {
         if let Key::EraSummary = key {
             // Writing an `EraInfo` for 100 validators will not exceed write size limit.
+            self.observe(|observer| observer.record_write(&key));
             self.tracking_copy
                 .borrow_mut()
                 .write(key, StoredValue::EraInfo(value));
@@ -655,6 +696,30 @@ where
         &mut self.transfers
     }
 
+    /// Returns list of events emitted by contracts.
+    pub fn events(&self) -> &Vec<ContractEvent> {
+        &self.events
+    }
+
+    /// Returns mutable list of events emitted by contracts.
+    pub fn events_mut(&mut self) -> &mut Vec<ContractEvent> {
+        &mut self.events
+    }
+
+    /// Records an event emitted in the context of the currently executing account or contract.
+    ///
+    /// Any keys or URefs within the payload must be known to the current context.
+    pub(crate) fn emit_event(&mut self, topic: String, payload: CLValue) -> Result<(), Error> {
+        self.validate_cl_value(&payload)?;
+        let event = ContractEvent {
+            emitter: self.base_key,
+            topic,
+            payload,
+        };
+        self.events.push(event);
+        Ok(())
+    }
+
     fn validate_cl_value(&self, cl_value: &CLValue) -> Result<(), Error> {
         match cl_value.cl_type() {
             CLType::Bool
@@ -677,6 +742,9 @@ where
             | CLType::Tuple3(_)
             | CLType::Any
             | CLType::PublicKey => Ok(()),
+            CLType::Struct { .. } | CLType::Enum { .. } => utils::extract_urefs(cl_value)?
+                .iter()
+                .try_for_each(|uref| self.validate_uref(uref)),
             CLType::Key => {
                 let key: Key = cl_value.to_owned().into_t()?; // TODO: optimize?
                 self.validate_key(&key)
@@ -796,6 +864,7 @@ where
             Key::Unbond(_) => true,
             Key::ChainspecRegistry => true,
             Key::ChecksumRegistry => true,
+            Key::ContractSchema(_) => true,
         }
     }
 
@@ -816,6 +885,7 @@ where
             Key::Unbond(_) => false,
             Key::ChainspecRegistry => false,
             Key::ChecksumRegistry => false,
+            Key::ContractSchema(_) => false,
         }
     }
 
@@ -836,6 +906,7 @@ where
             Key::Unbond(_) => false,
             Key::ChainspecRegistry => false,
             Key::ChecksumRegistry => false,
+            Key::ContractSchema(_) => false,
         }
     }
 
@@ -885,7 +956,9 @@ where
 
         let gas_cost = storage_costs.calculate_gas_cost(bytes_count);
 
-        self.charge_gas(gas_cost)
+        self.charge_gas(gas_cost)?;
+        self.observe(|observer| observer.record_storage(bytes_count, gas_cost));
+        Ok(())
     }
 
     /// Charges gas for using a host system contract's entrypoint.
@@ -900,7 +973,9 @@ where
             return Ok(());
         }
         let amount: Gas = call_cost.into();
-        self.charge_gas(amount)
+        self.charge_gas(amount)?;
+        self.observe(|observer| observer.record_system_contract_call(amount));
+        Ok(())
     }
 
     /// Writes data to global state with a measurement.
@@ -918,9 +993,9 @@ where
         let bytes_count = stored_value.serialized_length();
         self.charge_gas_storage(bytes_count)?;
 
-        self.tracking_copy
-            .borrow_mut()
-            .write(key.into(), stored_value);
+        let key = key.into();
+        self.observe(|observer| observer.record_write(&key));
+        self.tracking_copy.borrow_mut().write(key, stored_value);
         Ok(())
     }
 
@@ -949,6 +1024,7 @@ where
         let value_bytes_count = value.serialized_length();
         self.charge_gas_storage(value_bytes_count)?;
 
+        self.observe(|observer| observer.record_add(&key));
         match self
             .tracking_copy
             .borrow_mut()
@@ -1200,6 +1276,7 @@ where
         &mut self,
         dictionary_key: Key,
     ) -> Result<Option<CLValue>, Error> {
+        self.observe(|observer| observer.record_read(&dictionary_key));
         let maybe_stored_value = self
             .tracking_copy
             .borrow_mut()
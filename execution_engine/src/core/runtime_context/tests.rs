@@ -15,9 +15,9 @@ use casper_types::{
     bytesrepr::ToBytes,
     contracts::NamedKeys,
     system::{AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT},
-    AccessRights, BlockTime, CLValue, ContextAccessRights, Contract, ContractHash, DeployHash,
-    EntryPointType, EntryPoints, Gas, Key, Phase, ProtocolVersion, PublicKey, RuntimeArgs,
-    SecretKey, StoredValue, URef, KEY_HASH_LENGTH, U256, U512,
+    AccessRights, BlockTime, CLType, CLValue, ContextAccessRights, Contract, ContractHash,
+    DeployHash, EntryPointType, EntryPoints, Gas, Key, Phase, ProtocolVersion, PublicKey,
+    RuntimeArgs, SecretKey, StoredValue, URef, KEY_HASH_LENGTH, U256, U512,
 };
 
 use super::{Error, RuntimeContext};
@@ -218,6 +218,35 @@ fn use_uref_forged() {
     assert_forged_reference(query_result);
 }
 
+#[test]
+fn use_uref_forged_in_struct() {
+    let mut rng = AddressGenerator::new(&DEPLOY_HASH, PHASE);
+    let uref_as_key = create_uref_as_key(&mut rng, AccessRights::READ_WRITE);
+    let forged_uref = create_uref_as_key(&mut rng, AccessRights::READ_WRITE);
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(String::new(), uref_as_key);
+
+    // A struct holding an optional key, whose urefs are validated like a plain key's.
+    let struct_value = |key: Key| {
+        let cl_type = CLType::new_struct(
+            "Holder",
+            vec![("key", CLType::Option(Box::new(CLType::Key)))],
+        );
+        let value = CLValue::from_components(cl_type, Some(key).to_bytes().unwrap());
+        StoredValue::CLValue(value)
+    };
+
+    let query_result = build_runtime_context_and_execute(named_keys.clone(), |mut rc| {
+        rc.metered_write_gs(uref_as_key, struct_value(uref_as_key))
+    });
+    query_result.expect("writing a struct holding a valid uref should succeed");
+
+    let query_result = build_runtime_context_and_execute(named_keys, |mut rc| {
+        rc.metered_write_gs(uref_as_key, struct_value(forged_uref))
+    });
+    assert_forged_reference(query_result);
+}
+
 #[test]
 fn account_key_not_writeable() {
     let mut rng = rand::thread_rng();
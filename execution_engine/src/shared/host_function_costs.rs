@@ -78,6 +78,13 @@ const DEFAULT_DICTIONARY_PUT_VALUE_SIZE_WEIGHT: u32 = 520;
 
 const DEFAULT_NEW_DICTIONARY_COST: u32 = DEFAULT_NEW_UREF_COST;
 
+const DEFAULT_VERIFY_SIGNATURE_COST: u32 = 1_300_000;
+const DEFAULT_VERIFY_SIGNATURE_MESSAGE_SIZE_WEIGHT: u32 = 30;
+
+const DEFAULT_EMIT_EVENT_COST: u32 = 14_000;
+const DEFAULT_EMIT_EVENT_TOPIC_SIZE_WEIGHT: u32 = 980;
+const DEFAULT_EMIT_EVENT_PAYLOAD_SIZE_WEIGHT: u32 = 980;
+
 pub(crate) const DEFAULT_HOST_FUNCTION_NEW_DICTIONARY: HostFunction<[Cost; 1]> =
     HostFunction::new(DEFAULT_NEW_DICTIONARY_COST, [NOT_USED]);
 
@@ -290,6 +297,12 @@ pub struct HostFunctionCosts {
     pub random_bytes: HostFunction<[Cost; 2]>,
     /// Cost of calling the `enable_contract_version` host function.
     pub enable_contract_version: HostFunction<[Cost; 4]>,
+    /// Cost of calling the `verify_signature` host function.
+    pub verify_signature: HostFunction<[Cost; 6]>,
+    /// Cost of calling the `emit_event` host function.
+    pub emit_event: HostFunction<[Cost; 4]>,
+    /// Cost of calling the `add_contract_version_with_schema` host function.
+    pub add_contract_version_with_schema: HostFunction<[Cost; 12]>,
 }
 
 impl Default for HostFunctionCosts {
@@ -422,6 +435,27 @@ impl Default for HostFunctionCosts {
             blake2b: HostFunction::default(),
             random_bytes: HostFunction::default(),
             enable_contract_version: HostFunction::default(),
+            verify_signature: HostFunction::new(
+                DEFAULT_VERIFY_SIGNATURE_COST,
+                [
+                    NOT_USED,
+                    DEFAULT_VERIFY_SIGNATURE_MESSAGE_SIZE_WEIGHT,
+                    NOT_USED,
+                    NOT_USED,
+                    NOT_USED,
+                    NOT_USED,
+                ],
+            ),
+            emit_event: HostFunction::new(
+                DEFAULT_EMIT_EVENT_COST,
+                [
+                    NOT_USED,
+                    DEFAULT_EMIT_EVENT_TOPIC_SIZE_WEIGHT,
+                    NOT_USED,
+                    DEFAULT_EMIT_EVENT_PAYLOAD_SIZE_WEIGHT,
+                ],
+            ),
+            add_contract_version_with_schema: HostFunction::default(),
         }
     }
 }
@@ -473,6 +507,9 @@ impl ToBytes for HostFunctionCosts {
         ret.append(&mut self.blake2b.to_bytes()?);
         ret.append(&mut self.random_bytes.to_bytes()?);
         ret.append(&mut self.enable_contract_version.to_bytes()?);
+        ret.append(&mut self.verify_signature.to_bytes()?);
+        ret.append(&mut self.emit_event.to_bytes()?);
+        ret.append(&mut self.add_contract_version_with_schema.to_bytes()?);
         Ok(ret)
     }
 
@@ -521,6 +558,9 @@ impl ToBytes for HostFunctionCosts {
             + self.blake2b.serialized_length()
             + self.random_bytes.serialized_length()
             + self.enable_contract_version.serialized_length()
+            + self.verify_signature.serialized_length()
+            + self.emit_event.serialized_length()
+            + self.add_contract_version_with_schema.serialized_length()
     }
 }
 
@@ -570,6 +610,9 @@ impl FromBytes for HostFunctionCosts {
         let (blake2b, rem) = FromBytes::from_bytes(rem)?;
         let (random_bytes, rem) = FromBytes::from_bytes(rem)?;
         let (enable_contract_version, rem) = FromBytes::from_bytes(rem)?;
+        let (verify_signature, rem) = FromBytes::from_bytes(rem)?;
+        let (emit_event, rem) = FromBytes::from_bytes(rem)?;
+        let (add_contract_version_with_schema, rem) = FromBytes::from_bytes(rem)?;
         Ok((
             HostFunctionCosts {
                 read_value,
@@ -616,6 +659,9 @@ impl FromBytes for HostFunctionCosts {
                 blake2b,
                 random_bytes,
                 enable_contract_version,
+                verify_signature,
+                emit_event,
+                add_contract_version_with_schema,
             },
             rem,
         ))
@@ -669,6 +715,9 @@ impl Distribution<HostFunctionCosts> for Standard {
             blake2b: rng.gen(),
             random_bytes: rng.gen(),
             enable_contract_version: rng.gen(),
+            verify_signature: rng.gen(),
+            emit_event: rng.gen(),
+            add_contract_version_with_schema: rng.gen(),
         }
     }
 }
@@ -730,6 +779,9 @@ pub mod gens {
             blake2b in host_function_cost_arb(),
             random_bytes in host_function_cost_arb(),
             enable_contract_version in host_function_cost_arb(),
+            verify_signature in host_function_cost_arb(),
+            emit_event in host_function_cost_arb(),
+            add_contract_version_with_schema in host_function_cost_arb(),
         ) -> HostFunctionCosts {
             HostFunctionCosts {
                 read_value,
@@ -776,6 +828,9 @@ pub mod gens {
                 blake2b,
                 random_bytes,
                 enable_contract_version,
+                verify_signature,
+                emit_event,
+                add_contract_version_with_schema,
             }
         }
     }
@@ -322,6 +322,67 @@ impl Distribution<ControlFlowCosts> for Standard {
     }
 }
 
+/// A class of Wasm opcodes sharing a single cost in the [`OpcodeCosts`] table.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum OpcodeClass {
+    /// Bit operations.
+    Bit,
+    /// Arithmetic add operations.
+    Add,
+    /// Mul operations.
+    Mul,
+    /// Div operations.
+    Div,
+    /// Memory load operations.
+    Load,
+    /// Memory store operations.
+    Store,
+    /// Const operations.
+    Const,
+    /// Local operations.
+    Local,
+    /// Global operations.
+    Global,
+    /// Integer comparison operations.
+    IntegerComparison,
+    /// Conversion operations.
+    Conversion,
+    /// Unreachable operation.
+    Unreachable,
+    /// Nop operation.
+    Nop,
+    /// Get current memory operation.
+    CurrentMemory,
+    /// Grow memory operation.
+    GrowMemory,
+    /// Control flow operations.
+    ControlFlow,
+}
+
+impl OpcodeClass {
+    /// Returns the name of the class, as used in the chainspec.
+    pub fn name(&self) -> &'static str {
+        match self {
+            OpcodeClass::Bit => "bit",
+            OpcodeClass::Add => "add",
+            OpcodeClass::Mul => "mul",
+            OpcodeClass::Div => "div",
+            OpcodeClass::Load => "load",
+            OpcodeClass::Store => "store",
+            OpcodeClass::Const => "const",
+            OpcodeClass::Local => "local",
+            OpcodeClass::Global => "global",
+            OpcodeClass::IntegerComparison => "integer_comparison",
+            OpcodeClass::Conversion => "conversion",
+            OpcodeClass::Unreachable => "unreachable",
+            OpcodeClass::Nop => "nop",
+            OpcodeClass::CurrentMemory => "current_memory",
+            OpcodeClass::GrowMemory => "grow_memory",
+            OpcodeClass::ControlFlow => "control_flow",
+        }
+    }
+}
+
 /// Definition of a cost table for Wasm opcodes.
 ///
 /// This is taken (partially) from parity-ethereum.
@@ -363,20 +424,22 @@ pub struct OpcodeCosts {
     pub control_flow: ControlFlowCosts,
 }
 
-impl Rules for OpcodeCosts {
-    fn instruction_cost(&self, instruction: &Instruction) -> Option<u32> {
+impl OpcodeCosts {
+    /// Returns the class of the given instruction along with its cost, or `None` if the
+    /// instruction is not supported.
+    pub fn instruction_class(&self, instruction: &Instruction) -> Option<(OpcodeClass, u32)> {
         match instruction {
-            Instruction::Unreachable => Some(self.unreachable),
-            Instruction::Nop => Some(self.nop),
+            Instruction::Unreachable => Some((OpcodeClass::Unreachable, self.unreachable)),
+            Instruction::Nop => Some((OpcodeClass::Nop, self.nop)),
 
             // Control flow class of opcodes is charged for each of the opcode individually.
-            Instruction::Block(_) => Some(self.control_flow.block),
-            Instruction::Loop(_) => Some(self.control_flow.op_loop),
-            Instruction::If(_) => Some(self.control_flow.op_if),
-            Instruction::Else => Some(self.control_flow.op_else),
-            Instruction::End => Some(self.control_flow.end),
-            Instruction::Br(_) => Some(self.control_flow.br),
-            Instruction::BrIf(_) => Some(self.control_flow.br_if),
+            Instruction::Block(_) => Some((OpcodeClass::ControlFlow, self.control_flow.block)),
+            Instruction::Loop(_) => Some((OpcodeClass::ControlFlow, self.control_flow.op_loop)),
+            Instruction::If(_) => Some((OpcodeClass::ControlFlow, self.control_flow.op_if)),
+            Instruction::Else => Some((OpcodeClass::ControlFlow, self.control_flow.op_else)),
+            Instruction::End => Some((OpcodeClass::ControlFlow, self.control_flow.end)),
+            Instruction::Br(_) => Some((OpcodeClass::ControlFlow, self.control_flow.br)),
+            Instruction::BrIf(_) => Some((OpcodeClass::ControlFlow, self.control_flow.br_if)),
             Instruction::BrTable(br_table_data) => {
                 // If we're unable to fit table size in `u32` to measure the cost, then such wasm
                 // would be rejected. This is unlikely scenario as we impose a limit
@@ -389,18 +452,22 @@ impl Rules for OpcodeCosts {
                     br_table_size.checked_mul(self.control_flow.br_table.size_multiplier)?;
 
                 let br_table_cost = br_table_cost.checked_add(table_size_part)?;
-                Some(br_table_cost)
+                Some((OpcodeClass::ControlFlow, br_table_cost))
             }
-            Instruction::Return => Some(self.control_flow.op_return),
-            Instruction::Call(_) => Some(self.control_flow.call),
-            Instruction::CallIndirect(_, _) => Some(self.control_flow.call_indirect),
-            Instruction::Drop => Some(self.control_flow.drop),
-            Instruction::Select => Some(self.control_flow.select),
+            Instruction::Return => Some((OpcodeClass::ControlFlow, self.control_flow.op_return)),
+            Instruction::Call(_) => Some((OpcodeClass::ControlFlow, self.control_flow.call)),
+            Instruction::CallIndirect(_, _) => {
+                Some((OpcodeClass::ControlFlow, self.control_flow.call_indirect))
+            }
+            Instruction::Drop => Some((OpcodeClass::ControlFlow, self.control_flow.drop)),
+            Instruction::Select => Some((OpcodeClass::ControlFlow, self.control_flow.select)),
 
             Instruction::GetLocal(_) | Instruction::SetLocal(_) | Instruction::TeeLocal(_) => {
-                Some(self.local)
+                Some((OpcodeClass::Local, self.local))
+            }
+            Instruction::GetGlobal(_) | Instruction::SetGlobal(_) => {
+                Some((OpcodeClass::Global, self.global))
             }
-            Instruction::GetGlobal(_) | Instruction::SetGlobal(_) => Some(self.global),
 
             Instruction::I32Load(_, _)
             | Instruction::I64Load(_, _)
@@ -415,7 +482,7 @@ impl Rules for OpcodeCosts {
             | Instruction::I64Load16S(_, _)
             | Instruction::I64Load16U(_, _)
             | Instruction::I64Load32S(_, _)
-            | Instruction::I64Load32U(_, _) => Some(self.load),
+            | Instruction::I64Load32U(_, _) => Some((OpcodeClass::Load, self.load)),
 
             Instruction::I32Store(_, _)
             | Instruction::I64Store(_, _)
@@ -425,12 +492,16 @@ impl Rules for OpcodeCosts {
             | Instruction::I32Store16(_, _)
             | Instruction::I64Store8(_, _)
             | Instruction::I64Store16(_, _)
-            | Instruction::I64Store32(_, _) => Some(self.store),
+            | Instruction::I64Store32(_, _) => Some((OpcodeClass::Store, self.store)),
 
-            Instruction::CurrentMemory(_) => Some(self.current_memory),
-            Instruction::GrowMemory(_) => Some(self.grow_memory),
+            Instruction::CurrentMemory(_) => {
+                Some((OpcodeClass::CurrentMemory, self.current_memory))
+            }
+            Instruction::GrowMemory(_) => Some((OpcodeClass::GrowMemory, self.grow_memory)),
 
-            Instruction::I32Const(_) | Instruction::I64Const(_) => Some(self.op_const),
+            Instruction::I32Const(_) | Instruction::I64Const(_) => {
+                Some((OpcodeClass::Const, self.op_const))
+            }
 
             Instruction::F32Const(_) | Instruction::F64Const(_) => None, // float_const
 
@@ -455,7 +526,9 @@ impl Rules for OpcodeCosts {
             | Instruction::I64LeS
             | Instruction::I64LeU
             | Instruction::I64GeS
-            | Instruction::I64GeU => Some(self.integer_comparison),
+            | Instruction::I64GeU => {
+                Some((OpcodeClass::IntegerComparison, self.integer_comparison))
+            }
 
             Instruction::F32Eq
             | Instruction::F32Ne
@@ -470,16 +543,18 @@ impl Rules for OpcodeCosts {
             | Instruction::F64Le
             | Instruction::F64Ge => None, // Unsupported comparison operators for floats.
 
-            Instruction::I32Clz | Instruction::I32Ctz | Instruction::I32Popcnt => Some(self.bit),
+            Instruction::I32Clz | Instruction::I32Ctz | Instruction::I32Popcnt => {
+                Some((OpcodeClass::Bit, self.bit))
+            }
 
-            Instruction::I32Add | Instruction::I32Sub => Some(self.add),
+            Instruction::I32Add | Instruction::I32Sub => Some((OpcodeClass::Add, self.add)),
 
-            Instruction::I32Mul => Some(self.mul),
+            Instruction::I32Mul => Some((OpcodeClass::Mul, self.mul)),
 
             Instruction::I32DivS
             | Instruction::I32DivU
             | Instruction::I32RemS
-            | Instruction::I32RemU => Some(self.div),
+            | Instruction::I32RemU => Some((OpcodeClass::Div, self.div)),
 
             Instruction::I32And
             | Instruction::I32Or
@@ -491,15 +566,15 @@ impl Rules for OpcodeCosts {
             | Instruction::I32Rotr
             | Instruction::I64Clz
             | Instruction::I64Ctz
-            | Instruction::I64Popcnt => Some(self.bit),
+            | Instruction::I64Popcnt => Some((OpcodeClass::Bit, self.bit)),
 
-            Instruction::I64Add | Instruction::I64Sub => Some(self.add),
-            Instruction::I64Mul => Some(self.mul),
+            Instruction::I64Add | Instruction::I64Sub => Some((OpcodeClass::Add, self.add)),
+            Instruction::I64Mul => Some((OpcodeClass::Mul, self.mul)),
 
             Instruction::I64DivS
             | Instruction::I64DivU
             | Instruction::I64RemS
-            | Instruction::I64RemU => Some(self.div),
+            | Instruction::I64RemU => Some((OpcodeClass::Div, self.div)),
 
             Instruction::I64And
             | Instruction::I64Or
@@ -508,7 +583,7 @@ impl Rules for OpcodeCosts {
             | Instruction::I64ShrS
             | Instruction::I64ShrU
             | Instruction::I64Rotl
-            | Instruction::I64Rotr => Some(self.bit),
+            | Instruction::I64Rotr => Some((OpcodeClass::Bit, self.bit)),
 
             Instruction::F32Abs
             | Instruction::F32Neg
@@ -540,7 +615,7 @@ impl Rules for OpcodeCosts {
             | Instruction::F64Copysign => None, // Unsupported math operators for floats.
 
             Instruction::I32WrapI64 | Instruction::I64ExtendSI32 | Instruction::I64ExtendUI32 => {
-                Some(self.conversion)
+                Some((OpcodeClass::Conversion, self.conversion))
             }
 
             Instruction::I32TruncSF32
@@ -569,6 +644,13 @@ impl Rules for OpcodeCosts {
                                                        * for floats. */
         }
     }
+}
+
+impl Rules for OpcodeCosts {
+    fn instruction_cost(&self, instruction: &Instruction) -> Option<u32> {
+        self.instruction_class(instruction)
+            .map(|(_class, cost)| cost)
+    }
 
     fn memory_grow_cost(&self) -> Option<MemoryGrowCost> {
         NonZeroU32::new(self.grow_memory).map(MemoryGrowCost::Linear)
@@ -11,6 +11,8 @@ pub mod trie;
 /// Merkle Trie storage.
 pub mod trie_store;
 
+/// The maximum number of named databases in an environment: the trie store, and the scratch
+/// database used by garbage collection.
 const MAX_DBS: u32 = 2;
 
 #[cfg(test)]
@@ -1,7 +1,15 @@
-use std::{collections::HashMap, ops::Deref, sync::Arc};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    ops::Deref,
+    sync::{Arc, Mutex},
+};
+
+use lmdb::{Cursor, DatabaseFlags, RwTransaction, WriteFlags};
+use tracing::info;
 
 use casper_hashing::Digest;
-use casper_types::{Key, StoredValue};
+use casper_types::{bytesrepr, Key, StoredValue};
 
 use crate::{
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
@@ -14,7 +22,8 @@ use crate::{
         store::Store,
         transaction_source::{lmdb::LmdbEnvironment, Transaction, TransactionSource},
         trie::{
-            merkle_proof::TrieMerkleProof, operations::create_hashed_empty_trie, Trie, TrieRaw,
+            lazy_trie_tag, merkle_proof::TrieMerkleProof, operations::create_hashed_empty_trie,
+            Trie, TrieRaw, TrieTag,
         },
         trie_store::{
             lmdb::{LmdbTrieStore, ScratchTrieStore},
@@ -35,6 +44,29 @@ pub struct LmdbGlobalState {
     // TODO: make this a lazy-static
     /// Empty root hash used for a new trie.
     pub(crate) empty_root_hash: Digest,
+    /// Roots written while a garbage collection is in progress, which it has to retain too.
+    ///
+    /// `None` while no garbage collection is in progress.  Every write to the trie store happens
+    /// while holding this lock, so that a garbage collection batch never interleaves with a write.
+    gc_written_roots: Mutex<Option<Vec<Digest>>>,
+}
+
+/// The maximum number of tries marked or checked for deletion within a single garbage collection
+/// transaction.
+const GARBAGE_COLLECTION_BATCH_SIZE: usize = 10_000;
+
+/// The name of the scratch database holding the tries marked as reachable by a garbage collection.
+const GARBAGE_COLLECTION_MARKS_DB_NAME: &str = "GC_MARKS";
+
+/// The outcome of a garbage collection pass over the trie store.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GarbageCollectionResult {
+    /// The number of tries which are reachable from the retained state roots.
+    pub retained_tries: u64,
+    /// The number of unreachable tries which were deleted.
+    pub deleted_tries: u64,
+    /// The total size in bytes of the deleted tries.
+    pub reclaimed_bytes: u64,
 }
 
 /// Represents a "view" of global state at a particular root hash.
@@ -75,7 +107,25 @@ impl LmdbGlobalState {
             environment,
             trie_store,
             empty_root_hash,
+            gc_written_roots: Mutex::new(None),
+        }
+    }
+
+    /// Runs `write`, which writes to the trie store, and records the root it returns if a garbage
+    /// collection is in progress.
+    fn write_and_record_root<F>(&self, write: F) -> Result<Digest, error::Error>
+    where
+        F: FnOnce() -> Result<Digest, error::Error>,
+    {
+        let mut gc_written_roots = self
+            .gc_written_roots
+            .lock()
+            .expect("garbage collection lock poisoned");
+        let root = write()?;
+        if let Some(roots) = gc_written_roots.as_mut() {
+            roots.push(root);
         }
+        Ok(root)
     }
 
     /// Creates an in-memory cache for changes written.
@@ -94,16 +144,18 @@ impl LmdbGlobalState {
         prestate_hash: Digest,
         stored_values: HashMap<Key, StoredValue>,
     ) -> Result<Digest, error::Error> {
-        let scratch_trie = self.get_scratch_store();
-        let new_state_root = put_stored_values::<_, _, error::Error>(
-            &scratch_trie,
-            &scratch_trie,
-            correlation_id,
-            prestate_hash,
-            stored_values,
-        )?;
-        scratch_trie.write_root_to_db(new_state_root)?;
-        Ok(new_state_root)
+        self.write_and_record_root(|| {
+            let scratch_trie = self.get_scratch_store();
+            let new_state_root = put_stored_values::<_, _, error::Error>(
+                &scratch_trie,
+                &scratch_trie,
+                correlation_id,
+                prestate_hash,
+                stored_values,
+            )?;
+            scratch_trie.write_root_to_db(new_state_root)?;
+            Ok(new_state_root)
+        })
     }
 
     /// Gets a scratch trie store.
@@ -127,6 +179,201 @@ impl LmdbGlobalState {
     pub fn empty_state_root_hash(&self) -> Digest {
         self.empty_root_hash
     }
+
+    /// Deletes every trie which is not reachable from any of `retained_roots`.
+    ///
+    /// The empty root hash is always retained.  Retained roots which are not present in the trie
+    /// store are ignored.
+    ///
+    /// The reachable tries are marked in a scratch database in the same environment rather than in
+    /// memory, so that the size of global state isn't bounded by the available memory.  Both the
+    /// marking and the subsequent sweep of the trie store happen in batches of at most
+    /// [`GARBAGE_COLLECTION_BATCH_SIZE`] tries, each in its own read-write transaction, so that
+    /// writers are only blocked for the duration of a single batch.  Roots written in the meantime
+    /// are marked before the next batch is swept.
+    pub fn collect_garbage(
+        &self,
+        correlation_id: CorrelationId,
+        retained_roots: &[Digest],
+    ) -> Result<GarbageCollectionResult, error::Error> {
+        self.collect_garbage_in_batches(
+            correlation_id,
+            retained_roots,
+            GARBAGE_COLLECTION_BATCH_SIZE,
+        )
+    }
+
+    fn collect_garbage_in_batches(
+        &self,
+        _correlation_id: CorrelationId,
+        retained_roots: &[Digest],
+        batch_size: usize,
+    ) -> Result<GarbageCollectionResult, error::Error> {
+        // Start recording the roots written from now on, before taking the snapshot to mark.
+        *self
+            .gc_written_roots
+            .lock()
+            .expect("garbage collection lock poisoned") = Some(vec![]);
+        let result = self.mark_and_sweep(retained_roots, batch_size);
+        *self
+            .gc_written_roots
+            .lock()
+            .expect("garbage collection lock poisoned") = None;
+        let result = result?;
+
+        if self.environment.is_manual_sync_enabled() {
+            self.environment.sync()?;
+        }
+        info!(
+            retained_tries = result.retained_tries,
+            deleted_tries = result.deleted_tries,
+            reclaimed_bytes = result.reclaimed_bytes,
+            "collected global state garbage"
+        );
+        Ok(result)
+    }
+
+    fn mark_and_sweep(
+        &self,
+        retained_roots: &[Digest],
+        batch_size: usize,
+    ) -> Result<GarbageCollectionResult, error::Error> {
+        let db = self.trie_store.get_db();
+        let marks_db = self.environment.env().create_db(
+            Some(GARBAGE_COLLECTION_MARKS_DB_NAME),
+            DatabaseFlags::empty(),
+        )?;
+        let mut result = GarbageCollectionResult::default();
+
+        // Mark: find every trie reachable from the retained roots, discarding any marks left by an
+        // interrupted garbage collection first.
+        let mut pending: Vec<Digest> = retained_roots
+            .iter()
+            .copied()
+            .chain(std::iter::once(self.empty_root_hash))
+            .collect();
+        let mut txn = self.environment.env().begin_rw_txn()?;
+        txn.clear_db(marks_db)?;
+        loop {
+            result.retained_tries +=
+                mark_reachable(&mut txn, db, marks_db, &mut pending, batch_size)?;
+            txn.commit()?;
+            if pending.is_empty() {
+                break;
+            }
+            txn = self.environment.env().begin_rw_txn()?;
+        }
+
+        // Sweep: delete every trie which wasn't marked, one batch at a time.
+        let mut last_key: Option<Digest> = None;
+        loop {
+            let mut gc_written_roots = self
+                .gc_written_roots
+                .lock()
+                .expect("garbage collection lock poisoned");
+            let mut txn = self.environment.env().begin_rw_txn()?;
+            if let Some(written_roots) = gc_written_roots.as_mut() {
+                let mut pending = written_roots.drain(..).collect();
+                result.retained_tries +=
+                    mark_reachable(&mut txn, db, marks_db, &mut pending, usize::MAX)?;
+            }
+
+            let mut unreachable = vec![];
+            let mut batch_len = 0;
+            {
+                let mut cursor = lmdb::Transaction::open_ro_cursor(&txn, db)?;
+                let rows = match last_key {
+                    Some(last_key) => cursor.iter_from(last_key),
+                    None => cursor.iter_start(),
+                };
+                for row in rows {
+                    let (raw_key, raw_val) = row?;
+                    let trie_key = Digest::try_from(raw_key)
+                        .map_err(|_| error::Error::BytesRepr(bytesrepr::Error::Formatting))?;
+                    if Some(trie_key) == last_key {
+                        continue;
+                    }
+                    if batch_len == batch_size {
+                        break;
+                    }
+                    batch_len += 1;
+                    last_key = Some(trie_key);
+                    if !is_marked(&txn, marks_db, &trie_key)? {
+                        unreachable.push(trie_key);
+                        result.reclaimed_bytes += (raw_key.len() + raw_val.len()) as u64;
+                    }
+                }
+            }
+            for trie_key in &unreachable {
+                txn.del(db, trie_key, None)?;
+            }
+            result.deleted_tries += unreachable.len() as u64;
+            txn.commit()?;
+            drop(gc_written_roots);
+
+            if batch_len < batch_size {
+                break;
+            }
+        }
+
+        let mut txn = self.environment.env().begin_rw_txn()?;
+        txn.clear_db(marks_db)?;
+        txn.commit()?;
+        Ok(result)
+    }
+}
+
+/// Marks the tries reachable from `pending` in `marks_db`, and returns the number of newly marked
+/// tries.
+///
+/// At most `max_visits` tries are visited, and those still to be visited are left in `pending`.
+/// Tries already marked are not visited again, and tries which are not present in the trie store
+/// are ignored.
+fn mark_reachable(
+    txn: &mut RwTransaction,
+    db: lmdb::Database,
+    marks_db: lmdb::Database,
+    pending: &mut Vec<Digest>,
+    max_visits: usize,
+) -> Result<u64, error::Error> {
+    let mut marked = 0;
+    let mut visits = 0;
+    while visits < max_visits {
+        let trie_key = match pending.pop() {
+            Some(trie_key) => trie_key,
+            None => break,
+        };
+        if is_marked(&*txn, marks_db, &trie_key)? {
+            continue;
+        }
+        visits += 1;
+        let trie_raw = match lmdb::Transaction::get(&*txn, db, &trie_key) {
+            Ok(trie_raw) => trie_raw,
+            Err(lmdb::Error::NotFound) => continue,
+            Err(error) => return Err(error.into()),
+        };
+        // Optimization: Don't deserialize leaves as they have no descendants.
+        if lazy_trie_tag(trie_raw) != Some(TrieTag::Leaf) {
+            let trie: Trie<Key, StoredValue> = bytesrepr::deserialize_from_slice(trie_raw)?;
+            pending.extend(trie.iter_children());
+        }
+        txn.put(marks_db, &trie_key, &[0_u8; 0], WriteFlags::empty())?;
+        marked += 1;
+    }
+    Ok(marked)
+}
+
+/// Returns `true` if the trie is marked as reachable in `marks_db`.
+fn is_marked<T: lmdb::Transaction>(
+    txn: &T,
+    marks_db: lmdb::Database,
+    trie_key: &Digest,
+) -> Result<bool, error::Error> {
+    match txn.get(marks_db, trie_key) {
+        Ok(_) => Ok(true),
+        Err(lmdb::Error::NotFound) => Ok(false),
+        Err(error) => Err(error.into()),
+    }
 }
 
 fn compute_empty_root_hash() -> Result<(Digest, Trie<Key, StoredValue>), error::Error> {
@@ -217,14 +464,15 @@ impl CommitProvider for LmdbGlobalState {
         prestate_hash: Digest,
         effects: AdditiveMap<Key, Transform>,
     ) -> Result<Digest, Self::Error> {
-        commit::<LmdbEnvironment, LmdbTrieStore, _, Self::Error>(
-            &self.environment,
-            &self.trie_store,
-            correlation_id,
-            prestate_hash,
-            effects,
-        )
-        .map_err(Into::into)
+        self.write_and_record_root(|| {
+            commit::<LmdbEnvironment, LmdbTrieStore, _, Self::Error>(
+                &self.environment,
+                &self.trie_store,
+                correlation_id,
+                prestate_hash,
+                effects,
+            )
+        })
     }
 }
 
@@ -263,16 +511,18 @@ impl StateProvider for LmdbGlobalState {
     }
 
     fn put_trie(&self, correlation_id: CorrelationId, trie: &[u8]) -> Result<Digest, Self::Error> {
-        let mut txn = self.environment.create_read_write_txn()?;
-        let trie_hash = put_trie::<
-            Key,
-            StoredValue,
-            lmdb::RwTransaction,
-            LmdbTrieStore,
-            Self::Error,
-        >(correlation_id, &mut txn, &self.trie_store, trie)?;
-        txn.commit()?;
-        Ok(trie_hash)
+        self.write_and_record_root(|| {
+            let mut txn = self.environment.create_read_write_txn()?;
+            let trie_hash = put_trie::<
+                Key,
+                StoredValue,
+                lmdb::RwTransaction,
+                LmdbTrieStore,
+                Self::Error,
+            >(correlation_id, &mut txn, &self.trie_store, trie)?;
+            txn.commit()?;
+            Ok(trie_hash)
+        })
     }
 
     /// Finds all of the keys of missing directly descendant `Trie<K,V>` values.
@@ -300,6 +550,10 @@ impl StateProvider for LmdbGlobalState {
         mut state_root_hash: Digest,
         keys: &[Key],
     ) -> Result<DeleteResult, Self::Error> {
+        let mut gc_written_roots = self
+            .gc_written_roots
+            .lock()
+            .expect("garbage collection lock poisoned");
         let scratch_trie_store = self.get_scratch_store();
 
         let mut txn = scratch_trie_store.create_read_write_txn()?;
@@ -323,6 +577,9 @@ impl StateProvider for LmdbGlobalState {
         txn.commit()?;
 
         scratch_trie_store.write_root_to_db(state_root_hash)?;
+        if let Some(roots) = gc_written_roots.as_mut() {
+            roots.push(state_root_hash);
+        }
         Ok(DeleteResult::Deleted(state_root_hash))
     }
 }
@@ -505,4 +762,104 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn collect_garbage_deletes_only_unreachable_tries() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state(create_test_pairs);
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &test_pairs_updated {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+        let updated_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+
+        // Retaining both roots deletes nothing.
+        let result = state
+            .collect_garbage(correlation_id, &[root_hash, updated_hash])
+            .unwrap();
+        assert_eq!(result.deleted_tries, 0);
+        assert_eq!(result.reclaimed_bytes, 0);
+
+        let result = state
+            .collect_garbage(correlation_id, &[updated_hash])
+            .unwrap();
+        assert!(result.deleted_tries > 0);
+        assert!(result.reclaimed_bytes > 0);
+        assert!(state.checkout(root_hash).unwrap().is_none());
+        assert!(state.checkout(state.empty_root_hash).unwrap().is_some());
+
+        let updated_checkout = state.checkout(updated_hash).unwrap().unwrap();
+        for TestPair { key, value } in test_pairs_updated.iter().cloned() {
+            assert_eq!(
+                Some(value),
+                updated_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+
+        // A second pass has nothing left to delete.
+        let second_result = state
+            .collect_garbage(correlation_id, &[updated_hash])
+            .unwrap();
+        assert_eq!(second_result.deleted_tries, 0);
+        assert_eq!(second_result.retained_tries, result.retained_tries);
+    }
+
+    #[test]
+    fn collect_garbage_in_batches_retains_roots_written_meanwhile() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state(create_test_pairs);
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &test_pairs_updated {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+        let updated_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+
+        // Sweeping one trie per batch deletes the same tries as a single batch would.
+        let result = state
+            .collect_garbage_in_batches(correlation_id, &[updated_hash], 1)
+            .unwrap();
+        assert!(result.deleted_tries > 0);
+        assert!(state.checkout(root_hash).unwrap().is_none());
+        assert!(state.checkout(updated_hash).unwrap().is_some());
+
+        // A root written while a garbage collection is in progress is retained, even though it
+        // isn't one of the retained roots.
+        *state.gc_written_roots.lock().unwrap() = Some(vec![]);
+        let recreated_hash = state
+            .put_stored_values(
+                correlation_id,
+                state.empty_root_hash,
+                create_test_pairs()
+                    .iter()
+                    .map(|TestPair { key, value }| (*key, value.clone()))
+                    .collect(),
+            )
+            .unwrap();
+        assert_eq!(recreated_hash, root_hash);
+        state.mark_and_sweep(&[updated_hash], 1).unwrap();
+        *state.gc_written_roots.lock().unwrap() = None;
+        assert!(state.checkout(root_hash).unwrap().is_some());
+
+        // No marks are left behind.
+        let marks_db = state
+            .environment
+            .env()
+            .open_db(Some(GARBAGE_COLLECTION_MARKS_DB_NAME))
+            .unwrap();
+        let txn = state.environment.env().begin_ro_txn().unwrap();
+        let mut cursor = lmdb::Transaction::open_ro_cursor(&txn, marks_db).unwrap();
+        assert_eq!(cursor.iter_start().count(), 0);
+    }
 }
@@ -22,7 +22,7 @@ use casper_execution_engine::{
             engine_config::RefundHandling,
             era_validators::GetEraValidatorsRequest,
             execute_request::ExecuteRequest,
-            execution_result::ExecutionResult,
+            execution_result::{ExecutionResult, ExecutionResults},
             run_genesis_request::RunGenesisRequest,
             step::{StepRequest, StepSuccess},
             BalanceResult, EngineConfig, EngineConfigBuilder, EngineState, Error, GenesisSuccess,
@@ -31,6 +31,7 @@ use casper_execution_engine::{
             DEFAULT_MAX_QUERY_DEPTH,
         },
         execution,
+        runtime::{profile::GasProfile, trace::ExecutionTrace},
     },
     shared::{
         additive_map::AdditiveMap,
@@ -101,6 +102,10 @@ pub struct WasmTestBuilder<S> {
     engine_state: Rc<EngineState<S>>,
     /// [`ExecutionResult`] is wrapped in [`Rc`] to work around a missing [`Clone`] implementation
     exec_results: Vec<Vec<Rc<ExecutionResult>>>,
+    /// Traces of the traced exec calls, keyed by the index of the exec call.
+    exec_traces: BTreeMap<usize, Vec<ExecutionTrace>>,
+    /// Gas profiles of the profiled exec calls, keyed by the index of the exec call.
+    exec_profiles: BTreeMap<usize, Vec<GasProfile>>,
     upgrade_results: Vec<Result<UpgradeSuccess, engine_state::Error>>,
     prune_results: Vec<Result<PruneResult, engine_state::Error>>,
     genesis_hash: Option<Digest>,
@@ -141,6 +146,8 @@ impl<S> Clone for WasmTestBuilder<S> {
         WasmTestBuilder {
             engine_state: Rc::clone(&self.engine_state),
             exec_results: self.exec_results.clone(),
+            exec_traces: self.exec_traces.clone(),
+            exec_profiles: self.exec_profiles.clone(),
             upgrade_results: self.upgrade_results.clone(),
             prune_results: self.prune_results.clone(),
             genesis_hash: self.genesis_hash,
@@ -168,6 +175,8 @@ impl InMemoryWasmTestBuilder {
 
         WasmTestBuilder {
             exec_results: Vec::new(),
+            exec_traces: BTreeMap::new(),
+            exec_profiles: BTreeMap::new(),
             upgrade_results: Vec::new(),
             prune_results: Vec::new(),
             engine_state: Rc::new(engine_state),
@@ -193,6 +202,8 @@ impl InMemoryWasmTestBuilder {
         WasmTestBuilder {
             engine_state: Rc::new(engine_state),
             exec_results: Vec::new(),
+            exec_traces: BTreeMap::new(),
+            exec_profiles: BTreeMap::new(),
             upgrade_results: Vec::new(),
             prune_results: Vec::new(),
             genesis_hash: maybe_post_state_hash,
@@ -316,6 +327,8 @@ impl LmdbWasmTestBuilder {
         WasmTestBuilder {
             engine_state: Rc::new(engine_state),
             exec_results: Vec::new(),
+            exec_traces: BTreeMap::new(),
+            exec_profiles: BTreeMap::new(),
             upgrade_results: Vec::new(),
             prune_results: Vec::new(),
             genesis_hash: None,
@@ -421,6 +434,8 @@ impl LmdbWasmTestBuilder {
         let mut builder = WasmTestBuilder {
             engine_state: Rc::new(engine_state),
             exec_results: Vec::new(),
+            exec_traces: BTreeMap::new(),
+            exec_profiles: BTreeMap::new(),
             upgrade_results: Vec::new(),
             prune_results: Vec::new(),
             genesis_hash: None,
@@ -730,22 +745,58 @@ where
             .engine_state
             .run_execute(CorrelationId::new(), exec_request);
         assert!(maybe_exec_results.is_ok());
-        // Parse deploy results
-        let execution_results = maybe_exec_results.as_ref().unwrap();
+        self.push_exec_results(maybe_exec_results.unwrap());
+        self
+    }
+
+    /// Runs an [`ExecuteRequest`], tracing the execution of each of its deploys.
+    ///
+    /// The traces can be retrieved with [`WasmTestBuilder::get_exec_trace`].
+    pub fn exec_with_trace(&mut self, mut exec_request: ExecuteRequest) -> &mut Self {
+        let exec_request = {
+            let hash = self.post_state_hash.expect("expected post_state_hash");
+            exec_request.parent_state_hash = hash;
+            exec_request
+        };
+
+        let (execution_results, traces) = self
+            .engine_state
+            .run_execute_with_trace(CorrelationId::new(), exec_request)
+            .expect("should run traced execution");
+        let _ = self.exec_traces.insert(self.exec_results.len(), traces);
+        self.push_exec_results(execution_results);
+        self
+    }
+
+    /// Runs an [`ExecuteRequest`], profiling the gas used by each of its deploys.
+    ///
+    /// The gas profiles can be retrieved with [`WasmTestBuilder::get_exec_profile`] or written to
+    /// files with [`WasmTestBuilder::write_exec_profile`].
+    pub fn exec_with_profile(&mut self, mut exec_request: ExecuteRequest) -> &mut Self {
+        let exec_request = {
+            let hash = self.post_state_hash.expect("expected post_state_hash");
+            exec_request.parent_state_hash = hash;
+            exec_request
+        };
+
+        let (execution_results, profiles) = self
+            .engine_state
+            .run_execute_with_profile(CorrelationId::new(), exec_request)
+            .expect("should run profiled execution");
+        let _ = self.exec_profiles.insert(self.exec_results.len(), profiles);
+        self.push_exec_results(execution_results);
+        self
+    }
+
+    fn push_exec_results(&mut self, execution_results: ExecutionResults) {
         // Cache transformations
         self.transforms.extend(
             execution_results
                 .iter()
                 .map(|res| res.execution_journal().clone()),
         );
-        self.exec_results.push(
-            maybe_exec_results
-                .unwrap()
-                .into_iter()
-                .map(Rc::new)
-                .collect(),
-        );
-        self
+        self.exec_results
+            .push(execution_results.into_iter().map(Rc::new).collect());
     }
 
     /// Commit effects of previous exec call on the latest post-state hash.
@@ -994,6 +1045,41 @@ where
         Some(exec_results.iter().map(Rc::clone).collect())
     }
 
+    /// Returns the traces of the deploys of a specific exec, if it was run with
+    /// [`WasmTestBuilder::exec_with_trace`].
+    pub fn get_exec_trace(&self, index: usize) -> Option<&[ExecutionTrace]> {
+        self.exec_traces.get(&index).map(Vec::as_slice)
+    }
+
+    /// Returns the gas profiles of the deploys of a specific exec, if it was run with
+    /// [`WasmTestBuilder::exec_with_profile`].
+    pub fn get_exec_profile(&self, index: usize) -> Option<&[GasProfile]> {
+        self.exec_profiles.get(&index).map(Vec::as_slice)
+    }
+
+    /// Writes the gas profiles of the deploys of a specific exec, which must have been run with
+    /// [`WasmTestBuilder::exec_with_profile`], to the given directory.
+    ///
+    /// The profile of each deploy is written both as JSON, to `gas_profile_<exec>_<deploy>.json`,
+    /// and as folded stacks for flamegraph tools, to `gas_profile_<exec>_<deploy>.folded`.
+    pub fn write_exec_profile<P: AsRef<Path>>(&self, index: usize, dir: P) -> &Self {
+        let dir = dir.as_ref();
+        let profiles = self
+            .get_exec_profile(index)
+            .expect("should have gas profiles");
+        fs::create_dir_all(dir)
+            .unwrap_or_else(|error| panic!("should create {}: {}", dir.display(), error));
+        for (deploy_index, profile) in profiles.iter().enumerate() {
+            let path = dir.join(format!("gas_profile_{}_{}", index, deploy_index));
+            let json = serde_json::to_string_pretty(profile).expect("should encode gas profile");
+            fs::write(path.with_extension("json"), json)
+                .unwrap_or_else(|error| panic!("should write {}: {}", path.display(), error));
+            fs::write(path.with_extension("folded"), profile.to_folded_stacks())
+                .unwrap_or_else(|error| panic!("should write {}: {}", path.display(), error));
+        }
+        self
+    }
+
     /// Returns a count of exec results.
     pub fn get_exec_results_count(&self) -> usize {
         self.exec_results.len()
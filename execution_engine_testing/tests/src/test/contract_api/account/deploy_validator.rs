@@ -0,0 +1,408 @@
+use assert_matches::assert_matches;
+
+use casper_engine_test_support::{
+    utils, DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, ARG_AMOUNT,
+    DEFAULT_ACCOUNT_ADDR, DEFAULT_PAYMENT, PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::{
+    engine_state::{EngineConfigBuilder, Error, ExecuteRequest},
+    execution,
+};
+use casper_types::{
+    account::{AccountHash, Weight, DEPLOY_VALIDATOR_KEY},
+    runtime_args,
+    system::mint,
+    Key, RuntimeArgs, U512,
+};
+
+const CONTRACT_ADD_ASSOCIATED_KEY: &str = "add_associated_key.wasm";
+const CONTRACT_SET_ACTION_THRESHOLDS: &str = "set_action_thresholds.wasm";
+const CONTRACT_DEPLOY_VALIDATOR: &str = "deploy_validator.wasm";
+const CONTRACT_SET_DEPLOY_VALIDATOR: &str = "set_deploy_validator.wasm";
+const CONTRACT_DO_NOTHING: &str = "do_nothing.wasm";
+const ARG_ACCOUNT: &str = "account";
+const ARG_WEIGHT: &str = "weight";
+const ARG_KEY_MANAGEMENT_THRESHOLD: &str = "key_management_threshold";
+const ARG_DEPLOY_THRESHOLD: &str = "deploy_threshold";
+const ARG_REQUIRED_KEY: &str = "required_key";
+const ARG_VALIDATOR_KEY: &str = "validator_key";
+const KEY_1: AccountHash = AccountHash::new([254; 32]);
+const KEY_2: AccountHash = AccountHash::new([253; 32]);
+const KEY_3: AccountHash = AccountHash::new([252; 32]);
+const KEY_4: AccountHash = AccountHash::new([251; 32]);
+
+fn deploy_request(
+    session_file: &str,
+    session_args: RuntimeArgs,
+    authorization_keys: &[AccountHash],
+    deploy_hash: u8,
+) -> ExecuteRequest {
+    let deploy = DeployItemBuilder::new()
+        .with_address(*DEFAULT_ACCOUNT_ADDR)
+        .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT, })
+        .with_session_code(session_file, session_args)
+        .with_authorization_keys(authorization_keys)
+        .with_deploy_hash([deploy_hash; 32])
+        .build();
+    ExecuteRequestBuilder::from_deploy_item(deploy).build()
+}
+
+fn do_nothing_request(authorization_keys: &[AccountHash], deploy_hash: u8) -> ExecuteRequest {
+    deploy_request(
+        CONTRACT_DO_NOTHING,
+        RuntimeArgs::default(),
+        authorization_keys,
+        deploy_hash,
+    )
+}
+
+/// Gives the default account two more keys of weight 1, and sets the given thresholds.
+fn setup_account(
+    builder: &mut InMemoryWasmTestBuilder,
+    key_management_threshold: Weight,
+    deploy_threshold: Weight,
+) {
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    for (key, deploy_hash) in [(KEY_1, 1), (KEY_2, 2)] {
+        let add_key_request = deploy_request(
+            CONTRACT_ADD_ASSOCIATED_KEY,
+            runtime_args! { ARG_ACCOUNT => key, ARG_WEIGHT => Weight::new(1) },
+            &[*DEFAULT_ACCOUNT_ADDR],
+            deploy_hash,
+        );
+        builder.exec(add_key_request).expect_success().commit();
+    }
+
+    let set_thresholds_request = deploy_request(
+        CONTRACT_SET_ACTION_THRESHOLDS,
+        runtime_args! {
+            ARG_KEY_MANAGEMENT_THRESHOLD => key_management_threshold,
+            ARG_DEPLOY_THRESHOLD => deploy_threshold,
+        },
+        &[*DEFAULT_ACCOUNT_ADDR, KEY_1],
+        3,
+    );
+    builder
+        .exec(set_thresholds_request)
+        .expect_success()
+        .commit();
+}
+
+/// Installs a deploy validator which only accepts deploys approved by `required_key`, using all the
+/// keys of the account.
+fn install_validator_requiring(builder: &mut InMemoryWasmTestBuilder, required_key: AccountHash) {
+    let install_request = deploy_request(
+        CONTRACT_DEPLOY_VALIDATOR,
+        runtime_args! { ARG_REQUIRED_KEY => required_key },
+        &[*DEFAULT_ACCOUNT_ADDR, KEY_1, KEY_2],
+        4,
+    );
+    builder.exec(install_request).expect_success().commit();
+
+    let account = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR);
+    assert!(account.named_keys().contains_key(DEPLOY_VALIDATOR_KEY));
+}
+
+/// Installs a deploy validator which only accepts deploys approved by `KEY_1`.
+fn install_validator(builder: &mut InMemoryWasmTestBuilder) {
+    install_validator_requiring(builder, KEY_1)
+}
+
+/// Puts `maybe_validator_key` under the deploy validator named key of the account, or removes it
+/// if `None`, using all the keys of the account.
+fn set_validator_key(
+    builder: &mut InMemoryWasmTestBuilder,
+    maybe_validator_key: Option<Key>,
+    deploy_hash: u8,
+) {
+    let set_request = deploy_request(
+        CONTRACT_SET_DEPLOY_VALIDATOR,
+        runtime_args! { ARG_VALIDATOR_KEY => maybe_validator_key },
+        &[*DEFAULT_ACCOUNT_ADDR, KEY_1, KEY_2],
+        deploy_hash,
+    );
+    builder.exec(set_request).expect_success().commit();
+
+    let account = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR);
+    assert_eq!(
+        account.named_keys().get(DEPLOY_VALIDATOR_KEY),
+        maybe_validator_key.as_ref()
+    );
+}
+
+/// Returns a builder whose account can only be managed with all of its three keys, requires two of
+/// them to deploy, and has a deploy validator installed.
+fn validated_builder() -> InMemoryWasmTestBuilder {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    setup_account(&mut builder, Weight::new(3), Weight::new(2));
+    install_validator(&mut builder);
+    builder
+}
+
+/// Returns the error of the last deploy, asserting that it was charged for.
+fn get_charged_error(builder: &InMemoryWasmTestBuilder) -> Error {
+    let response = builder
+        .get_last_exec_results()
+        .expect("should have exec response");
+    let result = response.get(0).expect("should have a result");
+    assert!(
+        !result.has_precondition_failure(),
+        "should not be a precondition failure"
+    );
+    assert!(result.cost().value() > U512::zero(), "should have a cost");
+    result.as_error().cloned().expect("should have an error")
+}
+
+fn assert_rejected_by_validator(builder: &InMemoryWasmTestBuilder) {
+    let error = get_charged_error(builder);
+    assert_matches!(error, Error::DeployValidatorRejected(_));
+}
+
+#[ignore]
+#[test]
+fn should_accept_deploy_below_deploy_threshold_approved_by_validator() {
+    let mut builder = validated_builder();
+
+    // KEY_1 alone doesn't meet the deploy threshold of 2, but satisfies the validator.
+    builder
+        .exec(do_nothing_request(&[KEY_1], 5))
+        .expect_success()
+        .commit();
+}
+
+#[ignore]
+#[test]
+fn should_reject_deploy_meeting_deploy_threshold_rejected_by_validator() {
+    let mut builder = validated_builder();
+
+    // The default key and KEY_2 meet the deploy threshold, but don't satisfy the validator.
+    builder
+        .exec(do_nothing_request(&[*DEFAULT_ACCOUNT_ADDR, KEY_2], 5))
+        .commit();
+    assert_rejected_by_validator(&builder);
+}
+
+#[ignore]
+#[test]
+fn should_reject_native_transfer_rejected_by_validator() {
+    let mut builder = validated_builder();
+
+    let transfer_request = |authorization_keys: &[AccountHash], deploy_hash: u8| {
+        let deploy = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_empty_payment_bytes(runtime_args! {})
+            .with_transfer_args(runtime_args! {
+                mint::ARG_TARGET => KEY_4,
+                mint::ARG_AMOUNT => U512::from(2_500_000_000u64),
+                mint::ARG_ID => <Option<u64>>::None,
+            })
+            .with_authorization_keys(authorization_keys)
+            .with_deploy_hash([deploy_hash; 32])
+            .build();
+        ExecuteRequestBuilder::from_deploy_item(deploy).build()
+    };
+
+    builder
+        .exec(transfer_request(&[*DEFAULT_ACCOUNT_ADDR, KEY_2], 5))
+        .commit();
+    assert_rejected_by_validator(&builder);
+
+    builder
+        .exec(transfer_request(&[KEY_1], 6))
+        .expect_success()
+        .commit();
+}
+
+#[ignore]
+#[test]
+fn should_reject_unassociated_key_despite_validator() {
+    let mut builder = validated_builder();
+
+    builder
+        .exec(do_nothing_request(&[KEY_1, KEY_3], 5))
+        .commit();
+    let response = builder
+        .get_last_exec_results()
+        .expect("should have exec response");
+    let precondition_failure = utils::get_precondition_failure(&response);
+    assert_matches!(precondition_failure, Error::Authorization);
+}
+
+#[ignore]
+#[test]
+fn should_enforce_key_management_threshold_for_deploys_approved_by_validator() {
+    let mut builder = validated_builder();
+
+    // The validator accepts the deploy, but KEY_1 alone can't manage the account's keys.
+    let add_key_request = deploy_request(
+        CONTRACT_ADD_ASSOCIATED_KEY,
+        runtime_args! { ARG_ACCOUNT => KEY_3, ARG_WEIGHT => Weight::new(1) },
+        &[KEY_1],
+        5,
+    );
+    builder.exec(add_key_request).commit();
+    let error = builder.get_error().expect("should have error");
+    assert_matches!(error, Error::Exec(execution::Error::Revert(_)));
+
+    let account = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR);
+    assert!(!account.associated_keys().contains_key(&KEY_3));
+}
+
+#[ignore]
+#[test]
+fn should_require_key_management_threshold_to_change_validator() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    setup_account(&mut builder, Weight::new(2), Weight::new(1));
+
+    let install_request = deploy_request(
+        CONTRACT_DEPLOY_VALIDATOR,
+        runtime_args! { ARG_REQUIRED_KEY => KEY_1 },
+        &[*DEFAULT_ACCOUNT_ADDR],
+        4,
+    );
+    builder.exec(install_request).commit();
+    let error = builder.get_error().expect("should have error");
+    assert_matches!(
+        error,
+        Error::Exec(execution::Error::DeployValidatorPermissionDenied)
+    );
+
+    let account = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR);
+    assert!(!account.named_keys().contains_key(DEPLOY_VALIDATOR_KEY));
+
+    install_validator(&mut builder);
+}
+
+#[ignore]
+#[test]
+fn should_check_deploy_threshold_without_validator() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    setup_account(&mut builder, Weight::new(2), Weight::new(2));
+
+    builder.exec(do_nothing_request(&[KEY_1], 4)).commit();
+    let response = builder
+        .get_last_exec_results()
+        .expect("should have exec response");
+    let precondition_failure = utils::get_precondition_failure(&response);
+    assert_matches!(
+        precondition_failure,
+        Error::Exec(execution::Error::DeploymentAuthorizationFailure)
+    );
+
+    builder
+        .exec(do_nothing_request(&[KEY_1, KEY_2], 5))
+        .expect_success()
+        .commit();
+}
+
+#[ignore]
+#[test]
+fn should_reject_deploy_when_validator_exceeds_gas_limit() {
+    let engine_config = EngineConfigBuilder::new()
+        .with_deploy_validator_gas_limit(1)
+        .build();
+    let mut builder = InMemoryWasmTestBuilder::new_with_config(engine_config);
+    setup_account(&mut builder, Weight::new(2), Weight::new(2));
+    install_validator(&mut builder);
+
+    builder.exec(do_nothing_request(&[KEY_1], 5)).commit();
+    let error = get_charged_error(&builder);
+    assert_matches!(
+        error,
+        Error::DeployValidatorRejected(message)
+            if message == execution::Error::GasLimit.to_string()
+    );
+}
+
+#[ignore]
+#[test]
+fn should_charge_account_for_deploy_rejected_by_validator() {
+    let mut builder = validated_builder();
+    let account = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR);
+    let balance_before = builder.get_purse_balance(account.main_purse());
+
+    builder
+        .exec(do_nothing_request(&[*DEFAULT_ACCOUNT_ADDR, KEY_2], 5))
+        .commit();
+    assert_rejected_by_validator(&builder);
+
+    let balance_after = builder.get_purse_balance(account.main_purse());
+    assert!(balance_after < balance_before);
+}
+
+#[ignore]
+#[test]
+fn should_charge_validator_gas_to_deploy_approved_by_validator() {
+    let mut unvalidated_builder = InMemoryWasmTestBuilder::default();
+    setup_account(&mut unvalidated_builder, Weight::new(3), Weight::new(2));
+    unvalidated_builder
+        .exec(do_nothing_request(&[KEY_1, KEY_2], 5))
+        .expect_success()
+        .commit();
+    let unvalidated_cost = unvalidated_builder.last_exec_gas_cost();
+
+    let mut builder = validated_builder();
+    builder
+        .exec(do_nothing_request(&[KEY_1, KEY_2], 5))
+        .expect_success()
+        .commit();
+    let validated_cost = builder.last_exec_gas_cost();
+
+    assert!(validated_cost > unvalidated_cost);
+}
+
+#[ignore]
+#[test]
+fn should_not_lock_account_out_with_validator_rejecting_every_deploy() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    setup_account(&mut builder, Weight::new(3), Weight::new(2));
+    // KEY_4 isn't associated with the account, so no deploy can satisfy the validator.
+    install_validator_requiring(&mut builder, KEY_4);
+
+    builder
+        .exec(do_nothing_request(&[KEY_1, KEY_2], 5))
+        .commit();
+    assert_rejected_by_validator(&builder);
+
+    // Keys meeting the key management threshold aren't subject to the validator, and can remove
+    // it.
+    builder
+        .exec(do_nothing_request(
+            &[*DEFAULT_ACCOUNT_ADDR, KEY_1, KEY_2],
+            6,
+        ))
+        .expect_success()
+        .commit();
+    set_validator_key(&mut builder, None, 7);
+
+    builder
+        .exec(do_nothing_request(&[KEY_1, KEY_2], 8))
+        .expect_success()
+        .commit();
+}
+
+#[ignore]
+#[test]
+fn should_fall_back_to_deploy_threshold_with_dangling_validator() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    setup_account(&mut builder, Weight::new(3), Weight::new(2));
+    set_validator_key(&mut builder, Some(Key::Hash([42; 32])), 4);
+
+    builder.exec(do_nothing_request(&[KEY_1], 5)).commit();
+    let response = builder
+        .get_last_exec_results()
+        .expect("should have exec response");
+    let precondition_failure = utils::get_precondition_failure(&response);
+    assert_matches!(
+        precondition_failure,
+        Error::Exec(execution::Error::DeploymentAuthorizationFailure)
+    );
+
+    builder
+        .exec(do_nothing_request(&[KEY_1, KEY_2], 6))
+        .expect_success()
+        .commit();
+
+    set_validator_key(&mut builder, None, 7);
+}
@@ -1,5 +1,6 @@
 mod associated_keys;
 mod authorized_keys;
+mod deploy_validator;
 mod key_management_thresholds;
 mod named_keys;
 mod named_keys_stored;
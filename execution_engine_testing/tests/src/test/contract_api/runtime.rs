@@ -7,7 +7,11 @@ use casper_engine_test_support::{
     DEFAULT_PAYMENT, PRODUCTION_RUN_GENESIS_REQUEST,
 };
 use casper_execution_engine::core::{runtime_context::RANDOM_BYTES_COUNT, ADDRESS_LENGTH};
-use casper_types::{crypto, runtime_args, RuntimeArgs, BLAKE2B_DIGEST_LENGTH};
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    crypto, runtime_args, CLValue, ContractEvent, ContractHash, Key, PublicKey, RuntimeArgs,
+    SecretKey, BLAKE2B_DIGEST_LENGTH,
+};
 
 const ARG_BYTES: &str = "bytes";
 const ARG_AMOUNT: &str = "amount";
@@ -21,6 +25,20 @@ const RANDOM_BYTES_RESULT: &str = "random_bytes_result";
 const RANDOM_BYTES_PAYMENT_WASM: &str = "random_bytes_payment.wasm";
 const RANDOM_BYTES_PAYMENT_RESULT: &str = "random_bytes_payment_result";
 
+const VERIFY_SIGNATURE_WASM: &str = "verify_signature.wasm";
+const VERIFY_SIGNATURE_RESULT: &str = "verify_signature_result";
+const ARG_MESSAGE: &str = "message";
+const ARG_SIGNATURE: &str = "signature";
+const ARG_PUBLIC_KEY: &str = "public_key";
+
+const EMIT_EVENT_WASM: &str = "emit_event.wasm";
+const EMIT_EVENT_HASH_KEY_NAME: &str = "emit_event_hash";
+const ENTRY_POINT_EMIT: &str = "emit";
+const ENTRY_POINT_EMIT_AND_REVERT: &str = "emit_and_revert";
+const ARG_TOPIC: &str = "topic";
+const INSTALLED_TOPIC: &str = "installed";
+const TOPIC: &str = "topic";
+
 fn get_value<const COUNT: usize>(builder: &InMemoryWasmTestBuilder, result: &str) -> [u8; COUNT] {
     let account = builder
         .get_account(*DEFAULT_ACCOUNT_ADDR)
@@ -130,3 +148,169 @@ fn should_hash() {
         assert_eq!(digest, expected_digest);
     }
 }
+
+fn verify_signature(
+    builder: &mut InMemoryWasmTestBuilder,
+    signed_message: &[u8],
+    verified_message: &[u8],
+    secret_key: &SecretKey,
+) -> bool {
+    let public_key = PublicKey::from(secret_key);
+    let signature = crypto::sign(signed_message, secret_key, &public_key);
+    let signature_bytes = Bytes::from(signature.to_bytes().expect("should serialize"));
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        VERIFY_SIGNATURE_WASM,
+        runtime_args! {
+            ARG_MESSAGE => Bytes::from(verified_message.to_vec()),
+            ARG_SIGNATURE => signature_bytes,
+            ARG_PUBLIC_KEY => public_key,
+        },
+    )
+    .build();
+
+    builder.exec(exec_request).commit().expect_success();
+
+    let account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have account");
+    let uref = account
+        .named_keys()
+        .get(VERIFY_SIGNATURE_RESULT)
+        .expect("should have value");
+
+    builder
+        .query(None, *uref, &[])
+        .expect("should query")
+        .as_cl_value()
+        .cloned()
+        .expect("should be CLValue")
+        .into_t()
+        .expect("should convert")
+}
+
+#[ignore]
+#[test]
+fn should_verify_signatures() {
+    const MESSAGE: &[u8] = b"message";
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let secret_keys = [
+        SecretKey::ed25519_from_bytes([1; 32]).expect("should create ed25519 key"),
+        SecretKey::secp256k1_from_bytes([2; 32]).expect("should create secp256k1 key"),
+    ];
+
+    for secret_key in &secret_keys {
+        assert!(verify_signature(&mut builder, MESSAGE, MESSAGE, secret_key));
+    }
+}
+
+#[ignore]
+#[test]
+fn should_reject_invalid_signatures() {
+    const MESSAGE: &[u8] = b"message";
+    const OTHER_MESSAGE: &[u8] = b"other message";
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let secret_keys = [
+        SecretKey::ed25519_from_bytes([1; 32]).expect("should create ed25519 key"),
+        SecretKey::secp256k1_from_bytes([2; 32]).expect("should create secp256k1 key"),
+    ];
+
+    for secret_key in &secret_keys {
+        assert!(!verify_signature(
+            &mut builder,
+            MESSAGE,
+            OTHER_MESSAGE,
+            secret_key
+        ));
+    }
+}
+
+fn install_emit_event_contract(builder: &mut InMemoryWasmTestBuilder) -> ContractHash {
+    let exec_request =
+        ExecuteRequestBuilder::standard(*DEFAULT_ACCOUNT_ADDR, EMIT_EVENT_WASM, RuntimeArgs::new())
+            .build();
+    builder.exec(exec_request).commit().expect_success();
+
+    builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(EMIT_EVENT_HASH_KEY_NAME)
+        .copied()
+        .and_then(Key::into_hash)
+        .map(ContractHash::new)
+        .expect("should have contract hash")
+}
+
+fn last_exec_events(builder: &InMemoryWasmTestBuilder) -> Vec<ContractEvent> {
+    let exec_results = builder
+        .get_last_exec_results()
+        .expect("should have exec results");
+    assert_eq!(exec_results.len(), 1);
+    exec_results[0].events().to_vec()
+}
+
+#[ignore]
+#[test]
+fn should_record_events_emitted_by_session_and_contract() {
+    const MESSAGE: &str = "message";
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let contract_hash = install_emit_event_contract(&mut builder);
+    let expected_event = ContractEvent {
+        emitter: Key::Account(*DEFAULT_ACCOUNT_ADDR),
+        topic: INSTALLED_TOPIC.to_string(),
+        payload: CLValue::from_t(contract_hash).expect("should create CLValue"),
+    };
+    assert_eq!(last_exec_events(&builder), vec![expected_event]);
+
+    let exec_request = ExecuteRequestBuilder::contract_call_by_hash(
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EMIT,
+        runtime_args! {
+            ARG_TOPIC => TOPIC,
+            ARG_MESSAGE => MESSAGE,
+        },
+    )
+    .build();
+    builder.exec(exec_request).commit().expect_success();
+
+    let expected_event = ContractEvent {
+        emitter: Key::from(contract_hash),
+        topic: TOPIC.to_string(),
+        payload: CLValue::from_t(MESSAGE.to_string()).expect("should create CLValue"),
+    };
+    assert_eq!(last_exec_events(&builder), vec![expected_event]);
+}
+
+#[ignore]
+#[test]
+fn should_discard_events_of_failed_execution() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let contract_hash = install_emit_event_contract(&mut builder);
+
+    let exec_request = ExecuteRequestBuilder::contract_call_by_hash(
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EMIT_AND_REVERT,
+        runtime_args! {
+            ARG_TOPIC => TOPIC,
+            ARG_MESSAGE => "message",
+        },
+    )
+    .build();
+    builder.exec(exec_request).commit().expect_failure();
+
+    assert!(last_exec_events(&builder).is_empty());
+}
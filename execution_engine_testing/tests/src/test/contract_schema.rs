@@ -0,0 +1,86 @@
+use assert_matches::assert_matches;
+
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::{engine_state::Error, execution};
+use casper_types::{
+    contracts, runtime_args, CLType, ContractHash, ContractSchema, Key, Parameter, RuntimeArgs,
+};
+
+const CONTRACT_SCHEMA_WASM: &str = "contract_schema.wasm";
+const HASH_KEY_NAME: &str = "contract_schema_hash";
+const ARG_MISMATCH: &str = "mismatch";
+
+fn expected_schema() -> ContractSchema {
+    let mut schema = ContractSchema::new();
+    schema.add_entry_point(
+        "set_value",
+        vec![Parameter::new("value", CLType::U64)],
+        CLType::Unit,
+    );
+    schema.add_named_key("value", CLType::U64);
+    schema.add_dictionary("values", CLType::U64);
+    schema.add_event("value_set", CLType::U64);
+    schema
+}
+
+#[ignore]
+#[test]
+fn should_store_contract_schema() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_SCHEMA_WASM,
+        runtime_args! { ARG_MISMATCH => false },
+    )
+    .build();
+    builder.exec(exec_request).commit().expect_success();
+
+    let contract_hash = builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(HASH_KEY_NAME)
+        .copied()
+        .and_then(Key::into_hash)
+        .map(ContractHash::new)
+        .expect("should have contract hash");
+
+    let schema: ContractSchema = builder
+        .query(None, Key::contract_schema(contract_hash), &[])
+        .expect("should query schema")
+        .as_cl_value()
+        .cloned()
+        .expect("should be CLValue")
+        .into_t()
+        .expect("should convert");
+    assert_eq!(schema, expected_schema());
+}
+
+#[ignore]
+#[test]
+fn should_not_add_contract_version_with_mismatched_schema() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_SCHEMA_WASM,
+        runtime_args! { ARG_MISMATCH => true },
+    )
+    .build();
+    builder.exec(exec_request).commit().expect_failure();
+
+    let error = builder.get_error().expect("should have error");
+    let error = assert_matches!(error, Error::Exec(execution::Error::Revert(e)) => e);
+    assert_eq!(error, contracts::Error::ContractSchemaMismatch.into());
+
+    assert!(builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(HASH_KEY_NAME)
+        .is_none());
+}
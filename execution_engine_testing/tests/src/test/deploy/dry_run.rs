@@ -0,0 +1,92 @@
+use assert_matches::assert_matches;
+
+use casper_engine_test_support::{
+    utils, DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    DEFAULT_PAYMENT, PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::{
+    core::engine_state::{Error, ExecuteRequest},
+    shared::newtypes::CorrelationId,
+};
+use casper_types::{account::AccountHash, runtime_args, system::mint, Key, RuntimeArgs, U512};
+
+const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([42u8; 32]);
+const DO_NOTHING_WASM: &str = "do_nothing.wasm";
+const ARG_AMOUNT: &str = "amount";
+
+fn do_nothing_request(
+    account_hash: AccountHash,
+    authorization_keys: &[AccountHash],
+) -> ExecuteRequest {
+    let deploy = DeployItemBuilder::new()
+        .with_address(account_hash)
+        .with_session_code(DO_NOTHING_WASM, RuntimeArgs::default())
+        .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT })
+        .with_authorization_keys(authorization_keys)
+        .with_deploy_hash([1; 32])
+        .build();
+
+    ExecuteRequestBuilder::new().push_deploy(deploy).build()
+}
+
+#[ignore]
+#[test]
+fn should_dry_run_without_approvals_or_sufficient_balance() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let fund_account_request = ExecuteRequestBuilder::transfer(
+        *DEFAULT_ACCOUNT_ADDR,
+        runtime_args! {
+            mint::ARG_TARGET => ACCOUNT_1_ADDR,
+            mint::ARG_AMOUNT => U512::one(),
+            mint::ARG_ID => <Option<u64>>::None
+        },
+    )
+    .build();
+    builder.exec(fund_account_request).expect_success().commit();
+
+    builder
+        .exec(do_nothing_request(ACCOUNT_1_ADDR, &[ACCOUNT_1_ADDR]))
+        .commit();
+    let response = builder
+        .get_exec_result_owned(1)
+        .expect("there should be a response");
+    let precondition_failure = utils::get_precondition_failure(&response);
+    assert_matches!(precondition_failure, Error::InsufficientPayment);
+
+    let mut dry_run_request = do_nothing_request(ACCOUNT_1_ADDR, &[]);
+    dry_run_request.parent_state_hash = builder.get_post_state_hash();
+    let results = builder
+        .get_engine_state()
+        .run_dry_run(CorrelationId::new(), dry_run_request)
+        .expect("should dry run");
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_success());
+}
+
+#[ignore]
+#[test]
+fn should_not_take_payment_in_dry_run() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let main_purse = builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .main_purse();
+    let main_purse_balance_key = Key::Balance(main_purse.addr());
+
+    let mut dry_run_request = do_nothing_request(*DEFAULT_ACCOUNT_ADDR, &[*DEFAULT_ACCOUNT_ADDR]);
+    dry_run_request.parent_state_hash = builder.get_post_state_hash();
+    let results = builder
+        .get_engine_state()
+        .run_dry_run(CorrelationId::new(), dry_run_request)
+        .expect("should dry run");
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_success());
+    assert!(!results[0].cost().value().is_zero());
+    assert!(results[0]
+        .execution_journal()
+        .iter()
+        .all(|(key, _transform)| *key != main_purse_balance_key));
+}
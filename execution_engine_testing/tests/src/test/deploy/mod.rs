@@ -1,4 +1,5 @@
 mod context_association;
+mod dry_run;
 mod non_standard_payment;
 mod preconditions;
 mod receipts;
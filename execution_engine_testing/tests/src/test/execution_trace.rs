@@ -0,0 +1,109 @@
+use casper_engine_test_support::{
+    DeployItemBuilder, ExecuteRequestBuilder, DEFAULT_ACCOUNT_ADDR, DEFAULT_PAYMENT,
+};
+use casper_execution_engine::core::{
+    engine_state::ExecuteRequest,
+    runtime::trace::{FrameKind, TraceEvent},
+};
+use casper_types::{runtime_args, RuntimeArgs};
+
+use super::nested_contract_call::{
+    call_stored_contract_request, setup, CALL_VERSIONED_CONTRACT, DO_NOTHING_STORED_CONTRACT_NAME,
+    ENTRY_FUNCTION_NAME,
+};
+
+const ARG_AMOUNT: &str = "amount";
+const LOAD_NAMED_KEYS: &str = "casper_load_named_keys";
+
+#[ignore]
+#[test]
+fn should_trace_nested_contract_call() {
+    let (mut builder, contract_package_hash) = setup();
+
+    builder
+        .exec_with_trace(call_stored_contract_request(contract_package_hash))
+        .expect_success();
+
+    let traces = builder.get_exec_trace(1).expect("should have traces");
+    assert_eq!(traces.len(), 1);
+    let frames = traces[0].frames();
+    assert_eq!(*frames[0].kind(), FrameKind::Payment);
+
+    let session = frames
+        .iter()
+        .find(|frame| *frame.kind() == FrameKind::Session)
+        .expect("should have session frame");
+    assert!(session.error().is_none());
+
+    let call_versioned_contract = session
+        .events()
+        .iter()
+        .find_map(|event| match event {
+            TraceEvent::HostFunction(call) if call.name() == CALL_VERSIONED_CONTRACT => Some(call),
+            _ => None,
+        })
+        .expect("should have called versioned contract");
+
+    let calls = session.calls();
+    assert_eq!(calls.len(), 1);
+    let called = calls[0];
+    match called.kind() {
+        FrameKind::StoredContract {
+            contract_package_hash: called_package_hash,
+            entry_point,
+            ..
+        } => {
+            assert_eq!(*called_package_hash, contract_package_hash);
+            assert_eq!(entry_point, ENTRY_FUNCTION_NAME);
+        }
+        kind => panic!("unexpected frame kind {:?}", kind),
+    }
+    assert!(called.error().is_none());
+    assert!(!called.gas_used().is_zero());
+    assert!(call_versioned_contract.gas_used() >= called.gas_used());
+    assert!(session.gas_used() > call_versioned_contract.gas_used());
+    assert!(called.events().iter().any(|event| {
+        matches!(event, TraceEvent::HostFunction(call) if call.name() == LOAD_NAMED_KEYS)
+    }));
+}
+
+#[ignore]
+#[test]
+fn should_not_change_execution_when_tracing() {
+    let (mut builder, _contract_package_hash) = setup();
+
+    assert!(builder.get_exec_trace(0).is_none());
+
+    let install_request = || -> ExecuteRequest {
+        let deploy = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_session_code(DO_NOTHING_STORED_CONTRACT_NAME, RuntimeArgs::default())
+            .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT })
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+            .with_deploy_hash([42; 32])
+            .build();
+        ExecuteRequestBuilder::new().push_deploy(deploy).build()
+    };
+
+    let results_without_trace = builder
+        .clone()
+        .exec(install_request())
+        .expect_success()
+        .get_last_exec_results()
+        .expect("should have results");
+
+    builder.exec_with_trace(install_request()).expect_success();
+    let results_with_trace = builder
+        .get_last_exec_results()
+        .expect("should have results");
+
+    assert_eq!(
+        results_without_trace[0].execution_journal(),
+        results_with_trace[0].execution_journal()
+    );
+    assert_eq!(
+        results_without_trace[0].cost(),
+        results_with_trace[0].cost()
+    );
+    assert!(!builder.get_exec_trace(1).expect("should have traces")[0].is_empty());
+}
@@ -0,0 +1,108 @@
+use casper_execution_engine::core::runtime::profile::GasProfile;
+use casper_types::U512;
+
+use super::nested_contract_call::{
+    call_stored_contract_request, setup, CALL_VERSIONED_CONTRACT, ENTRY_FUNCTION_NAME,
+};
+
+fn attributed_gas(profile: &GasProfile) -> U512 {
+    let opcodes = profile
+        .opcodes()
+        .values()
+        .fold(U512::zero(), |total, gas| total + *gas);
+    let host_functions = profile
+        .host_functions()
+        .values()
+        .fold(U512::zero(), |total, host_function| {
+            total + host_function.gas()
+        });
+    opcodes + host_functions + profile.storage().gas() + profile.system_contract_calls()
+}
+
+#[ignore]
+#[test]
+fn should_profile_nested_contract_call() {
+    let (mut builder, contract_package_hash) = setup();
+
+    builder
+        .exec_with_profile(call_stored_contract_request(contract_package_hash))
+        .expect_success();
+
+    let profiles = builder.get_exec_profile(1).expect("should have profiles");
+    assert_eq!(profiles.len(), 1);
+    let profile = &profiles[0];
+
+    assert!(profile.entry_points().contains_key("payment"));
+    let session = &profile.entry_points()["session"];
+    assert_eq!(session.calls(), 1);
+
+    let (called_name, called) = profile
+        .entry_points()
+        .iter()
+        .find(|(name, _)| name.ends_with(&format!("::{}", ENTRY_FUNCTION_NAME)))
+        .expect("should have profiled called entry point");
+    assert!(called_name.starts_with("contract-"));
+    assert_eq!(called.calls(), 1);
+    assert!(!called.gas().is_zero());
+    assert!(session.gas() > called.gas());
+
+    assert_eq!(profile.host_functions()[CALL_VERSIONED_CONTRACT].calls(), 1);
+    assert!(!profile.opcodes().is_empty());
+    assert_eq!(attributed_gas(profile), profile.total_gas());
+
+    let folded_stacks = profile.to_folded_stacks();
+    let nested_stack_prefix = format!("session;host:{};{};", CALL_VERSIONED_CONTRACT, called_name);
+    assert!(folded_stacks
+        .lines()
+        .any(|line| line.starts_with(&nested_stack_prefix)));
+}
+
+#[ignore]
+#[test]
+fn should_not_change_execution_when_profiling() {
+    let (mut builder, contract_package_hash) = setup();
+
+    let results_without_profile = builder
+        .clone()
+        .exec(call_stored_contract_request(contract_package_hash))
+        .expect_success()
+        .get_last_exec_results()
+        .expect("should have results");
+
+    builder
+        .exec_with_profile(call_stored_contract_request(contract_package_hash))
+        .expect_success();
+    let results_with_profile = builder
+        .get_last_exec_results()
+        .expect("should have results");
+
+    assert_eq!(
+        results_without_profile[0].execution_journal(),
+        results_with_profile[0].execution_journal()
+    );
+    assert_eq!(
+        results_without_profile[0].cost(),
+        results_with_profile[0].cost()
+    );
+}
+
+#[ignore]
+#[test]
+fn should_write_profile_files() {
+    let (mut builder, contract_package_hash) = setup();
+    let dir = tempfile::tempdir().expect("should create temp dir");
+
+    builder
+        .exec_with_profile(call_stored_contract_request(contract_package_hash))
+        .expect_success()
+        .write_exec_profile(1, dir.path());
+
+    let json = std::fs::read_to_string(dir.path().join("gas_profile_1_0.json"))
+        .expect("should read JSON profile");
+    let profile: GasProfile = serde_json::from_str(&json).expect("should decode JSON profile");
+    assert_eq!(profile, builder.get_exec_profile(1).unwrap()[0]);
+
+    let folded_stacks = std::fs::read_to_string(dir.path().join("gas_profile_1_0.folded"))
+        .expect("should read folded stacks");
+    assert_eq!(folded_stacks, profile.to_folded_stacks());
+}
@@ -3,13 +3,17 @@ mod chainspec_registry;
 mod check_transfer_success;
 mod contract_api;
 mod contract_context;
+mod contract_schema;
 mod deploy;
+mod execution_trace;
 mod explorer;
 mod gas_counter;
+mod gas_profile;
 mod get_balance;
 mod groups;
 mod host_function_costs;
 mod manage_groups;
+mod nested_contract_call;
 mod private_chain;
 mod regression;
 mod step;
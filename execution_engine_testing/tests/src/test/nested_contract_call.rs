@@ -0,0 +1,59 @@
+//! Shared fixture for tests observing a session which calls into a stored contract, as used by the
+//! execution trace and gas profile tests.
+
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::engine_state::ExecuteRequest;
+use casper_types::{
+    contracts::CONTRACT_INITIAL_VERSION, runtime_args, ContractPackageHash, Key, RuntimeArgs,
+};
+
+pub const DO_NOTHING_STORED_CONTRACT_NAME: &str = "do_nothing_stored.wasm";
+pub const DO_NOTHING_STORED_CALLER_CONTRACT_NAME: &str = "do_nothing_stored_caller.wasm";
+pub const DO_NOTHING_PACKAGE_HASH_KEY_NAME: &str = "do_nothing_package_hash";
+pub const ENTRY_FUNCTION_NAME: &str = "delegate";
+pub const ARG_CONTRACT_PACKAGE: &str = "contract_package";
+pub const ARG_VERSION: &str = "version";
+pub const ARG_NEW_PURSE_NAME: &str = "new_purse_name";
+pub const CALL_VERSIONED_CONTRACT: &str = "casper_call_versioned_contract";
+
+/// Runs genesis and installs the stored `do_nothing` contract, returning its package hash.
+pub fn setup() -> (InMemoryWasmTestBuilder, ContractPackageHash) {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        DO_NOTHING_STORED_CONTRACT_NAME,
+        RuntimeArgs::default(),
+    )
+    .build();
+    builder.exec(exec_request).expect_success().commit();
+
+    let contract_package_hash = builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(DO_NOTHING_PACKAGE_HASH_KEY_NAME)
+        .copied()
+        .and_then(Key::into_hash)
+        .map(ContractPackageHash::new)
+        .expect("should have contract package hash");
+
+    (builder, contract_package_hash)
+}
+
+/// Returns a request whose session calls the stored `do_nothing` contract installed by `setup`.
+pub fn call_stored_contract_request(contract_package_hash: ContractPackageHash) -> ExecuteRequest {
+    ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        DO_NOTHING_STORED_CALLER_CONTRACT_NAME,
+        runtime_args! {
+            ARG_CONTRACT_PACKAGE => contract_package_hash,
+            ARG_VERSION => CONTRACT_INITIAL_VERSION,
+            ARG_NEW_PURSE_NAME => "purse",
+        },
+    )
+    .build()
+}
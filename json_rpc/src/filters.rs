@@ -7,6 +7,7 @@
 mod tests;
 
 use bytes::Bytes;
+use futures::future;
 use http::{header::CONTENT_TYPE, HeaderMap, StatusCode};
 use serde_json::{json, Map, Value};
 use tracing::{debug, trace, warn};
@@ -14,14 +15,14 @@ use warp::{
     body,
     filters::BoxedFilter,
     reject::{self, Rejection},
-    reply::{self, WithStatus},
+    reply::{self, Reply, WithStatus},
     Filter,
 };
 
 use crate::{
     error::{Error, ReservedErrorCode},
     rejections::{BodyTooLarge, MissingContentTypeHeader, MissingId, UnsupportedMediaType},
-    request::{ErrorOrRejection, Request},
+    request::{BatchElement, ErrorOrRejection, Request},
     request_handlers::RequestHandlers,
     response::Response,
 };
@@ -66,8 +67,8 @@ pub fn base_filter<P: AsRef<str>>(path: P, max_body_bytes: u32) -> BoxedFilter<(
         .boxed()
 }
 
-/// Handles parsing a JSON-RPC request from the given HTTP body, executing it using the appropriate
-/// handler, and providing a JSON-RPC response (which could be a success or failure).
+/// Handles executing a single JSON-RPC request using the appropriate handler, and providing a
+/// JSON-RPC response (which could be a success or failure).
 ///
 /// Returns an `Err(Rejection)` only if the request is a Notification as per the JSON-RPC
 /// specification, i.e. the request doesn't contain an "id" field.  In this case, no JSON-RPC
@@ -75,53 +76,194 @@ pub fn base_filter<P: AsRef<str>>(path: P, max_body_bytes: u32) -> BoxedFilter<(
 ///
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
+async fn handle_request(
+    unvalidated_request: Map<String, Value>,
+    handlers: &RequestHandlers,
+    allow_unknown_fields: bool,
+) -> Result<Response, Rejection> {
+    let response = match Request::new(unvalidated_request, allow_unknown_fields) {
+        Ok(request) => handlers.handle_request(request).await,
+        Err(ErrorOrRejection::Error { id, error }) => {
+            debug!(?error, "got an invalid request");
+            Response::new_failure(id, error)
+        }
+        Err(ErrorOrRejection::Rejection(rejection)) => {
+            debug!(?rejection, "rejecting an invalid request");
+            return Err(rejection);
+        }
+    };
+    Ok(response)
+}
+
+/// Handles executing a single element of a batch of JSON-RPC requests.
+///
+/// Returns `None` if the element is a valid Notification, as no response should be sent for it.
+async fn handle_batch_element(
+    unvalidated_request: Value,
+    handlers: &RequestHandlers,
+    allow_unknown_fields: bool,
+) -> Option<Response> {
+    let unvalidated_request = match unvalidated_request {
+        Value::Object(unvalidated_request) => unvalidated_request,
+        _ => {
+            debug!("got a batch element which is not an object");
+            let error = Error::new(
+                ReservedErrorCode::InvalidRequest,
+                "Expected each batch element to be a JSON-RPC request object",
+            );
+            return Some(Response::new_failure(Value::Null, error));
+        }
+    };
+
+    match Request::new_batch_element(unvalidated_request, allow_unknown_fields) {
+        Ok(BatchElement::Request(request)) => Some(handlers.handle_request(request).await),
+        Ok(BatchElement::Notification(notification)) => {
+            let _ = handlers.handle_request(notification).await;
+            None
+        }
+        Err(ErrorOrRejection::Error { id, error }) => {
+            debug!(?error, "got an invalid request in batch");
+            Some(Response::new_failure(id, error))
+        }
+        Err(ErrorOrRejection::Rejection(rejection)) => {
+            // Batch elements are never rejected since Notifications are valid within a batch, but
+            // if one is, treat it as requiring no response.
+            debug!(?rejection, "rejecting an invalid request in batch");
+            None
+        }
+    }
+}
+
+/// Handles executing a batch of JSON-RPC requests, returning the JSON-RPC responses in the same
+/// order as the requests.
+///
+/// As per the JSON-RPC specification, Notifications (requests without an "id" field) within the
+/// batch are executed, but no response is included for them.  Hence the returned collection may be
+/// empty.
+///
+/// The requests are executed concurrently.  The batch must contain at least one element, and no
+/// more than `max_batch_size` elements, otherwise a single JSON-RPC failure response is returned.
+async fn handle_batch(
+    unvalidated_requests: Vec<Value>,
+    handlers: &RequestHandlers,
+    allow_unknown_fields: bool,
+    max_batch_size: usize,
+) -> Result<Vec<Response>, Response> {
+    if unvalidated_requests.is_empty() {
+        debug!("got an empty batch");
+        let error = Error::new(ReservedErrorCode::InvalidRequest, "Empty batch");
+        return Err(Response::new_failure(Value::Null, error));
+    }
+    if unvalidated_requests.len() > max_batch_size {
+        debug!(
+            batch_size = unvalidated_requests.len(),
+            max_batch_size, "got a batch which is too large"
+        );
+        let error = Error::new(
+            ReservedErrorCode::InvalidRequest,
+            format!(
+                "Batch of {} requests exceeds the maximum allowed of {}",
+                unvalidated_requests.len(),
+                max_batch_size
+            ),
+        );
+        return Err(Response::new_failure(Value::Null, error));
+    }
+
+    let maybe_responses = unvalidated_requests.into_iter().map(|unvalidated_request| {
+        handle_batch_element(unvalidated_request, handlers, allow_unknown_fields)
+    });
+
+    Ok(future::join_all(maybe_responses)
+        .await
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+/// Handles parsing a JSON-RPC request or batch of requests from the given HTTP body, executing them
+/// using the appropriate handlers, and providing a reply.
+///
+/// Returns an `Err(Rejection)` only if the body is a single request which is a Notification as per
+/// the JSON-RPC specification, i.e. the request doesn't contain an "id" field.  In this case, no
+/// JSON-RPC response is sent to the client.
+///
+/// If the body is a batch consisting solely of Notifications, an empty HTTP 204 (no content) reply
+/// is returned.
+///
+/// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
+/// respond with an error.
 async fn handle_body(
     body: Bytes,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
-) -> Result<Response, Rejection> {
-    let response = match serde_json::from_slice::<Map<String, Value>>(&body) {
-        Ok(unvalidated_request) => match Request::new(unvalidated_request, allow_unknown_fields) {
-            Ok(request) => handlers.handle_request(request).await,
-            Err(ErrorOrRejection::Error { id, error }) => {
-                debug!(?error, "got an invalid request");
-                Response::new_failure(id, error)
-            }
-            Err(ErrorOrRejection::Rejection(rejection)) => {
-                debug!(?rejection, "rejecting an invalid request");
-                return Err(rejection);
+    max_batch_size: usize,
+) -> Result<reply::Response, Rejection> {
+    let json = match serde_json::from_slice::<Value>(&body) {
+        Ok(Value::Object(unvalidated_request)) => {
+            let response =
+                handle_request(unvalidated_request, &handlers, allow_unknown_fields).await?;
+            reply::json(&response)
+        }
+        Ok(Value::Array(unvalidated_requests)) => {
+            match handle_batch(
+                unvalidated_requests,
+                &handlers,
+                allow_unknown_fields,
+                max_batch_size,
+            )
+            .await
+            {
+                Ok(responses) if responses.is_empty() => {
+                    return Ok(StatusCode::NO_CONTENT.into_response());
+                }
+                Ok(responses) => reply::json(&responses),
+                Err(response) => reply::json(&response),
             }
-        },
+        }
+        Ok(_) => {
+            debug!("got json which is neither an object nor an array");
+            let error = Error::new(
+                ReservedErrorCode::InvalidRequest,
+                "Expected a JSON-RPC request object or a batch array of request objects",
+            );
+            reply::json(&Response::new_failure(Value::Null, error))
+        }
         Err(error) => {
             debug!(%error, "got bad json");
             let error = Error::new(ReservedErrorCode::ParseError, error.to_string());
-            Response::new_failure(Value::Null, error)
+            reply::json(&Response::new_failure(Value::Null, error))
         }
     };
-    Ok(response)
+    Ok(reply::with_status(json, StatusCode::OK).into_response())
 }
 
-/// Returns a boxed warp filter which handles parsing a JSON-RPC request from the given HTTP body,
-/// executing it using the appropriate handler, and providing a reply.
+/// Returns a boxed warp filter which handles parsing a JSON-RPC request or batch of requests from
+/// the given HTTP body, executing them using the appropriate handlers, and providing a reply.
 ///
-/// The reply will normally be built from a JSON-RPC response (which could be a success or failure).
+/// The reply will normally be built from a JSON-RPC response (which could be a success or failure),
+/// or from an array of JSON-RPC responses if the body is a batch.
 ///
 /// However, the reply could be built from a [`Rejection`] if the request is a Notification as per
 /// the JSON-RPC specification, i.e. the request doesn't contain an "id" field.  In this case, no
-/// JSON-RPC response is sent to the client, only an HTTP response.
+/// JSON-RPC response is sent to the client, only an HTTP response.  Notifications within a batch
+/// are executed, but omitted from the reply.
 ///
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
+///
+/// Batches containing more than `max_batch_size` requests are not executed; instead the server
+/// responds with an error.
 pub fn main_filter(
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
-) -> BoxedFilter<(WithStatus<reply::Json>,)> {
+    max_batch_size: usize,
+) -> BoxedFilter<(reply::Response,)> {
     body::bytes()
         .and_then(move |body| {
             let handlers = handlers.clone();
-            async move { handle_body(body, handlers, allow_unknown_fields).await }
+            async move { handle_body(body, handlers, allow_unknown_fields, max_batch_size).await }
         })
-        .map(|response| reply::with_status(reply::json(&response), StatusCode::OK))
         .boxed()
 }
 
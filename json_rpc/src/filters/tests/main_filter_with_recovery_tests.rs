@@ -16,6 +16,7 @@ use crate::{
 
 const GET_GOOD_THING: &str = "get good thing";
 const GET_BAD_THING: &str = "get bad thing";
+const MAX_BATCH_SIZE: usize = 3;
 
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug)]
 struct GoodThing {
@@ -49,13 +50,18 @@ async fn from_http_response(response: http::Response<hyper::Body>) -> Response {
     serde_json::from_slice(&body_bytes).unwrap()
 }
 
+async fn batch_from_http_response(response: http::Response<hyper::Body>) -> Vec<Response> {
+    let body_bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    serde_json::from_slice(&body_bytes).unwrap()
+}
+
 fn main_filter_with_recovery() -> BoxedFilter<(impl Reply,)> {
     let mut handlers = RequestHandlersBuilder::new();
     handlers.register_handler(GET_GOOD_THING, Arc::new(get_good_thing));
     handlers.register_handler(GET_BAD_THING, Arc::new(get_bad_thing));
     let handlers = handlers.build();
 
-    main_filter(handlers, false)
+    main_filter(handlers, false, MAX_BATCH_SIZE)
         .recover(handle_rejection)
         .boxed()
 }
@@ -318,3 +324,228 @@ async fn should_handle_invalid_json() {
         )
     );
 }
+
+#[tokio::test]
+async fn should_handle_json_which_is_neither_object_nor_array() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // This should get handled by `filters::handle_body` and return Response::Failure (invalid
+    // request) to the client as it is valid JSON, but neither a request nor a batch.
+    let http_response = warp::test::request()
+        .body(r#"1"#)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_response = from_http_response(http_response).await;
+    assert_eq!(rpc_response.id(), &Value::Null);
+    assert_eq!(
+        rpc_response.error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Expected a JSON-RPC request object or a batch array of request objects"
+        )
+    );
+}
+
+#[tokio::test]
+async fn should_handle_valid_batch() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // This should get handled by `filters::handle_batch`, returning one response per request in the
+    // same order as the requests, with per-element errors.
+    let http_response = warp::test::request()
+        .body(
+            r#"[
+                {"jsonrpc":"2.0","id":"a","method":"get good thing","params":["one"]},
+                {"jsonrpc":"2.0","id":"b","method":"get good thing"},
+                {"jsonrpc":"2.0","id":"c","method":"get good thing","params":["three"]}
+            ]"#,
+        )
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_responses = batch_from_http_response(http_response).await;
+    assert_eq!(rpc_responses.len(), 3);
+
+    assert_eq!(rpc_responses[0].id(), "a");
+    assert_eq!(
+        rpc_responses[0].result(),
+        Some(GoodThing {
+            good_thing: "one".to_string()
+        })
+    );
+
+    assert_eq!(rpc_responses[1].id(), "b");
+    assert_eq!(
+        rpc_responses[1].error().unwrap(),
+        &Error::new(ReservedErrorCode::InvalidParams, "no params")
+    );
+
+    assert_eq!(rpc_responses[2].id(), "c");
+    assert_eq!(
+        rpc_responses[2].result(),
+        Some(GoodThing {
+            good_thing: "three".to_string()
+        })
+    );
+}
+
+#[tokio::test]
+async fn should_handle_batch_with_invalid_elements() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // This should get handled by `filters::handle_batch`, returning a Response::Failure (invalid
+    // request) for each invalid element, with a null ID where the element is not a request object.
+    let http_response = warp::test::request()
+        .body(r#"[1,{"jsonrpc":"2.0","id":2,"method":{"not":"a string"}}]"#)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_responses = batch_from_http_response(http_response).await;
+    assert_eq!(rpc_responses.len(), 2);
+
+    assert_eq!(rpc_responses[0].id(), &Value::Null);
+    assert_eq!(
+        rpc_responses[0].error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Expected each batch element to be a JSON-RPC request object"
+        )
+    );
+
+    assert_eq!(rpc_responses[1].id(), 2);
+    assert_eq!(
+        rpc_responses[1].error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Expected 'method' to be a String"
+        )
+    );
+}
+
+#[tokio::test]
+async fn should_omit_notifications_from_batch_response() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // This should get handled by `filters::handle_batch`, returning a response only for the
+    // element which has an ID.
+    let http_response = warp::test::request()
+        .body(
+            r#"[
+                {"jsonrpc":"2.0","method":"get good thing","params":["one"]},
+                {"jsonrpc":"2.0","id":"b","method":"get good thing","params":["two"]}
+            ]"#,
+        )
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_responses = batch_from_http_response(http_response).await;
+    assert_eq!(rpc_responses.len(), 1);
+    assert_eq!(rpc_responses[0].id(), "b");
+    assert_eq!(
+        rpc_responses[0].result(),
+        Some(GoodThing {
+            good_thing: "two".to_string()
+        })
+    );
+}
+
+#[tokio::test]
+async fn should_handle_batch_of_only_notifications() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // This should get handled by `filters::handle_body` and return no JSON-RPC response, only an
+    // empty HTTP response (no content) as all elements are notifications.
+    let http_response = warp::test::request()
+        .body(
+            r#"[
+                {"jsonrpc":"2.0","method":"get good thing","params":["one"]},
+                {"jsonrpc":"2.0","method":"get good thing","params":["two"]}
+            ]"#,
+        )
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::NO_CONTENT);
+    let body_bytes = hyper::body::to_bytes(http_response.into_body())
+        .await
+        .unwrap();
+    assert!(body_bytes.is_empty());
+}
+
+#[tokio::test]
+async fn should_handle_empty_batch() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // This should get handled by `filters::handle_batch` and return a single Response::Failure
+    // (invalid request) to the client as the batch is empty.
+    let http_response = warp::test::request()
+        .body(r#"[]"#)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_response = from_http_response(http_response).await;
+    assert_eq!(rpc_response.id(), &Value::Null);
+    assert_eq!(
+        rpc_response.error().unwrap(),
+        &Error::new(ReservedErrorCode::InvalidRequest, "Empty batch")
+    );
+}
+
+#[tokio::test]
+async fn should_handle_batch_which_is_too_large() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // This should get handled by `filters::handle_batch` and return a single Response::Failure
+    // (invalid request) to the client as the batch exceeds `MAX_BATCH_SIZE`.
+    let request = r#"{"jsonrpc":"2.0","id":1,"method":"get good thing","params":["one"]}"#;
+    let batch = format!("[{}]", vec![request; MAX_BATCH_SIZE + 1].join(","));
+    let http_response = warp::test::request()
+        .body(batch)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_response = from_http_response(http_response).await;
+    assert_eq!(rpc_response.id(), &Value::Null);
+    assert_eq!(
+        rpc_response.error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Batch of 4 requests exceeds the maximum allowed of 3"
+        )
+    );
+}
@@ -40,7 +40,14 @@
 //!     let path = "rpc";
 //!     let max_body_bytes = 1024;
 //!     let allow_unknown_fields = false;
-//!     let route = casper_json_rpc::route(path, max_body_bytes, handlers, allow_unknown_fields);
+//!     let max_batch_size = 10;
+//!     let route = casper_json_rpc::route(
+//!         path,
+//!         max_body_bytes,
+//!         handlers,
+//!         allow_unknown_fields,
+//!         max_batch_size,
+//!     );
 //!
 //!     // Convert it into a `Service` and run it.
 //!     let make_svc = hyper::service::make_service_fn(move |_| {
@@ -64,6 +71,15 @@
 //!
 //! Generally a set of custom error codes should be provided.  These should all implement
 //! [`ErrorCodeT`].
+//!
+//! # Batches
+//!
+//! A JSON array of requests is handled as a batch as per
+//! [the JSON-RPC specification](https://www.jsonrpc.org/specification#batch).  The requests are
+//! executed concurrently and their responses are returned as a JSON array in the same order.
+//! Notifications (requests without an "id" field) within a batch are executed, but no response is
+//! included for them.  A single Notification sent outside a batch is rejected with an HTTP 400
+//! (bad request) error.
 
 #![doc(html_root_url = "https://docs.rs/casper-json-rpc/1.1.0")]
 #![doc(
@@ -118,15 +134,22 @@ pub enum CorsOrigin {
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
 ///
+/// `max_batch_size` sets an upper limit for the number of requests in a single JSON-RPC batch.
+///
 /// For further details, see the docs for the [`filters`] functions.
 pub fn route<P: AsRef<str>>(
     path: P,
     max_body_bytes: u32,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
+    max_batch_size: usize,
 ) -> BoxedFilter<(impl Reply,)> {
     filters::base_filter(path, max_body_bytes)
-        .and(filters::main_filter(handlers, allow_unknown_fields))
+        .and(filters::main_filter(
+            handlers,
+            allow_unknown_fields,
+            max_batch_size,
+        ))
         .recover(filters::handle_rejection)
         .boxed()
 }
@@ -146,6 +169,8 @@ pub fn route<P: AsRef<str>>(
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
 ///
+/// `max_batch_size` sets an upper limit for the number of requests in a single JSON-RPC batch.
+///
 /// Note that this is a convenience function combining the lower-level functions in [`filters`]
 /// along with [a warp CORS filter](https://docs.rs/warp/latest/warp/filters/cors/index.html) which
 ///   * allows any origin or specified origin
@@ -158,10 +183,15 @@ pub fn route_with_cors<P: AsRef<str>>(
     max_body_bytes: u32,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
+    max_batch_size: usize,
     cors_header: &CorsOrigin,
 ) -> BoxedFilter<(impl Reply,)> {
     filters::base_filter(path, max_body_bytes)
-        .and(filters::main_filter(handlers, allow_unknown_fields))
+        .and(filters::main_filter(
+            handlers,
+            allow_unknown_fields,
+            max_batch_size,
+        ))
         .recover(filters::handle_rejection)
         .with(match cors_header {
             CorsOrigin::Any => warp::cors()
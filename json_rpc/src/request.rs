@@ -31,6 +31,15 @@ pub(crate) struct Request {
     pub params: Option<Params>,
 }
 
+/// A validated element of a batch request.
+pub(crate) enum BatchElement {
+    /// A request which requires a response.
+    Request(Request),
+    /// A Notification as per the JSON-RPC specification, i.e. a request without an "id" field.
+    /// It is executed, but no response is sent for it.
+    Notification(Request),
+}
+
 /// Returns `Ok` if `id` is a String, Null or a Number with no fractional part.
 fn is_valid(id: &Value) -> Result<(), Error> {
     match id {
@@ -161,6 +170,23 @@ impl Request {
 
         Ok(Request { id, method, params })
     }
+
+    /// Returns `Ok` if the request is valid as per
+    /// [the JSON-RPC specification](https://www.jsonrpc.org/specification#request_object).
+    ///
+    /// Unlike [`Request::new`], a missing "id" field does not cause a `Rejection`; instead the
+    /// request is classed as a Notification.  Any validation errors of a Notification are returned
+    /// with a Null "id", as required for batch responses.
+    pub(super) fn new_batch_element(
+        mut request: Map<String, Value>,
+        allow_unknown_fields: bool,
+    ) -> Result<BatchElement, ErrorOrRejection> {
+        if request.contains_key(ID_FIELD_NAME) {
+            return Request::new(request, allow_unknown_fields).map(BatchElement::Request);
+        }
+        let _ = request.insert(ID_FIELD_NAME.to_string(), Value::Null);
+        Request::new(request, allow_unknown_fields).map(BatchElement::Notification)
+    }
 }
 
 #[cfg(test)]
@@ -266,6 +292,68 @@ mod tests {
         };
     }
 
+    #[test]
+    fn should_validate_batch_element_with_missing_id_as_notification() {
+        let request = json!({
+            JSONRPC_FIELD_NAME: JSON_RPC_VERSION,
+            METHOD_FIELD_NAME: "a",
+        })
+        .as_object()
+        .cloned()
+        .unwrap();
+
+        match Request::new_batch_element(request, false) {
+            Ok(BatchElement::Notification(notification)) => {
+                assert_eq!(notification.id, Value::Null);
+                assert_eq!(notification.method, "a");
+            }
+            _ => panic!("should be notification"),
+        };
+    }
+
+    #[test]
+    fn should_validate_batch_element_with_null_id_as_request() {
+        let request = json!({
+            JSONRPC_FIELD_NAME: JSON_RPC_VERSION,
+            ID_FIELD_NAME: Value::Null,
+            METHOD_FIELD_NAME: "a",
+        })
+        .as_object()
+        .cloned()
+        .unwrap();
+
+        match Request::new_batch_element(request, false) {
+            Ok(BatchElement::Request(request)) => assert_eq!(request.id, Value::Null),
+            _ => panic!("should be request"),
+        };
+    }
+
+    #[test]
+    fn should_fail_to_validate_invalid_notification_batch_element() {
+        let request = json!({
+            JSONRPC_FIELD_NAME: JSON_RPC_VERSION,
+            METHOD_FIELD_NAME: 1,
+        })
+        .as_object()
+        .cloned()
+        .unwrap();
+
+        let error = match Request::new_batch_element(request, false) {
+            Err(ErrorOrRejection::Error {
+                id: Value::Null,
+                error,
+            }) => error,
+            _ => panic!("should be error"),
+        };
+        assert_eq!(
+            error,
+            Error::new(
+                ReservedErrorCode::InvalidRequest,
+                "Expected 'method' to be a String"
+            )
+        );
+    }
+
     #[test]
     fn should_fail_to_validate_with_invalid_jsonrpc_field_value() {
         let request = json!({
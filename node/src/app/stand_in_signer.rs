@@ -0,0 +1,48 @@
+//! # Stand-in remote signer
+//!
+//! A minimal remote signer holding a single secret key, for testing nodes configured to sign via a
+//! remote signer. Run with `--help` to see available command-line arguments.
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use structopt::StructOpt;
+
+use casper_node::{
+    logging,
+    signer::{Address, StandInSigner},
+};
+use casper_types::SecretKey;
+
+// Note: The docstring on `Opts` is the help shown when calling the binary with `--help`.
+#[derive(Debug, StructOpt)]
+/// Stand-in remote signer for Casper validators, for testing only.
+struct Opts {
+    /// Path to the validator's secret key file.
+    #[structopt(long, parse(from_os_str))]
+    secret_key: PathBuf,
+
+    /// Address to listen on: either `unix:<path>` for a Unix domain socket, or `<host>:<port>`
+    /// for a TCP socket.
+    #[structopt(long)]
+    address: Address,
+
+    /// Refuse all signing requests.
+    #[structopt(long)]
+    refuse: bool,
+
+    /// Delay before responding to each signing request, e.g. `500ms`.
+    #[structopt(long, parse(try_from_str = humantime::parse_duration), default_value = "0s")]
+    delay: Duration,
+}
+
+/// Main function.
+fn main() -> anyhow::Result<()> {
+    logging::init_with_config(&Default::default())?;
+    let opts = Opts::from_args();
+    let secret_key = SecretKey::from_file(&opts.secret_key)?;
+    StandInSigner::new(Arc::new(secret_key))
+        .with_refuse(opts.refuse)
+        .with_delay(opts.delay)
+        .serve(&opts.address)?;
+    Ok(())
+}
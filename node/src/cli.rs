@@ -3,6 +3,9 @@
 //! Most configuration is done via config files (see [`config`](../config/index.html) for details).
 
 pub mod arglang;
+pub mod db;
+pub mod global_state;
+pub mod slashing_protection;
 
 use std::{
     alloc::System,
@@ -21,7 +24,7 @@ use toml::{value::Table, Value};
 use tracing::info;
 
 use crate::{
-    components::network::Identity as NetworkIdentity,
+    components::{network::Identity as NetworkIdentity, storage::Storage},
     logging,
     reactor::{main_reactor, Runner},
     setup_signal_hooks,
@@ -78,6 +81,39 @@ pub enum Cli {
         #[structopt(long)]
         new_config: PathBuf,
     },
+    /// Inspect the storage of a node which is not running.
+    ///
+    /// The storage is opened read-only, using the storage settings of the given configuration file
+    /// and the chainspec it refers to.
+    Db {
+        /// Path to configuration file.
+        config: PathBuf,
+
+        #[structopt(subcommand)]
+        command: db::DbCommand,
+    },
+    /// Export or import a global state snapshot while the node is not running.
+    ///
+    /// The global state is located using the storage settings of the given configuration file and
+    /// the chainspec it refers to.
+    GlobalState {
+        /// Path to configuration file.
+        config: PathBuf,
+
+        #[structopt(subcommand)]
+        command: global_state::GlobalStateCommand,
+    },
+    /// Export or import the validator's slashing protection database while the node is not
+    /// running.
+    ///
+    /// The database is located using the consensus settings of the given configuration file.
+    SlashingProtection {
+        /// Path to configuration file.
+        config: PathBuf,
+
+        #[structopt(subcommand)]
+        command: slashing_protection::SlashingProtectionCommand,
+    },
 }
 
 #[derive(Debug)]
@@ -235,6 +271,38 @@ impl Cli {
                 )?;
                 Ok(ExitCode::Success as i32)
             }
+            Cli::Db { config, command } => {
+                let config = Self::init(&config, vec![])?;
+                let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_path(config.dir())?;
+                let storage_config = WithDir::new(config.dir(), config.value().storage.clone());
+                let storage = Storage::open_read_only(
+                    &storage_config,
+                    chainspec.protocol_config.activation_point.era_id(),
+                    &chainspec.network_config.name,
+                    chainspec.deploy_config.max_ttl.into(),
+                    chainspec.core_config.recent_era_count(),
+                )
+                .context("could not open storage")?;
+                command.run(&storage)
+            }
+            Cli::GlobalState { config, command } => {
+                let config = Self::init(&config, vec![])?;
+                let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_path(config.dir())?;
+                let storage_dir = config
+                    .with_dir(config.value().storage.path.clone())
+                    .join(&chainspec.network_config.name);
+                command.run(
+                    &storage_dir,
+                    &config.value().contract_runtime,
+                    chainspec.protocol_version(),
+                )
+            }
+            Cli::SlashingProtection { config, command } => {
+                let config = Self::init(&config, vec![])?;
+                let path =
+                    config.with_dir(config.value().consensus.slashing_protection_path.clone());
+                command.run(&path)
+            }
         }
     }
 
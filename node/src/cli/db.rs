@@ -0,0 +1,117 @@
+//! The `db` subcommand, for inspecting the storage of a node which is not running.
+
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use serde::Serialize;
+use structopt::StructOpt;
+
+use casper_hashing::Digest;
+
+use crate::{
+    components::storage::Storage,
+    types::{BlockHash, DeployHash, ExitCode, JsonBlock},
+};
+
+/// A hex-encoded block or deploy hash given on the command line.
+#[derive(Debug)]
+pub struct HexDigest(Digest);
+
+impl FromStr for HexDigest {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let digest = Digest::from_hex(input)
+            .map_err(|error| anyhow::anyhow!("{}", error))
+            .context("could not parse hex-encoded hash")?;
+        Ok(HexDigest(digest))
+    }
+}
+
+impl HexDigest {
+    /// Returns the parsed hash.
+    pub fn into_inner(self) -> Digest {
+        self.0
+    }
+}
+
+/// The storage inspection to run.
+#[derive(Debug, StructOpt)]
+pub enum DbCommand {
+    /// Print the highest stored block.
+    HighestBlock,
+    /// Print the range of block heights for which the node holds all the data.
+    AvailableBlockRange,
+    /// Print the block with the given hash.
+    Block {
+        /// Hex-encoded hash of the block.
+        block_hash: HexDigest,
+    },
+    /// Print the deploy with the given hash.
+    Deploy {
+        /// Hex-encoded hash of the deploy.
+        deploy_hash: HexDigest,
+    },
+    /// Print the sequences of heights of the blocks marked as complete, highest first.
+    CompletedBlocks,
+    /// Print the number of entries and the size of each named database.
+    Sizes,
+    /// Check that the block header, block body and approvals hashes databases are consistent.
+    ///
+    /// Exits with an error if any inconsistencies are found.
+    CheckIntegrity,
+}
+
+impl DbCommand {
+    /// Runs the inspection against the given storage, printing the results to stdout.
+    pub(super) fn run(self, storage: &Storage) -> anyhow::Result<i32> {
+        match self {
+            DbCommand::HighestBlock => match storage.read_highest_block()? {
+                Some(block) => print_json(&JsonBlock::new(&block, None))?,
+                None => println!("no blocks stored"),
+            },
+            DbCommand::AvailableBlockRange => {
+                println!("{}", storage.get_available_block_range());
+            }
+            DbCommand::Block { block_hash } => {
+                let block_hash = BlockHash::new(block_hash.into_inner());
+                match storage.read_block(&block_hash)? {
+                    Some(block) => print_json(&JsonBlock::new(&block, None))?,
+                    None => bail!("block {} not found", block_hash),
+                }
+            }
+            DbCommand::Deploy { deploy_hash } => {
+                let deploy_hash = DeployHash::new(deploy_hash.into_inner());
+                match storage.read_deploy_by_hash(&deploy_hash)? {
+                    Some(deploy) => print_json(&deploy)?,
+                    None => bail!("deploy {} not found", deploy_hash),
+                }
+            }
+            DbCommand::CompletedBlocks => {
+                for sequence in storage.completed_block_sequences() {
+                    println!("[{}, {}]", sequence.low(), sequence.high());
+                }
+            }
+            DbCommand::Sizes => {
+                for stats in storage.database_stats()? {
+                    println!("{}", stats);
+                }
+            }
+            DbCommand::CheckIntegrity => {
+                let report = storage.check_integrity()?;
+                print!("{}", report);
+                if !report.is_ok() {
+                    bail!("storage integrity check failed");
+                }
+            }
+        }
+        Ok(ExitCode::Success as i32)
+    }
+}
+
+/// Prints the given value to stdout as pretty-printed JSON.
+fn print_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    let json = serde_json::to_string_pretty(value).context("could not encode as JSON")?;
+    println!("{}", json);
+    Ok(())
+}
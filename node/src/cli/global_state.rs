@@ -0,0 +1,104 @@
+//! The `global-state` subcommand, for exporting and importing global state snapshots while the node
+//! is not running.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context;
+use lmdb::DatabaseFlags;
+use structopt::StructOpt;
+use tracing::info;
+
+use casper_execution_engine::{
+    core::engine_state::{EngineConfig, EngineState},
+    shared::newtypes::CorrelationId,
+    storage::{
+        global_state::lmdb::LmdbGlobalState, transaction_source::lmdb::LmdbEnvironment,
+        trie_store::lmdb::LmdbTrieStore,
+    },
+};
+use casper_types::ProtocolVersion;
+
+use super::db::HexDigest;
+use crate::{components::contract_runtime::Config, types::ExitCode};
+
+/// The global state snapshot operation to run.
+#[derive(Debug, StructOpt)]
+pub enum GlobalStateCommand {
+    /// Write the global state under the given state root hash to a snapshot file.
+    Export {
+        /// Hex-encoded state root hash of the global state to export.
+        #[structopt(long)]
+        state_root_hash: HexDigest,
+        /// Path of the snapshot file to create.
+        #[structopt(long)]
+        output: PathBuf,
+    },
+    /// Write the global state held by a snapshot file to the node's global state.
+    Import {
+        /// Path of the snapshot file to read.
+        #[structopt(long)]
+        input: PathBuf,
+    },
+}
+
+impl GlobalStateCommand {
+    /// Runs the operation against the global state stored in `storage_dir`.
+    pub(super) fn run(
+        self,
+        storage_dir: &Path,
+        contract_runtime_config: &Config,
+        protocol_version: ProtocolVersion,
+    ) -> anyhow::Result<i32> {
+        let environment = Arc::new(LmdbEnvironment::new(
+            storage_dir,
+            contract_runtime_config.max_global_state_size_or_default(),
+            contract_runtime_config.max_readers_or_default(),
+            contract_runtime_config.manual_sync_enabled_or_default(),
+        )?);
+        let trie_store = Arc::new(LmdbTrieStore::new(
+            &environment,
+            None,
+            DatabaseFlags::empty(),
+        )?);
+        let global_state = LmdbGlobalState::empty(environment, trie_store)?;
+        let engine_state = EngineState::new(global_state, EngineConfig::default());
+
+        match self {
+            GlobalStateCommand::Export {
+                state_root_hash,
+                output,
+            } => {
+                let file = File::create(&output)
+                    .with_context(|| format!("could not create {}", output.display()))?;
+                let trie_count = engine_state.export_snapshot(
+                    CorrelationId::new(),
+                    protocol_version,
+                    state_root_hash.into_inner(),
+                    BufWriter::new(file),
+                )?;
+                info!(trie_count, output = %output.display(), "exported global state snapshot");
+            }
+            GlobalStateCommand::Import { input } => {
+                let file = File::open(&input)
+                    .with_context(|| format!("could not open {}", input.display()))?;
+                let header =
+                    engine_state.import_snapshot(CorrelationId::new(), BufReader::new(file))?;
+                engine_state.flush_environment()?;
+                if header.protocol_version() != protocol_version {
+                    info!(
+                        snapshot_protocol_version = %header.protocol_version(),
+                        %protocol_version,
+                        "imported snapshot was exported by a different protocol version"
+                    );
+                }
+                println!("{}", header.state_root_hash());
+            }
+        }
+        Ok(ExitCode::Success as i32)
+    }
+}
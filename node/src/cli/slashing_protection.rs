@@ -0,0 +1,70 @@
+//! The `slashing-protection` subcommand, for exporting and importing the validator's slashing
+//! protection database while the node is not running.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use structopt::StructOpt;
+use tracing::info;
+
+use crate::{
+    signer::slashing_protection::{Interchange, SlashingProtection},
+    types::ExitCode,
+};
+
+/// The slashing protection database operation to run.
+#[derive(Debug, StructOpt)]
+pub enum SlashingProtectionCommand {
+    /// Write all records of the database to a JSON file in the interchange format.
+    Export {
+        /// Path of the JSON file to create.
+        #[structopt(long)]
+        output: PathBuf,
+    },
+    /// Merge the records of a JSON file in the interchange format into the database, creating it
+    /// if it doesn't exist.
+    Import {
+        /// Path of the JSON file to read.
+        #[structopt(long)]
+        input: PathBuf,
+    },
+}
+
+impl SlashingProtectionCommand {
+    /// Runs the operation against the slashing protection database at `path`.
+    pub(super) fn run(self, path: &Path) -> anyhow::Result<i32> {
+        match self {
+            SlashingProtectionCommand::Export { output } => {
+                let slashing_protection = SlashingProtection::open_existing(path)
+                    .with_context(|| format!("could not open {}", path.display()))?;
+                let interchange = slashing_protection.export();
+                let file = File::create(&output)
+                    .with_context(|| format!("could not create {}", output.display()))?;
+                let mut writer = BufWriter::new(file);
+                serde_json::to_writer_pretty(&mut writer, &interchange)?;
+                writer.flush()?;
+                info!(
+                    record_count = interchange.records.len(),
+                    output = %output.display(),
+                    "exported slashing protection records"
+                );
+            }
+            SlashingProtectionCommand::Import { input } => {
+                let file = File::open(&input)
+                    .with_context(|| format!("could not open {}", input.display()))?;
+                let interchange: Interchange = serde_json::from_reader(BufReader::new(file))
+                    .with_context(|| format!("could not parse {}", input.display()))?;
+                let slashing_protection =
+                    SlashingProtection::open(path, &interchange.public_key)
+                        .with_context(|| format!("could not open {}", path.display()))?;
+                let record_count = slashing_protection.import(interchange)?;
+                info!(record_count, "imported slashing protection records");
+            }
+        }
+        Ok(ExitCode::Success as i32)
+    }
+}
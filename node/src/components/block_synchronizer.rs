@@ -1420,6 +1420,10 @@ impl<REv: ReactorEvent> Component<REv> for BlockSynchronizer {
                     BlockSynchronizerRequest::Status { responder } => {
                         responder.respond(self.status()).ignore()
                     }
+                    // the contract runtime doesn't prune global state while it's being synced
+                    BlockSynchronizerRequest::IsSyncingGlobalState { responder } => {
+                        responder.respond(self.global_sync.is_syncing()).ignore()
+                    }
                     // prompts for what data (if any) is needed next to acquire block(s) being
                     // sync'd
                     BlockSynchronizerRequest::NeedNext => self.need_next(effect_builder, rng),
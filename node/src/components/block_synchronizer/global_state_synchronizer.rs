@@ -253,6 +253,11 @@ impl GlobalStateSynchronizer {
         self.last_progress
     }
 
+    /// Returns whether a global state is currently being synced.
+    pub(super) fn is_syncing(&self) -> bool {
+        self.request_state.is_some()
+    }
+
     fn handle_request<REv>(
         &mut self,
         request: SyncGlobalStateRequest,
@@ -57,7 +57,7 @@ use protocols::{highway::HighwayProtocol, zug::Zug};
 use traits::Context;
 
 pub(crate) use cl_context::ClContext;
-pub(crate) use config::{ChainspecConsensusExt, Config};
+pub(crate) use config::{ChainspecConsensusExt, Config, LoadSignerError};
 pub(crate) use consensus_protocol::{BlockContext, EraReport, ProposedBlock};
 pub(crate) use era_supervisor::{debug::EraDump, EraSupervisor, SerializedMessage};
 #[cfg(test)]
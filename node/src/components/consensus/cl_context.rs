@@ -2,40 +2,41 @@ use std::sync::Arc;
 
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{error, info};
 
 use casper_hashing::Digest;
-use casper_types::{crypto, PublicKey, SecretKey, Signature};
+use casper_types::{crypto, EraId, PublicKey, SecretKey, Signature};
 
 use crate::{
     components::consensus::traits::{ConsensusValueT, Context, ValidatorSecret},
+    signer::{
+        slashing_protection::{MessageKind, Slot},
+        Signer,
+    },
     types::BlockPayload,
 };
 
+/// The signer of a validator in a single era.
 #[derive(DataSize)]
 pub struct Keypair {
-    secret_key: Arc<SecretKey>,
-    public_key: PublicKey,
+    signer: Signer,
+    era_id: EraId,
 }
 
 impl Keypair {
-    pub(crate) fn new(secret_key: Arc<SecretKey>, public_key: PublicKey) -> Self {
-        Self {
-            secret_key,
-            public_key,
-        }
+    pub(crate) fn new(signer: Signer, era_id: EraId) -> Self {
+        Self { signer, era_id }
     }
 
     #[cfg(test)]
     pub(crate) fn public_key(&self) -> &PublicKey {
-        &self.public_key
+        self.signer.public_key()
     }
 }
 
 impl From<Arc<SecretKey>> for Keypair {
     fn from(secret_key: Arc<SecretKey>) -> Self {
-        let public_key: PublicKey = secret_key.as_ref().into();
-        Self::new(secret_key, public_key)
+        Self::new(Signer::local(secret_key), EraId::default())
     }
 }
 
@@ -43,8 +44,29 @@ impl ValidatorSecret for Keypair {
     type Hash = Digest;
     type Signature = Signature;
 
-    fn sign(&self, hash: &Digest) -> Signature {
-        crypto::sign(hash, self.secret_key.as_ref(), &self.public_key)
+    fn sign(&self, hash: &Digest) -> Option<Signature> {
+        match self.signer.sign(hash) {
+            Ok(signature) => Some(signature),
+            Err(err) => {
+                error!(%err, %hash, "failed to sign consensus message");
+                None
+            }
+        }
+    }
+
+    fn sign_unique(&self, kind: MessageKind, number: u64, hash: &Digest) -> Option<Signature> {
+        let slot = Slot {
+            era_id: self.era_id,
+            kind,
+            number,
+        };
+        match self.signer.sign_unique(slot, hash) {
+            Ok(signature) => Some(signature),
+            Err(err) => {
+                error!(%err, %hash, "failed to sign consensus message");
+                None
+            }
+        }
     }
 }
 
@@ -82,17 +104,13 @@ impl Context for ClContext {
 mod specimen_support {
     use super::Keypair;
     use crate::utils::specimen::{Cache, LargestSpecimen, SizeEstimator};
-    use casper_types::{PublicKey, SecretKey};
+    use casper_types::SecretKey;
     use std::sync::Arc;
 
     impl LargestSpecimen for Keypair {
         fn largest_specimen<E: SizeEstimator>(estimator: &E, cache: &mut Cache) -> Self {
             let secret_key = SecretKey::largest_specimen(estimator, cache);
-            let public_key = PublicKey::from(&secret_key);
-            Keypair {
-                secret_key: Arc::new(secret_key),
-                public_key,
-            }
+            Keypair::from(Arc::new(secret_key))
         }
     }
 }
@@ -1,9 +1,13 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use casper_types::{PublicKey, SecretKey};
+use casper_types::SecretKey;
 
 use crate::{
     components::consensus::{
@@ -11,18 +15,25 @@ use crate::{
         protocols::{highway::config::Config as HighwayConfig, zug::config::Config as ZugConfig},
         EraId,
     },
+    signer::{
+        self,
+        slashing_protection::{self, SlashingProtection},
+        Signer,
+    },
     types::Chainspec,
     utils::{External, LoadError, Loadable},
 };
 
 const DEFAULT_MAX_EXECUTION_DELAY: u64 = 3;
+const DEFAULT_SLASHING_PROTECTION_PATH: &str = "slashing_protection.db";
 
 /// Consensus configuration.
 #[derive(DataSize, Debug, Serialize, Deserialize, Clone)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
 #[serde(deny_unknown_fields)]
 pub struct Config {
-    /// Path to secret key file.
+    /// Path to secret key file. Not used if the remote signer is enabled.
+    #[serde(default)]
     pub secret_key_path: External,
     /// The maximum number of blocks by which execution is allowed to lag behind finalization.
     /// If it is more than that, consensus will pause, and resume once the executor has caught up.
@@ -33,6 +44,13 @@ pub struct Config {
     /// Zug-specific node configuration.
     #[serde(default)]
     pub zug: ZugConfig,
+    /// Remote signer configuration.
+    #[serde(default)]
+    pub remote_signer: signer::Config,
+    /// Path to the slashing protection database. A relative path is relative to the config file's
+    /// directory. This is required rather than defaulted, since the config directory is versioned
+    /// and a default path there would leave the records behind on an upgrade.
+    pub slashing_protection_path: PathBuf,
 }
 
 impl Default for Config {
@@ -42,21 +60,44 @@ impl Default for Config {
             max_execution_delay: DEFAULT_MAX_EXECUTION_DELAY,
             highway: HighwayConfig::default(),
             zug: ZugConfig::default(),
+            remote_signer: signer::Config::default(),
+            slashing_protection_path: PathBuf::from(DEFAULT_SLASHING_PROTECTION_PATH),
         }
     }
 }
 
 type LoadKeyError = LoadError<<Arc<SecretKey> as Loadable>::Error>;
 
+/// Error creating the validator's signer.
+#[derive(Debug, Error)]
+pub(crate) enum LoadSignerError {
+    /// The secret key file couldn't be loaded.
+    #[error(transparent)]
+    LoadKey(#[from] LoadKeyError),
+    /// The remote signer couldn't be reached.
+    #[error(transparent)]
+    RemoteSigner(#[from] signer::Error),
+    /// The slashing protection database couldn't be opened.
+    #[error(transparent)]
+    SlashingProtection(#[from] slashing_protection::Error),
+}
+
 impl Config {
-    /// Loads the secret key from the configuration file and derives the public key.
-    pub(crate) fn load_keys<P: AsRef<Path>>(
-        &self,
-        root: P,
-    ) -> Result<(Arc<SecretKey>, PublicKey), LoadKeyError> {
-        let secret_signing_key: Arc<SecretKey> = self.secret_key_path.clone().load(root)?;
-        let public_key: PublicKey = PublicKey::from(secret_signing_key.as_ref());
-        Ok((secret_signing_key, public_key))
+    /// Connects to the remote signer if it is enabled, or otherwise loads the secret key from the
+    /// configuration file, and opens the slashing protection database.
+    pub(crate) fn load_signer<P: AsRef<Path>>(&self, root: P) -> Result<Signer, LoadSignerError> {
+        let signer = if self.remote_signer.enable {
+            Signer::remote(&self.remote_signer)?
+        } else {
+            let secret_signing_key: Arc<SecretKey> =
+                self.secret_key_path.clone().load(root.as_ref())?;
+            Signer::local(secret_signing_key)
+        };
+        let slashing_protection = SlashingProtection::open(
+            &root.as_ref().join(&self.slashing_protection_path),
+            signer.public_key(),
+        )?;
+        Ok(signer.with_slashing_protection(slashing_protection))
     }
 }
 
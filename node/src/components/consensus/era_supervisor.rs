@@ -31,7 +31,7 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tracing::{debug, error, info, trace, warn};
 
 use casper_hashing::Digest;
-use casper_types::{AsymmetricType, EraId, PublicKey, SecretKey, TimeDiff, Timestamp};
+use casper_types::{AsymmetricType, EraId, PublicKey, TimeDiff, Timestamp};
 
 use crate::{
     components::{
@@ -54,6 +54,7 @@ use crate::{
         AutoClosingResponder, EffectBuilder, EffectExt, Effects, Responder,
     },
     fatal, protocol,
+    signer::Signer,
     types::{
         chainspec::ConsensusProtocolName, BlockHash, BlockHeader, Chainspec, Deploy, DeployHash,
         DeployOrTransferHash, FinalizedApprovals, FinalizedBlock, MetaBlockState, NodeId,
@@ -95,7 +96,7 @@ pub struct EraSupervisor {
     /// Since eras at or before the most recent activation point are never instantiated, shortly
     /// after that there can temporarily be fewer than three entries in the map.
     open_eras: BTreeMap<EraId, Era>,
-    secret_signing_key: Arc<SecretKey>,
+    signer: Signer,
     public_signing_key: PublicKey,
     chainspec: Arc<Chainspec>,
     config: Config,
@@ -127,20 +128,20 @@ impl EraSupervisor {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         storage_dir: &Path,
-        secret_signing_key: Arc<SecretKey>,
-        public_signing_key: PublicKey,
+        signer: Signer,
         config: Config,
         chainspec: Arc<Chainspec>,
         registry: &Registry,
     ) -> Result<Self, Error> {
         let unit_files_folder = storage_dir.join("unit_files");
         std::fs::create_dir_all(&unit_files_folder)?;
+        let public_signing_key = signer.public_key().clone();
         info!(our_id = %public_signing_key, "EraSupervisor pubkey",);
         let metrics = Metrics::new(registry)?;
 
         let era_supervisor = Self {
             open_eras: Default::default(),
-            secret_signing_key,
+            signer,
             public_signing_key,
             chainspec,
             config,
@@ -377,7 +378,7 @@ impl EraSupervisor {
             vec![]
         } else {
             info!(era = era_id.value(), %our_id, "start voting");
-            let secret = Keypair::new(self.secret_signing_key.clone(), our_id.clone());
+            let secret = Keypair::new(self.signer.clone(), era_id);
             let instance_id = self.era(era_id).consensus.instance_id();
             let unit_hash_file = self.unit_file(instance_id);
             self.era_mut(era_id).consensus.activate_validator(
@@ -512,6 +513,7 @@ impl EraSupervisor {
                 start_time,
                 seed,
                 now,
+                Some(self.highway_wal_file(&instance_id)),
             ),
             ConsensusProtocolName::Zug => Zug::new_boxed(
                 instance_id,
@@ -597,6 +599,12 @@ impl EraSupervisor {
                         err => warn!(?err, "could not delete unit hash file"),
                     }
                 }
+                if let Err(err) = fs::remove_file(self.highway_wal_file(&instance_id)) {
+                    match err.kind() {
+                        io::ErrorKind::NotFound => {}
+                        err => warn!(?err, "could not delete Highway WAL file"),
+                    }
+                }
             }
         }
 
@@ -612,6 +620,15 @@ impl EraSupervisor {
         ))
     }
 
+    /// Returns the path to the era's Highway write-ahead log.
+    fn highway_wal_file(&self, instance_id: &Digest) -> PathBuf {
+        self.unit_files_folder.join(format!(
+            "highway_wal_{:?}_{}.dat",
+            instance_id,
+            self.public_signing_key.to_hex()
+        ))
+    }
+
     /// Applies `f` to the consensus protocol of the specified era.
     fn delegate_to_era<REv: ReactorEventT, F>(
         &mut self,
@@ -128,7 +128,7 @@ impl<C: Context> ActiveValidator<C> {
             paused: false,
         };
         let mut effects = av.schedule_timer(start_time, state);
-        effects.push(av.send_ping(current_time, instance_id));
+        effects.extend(av.send_ping(current_time, instance_id));
         (av, effects)
     }
 
@@ -215,15 +215,19 @@ impl<C: Context> ActiveValidator<C> {
         let one_max_round_ago = timestamp.saturating_sub(state.params().max_round_length());
         if !state.has_ping(self.vidx, one_max_round_ago + TimeDiff::from_millis(1)) {
             warn!(%timestamp, "too many validators offline, sending ping");
-            effects.push(self.send_ping(timestamp, instance_id));
+            effects.extend(self.send_ping(timestamp, instance_id));
         }
         effects
     }
 
-    /// Creates a Ping vertex.
-    pub(crate) fn send_ping(&self, timestamp: Timestamp, instance_id: C::InstanceId) -> Effect<C> {
-        let ping = Ping::new(self.vidx, timestamp, instance_id, &self.secret);
-        Effect::NewVertex(ValidVertex(Vertex::Ping(ping)))
+    /// Creates a Ping vertex, or returns `None` if it couldn't be signed.
+    pub(crate) fn send_ping(
+        &self,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    ) -> Option<Effect<C>> {
+        let ping = Ping::new(self.vidx, timestamp, instance_id, &self.secret)?;
+        Some(Effect::NewVertex(ValidVertex(Vertex::Ping(ping))))
     }
 
     /// Returns whether enough validators are online to finalize values with the target fault
@@ -266,8 +270,9 @@ impl<C: Context> ActiveValidator<C> {
             }
         };
         if self.should_endorse(uhash, state) {
-            let endorsement = self.endorse(uhash);
-            effects.extend(vec![Effect::NewVertex(ValidVertex(endorsement))]);
+            if let Some(endorsement) = self.endorse(uhash) {
+                effects.push(Effect::NewVertex(ValidVertex(endorsement)));
+            }
         }
         effects
     }
@@ -290,7 +295,7 @@ impl<C: Context> ActiveValidator<C> {
                 let unit = state.unit(v);
                 unit.new_hash_obs(state, vidx)
             })
-            .map(|v| self.endorse(v))
+            .filter_map(|v| self.endorse(v))
             .map(|endorsement| Effect::NewVertex(ValidVertex(endorsement)))
             .collect()
     }
@@ -459,7 +464,7 @@ impl<C: Context> ActiveValidator<C> {
             endorsed,
         }
         .into_hashed();
-        let swunit = SignedWireUnit::new(hwunit, &self.secret);
+        let swunit = SignedWireUnit::new(hwunit, &self.secret)?;
         write_last_unit(&self.unit_file, swunit.clone()).unwrap_or_else(|err| {
             panic!(
                 "should successfully write unit's hash to {:?}, got {:?}",
@@ -559,11 +564,16 @@ impl<C: Context> ActiveValidator<C> {
                 .any(|(vidx, _)| state.is_faulty(vidx) && unit.new_hash_obs(state, vidx))
     }
 
-    /// Creates endorsement of the `vhash`.
-    fn endorse(&self, vhash: &C::Hash) -> Vertex<C> {
+    /// Creates endorsement of the `vhash`, or returns `None` if it couldn't be signed.
+    fn endorse(&self, vhash: &C::Hash) -> Option<Vertex<C>> {
         let endorsement = Endorsement::new(*vhash, self.vidx);
-        let signature = self.secret.sign(&endorsement.hash());
-        Vertex::Endorsements(SignedEndorsement::new(endorsement, signature).into())
+        // Endorsing units on conflicting forks of the same creator is a fault too, proven by
+        // `Evidence::Endorsements`, but slashing protection doesn't record endorsements yet: see
+        // the `signer::slashing_protection` module documentation.
+        let signature = self.secret.sign(&endorsement.hash())?;
+        Some(Vertex::Endorsements(
+            SignedEndorsement::new(endorsement, signature).into(),
+        ))
     }
 
     /// Returns a panorama that is valid to use in our own unit at the given timestamp.
@@ -988,7 +998,8 @@ mod tests {
             TEST_INSTANCE_ID,
         );
 
-        let ping = Vertex::Ping(Ping::new(ALICE, 500.into(), TEST_INSTANCE_ID, &ALICE_SEC));
+        let ping =
+            Vertex::Ping(Ping::new(ALICE, 500.into(), TEST_INSTANCE_ID, &ALICE_SEC).unwrap());
 
         // The ping is suspicious if it is newer than the latest ping (or unit) that has been added
         // to the state.
@@ -243,7 +243,7 @@ impl<C: Context> Highway<C> {
     /// Returns the next missing dependency, or `None` if all dependencies of `pvv` are satisfied.
     ///
     /// If this returns `None`, `validate_vertex` can be called.
-    pub(super) fn missing_dependency(&self, pvv: &PreValidatedVertex<C>) -> Option<Dependency<C>> {
+    pub(crate) fn missing_dependency(&self, pvv: &PreValidatedVertex<C>) -> Option<Dependency<C>> {
         match pvv.inner() {
             Vertex::Evidence(_) | Vertex::Ping(_) => None,
             Vertex::Endorsements(endorsements) => {
@@ -824,7 +824,7 @@ pub(crate) mod tests {
         assert_eq!(Err(expected), highway.pre_validate_vertex(invalid_vertex));
 
         let hwunit = wunit.into_hashed();
-        let valid_signature = CAROL_SEC.sign(&hwunit.hash());
+        let valid_signature = CAROL_SEC.sign(&hwunit.hash()).unwrap();
         let correct_signature_unit = SignedWireUnit {
             hashed_wire_unit: hwunit,
             signature: valid_signature,
@@ -918,9 +918,9 @@ pub(crate) mod tests {
                         wunit1: &WireUnit<TestContext>,
                         signer1: &TestSecret| {
             let hwunit0 = wunit0.clone().into_hashed();
-            let swunit0 = SignedWireUnit::new(hwunit0, signer0);
+            let swunit0 = SignedWireUnit::new(hwunit0, signer0).unwrap();
             let hwunit1 = wunit1.clone().into_hashed();
-            let swunit1 = SignedWireUnit::new(hwunit1, signer1);
+            let swunit1 = SignedWireUnit::new(hwunit1, signer1).unwrap();
             let evidence = Evidence::Equivocation(swunit0, swunit1);
             let vertex = Vertex::Evidence(evidence);
             highway
@@ -1014,7 +1014,7 @@ pub(crate) mod tests {
         // Ping by validator that is not bonded, with an index that is outside of boundaries of the
         // state.
         let ping: Vertex<TestContext> =
-            Vertex::Ping(Ping::new(DAN, now, TEST_INSTANCE_ID, &DAN_SEC));
+            Vertex::Ping(Ping::new(DAN, now, TEST_INSTANCE_ID, &DAN_SEC).unwrap());
         assert!(
             DAN.0 >= WEIGHTS.len() as u32,
             "should use validator that is not bonded"
@@ -1040,9 +1040,9 @@ pub(crate) mod tests {
         let _effects =
             highway.activate_validator(ALICE.0, ALICE_SEC.clone(), now, None, target_ftt);
 
-        let ping = Vertex::Ping(Ping::new(ALICE, now, TEST_INSTANCE_ID, &ALICE_SEC));
+        let ping = Vertex::Ping(Ping::new(ALICE, now, TEST_INSTANCE_ID, &ALICE_SEC).unwrap());
         assert!(!highway.is_doppelganger_vertex(&ping));
-        let ping = Vertex::Ping(Ping::new(ALICE, later, TEST_INSTANCE_ID, &ALICE_SEC));
+        let ping = Vertex::Ping(Ping::new(ALICE, later, TEST_INSTANCE_ID, &ALICE_SEC).unwrap());
         assert!(highway.is_doppelganger_vertex(&ping));
     }
 }
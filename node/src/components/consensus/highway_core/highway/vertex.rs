@@ -5,14 +5,17 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use casper_types::Timestamp;
 
-use crate::components::consensus::{
-    highway_core::{
-        endorsement::SignedEndorsement,
-        highway::{PingError, VertexError},
-        state::Panorama,
+use crate::{
+    components::consensus::{
+        highway_core::{
+            endorsement::SignedEndorsement,
+            highway::{PingError, VertexError},
+            state::Panorama,
+        },
+        traits::{Context, ValidatorSecret},
+        utils::{ValidatorIndex, Validators},
     },
-    traits::{Context, ValidatorSecret},
-    utils::{ValidatorIndex, Validators},
+    signer::slashing_protection::MessageKind,
 };
 
 #[allow(clippy::integer_arithmetic)]
@@ -307,15 +310,20 @@ where
 }
 
 impl<C: Context> SignedWireUnit<C> {
+    /// Signs the unit, or returns `None` if signing failed.
     pub(crate) fn new(
         hashed_wire_unit: HashedWireUnit<C>,
         secret_key: &C::ValidatorSecret,
-    ) -> Self {
-        let signature = secret_key.sign(&hashed_wire_unit.hash);
-        SignedWireUnit {
+    ) -> Option<Self> {
+        let signature = secret_key.sign_unique(
+            MessageKind::HighwayUnit,
+            hashed_wire_unit.wire_unit().seq_number,
+            &hashed_wire_unit.hash,
+        )?;
+        Some(SignedWireUnit {
             hashed_wire_unit,
             signature,
-        }
+        })
     }
 
     pub(crate) fn wire_unit(&self) -> &WireUnit<C> {
@@ -490,20 +498,21 @@ where
 }
 
 impl<C: Context> Ping<C> {
-    /// Creates a new signed ping.
+    /// Creates a new signed ping, or returns `None` if signing failed.
     pub(crate) fn new(
         creator: ValidatorIndex,
         timestamp: Timestamp,
         instance_id: C::InstanceId,
         sk: &C::ValidatorSecret,
-    ) -> Self {
-        let signature = sk.sign(&Self::hash(creator, timestamp, instance_id));
-        Ping {
+    ) -> Option<Self> {
+        // Pings can't be evidence of equivocation, so slashing protection doesn't record them.
+        let signature = sk.sign(&Self::hash(creator, timestamp, instance_id))?;
+        Some(Ping {
             creator,
             timestamp,
             instance_id,
             signature,
-        }
+        })
     }
 
     /// The creator who signals that it is online.
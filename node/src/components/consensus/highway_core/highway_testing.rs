@@ -38,6 +38,7 @@ use crate::{
         utils::{Validators, Weight},
         BlockContext,
     },
+    signer::slashing_protection::MessageKind,
     NodeRng,
 };
 
@@ -283,7 +284,7 @@ impl HighwayValidator {
                                 }
                                 let secret = TestSecret(wunit2.creator.0.into());
                                 let hwunit2 = wunit2.into_hashed();
-                                let swunit2 = SignedWireUnit::new(hwunit2, &secret);
+                                let swunit2 = SignedWireUnit::new(hwunit2, &secret).unwrap();
                                 let vertex2 = Box::new(Vertex::Unit(swunit2));
                                 vec![msg, HighwayMessage::NewVertex(vertex2)]
                             }
@@ -1037,8 +1038,17 @@ impl ValidatorSecret for TestSecret {
     type Hash = HashWrapper;
     type Signature = SignatureWrapper;
 
-    fn sign(&self, data: &Self::Hash) -> Self::Signature {
-        SignatureWrapper(data.0 + self.0)
+    fn sign(&self, data: &Self::Hash) -> Option<Self::Signature> {
+        Some(SignatureWrapper(data.0 + self.0))
+    }
+
+    fn sign_unique(
+        &self,
+        _kind: MessageKind,
+        _number: u64,
+        data: &Self::Hash,
+    ) -> Option<Self::Signature> {
+        self.sign(data)
     }
 }
 
@@ -9,13 +9,18 @@ use std::{
 use datasize::DataSize;
 
 use super::*;
-use crate::components::consensus::{
-    highway_core::{
-        evidence::EvidenceError,
-        highway::Dependency,
-        highway_testing::{TEST_BLOCK_REWARD, TEST_ENDORSEMENT_EVIDENCE_LIMIT, TEST_INSTANCE_ID},
+use crate::{
+    components::consensus::{
+        highway_core::{
+            evidence::EvidenceError,
+            highway::Dependency,
+            highway_testing::{
+                TEST_BLOCK_REWARD, TEST_ENDORSEMENT_EVIDENCE_LIMIT, TEST_INSTANCE_ID,
+            },
+        },
+        traits::{ConsensusValueT, ValidatorSecret},
     },
-    traits::{ConsensusValueT, ValidatorSecret},
+    signer::slashing_protection::MessageKind,
 };
 
 pub(crate) const WEIGHTS: &[Weight] = &[Weight(3), Weight(4), Weight(5)];
@@ -47,8 +52,17 @@ impl ValidatorSecret for TestSecret {
     type Hash = u64;
     type Signature = u64;
 
-    fn sign(&self, data: &Self::Hash) -> Self::Signature {
-        data + u64::from(self.0)
+    fn sign(&self, data: &Self::Hash) -> Option<Self::Signature> {
+        Some(data + u64::from(self.0))
+    }
+
+    fn sign_unique(
+        &self,
+        _kind: MessageKind,
+        _number: u64,
+        data: &Self::Hash,
+    ) -> Option<Self::Signature> {
+        self.sign(data)
     }
 }
 
@@ -186,12 +200,12 @@ fn add_unit() -> Result<(), AddUnitError<TestContext>> {
         round_exp: 0u8,
         endorsed: BTreeSet::new(),
     };
-    let unit = SignedWireUnit::new(wunit.clone().into_hashed(), &BOB_SEC);
+    let unit = SignedWireUnit::new(wunit.clone().into_hashed(), &BOB_SEC).unwrap();
     let maybe_err = state.add_unit(unit).err().map(unit_err);
     assert_eq!(Some(UnitError::SequenceNumber), maybe_err);
     // Still not valid: This would be the third unit in the first round.
     wunit.seq_number = 2;
-    let unit = SignedWireUnit::new(wunit.into_hashed(), &BOB_SEC);
+    let unit = SignedWireUnit::new(wunit.into_hashed(), &BOB_SEC).unwrap();
     let maybe_err = state.add_unit(unit).err().map(unit_err);
     assert_eq!(Some(UnitError::ThreeUnitsInRound), maybe_err);
 
@@ -508,7 +522,7 @@ fn validate_lnc_mixed_citations() -> Result<(), AddUnitError<TestContext>> {
     //                                    || |
     //                                    || |
     // Bob                     b0<---------+ |
-    //                          +          | |
+    //                          + | |
     //                          |          | |
     //                    +c1<--+          | |
     // Carol         c0<--+                | |
@@ -549,12 +563,12 @@ fn validate_lnc_transitive_endorsement() -> Result<(), AddUnitError<TestContext>
     // c1 doesn't have to be endorsed, it is enough that c0 is.
     //
     // Alice           a0<-----------+
-    //                 +             |
+    //                 + |
     //          b0<----+             |
     // Bob                           |
     //                               |
     //          b0'<---+             |
-    //                 +             |
+    //                 + |
     // Carol           c0<---+c1<----+
     //                               |
     //                               |
@@ -586,12 +600,12 @@ fn validate_lnc_cite_descendant_of_equivocation() -> Result<(), AddUnitError<Tes
     // This is still detected as violation of the LNC.
     //
     // Alice                  a0<----+
-    //                        +      |
+    //                        + |
     //          b0<---+b1<----+      |
     // Bob                           |
     //                               |
     //          b0'<---+             |
-    //                 +             |
+    //                 + |
     // Carol           c0            |
     //                  ^            +
     // Dan              +----------+d0
@@ -3,12 +3,13 @@ mod participation;
 mod round_success_meter;
 #[cfg(test)]
 mod tests;
+mod wal;
 
 use std::{
     any::Any,
     collections::{BTreeMap, HashMap, HashSet},
     fmt::Debug,
-    iter,
+    iter, mem,
     path::PathBuf,
 };
 
@@ -45,7 +46,10 @@ use crate::{
     NodeRng,
 };
 
-use self::round_success_meter::RoundSuccessMeter;
+use self::{
+    round_success_meter::RoundSuccessMeter,
+    wal::{Entry, ReadWal, WriteWal},
+};
 
 /// Never allow more than this many units in a piece of evidence for conflicting endorsements,
 /// even if eras are longer than this.
@@ -82,10 +86,18 @@ where
     pvv_cache: HashMap<Dependency<C>, PreValidatedVertex<C>>,
     evidence_only: bool,
     config: config::Config,
+    /// Whether this instance uses a Write-Ahead Log. Only set once the log has been replayed.
+    wal_enabled: bool,
+    /// The Write-Ahead Log recording every vertex we add to the protocol state. If it is enabled
+    /// but `None`, the WAL could not be read and we must not activate the validator.
+    write_wal: Option<WriteWal<C>>,
 }
 
 impl<C: Context + 'static> HighwayProtocol<C> {
     /// Creates a new boxed `HighwayProtocol` instance.
+    ///
+    /// If a `wal_file` is given, all vertices previously recorded in it are added to the protocol
+    /// state, and every vertex added from now on is appended to it.
     #[allow(clippy::too_many_arguments, clippy::type_complexity)]
     pub(crate) fn new_boxed(
         instance_id: C::InstanceId,
@@ -98,6 +110,7 @@ impl<C: Context + 'static> HighwayProtocol<C> {
         era_start_time: Timestamp,
         seed: u64,
         now: Timestamp,
+        wal_file: Option<PathBuf>,
     ) -> (Box<dyn ConsensusProtocol<C>>, ProtocolOutcomes<C>) {
         let validators_count = validator_stakes.len();
         let validators = protocols::common::validators::<C>(faulty, inactive, validator_stakes);
@@ -177,10 +190,10 @@ impl<C: Context + 'static> HighwayProtocol<C> {
             endorsement_evidence_limit,
         );
 
-        let outcomes = Self::initialize_timers(now, era_start_time, &config.highway);
+        let mut outcomes = Self::initialize_timers(now, era_start_time, &config.highway);
 
         let highway = Highway::new(instance_id, validators, params);
-        let hw_proto = Box::new(HighwayProtocol {
+        let mut hw_proto = Box::new(HighwayProtocol {
             pending_values: HashMap::new(),
             finality_detector: FinalityDetector::new(ftt),
             highway,
@@ -189,8 +202,16 @@ impl<C: Context + 'static> HighwayProtocol<C> {
             pvv_cache: Default::default(),
             evidence_only: false,
             config: config.highway.clone(),
+            wal_enabled: false,
+            write_wal: None,
         });
 
+        if let Some(wal_file) = wal_file {
+            outcomes.extend(hw_proto.open_wal(wal_file, now));
+            // Only enable the WAL now, so that the vertices we replay aren't recorded again.
+            hw_proto.wal_enabled = true;
+        }
+
         (hw_proto, outcomes)
     }
 
@@ -231,6 +252,12 @@ impl<C: Context + 'static> HighwayProtocol<C> {
     fn process_av_effect(&mut self, effect: AvEffect<C>, now: Timestamp) -> ProtocolOutcomes<C> {
         match effect {
             AvEffect::NewVertex(vv) => {
+                // We only gossip the new vertex if we are able to record it. If that fails we
+                // wouldn't know about our own vertex after a restart and risk equivocating.
+                if !self.record_entry(&Entry::Vertex(vv.inner().clone())) {
+                    error!(vertex = ?vv.inner(), "could not record new vertex in WAL");
+                    return vec![];
+                }
                 self.log_unit_size(vv.inner(), "sending new unit");
                 self.calculate_round_length(&vv, now);
                 self.process_new_vertex(vv)
@@ -415,6 +442,9 @@ impl<C: Context + 'static> HighwayProtocol<C> {
         // round has finished, we now have all the vertices from that round in the state, and no
         // newer ones.
         self.calculate_round_length(&vv, now);
+        // Failing to record a vertex created by someone else doesn't put us at risk of
+        // equivocating, so we add it to the state regardless.
+        let _ = self.record_entry(&Entry::Vertex(vv.inner().clone()));
         let av_effects = self.highway.add_valid_vertex(vv, now);
         // Once vertex is added to the state, we can remove it from the cache.
         self.pvv_cache.remove(&vertex_id);
@@ -422,6 +452,129 @@ impl<C: Context + 'static> HighwayProtocol<C> {
         outcomes
     }
 
+    /// Adds a vertex to the WAL such that we can restore our protocol state and avoid equivocating
+    /// upon recovery if the node shuts down. Returns `true` if the vertex was added successfully
+    /// or if there is no WAL for this instance.
+    ///
+    /// If recording fails, we deactivate the validator: We must not create any new vertices that
+    /// we might not remember after a restart.
+    fn record_entry(&mut self, entry: &Entry<C>) -> bool {
+        match self.write_wal.as_mut().map(|ww| ww.record_entry(entry)) {
+            None => !self.wal_enabled,
+            Some(Ok(())) => true,
+            Some(Err(err)) => {
+                self.deactivate_validator();
+                error!(%err, "could not record a vertex to the WAL; deactivating");
+                false
+            }
+        }
+    }
+
+    /// Adds all vertices we've previously recorded in our write-ahead log to the protocol state,
+    /// and sets up the log for appending future vertices. If it fails it prints an error log and
+    /// the WAL remains `None`: That way we can still observe the protocol but not participate as
+    /// a validator.
+    fn open_wal(&mut self, wal_file: PathBuf, now: Timestamp) -> ProtocolOutcomes<C> {
+        // Open the file for reading.
+        let mut read_wal = match ReadWal::<C>::new(&wal_file) {
+            Ok(read_wal) => read_wal,
+            Err(err) => {
+                error!(%err, "could not create a ReadWal using this file");
+                return vec![];
+            }
+        };
+
+        // Read all vertices recorded in the file. Our own vertices are recorded after the ones
+        // they were created in response to, so they can appear before their dependencies: We
+        // keep those until they can be added.
+        let mut pending_vertices = vec![];
+        loop {
+            match read_wal.read_next_entry() {
+                Ok(Some(Entry::Vertex(vertex))) => {
+                    if self.highway.has_vertex(&vertex) {
+                        continue;
+                    }
+                    match self.highway.pre_validate_vertex(vertex) {
+                        Ok(pvv) => pending_vertices.push(pvv),
+                        Err((vertex, err)) => {
+                            error!(?vertex, ?err, "invalid vertex in WAL");
+                            return vec![]; // Not setting WAL file; won't actively participate.
+                        }
+                    }
+                }
+                Ok(None) => break,
+                Err(err) => {
+                    error!(
+                        ?err,
+                        "couldn't read a vertex from the WAL: was this node recently shut down?"
+                    );
+                    return vec![]; // Not setting WAL file; won't actively participate.
+                }
+            }
+        }
+
+        let mut outcomes = vec![];
+        loop {
+            let pending_count = pending_vertices.len();
+            for pvv in mem::take(&mut pending_vertices) {
+                if self.highway.has_vertex(pvv.inner()) {
+                    continue;
+                }
+                if self.highway.missing_dependency(&pvv).is_some() {
+                    pending_vertices.push(pvv);
+                    continue;
+                }
+                match self.highway.validate_vertex(pvv) {
+                    Ok(vv) => outcomes.extend(self.add_valid_vertex(vv, now)),
+                    Err((pvv, err)) => {
+                        error!(vertex = ?pvv.inner(), ?err, "invalid vertex in WAL");
+                        return vec![]; // Not setting WAL file; won't actively participate.
+                    }
+                }
+            }
+            if pending_vertices.is_empty() {
+                break;
+            }
+            if pending_vertices.len() == pending_count {
+                error!(
+                    count = pending_count,
+                    "vertices in WAL have missing dependencies"
+                );
+                return vec![]; // Not setting WAL file; won't actively participate.
+            }
+        }
+        outcomes.extend(self.detect_finality());
+
+        // Open the file for appending.
+        match WriteWal::new(&wal_file) {
+            Ok(write_wal) => self.write_wal = Some(write_wal),
+            Err(err) => error!(?err, ?wal_file, "could not create a WAL using this file"),
+        }
+
+        // Ignore most outcomes: These have been processed before the restart.
+        outcomes
+            .into_iter()
+            .filter(|outcome| match outcome {
+                ProtocolOutcome::FttExceeded
+                | ProtocolOutcome::WeAreFaulty
+                | ProtocolOutcome::FinalizedBlock(_)
+                | ProtocolOutcome::ValidateConsensusValue { .. }
+                | ProtocolOutcome::HandledProposedBlock(..)
+                | ProtocolOutcome::NewEvidence(_) => true,
+                ProtocolOutcome::SendEvidence(_, _)
+                | ProtocolOutcome::CreatedGossipMessage(_)
+                | ProtocolOutcome::CreatedTargetedMessage(_, _)
+                | ProtocolOutcome::CreatedMessageToRandomPeer(_)
+                | ProtocolOutcome::CreatedRequestToRandomPeer(_)
+                | ProtocolOutcome::ScheduleTimer(_, _)
+                | ProtocolOutcome::QueueAction(_)
+                | ProtocolOutcome::CreateNewBlock(_)
+                | ProtocolOutcome::DoppelgangerDetected
+                | ProtocolOutcome::Disconnect(_) => false,
+            })
+            .collect()
+    }
+
     /// Returns an instance of `RoundSuccessMeter` for the new era: resetting the counters where
     /// appropriate.
     fn next_era_round_succ_meter(&self, timestamp: Timestamp) -> RoundSuccessMeter<C> {
@@ -1053,6 +1206,10 @@ where
         now: Timestamp,
         unit_hash_file: Option<PathBuf>,
     ) -> ProtocolOutcomes<C> {
+        if self.wal_enabled && self.write_wal.is_none() {
+            error!(?our_id, "missing WAL; not activating");
+            return vec![];
+        }
         let ftt = self.finality_detector.fault_tolerance_threshold();
         let av_effects = self
             .highway
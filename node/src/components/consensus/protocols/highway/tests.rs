@@ -1,4 +1,12 @@
-use std::{collections::BTreeSet, sync::Arc};
+use std::{
+    collections::BTreeSet,
+    fs::OpenOptions,
+    io::{Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use tempfile::tempdir;
 
 use casper_types::{testing::TestRng, PublicKey, TimeDiff, Timestamp, U512};
 
@@ -6,7 +14,7 @@ use crate::{
     components::consensus::{
         cl_context::{ClContext, Keypair},
         config::Config,
-        consensus_protocol::{ConsensusProtocol, ProtocolOutcome},
+        consensus_protocol::{ConsensusProtocol, ProtocolOutcome, ProtocolOutcomes},
         highway_core::{
             highway::{SignedWireUnit, Vertex, WireUnit},
             highway_testing,
@@ -56,6 +64,29 @@ pub(crate) fn new_test_highway_protocol<I1, I2, T>(
     weights: I1,
     init_faulty: I2,
 ) -> Box<dyn ConsensusProtocol<ClContext>>
+where
+    I1: IntoIterator<Item = (PublicKey, T)>,
+    I2: IntoIterator<Item = PublicKey>,
+    T: Into<U512>,
+{
+    let (hw_proto, outcomes) = new_test_highway_protocol_with_wal(weights, init_faulty, None);
+    // We expect three messages:
+    // * log participation timer,
+    // * log synchronizer queue length timer,
+    // * purge synchronizer queue timer
+    // If there are more, the tests might need to handle them.
+    assert_eq!(3, outcomes.len());
+    hw_proto
+}
+
+fn new_test_highway_protocol_with_wal<I1, I2, T>(
+    weights: I1,
+    init_faulty: I2,
+    wal_file: Option<PathBuf>,
+) -> (
+    Box<dyn ConsensusProtocol<ClContext>>,
+    ProtocolOutcomes<ClContext>,
+)
 where
     I1: IntoIterator<Item = (PublicKey, T)>,
     I2: IntoIterator<Item = PublicKey>,
@@ -77,7 +108,7 @@ where
     };
     // Timestamp of the genesis era start and test start.
     let start_timestamp: Timestamp = 0.into();
-    let (hw_proto, outcomes) = HighwayProtocol::<ClContext>::new_boxed(
+    HighwayProtocol::<ClContext>::new_boxed(
         ClContext::hash(INSTANCE_ID_DATA),
         weights.into_iter().collect(),
         &init_faulty.into_iter().collect(),
@@ -88,14 +119,8 @@ where
         start_timestamp,
         0,
         start_timestamp,
-    );
-    // We expect three messages:
-    // * log participation timer,
-    // * log synchronizer queue length timer,
-    // * purge synchronizer queue timer
-    // If there are more, the tests might need to handle them.
-    assert_eq!(3, outcomes.len());
-    hw_proto
+        wal_file,
+    )
 }
 
 pub(crate) const N: Observation<ClContext> = Observation::None;
@@ -121,7 +146,7 @@ fn send_a_wire_unit_with_too_small_a_round_exp() {
     };
     let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     let sender = *ALICE_NODE_ID;
@@ -151,7 +176,7 @@ fn send_a_valid_wire_unit() {
     };
     let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
 
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
@@ -199,7 +224,7 @@ fn detect_doppelganger() {
     };
     let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     // Activate ALICE as validator.
@@ -233,3 +258,155 @@ fn max_rounds_per_era_returns_the_correct_value_for_prod_chainspec_value() {
 
     assert_eq!(219, max_rounds_per_era);
 }
+
+/// Returns a proposal by Alice and a unit by Alice citing it, as the only validator.
+fn alice_proposal_and_witness() -> (Vertex<ClContext>, Vertex<ClContext>) {
+    let instance_id = ClContext::hash(INSTANCE_ID_DATA);
+    let now = Timestamp::zero();
+    let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+    let proposal = SignedWireUnit::new(
+        WireUnit {
+            panorama: Panorama::from(vec![N]),
+            creator: ALICE,
+            instance_id,
+            value: Some(Arc::new(BlockPayload::new(vec![], vec![], vec![], false))),
+            seq_number: 0,
+            timestamp: now,
+            round_exp: 0,
+            endorsed: BTreeSet::new(),
+        }
+        .into_hashed(),
+        &alice_keypair,
+    )
+    .unwrap();
+    let witness = SignedWireUnit::new(
+        WireUnit {
+            panorama: Panorama::from(vec![Observation::Correct(proposal.hash())]),
+            creator: ALICE,
+            instance_id,
+            value: None,
+            seq_number: 1,
+            timestamp: now,
+            round_exp: 0,
+            endorsed: BTreeSet::new(),
+        }
+        .into_hashed(),
+        &alice_keypair,
+    )
+    .unwrap();
+    (Vertex::Unit(proposal), Vertex::Unit(witness))
+}
+
+/// Sends the vertex to the protocol instance and adds it to the protocol state.
+fn add_vertex(
+    rng: &mut TestRng,
+    highway_protocol: &mut dyn ConsensusProtocol<ClContext>,
+    vertex: Vertex<ClContext>,
+) {
+    let now = Timestamp::zero();
+    let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(vertex);
+    let msg = SerializedMessage::from_message(&highway_message);
+    let mut outcomes = highway_protocol.handle_message(rng, *ALICE_NODE_ID, msg, now);
+    while let Some(outcome) = outcomes.pop() {
+        if let ProtocolOutcome::QueueAction(ACTION_ID_VERTEX) = outcome {
+            outcomes.extend(highway_protocol.handle_action(ACTION_ID_VERTEX, now))
+        }
+    }
+}
+
+fn has_vertex(
+    highway_protocol: &dyn ConsensusProtocol<ClContext>,
+    vertex: &Vertex<ClContext>,
+) -> bool {
+    highway_protocol
+        .as_any()
+        .downcast_ref::<HighwayProtocol<ClContext>>()
+        .expect("should be a Highway instance")
+        .highway()
+        .has_vertex(vertex)
+}
+
+#[test]
+fn replay_vertices_from_wal() {
+    let mut rng = TestRng::new();
+    let validators = vec![(ALICE_PUBLIC_KEY.clone(), 100)];
+    let (proposal, witness) = alice_proposal_and_witness();
+
+    // Create a temporary directory which will be removed upon dropping the dir variable,
+    // using it to store the WAL file.
+    let dir = tempdir().unwrap();
+    let wal_file = dir.path().join("wal");
+
+    let (mut highway_protocol, _) =
+        new_test_highway_protocol_with_wal(validators.clone(), vec![], Some(wal_file.clone()));
+    add_vertex(&mut rng, &mut *highway_protocol, proposal.clone());
+    add_vertex(&mut rng, &mut *highway_protocol, witness.clone());
+    assert!(has_vertex(&*highway_protocol, &proposal));
+    assert!(has_vertex(&*highway_protocol, &witness));
+    drop(highway_protocol);
+
+    // After a restart, both vertices are restored from the WAL.
+    let (highway_protocol, outcomes) =
+        new_test_highway_protocol_with_wal(validators, vec![], Some(wal_file));
+    assert!(has_vertex(&*highway_protocol, &proposal));
+    assert!(has_vertex(&*highway_protocol, &witness));
+    // The replayed proposal is reported, but nothing is gossiped again.
+    assert!(outcomes
+        .iter()
+        .any(|outcome| matches!(outcome, ProtocolOutcome::HandledProposedBlock(_))));
+    assert!(!outcomes
+        .iter()
+        .any(|outcome| matches!(outcome, ProtocolOutcome::CreatedGossipMessage(_))));
+}
+
+#[test]
+fn replay_wal_with_incomplete_entries() {
+    let mut rng = TestRng::new();
+    let validators = vec![(ALICE_PUBLIC_KEY.clone(), 100)];
+    let (proposal, witness) = alice_proposal_and_witness();
+
+    let dir = tempdir().unwrap();
+    let wal_file = dir.path().join("wal");
+
+    let (mut highway_protocol, _) =
+        new_test_highway_protocol_with_wal(validators.clone(), vec![], Some(wal_file.clone()));
+    add_vertex(&mut rng, &mut *highway_protocol, proposal.clone());
+    add_vertex(&mut rng, &mut *highway_protocol, witness.clone());
+    drop(highway_protocol);
+
+    // Drop the last byte of the file, as if the node crashed while writing the witness.
+    let mut file = OpenOptions::new().append(true).open(&wal_file).unwrap();
+    file.seek(SeekFrom::End(-1)).unwrap();
+    let position = file.stream_position().unwrap();
+    file.set_len(position).unwrap();
+    drop(file);
+
+    // Only the proposal is restored, and the incomplete entry is removed from the file.
+    let (mut highway_protocol, _) =
+        new_test_highway_protocol_with_wal(validators.clone(), vec![], Some(wal_file.clone()));
+    assert!(has_vertex(&*highway_protocol, &proposal));
+    assert!(!has_vertex(&*highway_protocol, &witness));
+    // The WAL could be read, so we can participate as a validator.
+    let _ = highway_protocol.activate_validator(
+        ALICE_PUBLIC_KEY.clone(),
+        Keypair::from(Arc::clone(&*ALICE_SECRET_KEY)),
+        Timestamp::zero(),
+        None,
+    );
+    assert!(highway_protocol.is_active());
+    highway_protocol.deactivate_validator();
+
+    // Vertices added now are appended after the last complete entry.
+    add_vertex(&mut rng, &mut *highway_protocol, witness.clone());
+    drop(highway_protocol);
+
+    // Append a size which is larger than the rest of the file, as if it were corrupted.
+    let mut file = OpenOptions::new().append(true).open(&wal_file).unwrap();
+    file.write_all(&u64::MAX.to_le_bytes()).unwrap();
+    drop(file);
+
+    let (highway_protocol, _) =
+        new_test_highway_protocol_with_wal(validators, vec![], Some(wal_file));
+    assert!(has_vertex(&*highway_protocol, &proposal));
+    assert!(has_vertex(&*highway_protocol, &witness));
+}
@@ -0,0 +1,172 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Seek, Write},
+    marker::PhantomData,
+    mem,
+    path::PathBuf,
+};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::components::consensus::{highway_core::highway::Vertex, traits::Context};
+
+/// An entry in the Write-Ahead Log, storing a vertex we had added to our protocol state.
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(bound(
+    serialize = "C::Hash: Serialize",
+    deserialize = "C::Hash: Deserialize<'de>",
+))]
+pub(crate) enum Entry<C: Context> {
+    /// A vertex, created by us or received from a peer.
+    Vertex(Vertex<C>),
+}
+
+/// A Write-Ahead Log to store every vertex on disk when we add it to the protocol state.
+#[derive(Debug)]
+pub(crate) struct WriteWal<C: Context> {
+    writer: BufWriter<File>,
+    phantom_context: PhantomData<C>,
+}
+
+impl<C: Context> DataSize for WriteWal<C> {
+    const IS_DYNAMIC: bool = true;
+
+    const STATIC_HEAP_SIZE: usize = 0;
+
+    fn estimate_heap_size(&self) -> usize {
+        self.writer.capacity()
+    }
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum WriteWalError {
+    #[error("Could not get serialized vertex size: {0}")]
+    CouldntGetSerializedSize(bincode::Error),
+    #[error("Could not serialize size: {0}")]
+    CouldntSerializeSizeIntoWriter(io::Error),
+    #[error("Could not serialize vertex: {0}")]
+    CouldntSerializeVertexIntoWriter(bincode::Error),
+    #[error("Could not flush vertex to disk: {0}")]
+    CouldntFlushVertexToDisk(io::Error),
+    #[error("Could not open file: {0}")]
+    FileCouldntBeOpened(io::Error),
+}
+
+impl<C: Context> WriteWal<C> {
+    pub(crate) fn new(wal_path: &PathBuf) -> Result<Self, WriteWalError> {
+        let file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(wal_path)
+            .map_err(WriteWalError::FileCouldntBeOpened)?;
+        Ok(WriteWal {
+            writer: BufWriter::new(file),
+            phantom_context: PhantomData,
+        })
+    }
+
+    pub(crate) fn record_entry(&mut self, entry: &Entry<C>) -> Result<(), WriteWalError> {
+        // First write the size of the entry as a serialized u64.
+        let entry_size =
+            bincode::serialized_size(entry).map_err(WriteWalError::CouldntGetSerializedSize)?;
+        self.writer
+            .write_all(&entry_size.to_le_bytes())
+            .map_err(WriteWalError::CouldntSerializeSizeIntoWriter)?;
+        // Write the serialized entry itself.
+        bincode::serialize_into(&mut self.writer, entry)
+            .map_err(WriteWalError::CouldntSerializeVertexIntoWriter)?;
+        self.writer
+            .flush()
+            .map_err(WriteWalError::CouldntFlushVertexToDisk)?;
+        Ok(())
+    }
+}
+
+/// A buffer to read a Write-Ahead Log from disk and deserialize its vertices.
+#[derive(Debug)]
+pub(crate) struct ReadWal<C: Context> {
+    reader: BufReader<File>,
+    phantom_context: PhantomData<C>,
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum ReadWalError {
+    #[error("Could not create file at {0}: {1}")]
+    FileCouldntBeCreated(PathBuf, io::Error),
+    #[error(transparent)]
+    OtherIOError(#[from] io::Error),
+    #[error("could not deserialize WAL entry: {0}")]
+    CouldNotDeserialize(bincode::Error),
+}
+
+impl<C: Context> ReadWal<C> {
+    pub(crate) fn new(wal_path: &PathBuf) -> Result<Self, ReadWalError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .open(wal_path)
+            .map_err(|err| ReadWalError::FileCouldntBeCreated(wal_path.clone(), err))?;
+        Ok(ReadWal {
+            reader: BufReader::new(file),
+            phantom_context: PhantomData,
+        })
+    }
+
+    /// Reads the next entry from the WAL, or returns an error.
+    /// If there are 0 bytes left it returns `Ok(None)`.
+    pub(crate) fn read_next_entry(&mut self) -> Result<Option<Entry<C>>, ReadWalError> {
+        // Remember the current position: If we encounter an unreadable entry we trim the file at
+        // this point so we can continue appending entries after it.
+        let position = self.reader.stream_position()?;
+
+        // Deserialize the size of the entry, in bytes, as a u64.
+        let mut entry_size_buf = [0u8; mem::size_of::<u64>()];
+        if let Err(err) = self.reader.read_exact(&mut entry_size_buf) {
+            if err.kind() == io::ErrorKind::UnexpectedEof {
+                self.trim_file(position)?;
+                return Ok(None);
+            }
+            return Err(ReadWalError::OtherIOError(err));
+        }
+        let entry_size = u64::from_le_bytes(entry_size_buf);
+
+        // If the size is corrupted, don't try to allocate a buffer for it: An entry that doesn't
+        // fit into the rest of the file is incomplete anyway.
+        let file_len = self.reader.get_ref().metadata()?.len();
+        if entry_size > file_len.saturating_sub(self.reader.stream_position()?) {
+            self.trim_file(position)?;
+            return Ok(None);
+        }
+
+        // Read the serialized entry itself.
+        let mut entry_buf = vec![0; entry_size as usize];
+        if let Err(err) = self.reader.read_exact(&mut entry_buf) {
+            if err.kind() == io::ErrorKind::UnexpectedEof {
+                self.trim_file(position)?;
+                return Ok(None);
+            }
+            return Err(ReadWalError::OtherIOError(err));
+        }
+
+        // Deserialize and return the entry.
+        let entry = bincode::deserialize(&entry_buf).map_err(ReadWalError::CouldNotDeserialize)?;
+        Ok(Some(entry))
+    }
+
+    /// Trims the file to the given length and logs a warning if any bytes were removed.
+    ///
+    /// This should be called with the position where the last complete entry ended. Incomplete
+    /// entries can safely be removed because we only gossip our own vertices after writing them
+    /// and flushing the buffer, so we won't remove any vertex that we already sent.
+    fn trim_file(&mut self, position: u64) -> Result<(), ReadWalError> {
+        if self.reader.stream_position()? > position {
+            warn!("removing incomplete entry from WAL");
+            self.reader.get_mut().set_len(position)?;
+        }
+        Ok(())
+    }
+}
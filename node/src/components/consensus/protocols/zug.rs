@@ -652,7 +652,7 @@ impl<C: Context + 'static> Zug<C> {
             content,
             validator_idx,
             secret_key,
-        );
+        )?;
         // We only return the new message if we are able to record it. If that fails we
         // wouldn't know about our own message after a restart and risk double-signing.
         if self.record_entry(&Entry::SignedMessage(signed_msg.clone()))
@@ -2548,6 +2548,7 @@ mod specimen_support {
                 LargestSpecimen::largest_specimen(estimator, cache),
                 &LargestSpecimen::largest_specimen(estimator, cache),
             )
+            .expect("local signing should not fail")
         }
     }
 
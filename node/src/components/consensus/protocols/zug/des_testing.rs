@@ -36,6 +36,7 @@ use crate::{
         utils::{Validators, Weight},
         ActionId, BlockContext, SerializedMessage, TimerId,
     },
+    signer::slashing_protection::MessageKind,
     types::NodeId,
     NodeRng,
 };
@@ -313,7 +314,8 @@ impl ZugValidator {
                                     )),
                                     signed_msg.validator_idx,
                                     &TestSecret(signed_msg.validator_idx.0.into()),
-                                );
+                                )
+                                .unwrap();
                                 vec![
                                     ZugMessage::GossipMessage(SerializedMessage::from_message(
                                         &ZugProtocolMessage::Signed(conflicting_message),
@@ -328,7 +330,8 @@ impl ZugValidator {
                                     Content::<TestContext>::Vote(!vote),
                                     signed_msg.validator_idx,
                                     &TestSecret(signed_msg.validator_idx.0.into()),
-                                );
+                                )
+                                .unwrap();
                                 vec![
                                     ZugMessage::GossipMessage(SerializedMessage::from_message(
                                         &ZugProtocolMessage::Signed(conflicting_message),
@@ -1009,8 +1012,17 @@ impl ValidatorSecret for TestSecret {
     type Hash = HashWrapper;
     type Signature = SignatureWrapper;
 
-    fn sign(&self, data: &Self::Hash) -> Self::Signature {
-        SignatureWrapper(data.0 + self.0)
+    fn sign(&self, data: &Self::Hash) -> Option<Self::Signature> {
+        Some(SignatureWrapper(data.0 + self.0))
+    }
+
+    fn sign_unique(
+        &self,
+        _kind: MessageKind,
+        _number: u64,
+        data: &Self::Hash,
+    ) -> Option<Self::Signature> {
+        self.sign(data)
     }
 }
 
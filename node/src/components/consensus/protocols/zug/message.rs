@@ -11,6 +11,7 @@ use crate::{
         traits::{ConsensusNetworkMessage, Context, ValidatorSecret},
         utils::ValidatorIndex,
     },
+    signer::slashing_protection::MessageKind,
     utils::ds,
 };
 
@@ -128,22 +129,26 @@ where
 }
 
 impl<C: Context> SignedMessage<C> {
-    /// Creates a new signed message with a valid signature.
+    /// Creates a new signed message with a valid signature, or returns `None` if signing failed.
     pub(crate) fn sign_new(
         round_id: RoundId,
         instance_id: C::InstanceId,
         content: Content<C>,
         validator_idx: ValidatorIndex,
         secret: &C::ValidatorSecret,
-    ) -> SignedMessage<C> {
+    ) -> Option<SignedMessage<C>> {
         let hash = Self::hash_fields(round_id, &instance_id, &content, validator_idx);
-        SignedMessage {
+        let kind = match content {
+            Content::Echo(_) => MessageKind::ZugEcho,
+            Content::Vote(_) => MessageKind::ZugVote,
+        };
+        Some(SignedMessage {
             round_id,
             instance_id,
             content,
             validator_idx,
-            signature: secret.sign(&hash),
-        }
+            signature: secret.sign_unique(kind, u64::from(round_id), &hash)?,
+        })
     }
 
     /// Creates a new signed message with the alternative content and signature.
@@ -216,6 +216,7 @@ mod tests {
                 validator_idx,
                 &alice_keypair,
             )
+            .unwrap()
         })
     }
 
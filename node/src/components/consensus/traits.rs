@@ -6,6 +6,8 @@ use std::{
 use datasize::DataSize;
 use serde::{de::DeserializeOwned, Serialize};
 
+use crate::signer::slashing_protection::MessageKind;
+
 /// A validator identifier.
 pub trait ValidatorIdT: Eq + Ord + Clone + Debug + Hash + Send + DataSize + Display {}
 impl<VID> ValidatorIdT for VID where VID: Eq + Ord + Clone + Debug + Hash + Send + DataSize + Display
@@ -45,7 +47,21 @@ pub trait ValidatorSecret: Send + DataSize {
 
     type Signature: Eq + PartialEq + Clone + Debug + Hash + Serialize + DeserializeOwned + DataSize;
 
-    fn sign(&self, hash: &Self::Hash) -> Self::Signature;
+    /// Signs the hash, or returns `None` if signing failed, e.g. because a remote signer refused.
+    ///
+    /// Messages of which a validator may sign at most one per round or sequence number must be
+    /// signed via `sign_unique` instead.
+    fn sign(&self, hash: &Self::Hash) -> Option<Self::Signature>;
+
+    /// Signs the hash of a message of the given kind with the given round or sequence number, or
+    /// returns `None` if signing failed, or slashing protection refused it because a different
+    /// message of that kind and number was already signed in this era.
+    fn sign_unique(
+        &self,
+        kind: MessageKind,
+        number: u64,
+        hash: &Self::Hash,
+    ) -> Option<Self::Signature>;
 }
 
 /// The collection of types the user can choose for cryptography, IDs, transactions, etc.
@@ -4,6 +4,7 @@ mod config;
 mod error;
 mod metrics;
 mod operations;
+mod parallel_execution;
 #[cfg(test)]
 mod tests;
 mod types;
@@ -14,7 +15,10 @@ use std::{
     convert::TryInto,
     fmt::{self, Debug, Display, Formatter},
     path::Path,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{self, AtomicBool},
+        Arc, Mutex,
+    },
     time::Instant,
 };
 
@@ -52,7 +56,9 @@ use crate::{
             UnexecutedBlockAnnouncement,
         },
         incoming::{TrieDemand, TrieRequest, TrieRequestIncoming},
-        requests::{ContractRuntimeRequest, NetworkRequest, StorageRequest},
+        requests::{
+            BlockSynchronizerRequest, ContractRuntimeRequest, NetworkRequest, StorageRequest,
+        },
         EffectBuilder, EffectExt, Effects,
     },
     fatal,
@@ -67,7 +73,7 @@ pub(crate) use config::Config;
 pub(crate) use error::{BlockExecutionError, ConfigError};
 use metrics::Metrics;
 pub use operations::execute_finalized_block;
-use operations::execute_only;
+use operations::{execute_only, execute_only_sequence};
 pub(crate) use types::{
     BlockAndExecutionResults, EraValidatorsRequest, StepEffectAndUpcomingEraValidators,
 };
@@ -212,6 +218,13 @@ pub(crate) struct ContractRuntime {
     system_contract_registry: Option<SystemContractRegistry>,
     activation_point: ActivationPoint,
     prune_batch_size: u64,
+    /// The number of most recent eras whose global state is retained, if pruning is enabled.
+    retained_eras: Option<u64>,
+    /// Whether a global state garbage collection is currently running in the background.
+    #[data_size(skip)]
+    gc_in_progress: Arc<AtomicBool>,
+    /// The number of threads used to execute the native transfers of a block in parallel.
+    execution_threads: usize,
 }
 
 impl Debug for ContractRuntime {
@@ -226,6 +239,7 @@ where
         + From<ContractRuntimeAnnouncement>
         + From<NetworkRequest<Message>>
         + From<StorageRequest>
+        + From<BlockSynchronizerRequest>
         + From<MetaBlockAnnouncement>
         + From<UnexecutedBlockAnnouncement>
         + From<FatalAnnouncement>
@@ -333,6 +347,7 @@ impl ContractRuntime {
         REv: From<ContractRuntimeRequest>
             + From<ContractRuntimeAnnouncement>
             + From<StorageRequest>
+            + From<BlockSynchronizerRequest>
             + From<MetaBlockAnnouncement>
             + From<UnexecutedBlockAnnouncement>
             + From<FatalAnnouncement>
@@ -494,6 +509,7 @@ impl ContractRuntime {
                         let shared_pre_state = Arc::clone(&self.execution_pre_state);
                         let activation_point = self.activation_point;
                         let prune_batch_size = self.prune_batch_size;
+                        let execution_threads = self.execution_threads;
                         effects.extend(
                             Self::execute_finalized_block_or_requeue(
                                 engine_state,
@@ -508,6 +524,7 @@ impl ContractRuntime {
                                 activation_point,
                                 key_block_height_for_activation_point,
                                 prune_batch_size,
+                                execution_threads,
                                 meta_block_state,
                             )
                             .ignore(),
@@ -569,6 +586,8 @@ impl ContractRuntime {
             ContractRuntimeRequest::SpeculativeDeployExecution {
                 execution_prestate,
                 deploy,
+                state_overrides,
+                trace,
                 responder,
             } => {
                 let engine_state = Arc::clone(&self.engine_state);
@@ -578,6 +597,33 @@ impl ContractRuntime {
                             engine_state.as_ref(),
                             execution_prestate,
                             DeployItem::from((*deploy).clone()),
+                            state_overrides,
+                            trace,
+                        )
+                    })
+                    .await;
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
+            ContractRuntimeRequest::SpeculativeDeploySequenceExecution {
+                execution_prestate,
+                deploys,
+                dry_run,
+                responder,
+            } => {
+                let engine_state = Arc::clone(&self.engine_state);
+                async move {
+                    let result = run_intensive_task(move || {
+                        let deploy_items = deploys
+                            .iter()
+                            .map(|deploy| DeployItem::from((**deploy).clone()))
+                            .collect();
+                        execute_only_sequence(
+                            engine_state.as_ref(),
+                            execution_prestate,
+                            deploy_items,
+                            dry_run,
                         )
                     })
                     .await;
@@ -585,6 +631,33 @@ impl ContractRuntime {
                 }
                 .ignore()
             }
+            ContractRuntimeRequest::CollectGarbage {
+                switch_block_header,
+                pre_state_root_hash,
+            } => {
+                let retained_eras = match self.retained_eras {
+                    Some(retained_eras) => retained_eras,
+                    None => return Effects::new(),
+                };
+                if self.gc_in_progress.swap(true, atomic::Ordering::SeqCst) {
+                    debug!("ContractRuntime: global state pruning already in progress, skipping");
+                    return Effects::new();
+                }
+                let engine_state = Arc::clone(&self.engine_state);
+                let gc_in_progress = Arc::clone(&self.gc_in_progress);
+                async move {
+                    Self::collect_garbage(
+                        engine_state,
+                        effect_builder,
+                        *switch_block_header,
+                        pre_state_root_hash,
+                        retained_eras,
+                    )
+                    .await;
+                    gc_in_progress.store(false, atomic::Ordering::SeqCst);
+                }
+                .ignore()
+            }
         }
     }
 }
@@ -611,6 +684,7 @@ impl ContractRuntime {
         allow_unrestricted_transfers: bool,
         refund_handling: RefundHandling,
         fee_handling: FeeHandling,
+        deploy_validator_gas_limit: u64,
     ) -> Result<Self, ConfigError> {
         // TODO: This is bogus, get rid of this
         let execution_pre_state = Arc::new(Mutex::new(ExecutionPreState {
@@ -649,6 +723,7 @@ impl ContractRuntime {
             .with_allow_unrestricted_transfers(allow_unrestricted_transfers)
             .with_refund_handling(refund_handling)
             .with_fee_handling(fee_handling)
+            .with_deploy_validator_gas_limit(deploy_validator_gas_limit)
             .build();
 
         let engine_state = Arc::new(EngineState::new(global_state, engine_config));
@@ -665,6 +740,9 @@ impl ContractRuntime {
             system_contract_registry: None,
             activation_point,
             prune_batch_size,
+            retained_eras: contract_runtime_config.retained_eras,
+            gc_in_progress: Arc::new(AtomicBool::new(false)),
+            execution_threads: contract_runtime_config.execution_threads_or_default(),
         })
     }
 
@@ -756,6 +834,7 @@ impl ContractRuntime {
         activation_point: ActivationPoint,
         key_block_height_for_activation_point: u64,
         prune_batch_size: u64,
+        execution_threads: usize,
         mut meta_block_state: MetaBlockState,
     ) where
         REv: From<ContractRuntimeRequest>
@@ -767,6 +846,7 @@ impl ContractRuntime {
     {
         debug!("ContractRuntime: execute_finalized_block_or_requeue");
         let contract_runtime_metrics = metrics.clone();
+        let pre_state_root_hash = current_pre_state.pre_state_root_hash;
         let BlockAndExecutionResults {
             block,
             approvals_hashes,
@@ -784,6 +864,7 @@ impl ContractRuntime {
                 activation_point.era_id(),
                 key_block_height_for_activation_point,
                 prune_batch_size,
+                execution_threads,
             )
         })
         .await
@@ -795,6 +876,14 @@ impl ContractRuntime {
             }
         };
 
+        // Collect garbage in the background, so that executing the following blocks isn't held
+        // up by it.
+        if block.header().is_switch_block() {
+            effect_builder
+                .collect_global_state_garbage(block.header().clone(), pre_state_root_hash)
+                .await;
+        }
+
         let new_execution_pre_state = ExecutionPreState::from_block_header(block.header());
         {
             // The `shared_pre_state` could have been set to a block we just fully synced after
@@ -909,6 +998,71 @@ impl ContractRuntime {
         }
     }
 
+    /// Deletes the global state which is not reachable from the state root hash of any block in
+    /// the `retained_eras` eras ending with the given switch block.
+    ///
+    /// Nothing is deleted while a global state sync is in progress, as the tries synced so far are
+    /// not yet reachable from any stored block.
+    async fn collect_garbage<REv>(
+        engine_state: Arc<EngineState<LmdbGlobalState>>,
+        effect_builder: EffectBuilder<REv>,
+        switch_block_header: BlockHeader,
+        pre_state_root_hash: Digest,
+        retained_eras: u64,
+    ) where
+        REv: From<StorageRequest> + From<BlockSynchronizerRequest>,
+    {
+        if effect_builder.is_syncing_global_state().await {
+            debug!("ContractRuntime: global state sync in progress, skipping global state pruning");
+            return;
+        }
+        let oldest_switch_block_era_id = match switch_block_header
+            .era_id()
+            .value()
+            .checked_sub(retained_eras)
+        {
+            Some(era_id) => EraId::from(era_id),
+            None => return,
+        };
+        let (lowest_retained_height, mut retained_roots) = match effect_builder
+            .get_state_root_hashes_since_switch_block_from_storage(oldest_switch_block_era_id)
+            .await
+        {
+            Some(height_and_roots) => height_and_roots,
+            None => {
+                debug!(
+                    era_id = %oldest_switch_block_era_id,
+                    "ContractRuntime: switch block not stored, skipping global state pruning"
+                );
+                return;
+            }
+        };
+        // The parent and the switch block itself may not have been stored yet.
+        retained_roots.push(pre_state_root_hash);
+        retained_roots.push(*switch_block_header.state_root_hash());
+
+        // Not run as an intensive task, so that it doesn't take a slot away from block execution.
+        let result = tokio::task::spawn_blocking(move || {
+            engine_state.collect_garbage(CorrelationId::new(), &retained_roots)
+        })
+        .await
+        .expect("global state pruning panicked");
+        match result {
+            Ok(gc_result) => {
+                info!(
+                    lowest_retained_height,
+                    deleted_tries = gc_result.deleted_tries,
+                    reclaimed_bytes = gc_result.reclaimed_bytes,
+                    "pruned global state"
+                );
+                effect_builder
+                    .put_state_pruned_below_height_to_storage(lowest_retained_height)
+                    .await;
+            }
+            Err(error) => error!(%error, "failed to prune global state"),
+        }
+    }
+
     /// Reads the trie (or chunk of a trie) under the given key and index.
     pub(crate) fn get_trie(
         &self,
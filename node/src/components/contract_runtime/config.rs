@@ -7,6 +7,7 @@ const DEFAULT_MAX_GLOBAL_STATE_SIZE: usize = 805_306_368_000; // 750 GiB
 const DEFAULT_MAX_READERS: u32 = 512;
 const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
 const DEFAULT_MANUAL_SYNC_ENABLED: bool = true;
+const DEFAULT_EXECUTION_THREADS: usize = 1;
 
 /// Contract runtime configuration.
 #[derive(Clone, Copy, DataSize, Debug, Deserialize, Serialize)]
@@ -31,6 +32,21 @@ pub struct Config {
     ///
     /// Defaults to `true`.
     pub enable_manual_sync: Option<bool>,
+    /// The number of most recent eras whose global state is retained.
+    ///
+    /// When set, global state tries which are not reachable from the state root hash of any block
+    /// in the retained eras are deleted at the end of every era.
+    ///
+    /// Defaults to `None`, i.e. no global state is deleted.
+    pub retained_eras: Option<u64>,
+    /// The number of threads used to execute the native transfers of a block in parallel.
+    ///
+    /// The native transfers are executed speculatively against the block's pre-state, and a
+    /// speculative result is only used if it is identical to the result of executing the deploy
+    /// sequentially, so the block's post-state hash is not affected.
+    ///
+    /// Defaults to 1, i.e. all deploys are executed sequentially.
+    pub execution_threads: Option<usize>,
 }
 
 impl Config {
@@ -58,6 +74,11 @@ impl Config {
         self.enable_manual_sync
             .unwrap_or(DEFAULT_MANUAL_SYNC_ENABLED)
     }
+
+    /// Number of threads used to execute native transfers in parallel.
+    pub fn execution_threads_or_default(&self) -> usize {
+        self.execution_threads.unwrap_or(DEFAULT_EXECUTION_THREADS)
+    }
 }
 
 impl Default for Config {
@@ -67,6 +88,8 @@ impl Default for Config {
             max_readers: Some(DEFAULT_MAX_READERS),
             max_query_depth: Some(DEFAULT_MAX_QUERY_DEPTH),
             enable_manual_sync: Some(DEFAULT_MANUAL_SYNC_ENABLED),
+            retained_eras: None,
+            execution_threads: Some(DEFAULT_EXECUTION_THREADS),
         }
     }
 }
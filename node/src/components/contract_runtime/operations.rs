@@ -12,22 +12,28 @@ use casper_execution_engine::{
             StepSuccess,
         },
         execution,
+        runtime::trace::ExecutionTrace,
+    },
+    shared::{
+        additive_map::AdditiveMap, execution_journal::ExecutionJournal, newtypes::CorrelationId,
+        transform::Transform,
     },
-    shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
     storage::global_state::{lmdb::LmdbGlobalState, CommitProvider, StateProvider},
 };
 use casper_hashing::Digest;
 use casper_types::{
-    CLValue, DeployHash, EraId, ExecutionResult, Key, ProtocolVersion, PublicKey, U512,
+    CLValue, DeployHash, EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion, PublicKey,
+    StoredValue, U512,
 };
 
 use crate::{
     components::{
         consensus::EraReport,
         contract_runtime::{
-            error::BlockExecutionError, types::StepEffectAndUpcomingEraValidators,
-            BlockAndExecutionResults, ExecutionPreState, Metrics, SpeculativeExecutionState,
-            APPROVALS_CHECKSUM_NAME, EXECUTION_RESULTS_CHECKSUM_NAME,
+            error::BlockExecutionError, parallel_execution::SpeculativeExecution,
+            types::StepEffectAndUpcomingEraValidators, BlockAndExecutionResults, ExecutionPreState,
+            Metrics, SpeculativeExecutionState, APPROVALS_CHECKSUM_NAME,
+            EXECUTION_RESULTS_CHECKSUM_NAME,
         },
         fetcher::FetchItem,
     },
@@ -99,6 +105,7 @@ pub fn execute_finalized_block(
     activation_point_era_id: EraId,
     key_block_height_for_activation_point: u64,
     prune_batch_size: u64,
+    execution_threads: usize,
 ) -> Result<BlockAndExecutionResults, BlockExecutionError> {
     if finalized_block.height() != execution_pre_state.next_block_height {
         return Err(BlockExecutionError::WrongBlockHeight {
@@ -125,8 +132,29 @@ pub fn execute_finalized_block(
     // Create a new EngineState that reads from LMDB but only caches changes in memory.
     let scratch_state = engine_state.get_scratch_engine_state();
 
+    // If enabled, execute the native transfers in parallel against the pre-state.  Their results
+    // are only used below if they are identical to the results of executing them in order.
+    let transfer_count = deploys
+        .iter()
+        .filter(|deploy| deploy.session().is_transfer())
+        .count();
+    let mut maybe_speculative_execution = if execution_threads > 1 && transfer_count > 1 {
+        Some(SpeculativeExecution::run(
+            engine_state,
+            metrics.clone(),
+            protocol_version,
+            pre_state_root_hash,
+            block_time,
+            &finalized_block.proposer(),
+            &deploys,
+            execution_threads,
+        )?)
+    } else {
+        None
+    };
+
     // WARNING: Do not change the order of `deploys` as it will result in a different root hash.
-    for deploy in deploys {
+    for (index, deploy) in deploys.into_iter().enumerate() {
         let deploy_hash = *deploy.hash();
         let deploy_header = deploy.header().clone();
         let execute_request = ExecuteRequest::new(
@@ -137,14 +165,27 @@ pub fn execute_finalized_block(
             *finalized_block.proposer(),
         );
 
+        let maybe_speculative_result = match maybe_speculative_execution.as_mut() {
+            Some(speculative_execution) => {
+                speculative_execution.take_valid_result(index, &scratch_state)?
+            }
+            None => None,
+        };
+
         // TODO: this is currently working coincidentally because we are passing only one
         // deploy_item per exec. The execution results coming back from the EE lack the
         // mapping between deploy_hash and execution result, and this outer logic is
         // enriching it with the deploy hash. If we were passing multiple deploys per exec
         // the relation between the deploy and the execution results would be lost.
-        let result = execute(&scratch_state, metrics.clone(), execute_request)?;
+        let result = match maybe_speculative_result {
+            Some(result) => result,
+            None => execute(&scratch_state, metrics.clone(), execute_request)?,
+        };
 
         trace!(?deploy_hash, ?result, "deploy execution result");
+        if let Some(speculative_execution) = maybe_speculative_execution.as_mut() {
+            speculative_execution.record_writes(&result);
+        }
         // As for now a given state is expected to exist.
         let (state_hash, execution_result) = commit_execution_results(
             &scratch_state,
@@ -157,6 +198,14 @@ pub fn execute_finalized_block(
         state_root_hash = state_hash;
     }
 
+    if let Some(speculative_execution) = maybe_speculative_execution {
+        debug!(
+            transfer_count,
+            used_results = speculative_execution.used_results(),
+            "executed native transfers in parallel"
+        );
+    }
+
     // Write the deploy approvals and execution results Merkle root hashes to global state if there
     // were any deploys.
     let execution_results_checksum = compute_execution_results_checksum(
@@ -407,16 +456,19 @@ where
 /// Execute the transaction without commiting the effects.
 /// Intended to be used for discovery operations on read-only nodes.
 ///
+/// If `state_overrides` is not empty, the given values are stored in a scratch copy of the global
+/// state, on top of which the transaction is then executed.
+///
+/// If `trace` is `true`, the execution is traced and the trace is returned along with the result.
+///
 /// Returns effects of the execution.
-pub fn execute_only<S>(
-    engine_state: &EngineState<S>,
+pub fn execute_only(
+    engine_state: &EngineState<LmdbGlobalState>,
     execution_state: SpeculativeExecutionState,
     deploy: DeployItem,
-) -> Result<Option<ExecutionResult>, engine_state::Error>
-where
-    S: StateProvider + CommitProvider,
-    S::Error: Into<execution::Error>,
-{
+    state_overrides: BTreeMap<Key, StoredValue>,
+    trace: bool,
+) -> Result<Option<(ExecutionResult, Option<ExecutionTrace>)>, engine_state::Error> {
     let SpeculativeExecutionState {
         state_root_hash,
         block_time,
@@ -430,8 +482,18 @@ where
         protocol_version,
         PublicKey::System,
     );
-    let results = execute(engine_state, None, execute_request);
-    results.map(|mut execution_results| {
+    let results = if state_overrides.is_empty() {
+        execute_maybe_traced(engine_state, execute_request, trace)
+    } else {
+        let scratch_state = engine_state.get_scratch_engine_state();
+        let overrides = state_overrides
+            .into_iter()
+            .map(|(key, value)| (key, Transform::Write(value)))
+            .collect();
+        commit_transforms(&scratch_state, None, state_root_hash, overrides)?;
+        execute_maybe_traced(&scratch_state, execute_request, trace)
+    };
+    results.map(|(mut execution_results, mut traces)| {
         let len = execution_results.len();
         if len != 1 {
             warn!(
@@ -444,12 +506,92 @@ where
             // with `Some(_)` but `pop_front` already returns an `Option`.
             // We need to transform the `engine_state::ExecutionResult` into
             // `casper_types::ExecutionResult` as well.
-            execution_results.pop_front().map(Into::into)
+            execution_results
+                .pop_front()
+                .map(|execution_result| (execution_result.into(), traces.pop()))
         }
     })
 }
 
-fn execute<S>(
+/// Executes the request, tracing the execution of each deploy if `trace` is `true`.
+///
+/// Returns the execution results, along with the trace of each deploy if traced.
+fn execute_maybe_traced<S>(
+    engine_state: &EngineState<S>,
+    execute_request: ExecuteRequest,
+    trace: bool,
+) -> Result<(ExecutionResults, Vec<ExecutionTrace>), engine_state::Error>
+where
+    S: StateProvider + CommitProvider,
+    S::Error: Into<execution::Error>,
+{
+    if trace {
+        engine_state.run_execute_with_trace(CorrelationId::new(), execute_request)
+    } else {
+        execute(engine_state, None, execute_request)
+            .map(|execution_results| (execution_results, vec![]))
+    }
+}
+
+/// Executes the given deploys in order without commiting their effects, each on top of the
+/// effects of the ones before it.
+///
+/// If `dry_run` is `true`, the deploys are executed via [`EngineState::run_dry_run`], i.e. without
+/// checking their approvals and without running their payment code.
+///
+/// Returns the execution result of each deploy, along with the combined effects of all of them.
+pub fn execute_only_sequence(
+    engine_state: &EngineState<LmdbGlobalState>,
+    execution_state: SpeculativeExecutionState,
+    deploys: Vec<DeployItem>,
+    dry_run: bool,
+) -> Result<(Vec<ExecutionResult>, ExecutionEffect), engine_state::Error> {
+    let SpeculativeExecutionState {
+        state_root_hash,
+        block_time,
+        protocol_version,
+    } = execution_state;
+    // The effects of each deploy are committed to the scratch state, which keeps reporting the
+    // same state root hash while holding the current state.
+    let scratch_state = engine_state.get_scratch_engine_state();
+
+    let mut execution_results = Vec::with_capacity(deploys.len());
+    let mut combined_transforms: AdditiveMap<Key, Transform> = AdditiveMap::new();
+    for deploy in deploys {
+        let execute_request = ExecuteRequest::new(
+            state_root_hash,
+            block_time.millis(),
+            vec![deploy],
+            protocol_version,
+            PublicKey::System,
+        );
+        let ee_execution_results = if dry_run {
+            scratch_state.run_dry_run(CorrelationId::new(), execute_request)?
+        } else {
+            execute(&scratch_state, None, execute_request)?
+        };
+        for ee_execution_result in ee_execution_results {
+            let execution_journal = ee_execution_result.execution_journal().clone();
+            for (key, transform) in execution_journal.iter() {
+                combined_transforms.insert_add(*key, transform.clone());
+            }
+            commit_transforms(
+                &scratch_state,
+                None,
+                state_root_hash,
+                execution_journal.into(),
+            )?;
+            execution_results.push(ExecutionResult::from(ee_execution_result));
+        }
+    }
+
+    let mut combined_transforms: Vec<(Key, Transform)> = combined_transforms.into_iter().collect();
+    combined_transforms.sort_by_key(|(key, _transform)| *key);
+    let combined_effect = ExecutionEffect::from(ExecutionJournal::new(combined_transforms));
+    Ok((execution_results, combined_effect))
+}
+
+pub(super) fn execute<S>(
     engine_state: &EngineState<S>,
     metrics: Option<Arc<Metrics>>,
     execute_request: ExecuteRequest,
@@ -0,0 +1,338 @@
+//! Optimistic parallel execution of the native transfers of a block.
+//!
+//! Before the deploys of a block are executed in order, its native transfers are executed
+//! concurrently, each against the pre-state of the block.  When a native transfer's turn comes, its
+//! speculative result is used instead of executing it again, unless the value under any key in its
+//! footprint has been changed by an earlier deploy of the block, in which case it is re-executed
+//! against the current state.
+//!
+//! The footprint of a speculative result is the set of keys in its execution journal, along with
+//! the keys declared up front for every native transfer (see [`declared_footprint`]).  The journal
+//! records every key read, written or added to by the deploy, and the result of executing a deploy
+//! depends only on the values under the keys it reads.  Hence a speculative result whose footprint
+//! is unchanged is identical to the result of executing the deploy sequentially, and the block's
+//! post-state hash is the same as if all deploys were executed sequentially.
+//!
+//! Two refinements keep unrelated transfers from conflicting on the purses shared by all deploys:
+//!
+//! * keys are compared by value, so e.g. the payment purse, which every deploy empties again, is
+//!   not a conflict, and
+//! * keys which a deploy only added to (e.g. the proposer's purse receiving the fees) are not a
+//!   conflict as long as earlier deploys also only added to them, as wrapping additions commute.
+//!
+//! Native transfers from accounts with a deploy validator are never executed speculatively: the
+//! validator runs against a fork of the deploy's tracking copy, so the keys it reads are missing
+//! from the journal, and its decision could depend on a value changed by an earlier deploy.  An
+//! earlier deploy installing a validator changes the sender's account, which is part of the
+//! declared footprint, so such a transfer is re-executed too.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    panic,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use tracing::debug;
+
+use casper_execution_engine::{
+    core::engine_state::{
+        self, execution_result::ExecutionResults, DeployItem, EngineState, ExecuteRequest,
+        ExecutionResult as EngineExecutionResult,
+    },
+    shared::{newtypes::CorrelationId, transform::Transform},
+    storage::global_state::{
+        lmdb::{LmdbGlobalState, LmdbGlobalStateView},
+        scratch::ScratchGlobalState,
+        StateProvider, StateReader,
+    },
+};
+use casper_hashing::Digest;
+use casper_types::{account::DEPLOY_VALIDATOR_KEY, Key, ProtocolVersion, PublicKey, StoredValue};
+
+use super::{operations, Metrics};
+use crate::types::Deploy;
+
+/// The result of executing a native transfer against the pre-state of its block.
+struct SpeculativeResult {
+    /// The keys read or overwritten by the deploy.
+    read_keys: BTreeSet<Key>,
+    /// The keys which the deploy only added to.
+    added_keys: BTreeSet<Key>,
+    execution_results: ExecutionResults,
+}
+
+/// The speculative results of the native transfers of a block, and the state needed to validate
+/// them while the block's deploys are executed in order.
+pub(super) struct SpeculativeExecution {
+    /// The speculative results, indexed by the position of their deploy in the block.
+    results: Vec<Option<SpeculativeResult>>,
+    /// The pre-state of the block.
+    pre_state: LmdbGlobalStateView,
+    /// The pre-state root hash of the block, under which the scratch state holds the current
+    /// state.
+    pre_state_root_hash: Digest,
+    /// The keys written by the deploys executed so far, mapped to `true` if any of the writes was
+    /// not an addition.
+    written_keys: BTreeMap<Key, bool>,
+    /// The number of speculative results used so far.
+    used_results: usize,
+}
+
+impl SpeculativeExecution {
+    /// Executes the native transfers among `deploys` on up to `threads` threads, each against the
+    /// state under `pre_state_root_hash`.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn run(
+        engine_state: &EngineState<LmdbGlobalState>,
+        metrics: Option<Arc<Metrics>>,
+        protocol_version: ProtocolVersion,
+        pre_state_root_hash: Digest,
+        block_time: u64,
+        proposer: &PublicKey,
+        deploys: &[Deploy],
+        threads: usize,
+    ) -> Result<Self, engine_state::Error> {
+        let pre_state = engine_state
+            .get_state()
+            .checkout(pre_state_root_hash)?
+            .ok_or(engine_state::Error::RootNotFound(pre_state_root_hash))?;
+
+        let next_index = AtomicUsize::new(0);
+        let per_thread_results = thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|_| {
+                    let metrics = metrics.clone();
+                    let next_index = &next_index;
+                    let pre_state = &pre_state;
+                    scope.spawn(move || {
+                        let mut results = vec![];
+                        loop {
+                            let index = next_index.fetch_add(1, Ordering::Relaxed);
+                            let deploy = match deploys.get(index) {
+                                Some(deploy) => deploy,
+                                None => break results,
+                            };
+                            if let Some(result) = execute_transfer(
+                                engine_state,
+                                metrics.clone(),
+                                protocol_version,
+                                pre_state,
+                                pre_state_root_hash,
+                                block_time,
+                                proposer,
+                                deploy,
+                            ) {
+                                results.push((index, result));
+                            }
+                        }
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|error| panic::resume_unwind(error))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let mut results: Vec<_> = deploys.iter().map(|_| None).collect();
+        for (index, result) in per_thread_results.into_iter().flatten() {
+            results[index] = Some(result);
+        }
+
+        Ok(SpeculativeExecution {
+            results,
+            pre_state,
+            pre_state_root_hash,
+            written_keys: BTreeMap::new(),
+            used_results: 0,
+        })
+    }
+
+    /// Takes the speculative result of the deploy at `index` in the block, if there is one and it
+    /// is identical to the result of executing the deploy against `current_state`.
+    pub(super) fn take_valid_result(
+        &mut self,
+        index: usize,
+        current_state: &EngineState<ScratchGlobalState>,
+    ) -> Result<Option<ExecutionResults>, engine_state::Error> {
+        let result = match self.results.get_mut(index).and_then(Option::take) {
+            Some(result) => result,
+            None => return Ok(None),
+        };
+
+        let current_state = current_state
+            .tracking_copy(self.pre_state_root_hash)?
+            .ok_or(engine_state::Error::RootNotFound(self.pre_state_root_hash))?;
+        let is_changed = |key: &Key| -> Result<bool, engine_state::Error> {
+            let correlation_id = CorrelationId::new();
+            let pre_state_value = self.pre_state.read(correlation_id, key)?;
+            let current_value = current_state.reader().read(correlation_id, key)?;
+            Ok(pre_state_value != current_value)
+        };
+
+        for key in &result.read_keys {
+            if self.written_keys.contains_key(key) && is_changed(key)? {
+                debug!(?key, "speculative result invalidated by earlier deploy");
+                return Ok(None);
+            }
+        }
+        for key in &result.added_keys {
+            if self.written_keys.get(key) == Some(&true) && is_changed(key)? {
+                debug!(?key, "speculative result invalidated by earlier deploy");
+                return Ok(None);
+            }
+        }
+
+        self.used_results += 1;
+        Ok(Some(result.execution_results))
+    }
+
+    /// Records the keys written by the given execution results, which are about to be committed.
+    pub(super) fn record_writes(&mut self, execution_results: &ExecutionResults) {
+        for execution_result in execution_results {
+            for (key, transform) in execution_result.execution_journal().iter() {
+                if matches!(transform, Transform::Identity) {
+                    continue;
+                }
+                let is_overwritten = self.written_keys.entry(*key).or_insert(false);
+                *is_overwritten |= !is_addition(transform);
+            }
+        }
+    }
+
+    /// Returns the number of speculative results used so far.
+    pub(super) fn used_results(&self) -> usize {
+        self.used_results
+    }
+
+    /// Returns `true` if the deploy at `index` in the block has a speculative result.
+    #[cfg(test)]
+    pub(super) fn has_result(&self, index: usize) -> bool {
+        matches!(self.results.get(index), Some(Some(_)))
+    }
+}
+
+/// Executes the given deploy against the state under `pre_state_root_hash` if it is a native
+/// transfer.
+///
+/// Returns `None` if the deploy is not a native transfer, if its sender has a deploy validator, or
+/// if its execution didn't succeed, as the journal of a failed execution doesn't record the keys
+/// read before the failure.
+#[allow(clippy::too_many_arguments)]
+fn execute_transfer(
+    engine_state: &EngineState<LmdbGlobalState>,
+    metrics: Option<Arc<Metrics>>,
+    protocol_version: ProtocolVersion,
+    pre_state: &LmdbGlobalStateView,
+    pre_state_root_hash: Digest,
+    block_time: u64,
+    proposer: &PublicKey,
+    deploy: &Deploy,
+) -> Option<SpeculativeResult> {
+    if !deploy.session().is_transfer() {
+        return None;
+    }
+
+    match has_deploy_validator(pre_state, deploy) {
+        Ok(false) => (),
+        Ok(true) => return None,
+        Err(error) => {
+            debug!(deploy_hash = %deploy.hash(), %error, "failed to read sender account");
+            return None;
+        }
+    }
+
+    let execute_request = ExecuteRequest::new(
+        pre_state_root_hash,
+        block_time,
+        vec![DeployItem::from(deploy.clone())],
+        protocol_version,
+        proposer.clone(),
+    );
+    let execution_results = match operations::execute(engine_state, metrics, execute_request) {
+        Ok(execution_results) => execution_results,
+        Err(error) => {
+            debug!(deploy_hash = %deploy.hash(), %error, "speculative execution failed");
+            return None;
+        }
+    };
+    if !execution_results
+        .iter()
+        .all(EngineExecutionResult::is_success)
+    {
+        return None;
+    }
+
+    let mut read_keys: BTreeSet<Key> = declared_footprint(deploy, proposer).collect();
+    let mut transforms_by_key: BTreeMap<Key, bool> = BTreeMap::new();
+    for execution_result in &execution_results {
+        for (key, transform) in execution_result.execution_journal().iter() {
+            let is_addition_only = transforms_by_key.entry(*key).or_insert(true);
+            *is_addition_only &= is_addition(transform);
+        }
+    }
+    let mut added_keys = BTreeSet::new();
+    for (key, is_addition_only) in transforms_by_key {
+        if is_addition_only && !read_keys.contains(&key) {
+            let _ = added_keys.insert(key);
+        } else {
+            let _ = read_keys.insert(key);
+        }
+    }
+
+    Some(SpeculativeResult {
+        read_keys,
+        added_keys,
+        execution_results,
+    })
+}
+
+/// Returns the keys which every native transfer depends on, whether or not they are recorded in
+/// its execution journal: the account of the sender, and the account of the proposer, whose main
+/// purse is looked up outside of the deploy's tracking copy.
+fn declared_footprint(deploy: &Deploy, proposer: &PublicKey) -> impl Iterator<Item = Key> {
+    let sender = deploy.header().account().to_account_hash();
+    vec![
+        Key::Account(sender),
+        Key::Account(proposer.to_account_hash()),
+    ]
+    .into_iter()
+}
+
+/// Returns `true` if the sender of the deploy has a deploy validator in the pre-state.
+///
+/// The keys read by a deploy validator aren't recorded in the deploy's execution journal, so the
+/// speculative result of such a deploy couldn't be validated.
+fn has_deploy_validator(
+    pre_state: &LmdbGlobalStateView,
+    deploy: &Deploy,
+) -> Result<bool, engine_state::Error> {
+    let sender = Key::Account(deploy.header().account().to_account_hash());
+    match pre_state.read(CorrelationId::new(), &sender)? {
+        Some(StoredValue::Account(account)) => {
+            Ok(account.named_keys().contains_key(DEPLOY_VALIDATOR_KEY))
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Returns `true` if the transform adds to the existing value rather than replacing it.
+fn is_addition(transform: &Transform) -> bool {
+    matches!(
+        transform,
+        Transform::AddInt32(_)
+            | Transform::AddUInt64(_)
+            | Transform::AddUInt128(_)
+            | Transform::AddUInt256(_)
+            | Transform::AddUInt512(_)
+            | Transform::AddKeys(_)
+    )
+}
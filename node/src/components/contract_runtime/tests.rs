@@ -2,14 +2,21 @@ use std::{sync::Arc, time::Duration};
 
 use derive_more::{Display, From};
 use prometheus::Registry;
-use rand::RngCore;
+use rand::{Rng, RngCore};
 use serde::Serialize;
 use tempfile::TempDir;
 
-use casper_execution_engine::core::engine_state::ExecutableDeployItem;
-use casper_types::{runtime_args, EraId, PublicKey, RuntimeArgs, SecretKey, TimeDiff, U512};
+use casper_execution_engine::{
+    core::engine_state::ExecutableDeployItem,
+    shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
+    storage::global_state::{StateProvider, StateReader},
+};
+use casper_types::{
+    account::DEPLOY_VALIDATOR_KEY, runtime_args, CLValue, EraId, ExecutionEffect, ExecutionResult,
+    Key, PublicKey, RuntimeArgs, SecretKey, StoredValue, TimeDiff, U512,
+};
 
-use super::*;
+use super::{parallel_execution::SpeculativeExecution, *};
 use crate::{
     components::{
         consensus::EraReport,
@@ -65,6 +72,7 @@ impl<T: Unhandled> From<T> for Event {
     }
 }
 
+impl Unhandled for BlockSynchronizerRequest {}
 impl Unhandled for ControlAnnouncement {}
 impl Unhandled for FatalAnnouncement {}
 impl Unhandled for NetworkRequest<Message> {}
@@ -125,6 +133,7 @@ impl reactor::Reactor for Reactor {
             chainspec.core_config.allow_unrestricted_transfers,
             chainspec.core_config.refund_handling,
             chainspec.core_config.fee_handling,
+            chainspec.core_config.deploy_validator_gas_limit,
         )?;
 
         let reactor = Reactor {
@@ -392,3 +401,512 @@ async fn should_not_set_shared_pre_state_to_lower_block_height() {
         next_block_height
     );
 }
+
+/// A contract runtime with global state initialized from the local chainspec, used to compare the
+/// results of executing a block sequentially with those of executing it in parallel.
+struct ExecutionFixture {
+    chainspec: Chainspec,
+    contract_runtime: ContractRuntime,
+    genesis_pre_state: ExecutionPreState,
+    timestamp: Timestamp,
+    _storage_tempdir: TempDir,
+}
+
+impl ExecutionFixture {
+    fn new() -> Self {
+        let (chainspec, chainspec_raw_bytes) =
+            <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+        let storage_tempdir = tempfile::tempdir().unwrap();
+        let config = Config {
+            max_global_state_size: Some(100 * 1024 * 1024),
+            ..Config::default()
+        };
+        let contract_runtime = ContractRuntime::new(
+            chainspec.protocol_version(),
+            storage_tempdir.path(),
+            &config,
+            chainspec.wasm_config,
+            chainspec.system_costs_config,
+            chainspec.core_config.max_associated_keys,
+            chainspec.core_config.max_runtime_call_stack_height,
+            chainspec.core_config.minimum_delegation_amount,
+            chainspec.protocol_config.activation_point,
+            chainspec.core_config.prune_batch_size,
+            chainspec.core_config.strict_argument_checking,
+            chainspec.core_config.vesting_schedule_period.millis(),
+            Some(chainspec.core_config.max_delegators_per_validator),
+            &Registry::new(),
+            chainspec.core_config.administrators.clone(),
+            chainspec.core_config.allow_auction_bids,
+            chainspec.core_config.allow_unrestricted_transfers,
+            chainspec.core_config.refund_handling,
+            chainspec.core_config.fee_handling,
+            chainspec.core_config.deploy_validator_gas_limit,
+        )
+        .unwrap();
+        let post_commit_genesis_state_hash = contract_runtime
+            .commit_genesis(&chainspec, &chainspec_raw_bytes)
+            .unwrap()
+            .post_state_hash;
+        let genesis_pre_state = ExecutionPreState::new(
+            0,
+            post_commit_genesis_state_hash,
+            BlockHash::default(),
+            Digest::default(),
+        );
+
+        ExecutionFixture {
+            chainspec,
+            contract_runtime,
+            genesis_pre_state,
+            timestamp: Timestamp::now(),
+            _storage_tempdir: storage_tempdir,
+        }
+    }
+
+    /// Returns the secret keys of the accounts funded at genesis.
+    fn genesis_secret_keys() -> Vec<SecretKey> {
+        ["faucet", "node-1", "node-2", "node-3", "node-4", "node-5"]
+            .iter()
+            .map(|name| {
+                SecretKey::from_file(
+                    RESOURCES_PATH
+                        .join("local")
+                        .join("secret_keys")
+                        .join(format!("{}.pem", name)),
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn transfer(&self, sender: &SecretKey, target: &PublicKey, amount: U512, id: u64) -> Deploy {
+        let payment = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: runtime_args! {
+              "amount" => U512::from(self.chainspec.system_costs_config.wasmless_transfer_cost()),
+            },
+        };
+        let session = ExecutableDeployItem::Transfer {
+            args: runtime_args! {
+              "amount" => amount,
+              "target" => target.clone(),
+              "id" => Some(id),
+            },
+        };
+        Deploy::new(
+            self.timestamp,
+            TimeDiff::from_seconds(100),
+            1,
+            vec![],
+            self.chainspec.network_config.name.clone(),
+            payment,
+            session,
+            sender,
+            None,
+        )
+    }
+
+    /// Adds a deploy validator named key referring to `validator` to the given genesis account,
+    /// directly in the genesis state.
+    fn install_deploy_validator(&mut self, account: &PublicKey, validator: Key) {
+        let engine_state = self.contract_runtime.engine_state.as_ref();
+        let pre_state_root_hash = self.genesis_pre_state.pre_state_root_hash;
+        let account_key = Key::Account(account.to_account_hash());
+        let mut account = match engine_state
+            .get_state()
+            .checkout(pre_state_root_hash)
+            .unwrap()
+            .unwrap()
+            .read(CorrelationId::new(), &account_key)
+            .unwrap()
+        {
+            Some(StoredValue::Account(account)) => account,
+            other => panic!("expected genesis account, got {:?}", other),
+        };
+        let _ = account
+            .named_keys_mut()
+            .insert(DEPLOY_VALIDATOR_KEY.to_string(), validator);
+
+        let mut effects = AdditiveMap::new();
+        let _ = effects.insert(account_key, Transform::Write(StoredValue::Account(account)));
+        let post_state_root_hash = engine_state
+            .apply_effect(CorrelationId::new(), pre_state_root_hash, effects)
+            .unwrap();
+        self.genesis_pre_state = ExecutionPreState::new(
+            0,
+            post_state_root_hash,
+            BlockHash::default(),
+            Digest::default(),
+        );
+    }
+
+    /// Executes the given deploys in the first block after genesis.
+    fn execute(&self, deploys: Vec<Deploy>, execution_threads: usize) -> BlockAndExecutionResults {
+        let block_payload = BlockPayload::new(
+            vec![],
+            deploys.iter().map(DeployHashWithApprovals::from).collect(),
+            vec![],
+            true,
+        );
+        let proposer = PublicKey::from(&Self::genesis_secret_keys()[1]);
+        let finalized_block = FinalizedBlock::new(
+            block_payload,
+            None,
+            self.timestamp,
+            EraId::new(0),
+            0,
+            proposer,
+        );
+        execute_finalized_block(
+            self.contract_runtime.engine_state.as_ref(),
+            None,
+            self.chainspec.protocol_version(),
+            self.genesis_pre_state.clone(),
+            finalized_block,
+            deploys,
+            self.chainspec.protocol_config.activation_point.era_id(),
+            0,
+            self.chainspec.core_config.prune_batch_size,
+            execution_threads,
+        )
+        .unwrap()
+    }
+
+    /// Executes the given deploys speculatively in sequence on top of the genesis state.
+    fn execute_speculatively(
+        &self,
+        deploys: &[Deploy],
+        dry_run: bool,
+    ) -> (Vec<ExecutionResult>, ExecutionEffect) {
+        let execution_prestate = SpeculativeExecutionState {
+            state_root_hash: self.genesis_pre_state.pre_state_root_hash,
+            block_time: self.timestamp,
+            protocol_version: self.chainspec.protocol_version(),
+        };
+        operations::execute_only_sequence(
+            self.contract_runtime.engine_state.as_ref(),
+            execution_prestate,
+            deploys.iter().cloned().map(DeployItem::from).collect(),
+            dry_run,
+        )
+        .unwrap()
+    }
+
+    /// Checks that executing the given deploys in parallel yields the same block and execution
+    /// results as executing them sequentially.
+    fn assert_parallel_execution_matches_sequential(&self, deploys: Vec<Deploy>) {
+        let sequential = self.execute(deploys.clone(), 1);
+        for execution_threads in [2, 4, 8] {
+            let parallel = self.execute(deploys.clone(), execution_threads);
+            assert_eq!(
+                sequential.block.state_root_hash(),
+                parallel.block.state_root_hash(),
+                "post-state differs with {} threads",
+                execution_threads
+            );
+            assert_eq!(sequential.block.hash(), parallel.block.hash());
+            assert_eq!(sequential.execution_results, parallel.execution_results);
+        }
+    }
+}
+
+#[test]
+fn parallel_execution_should_match_sequential_for_independent_transfers() {
+    let mut rng = crate::new_rng();
+    let fixture = ExecutionFixture::new();
+    let amount = U512::from(
+        fixture
+            .chainspec
+            .deploy_config
+            .native_transfer_minimum_motes,
+    );
+
+    let deploys = ExecutionFixture::genesis_secret_keys()
+        .iter()
+        .enumerate()
+        .map(|(id, sender)| {
+            fixture.transfer(sender, &PublicKey::random(&mut rng), amount, id as u64)
+        })
+        .collect();
+
+    fixture.assert_parallel_execution_matches_sequential(deploys);
+}
+
+#[test]
+fn parallel_execution_should_match_sequential_for_conflicting_transfers() {
+    const DEPLOY_COUNT: u64 = 100;
+
+    let mut rng = crate::new_rng();
+    let fixture = ExecutionFixture::new();
+    let minimum_amount = U512::from(
+        fixture
+            .chainspec
+            .deploy_config
+            .native_transfer_minimum_motes,
+    );
+
+    // Senders are both the genesis accounts and new accounts, which can only transfer successfully
+    // once they have been funded by an earlier deploy of the block.
+    let mut senders = ExecutionFixture::genesis_secret_keys();
+    senders.extend(std::iter::repeat_with(|| SecretKey::random(&mut rng)).take(5));
+    let mut targets: Vec<PublicKey> = senders.iter().map(PublicKey::from).collect();
+    targets.extend(std::iter::repeat_with(|| PublicKey::random(&mut rng)).take(5));
+
+    let deploys = (0..DEPLOY_COUNT)
+        .map(|id| {
+            let sender = &senders[rng.gen_range(0..senders.len())];
+            let target = &targets[rng.gen_range(0..targets.len())];
+            // Include some transfers which fail for lack of funds.
+            let amount = match rng.gen_range(0..10_u64) {
+                0 => U512::MAX,
+                multiple => minimum_amount * U512::from(20 * multiple),
+            };
+            fixture.transfer(sender, target, amount, id)
+        })
+        .collect();
+
+    fixture.assert_parallel_execution_matches_sequential(deploys);
+}
+
+#[test]
+fn parallel_execution_should_not_speculate_for_accounts_with_deploy_validator() {
+    let mut rng = crate::new_rng();
+    let mut fixture = ExecutionFixture::new();
+    let amount = U512::from(
+        fixture
+            .chainspec
+            .deploy_config
+            .native_transfer_minimum_motes,
+    );
+
+    // The validator's hash doesn't refer to a contract, so the account falls back to its deploy
+    // threshold, but the named key alone must keep its transfers from being run speculatively.
+    let secret_keys = ExecutionFixture::genesis_secret_keys();
+    let validator_account = PublicKey::from(&secret_keys[0]);
+    fixture.install_deploy_validator(&validator_account, Key::Hash(rng.gen()));
+
+    let deploys: Vec<Deploy> = secret_keys
+        .iter()
+        .chain(std::iter::once(&secret_keys[0]))
+        .enumerate()
+        .map(|(id, sender)| {
+            fixture.transfer(sender, &PublicKey::random(&mut rng), amount, id as u64)
+        })
+        .collect();
+
+    let speculative_execution = SpeculativeExecution::run(
+        fixture.contract_runtime.engine_state.as_ref(),
+        None,
+        fixture.chainspec.protocol_version(),
+        fixture.genesis_pre_state.pre_state_root_hash,
+        fixture.timestamp.millis(),
+        &PublicKey::from(&secret_keys[1]),
+        &deploys,
+        4,
+    )
+    .unwrap();
+    for (index, deploy) in deploys.iter().enumerate() {
+        let is_validator_account = *deploy.header().account() == validator_account;
+        assert_eq!(
+            speculative_execution.has_result(index),
+            !is_validator_account
+        );
+    }
+
+    fixture.assert_parallel_execution_matches_sequential(deploys);
+}
+
+#[test]
+fn speculative_sequence_should_execute_deploys_on_top_of_each_other() {
+    let mut rng = crate::new_rng();
+    let fixture = ExecutionFixture::new();
+    let minimum_amount = U512::from(
+        fixture
+            .chainspec
+            .deploy_config
+            .native_transfer_minimum_motes,
+    );
+
+    // The second transfer can only succeed once its sender has been funded by the first one.
+    let funder = &ExecutionFixture::genesis_secret_keys()[0];
+    let new_account = SecretKey::random(&mut rng);
+    let deploys = vec![
+        fixture.transfer(
+            funder,
+            &PublicKey::from(&new_account),
+            minimum_amount * U512::from(20),
+            0,
+        ),
+        fixture.transfer(
+            &new_account,
+            &PublicKey::random(&mut rng),
+            minimum_amount,
+            1,
+        ),
+    ];
+
+    let (execution_results, _) = fixture.execute_speculatively(&deploys[1..], false);
+    assert!(matches!(
+        execution_results[..],
+        [ExecutionResult::Failure { .. }]
+    ));
+
+    let (execution_results, combined_effect) = fixture.execute_speculatively(&deploys, false);
+    assert_eq!(execution_results.len(), 2);
+    assert!(execution_results
+        .iter()
+        .all(|result| matches!(result, ExecutionResult::Success { .. })));
+    let new_account_key =
+        Key::Account(PublicKey::from(&new_account).to_account_hash()).to_formatted_string();
+    assert!(combined_effect
+        .transforms
+        .iter()
+        .any(|entry| entry.key == new_account_key));
+}
+
+#[test]
+fn speculative_dry_run_should_skip_approval_checks() {
+    let mut rng = crate::new_rng();
+    let fixture = ExecutionFixture::new();
+    let amount = U512::from(
+        fixture
+            .chainspec
+            .deploy_config
+            .native_transfer_minimum_motes,
+    );
+
+    // A transfer from a genesis account, approved by an unrelated key.
+    let sender = PublicKey::from(&ExecutionFixture::genesis_secret_keys()[0]);
+    let deploy = Deploy::new(
+        fixture.timestamp,
+        TimeDiff::from_seconds(100),
+        1,
+        vec![],
+        fixture.chainspec.network_config.name.clone(),
+        ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: runtime_args! {
+              "amount" => U512::from(fixture.chainspec.system_costs_config.wasmless_transfer_cost()),
+            },
+        },
+        ExecutableDeployItem::Transfer {
+            args: runtime_args! {
+              "amount" => amount,
+              "target" => PublicKey::random(&mut rng),
+              "id" => Some(0_u64),
+            },
+        },
+        &SecretKey::random(&mut rng),
+        Some(sender),
+    );
+    let deploys = vec![deploy];
+
+    let (execution_results, _) = fixture.execute_speculatively(&deploys, false);
+    assert!(matches!(
+        execution_results[..],
+        [ExecutionResult::Failure { .. }]
+    ));
+
+    let (execution_results, _) = fixture.execute_speculatively(&deploys, true);
+    assert!(matches!(
+        execution_results[..],
+        [ExecutionResult::Success { .. }]
+    ));
+}
+
+#[test]
+fn speculative_execution_should_apply_state_overrides() {
+    let mut rng = crate::new_rng();
+    let fixture = ExecutionFixture::new();
+    let state_root_hash = fixture.genesis_pre_state.pre_state_root_hash;
+    let execution_prestate = SpeculativeExecutionState {
+        state_root_hash,
+        block_time: fixture.timestamp,
+        protocol_version: fixture.chainspec.protocol_version(),
+    };
+
+    // A transfer of more than the sender's genesis balance.
+    let sender = &ExecutionFixture::genesis_secret_keys()[0];
+    let amount = U512::from(u128::MAX);
+    let deploy = fixture.transfer(sender, &PublicKey::random(&mut rng), amount, 0);
+    let main_purse = fixture
+        .contract_runtime
+        .engine_state
+        .tracking_copy(state_root_hash)
+        .unwrap()
+        .unwrap()
+        .get_account(
+            CorrelationId::new(),
+            PublicKey::from(sender).to_account_hash(),
+        )
+        .unwrap()
+        .main_purse();
+
+    let execute = |state_overrides| {
+        operations::execute_only(
+            fixture.contract_runtime.engine_state.as_ref(),
+            execution_prestate.clone(),
+            DeployItem::from(deploy.clone()),
+            state_overrides,
+            false,
+        )
+        .unwrap()
+        .unwrap()
+        .0
+    };
+
+    assert!(matches!(
+        execute(BTreeMap::new()),
+        ExecutionResult::Failure { .. }
+    ));
+
+    let mut state_overrides = BTreeMap::new();
+    let _ = state_overrides.insert(
+        Key::Balance(main_purse.addr()),
+        StoredValue::CLValue(CLValue::from_t(amount * U512::from(2)).unwrap()),
+    );
+    assert!(matches!(
+        execute(state_overrides),
+        ExecutionResult::Success { .. }
+    ));
+}
+
+#[test]
+fn speculative_execution_should_return_trace_only_when_requested() {
+    let mut rng = crate::new_rng();
+    let fixture = ExecutionFixture::new();
+    let execution_prestate = SpeculativeExecutionState {
+        state_root_hash: fixture.genesis_pre_state.pre_state_root_hash,
+        block_time: fixture.timestamp,
+        protocol_version: fixture.chainspec.protocol_version(),
+    };
+
+    let sender = &ExecutionFixture::genesis_secret_keys()[0];
+    let amount = U512::from(
+        fixture
+            .chainspec
+            .deploy_config
+            .native_transfer_minimum_motes,
+    );
+    let deploy = fixture.transfer(sender, &PublicKey::random(&mut rng), amount, 0);
+
+    let execute = |trace| {
+        operations::execute_only(
+            fixture.contract_runtime.engine_state.as_ref(),
+            execution_prestate.clone(),
+            DeployItem::from(deploy.clone()),
+            BTreeMap::new(),
+            trace,
+        )
+        .unwrap()
+        .unwrap()
+    };
+
+    let (untraced_result, no_trace) = execute(false);
+    assert!(no_trace.is_none());
+
+    let (traced_result, trace) = execute(true);
+    assert_eq!(traced_result, untraced_result);
+    assert!(!trace.expect("should have trace").is_empty());
+}
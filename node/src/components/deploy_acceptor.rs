@@ -20,7 +20,7 @@ use casper_execution_engine::core::engine_state::{
 };
 use casper_hashing::Digest;
 use casper_types::{
-    account::{Account, AccountHash},
+    account::{Account, AccountHash, DEPLOY_VALIDATOR_KEY},
     system::auction::ARG_AMOUNT,
     Contract, ContractHash, ContractPackage, ContractPackageHash, ContractVersion,
     ContractVersionKey, Key, ProtocolVersion, Timestamp, U512,
@@ -274,7 +274,22 @@ impl DeployAcceptor {
 
         // If this has been received from the speculative exec server, use the block specified in
         // the request, otherwise use the highest complete block.
-        if let Source::SpeculativeExec(block_header) = &source {
+        if let Source::SpeculativeExec {
+            block_header,
+            skip_account_checks,
+        } = &source
+        {
+            // The account is then only checked during execution, against the state the deploy
+            // is actually executed on.
+            if *skip_account_checks {
+                let block_header = block_header.clone();
+                return self.verify_payment_logic(
+                    effect_builder,
+                    Box::new(EventMetadata::new(deploy, source, maybe_responder)),
+                    block_header,
+                    verification_start_timestamp,
+                );
+            }
             let account_hash = deploy.header().account().to_account_hash();
             let account_key = Key::from(account_hash);
             let block_header = block_header.clone();
@@ -414,7 +429,13 @@ impl DeployAcceptor {
                     );
                 }
 
-                if !account.can_deploy_with(&authorization_keys) {
+                // An account's deploy validator decides in place of the deploy threshold when the
+                // deploy is executed, and a deploy it rejects is charged like a failed payment.
+                // Should the validator not refer to a usable contract, the engine falls back to
+                // the deploy threshold, and the deploy then fails authorization at execution.
+                if !account.named_keys().contains_key(DEPLOY_VALIDATOR_KEY)
+                    && !account.can_deploy_with(&authorization_keys)
+                {
                     let error = Error::parameter_failure(
                         &block_header,
                         DeployParameterFailure::InsufficientDeploySignatureWeight,
@@ -903,7 +924,7 @@ impl DeployAcceptor {
 
         // If this has been received from the speculative exec server, we just want to call the
         // responder and finish.  Otherwise store the deploy and announce it if required.
-        if let Source::SpeculativeExec(_) = event_metadata.source {
+        if let Source::SpeculativeExec { .. } = event_metadata.source {
             let effects = if let Some(responder) = event_metadata.maybe_responder {
                 responder.respond(Ok(())).ignore()
             } else {
@@ -934,7 +955,7 @@ impl DeployAcceptor {
             source,
             maybe_responder,
         } = *event_metadata;
-        if !matches!(source, Source::SpeculativeExec(_)) {
+        if !matches!(source, Source::SpeculativeExec { .. }) {
             self.metrics.observe_rejected(verification_start_timestamp);
         }
         let mut effects = Effects::new();
@@ -945,7 +966,7 @@ impl DeployAcceptor {
         }
 
         // If this has NOT been received from the speculative exec server, announce it.
-        if !matches!(source, Source::SpeculativeExec(_)) {
+        if !matches!(source, Source::SpeculativeExec { .. }) {
             effects.extend(
                 effect_builder
                     .announce_invalid_deploy(deploy, source)
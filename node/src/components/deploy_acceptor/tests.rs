@@ -24,7 +24,7 @@ use casper_execution_engine::{
     storage::trie::merkle_proof::TrieMerkleProof,
 };
 use casper_types::{
-    account::{Account, ActionThresholds, AssociatedKeys, Weight},
+    account::{Account, ActionThresholds, AssociatedKeys, Weight, DEPLOY_VALIDATOR_KEY},
     contracts::NamedKeys,
     testing::TestRng,
     CLValue, EraId, PublicKey, SecretKey, StoredValue, URef, U512,
@@ -171,6 +171,7 @@ enum TestScenario {
     FromClientRepeatedValidDeploy,
     FromClientAccountWithInsufficientWeight,
     FromClientAccountWithInvalidAssociatedKeys,
+    FromClientAccountWithDeployValidator,
     AccountWithUnknownBalance,
     FromClientCustomPaymentContract(ContractScenario),
     FromClientCustomPaymentContractPackage(ContractPackageScenario),
@@ -220,6 +221,7 @@ impl TestScenario {
             | TestScenario::FromClientRepeatedValidDeploy
             | TestScenario::FromClientAccountWithInsufficientWeight
             | TestScenario::FromClientAccountWithInvalidAssociatedKeys
+            | TestScenario::FromClientAccountWithDeployValidator
             | TestScenario::AccountWithUnknownBalance
             | TestScenario::DeployWithoutPaymentAmount
             | TestScenario::DeployWithMangledPaymentAmount
@@ -256,6 +258,7 @@ impl TestScenario {
             | TestScenario::FromClientRepeatedValidDeploy
             | TestScenario::FromClientAccountWithInvalidAssociatedKeys
             | TestScenario::FromClientAccountWithInsufficientWeight
+            | TestScenario::FromClientAccountWithDeployValidator
             | TestScenario::AccountWithUnknownBalance
             | TestScenario::BalanceCheckForDeploySentByPeer => {
                 Deploy::random_valid_native_transfer(rng)
@@ -366,6 +369,7 @@ impl TestScenario {
             | TestScenario::FromPeerAccountWithInvalidAssociatedKeys // account check skipped if from peer
             | TestScenario::FromClientRepeatedValidDeploy
             | TestScenario::FromClientValidDeploy
+            | TestScenario::FromClientAccountWithDeployValidator // threshold checked at execution
             | TestScenario::ShouldAcceptExpiredDeploySentByPeer=> true,
             TestScenario::FromPeerInvalidDeploy
             | TestScenario::FromClientInsufficientBalance
@@ -464,6 +468,19 @@ fn create_account(account_hash: AccountHash, test_scenario: &TestScenario) -> Ac
                 invalid_action_threshold,
             )
         }
+        TestScenario::FromClientAccountWithDeployValidator => {
+            let action_threshold = ActionThresholds::new(Weight::new(100u8), Weight::new(100u8))
+                .expect("should create action threshold");
+            let mut named_keys = NamedKeys::new();
+            named_keys.insert(DEPLOY_VALIDATOR_KEY.to_string(), Key::Hash([1; 32]));
+            Account::new(
+                account_hash,
+                named_keys,
+                URef::default(),
+                AssociatedKeys::new(account_hash, Weight::new(1)),
+                action_threshold,
+            )
+        }
         _ => Account::create(account_hash, NamedKeys::default(), URef::default()),
     }
 }
@@ -971,7 +988,8 @@ async fn run_deploy_acceptor_without_timeout(
             }
             // Check that a, new and valid, deploy sent by a client raises an `AcceptedNewDeploy`
             // announcement with the appropriate source.
-            TestScenario::FromClientValidDeploy => {
+            TestScenario::FromClientValidDeploy
+            | TestScenario::FromClientAccountWithDeployValidator => {
                 matches!(
                     event,
                     Event::DeployAcceptorAnnouncement(
@@ -1143,6 +1161,12 @@ async fn should_reject_valid_deploy_from_client_for_account_with_insufficient_we
     ))
 }
 
+#[tokio::test]
+async fn should_accept_valid_deploy_from_client_for_account_with_deploy_validator() {
+    let result = run_deploy_acceptor(TestScenario::FromClientAccountWithDeployValidator).await;
+    assert!(result.is_ok())
+}
+
 #[tokio::test]
 async fn should_reject_valid_deploy_from_client_for_insufficient_balance() {
     let result = run_deploy_acceptor(TestScenario::FromClientInsufficientBalance).await;
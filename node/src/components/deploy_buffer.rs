@@ -5,6 +5,7 @@ mod metrics;
 mod tests;
 
 use std::{
+    cmp::Ordering,
     collections::{btree_map, BTreeMap, BTreeSet, HashMap, HashSet},
     convert::TryInto,
     iter::FromIterator,
@@ -18,7 +19,7 @@ use prometheus::Registry;
 use smallvec::smallvec;
 use tracing::{debug, error, info, warn};
 
-use casper_types::Timestamp;
+use casper_types::{Motes, Timestamp, U512};
 
 use crate::{
     components::{
@@ -42,14 +43,57 @@ use crate::{
     utils::DisplayIter,
     NodeRng,
 };
-pub(crate) use config::Config;
+pub(crate) use config::{Config, DeployOrdering};
 pub(crate) use event::Event;
 
 use metrics::Metrics;
 
 const COMPONENT_NAME: &str = "deploy_buffer";
 
-type FootprintAndApprovals = (DeployFootprint, BTreeSet<Approval>);
+/// The data held for a deploy which has been received and stored, and is hence proposable.
+#[derive(DataSize, Debug)]
+struct BufferedDeploy {
+    footprint: DeployFootprint,
+    approvals: BTreeSet<Approval>,
+    // The time when the deploy was registered with the buffer.
+    received: Timestamp,
+}
+
+impl BufferedDeploy {
+    /// Returns the estimated payment for the deploy, i.e. its gas estimate at its gas price.
+    fn estimated_payment(&self) -> U512 {
+        Motes::from_gas(
+            self.footprint.gas_estimate,
+            self.footprint.header.gas_price(),
+        )
+        .map(|motes| motes.value())
+        .unwrap_or_else(U512::max_value)
+    }
+
+    /// Compares `self` to `other` according to the given ordering policy, where `Ordering::Less`
+    /// means `self` should be proposed before `other`.
+    fn priority_cmp(&self, other: &BufferedDeploy, deploy_ordering: DeployOrdering) -> Ordering {
+        let ordering = match deploy_ordering {
+            DeployOrdering::GasPrice => other
+                .footprint
+                .header
+                .gas_price()
+                .cmp(&self.footprint.header.gas_price()),
+            DeployOrdering::Age => Ordering::Equal,
+            DeployOrdering::PaymentPerByte => {
+                // Compare `other_payment / other_size` with `self_payment / self_size` without
+                // losing precision to integer division.
+                let self_size = U512::from(self.footprint.size_estimate.max(1));
+                let other_size = U512::from(other.footprint.size_estimate.max(1));
+                other
+                    .estimated_payment()
+                    .saturating_mul(self_size)
+                    .cmp(&self.estimated_payment().saturating_mul(other_size))
+            }
+        };
+        ordering.then_with(|| self.received.cmp(&other.received))
+    }
+}
 
 #[derive(DataSize, Debug)]
 pub(crate) struct DeployBuffer {
@@ -62,7 +106,7 @@ pub(crate) struct DeployBuffer {
     //
     // The timestamp is the time when the deploy expires.
     // Expired items are removed via a self-perpetuating expire event.
-    buffer: HashMap<DeployHash, (Timestamp, Option<FootprintAndApprovals>)>,
+    buffer: HashMap<DeployHash, (Timestamp, Option<BufferedDeploy>)>,
     // when a maybe-block is in flight, we pause inclusion
     // of the deploys within it in other proposed blocks
     // if the maybe-block becomes an actual block the
@@ -233,10 +277,14 @@ impl DeployBuffer {
             }
         };
         let expiry_time = deploy.header().expires();
-        let approvals = deploy.approvals().clone();
+        let buffered_deploy = BufferedDeploy {
+            footprint,
+            approvals: deploy.approvals().clone(),
+            received: Timestamp::now(),
+        };
         match self
             .buffer
-            .insert(*deploy_hash, (expiry_time, Some((footprint, approvals))))
+            .insert(*deploy_hash, (expiry_time, Some(buffered_deploy)))
         {
             Some(prev) => {
                 warn!(%deploy_hash, ?prev, "DeployBuffer: deploy upserted");
@@ -308,25 +356,38 @@ impl DeployBuffer {
         self.register_deploys(timestamp, finalized_block.deploy_and_transfer_hashes());
     }
 
-    /// Returns eligible deploys that are buffered and not held or dead.
+    /// Returns eligible deploys that are buffered and not held or dead, in the order in which they
+    /// should be proposed according to the configured `DeployOrdering`.
     fn proposable(&self) -> Vec<(DeployHashWithApprovals, DeployFootprint)> {
         debug!("DeployBuffer: getting proposable deploys");
+        let deploy_ordering = self.cfg.deploy_ordering();
         self.buffer
             .iter()
             .filter(|(dh, _)| !self.hold.values().any(|hs| hs.contains(dh)))
             .filter(|(dh, _)| !self.dead.contains(dh))
             .filter_map(|(dh, (_, maybe_data))| {
-                maybe_data.as_ref().map(|(footprint, approvals)| {
-                    (
-                        DeployHashWithApprovals::new(*dh, approvals.clone()),
-                        footprint.clone(),
-                    )
-                })
+                maybe_data
+                    .as_ref()
+                    .map(|buffered_deploy| (dh, buffered_deploy))
+            })
+            .sorted_by(|(dh1, buffered1), (dh2, buffered2)| {
+                buffered1
+                    .priority_cmp(buffered2, deploy_ordering)
+                    .then_with(|| dh1.cmp(dh2))
+            })
+            .map(|(dh, buffered_deploy)| {
+                (
+                    DeployHashWithApprovals::new(*dh, buffered_deploy.approvals.clone()),
+                    buffered_deploy.footprint.clone(),
+                )
             })
             .collect()
     }
 
     /// Returns a right-sized payload of deploys that can be proposed.
+    ///
+    /// Deploys are considered in priority order.  If a deploy would exceed the block's gas or size
+    /// limit, it is skipped and smaller deploys of lower priority are tried instead.
     fn appendable_block(&mut self, timestamp: Timestamp) -> AppendableBlock {
         let mut ret = AppendableBlock::new(self.deploy_config, timestamp);
         let mut holds = HashSet::new();
@@ -396,7 +457,7 @@ impl DeployBuffer {
                         AddError::ApprovalCount if has_multiple_approvals => {
                             // keep iterating, we can maybe fit in a deploy with fewer approvals
                         }
-                        AddError::ApprovalCount | AddError::GasLimit | AddError::BlockSize => {
+                        AddError::ApprovalCount => {
                             info!(
                                 ?deploy_hash,
                                 %error,
@@ -405,6 +466,15 @@ impl DeployBuffer {
                             // a block limit has been reached
                             break;
                         }
+                        AddError::GasLimit | AddError::BlockSize => {
+                            // keep iterating, we can maybe fit in a smaller deploy of lower
+                            // priority
+                            debug!(
+                                ?deploy_hash,
+                                %error,
+                                "DeployBuffer: deploy would exceed a block limit"
+                            );
+                        }
                     }
                 }
             }
@@ -4,12 +4,30 @@ use serde::{Deserialize, Serialize};
 use casper_types::TimeDiff;
 
 const DEFAULT_EXPIRY_CHECK_INTERVAL: &str = "1min";
+const DEFAULT_DEPLOY_ORDERING: DeployOrdering = DeployOrdering::GasPrice;
+
+/// The policy used to prioritize buffered deploys when filling a proposed block.
+///
+/// Ties are broken by the time the deploys were received, oldest first.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeployOrdering {
+    /// Deploys with the highest gas price are proposed first.
+    GasPrice,
+    /// Deploys which have been in the buffer the longest are proposed first.
+    Age,
+    /// Deploys with the highest estimated payment per byte of serialized deploy are proposed
+    /// first.
+    PaymentPerByte,
+}
 
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The interval of checking for expired deploys.
     pub expiry_check_interval: TimeDiff,
+    /// The policy used to prioritize deploys when proposing a block.
+    pub deploy_ordering: DeployOrdering,
 }
 
 impl Config {
@@ -17,12 +35,18 @@ impl Config {
     pub fn expiry_check_interval(&self) -> TimeDiff {
         self.expiry_check_interval
     }
+
+    /// Returns the policy used to prioritize deploys when proposing a block.
+    pub fn deploy_ordering(&self) -> DeployOrdering {
+        self.deploy_ordering
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             expiry_check_interval: DEFAULT_EXPIRY_CHECK_INTERVAL.parse().unwrap(),
+            deploy_ordering: DEFAULT_DEPLOY_ORDERING,
         }
     }
 }
@@ -5,7 +5,13 @@ use crate::{
     types::{Block, FinalizedBlock},
     utils,
 };
-use casper_types::{testing::TestRng, EraId, TimeDiff};
+use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    runtime_args,
+    testing::TestRng,
+    EraId, RuntimeArgs, SecretKey, TimeDiff,
+};
 use prometheus::Registry;
 use rand::Rng;
 
@@ -63,6 +69,39 @@ fn create_valid_deploys(
     deploys
 }
 
+// Generates a valid non-transfer deploy with the given gas price, payment amount and session code
+// size.
+fn create_deploy(
+    rng: &mut TestRng,
+    gas_price: u64,
+    payment_amount: u64,
+    session_size: usize,
+) -> Deploy {
+    let payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: runtime_args! {
+            "amount" => U512::from(payment_amount),
+        },
+    };
+    let session = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::from(vec![0; session_size]),
+        args: RuntimeArgs::new(),
+    };
+    let deploy = Deploy::new(
+        Timestamp::now(),
+        TimeDiff::from_seconds(3600),
+        gas_price,
+        vec![],
+        String::from("casper-example"),
+        payment,
+        session,
+        &SecretKey::random(rng),
+        None,
+    );
+    assert!(deploy.is_valid().is_ok());
+    deploy
+}
+
 fn create_invalid_deploys(rng: &mut TestRng, size: usize) -> Vec<Deploy> {
     let mut deploys = create_valid_deploys(rng, size, DeployType::Random, None, None);
 
@@ -394,6 +433,143 @@ fn register_deploys_and_blocks() {
     );
 }
 
+#[test]
+fn proposable_deploys_ordered_by_gas_price() {
+    let mut rng = TestRng::new();
+    let config = Config {
+        deploy_ordering: DeployOrdering::GasPrice,
+        ..Default::default()
+    };
+    let mut deploy_buffer =
+        DeployBuffer::new(DeployConfig::default(), config, &Registry::new()).unwrap();
+
+    let deploys = create_valid_deploys(&mut rng, 100, DeployType::Random, None, None);
+    deploys
+        .iter()
+        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+
+    let proposable = deploy_buffer.proposable();
+    assert_eq!(proposable.len(), deploys.len());
+    for pair in proposable.windows(2) {
+        assert!(pair[0].1.header.gas_price() >= pair[1].1.header.gas_price());
+    }
+}
+
+#[test]
+fn proposable_deploys_ordered_by_age() {
+    let mut rng = TestRng::new();
+    let config = Config {
+        deploy_ordering: DeployOrdering::Age,
+        ..Default::default()
+    };
+    let mut deploy_buffer =
+        DeployBuffer::new(DeployConfig::default(), config, &Registry::new()).unwrap();
+
+    let deploys = create_valid_deploys(&mut rng, 100, DeployType::Random, None, None);
+    deploys
+        .iter()
+        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+
+    // spread the receipt times out, as registering the deploys takes only a few milliseconds
+    let mut received_times = HashMap::new();
+    for (deploy_hash, (_, maybe_data)) in deploy_buffer.buffer.iter_mut() {
+        let buffered_deploy = maybe_data.as_mut().unwrap();
+        buffered_deploy.received = Timestamp::from(rng.gen_range(0..1_000_000));
+        received_times.insert(*deploy_hash, buffered_deploy.received);
+    }
+
+    let proposable = deploy_buffer.proposable();
+    assert_eq!(proposable.len(), deploys.len());
+    for pair in proposable.windows(2) {
+        assert!(received_times[pair[0].0.deploy_hash()] <= received_times[pair[1].0.deploy_hash()]);
+    }
+}
+
+#[test]
+fn proposable_deploys_ordered_by_payment_per_byte() {
+    let mut rng = TestRng::new();
+    let config = Config {
+        deploy_ordering: DeployOrdering::PaymentPerByte,
+        ..Default::default()
+    };
+    let mut deploy_buffer =
+        DeployBuffer::new(DeployConfig::default(), config, &Registry::new()).unwrap();
+
+    let deploys: Vec<_> = (0..100)
+        .map(|_| {
+            let gas_price = rng.gen_range(1..10);
+            let payment_amount = rng.gen_range(1_000..1_000_000);
+            let session_size = rng.gen_range(0..10_000);
+            create_deploy(&mut rng, gas_price, payment_amount, session_size)
+        })
+        .collect();
+    deploys
+        .iter()
+        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+
+    let payment_and_size = |footprint: &DeployFootprint| {
+        let payment = footprint.gas_estimate.value() * U512::from(footprint.header.gas_price());
+        (payment, U512::from(footprint.size_estimate))
+    };
+    let proposable = deploy_buffer.proposable();
+    assert_eq!(proposable.len(), deploys.len());
+    for pair in proposable.windows(2) {
+        let (payment_0, size_0) = payment_and_size(&pair[0].1);
+        let (payment_1, size_1) = payment_and_size(&pair[1].1);
+        assert!(payment_0 * size_1 >= payment_1 * size_0);
+    }
+}
+
+#[test]
+fn appendable_block_skips_deploys_exceeding_gas_limit() {
+    let mut rng = TestRng::new();
+    let deploy_config = DeployConfig {
+        block_gas_limit: 100,
+        ..Default::default()
+    };
+    let mut deploy_buffer =
+        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
+
+    // the deploy with the highest gas price alone exceeds the block gas limit, but the other two
+    // fit together
+    let too_expensive = create_deploy(&mut rng, 3, 360, 100);
+    let cheaper = create_deploy(&mut rng, 2, 120, 100);
+    let cheapest = create_deploy(&mut rng, 1, 30, 100);
+    for deploy in [&too_expensive, &cheaper, &cheapest] {
+        deploy_buffer.register_deploy(deploy.clone());
+    }
+
+    let appendable_block = deploy_buffer.appendable_block(Timestamp::now());
+    let expected = HashSet::from([*cheaper.hash(), *cheapest.hash()]);
+    assert_eq!(appendable_block.deploy_and_transfer_set(), &expected);
+
+    // the skipped deploy should still be proposable
+    let proposable = deploy_buffer.proposable();
+    assert_eq!(proposable.len(), 1);
+    assert_eq!(proposable[0].0.deploy_hash(), too_expensive.hash());
+}
+
+#[test]
+fn appendable_block_skips_deploys_exceeding_block_size() {
+    let mut rng = TestRng::new();
+    let too_large = create_deploy(&mut rng, 3, 10, 10_000);
+    let smaller = create_deploy(&mut rng, 2, 10, 1_000);
+    let smallest = create_deploy(&mut rng, 1, 10, 100);
+    let deploy_config = DeployConfig {
+        max_block_size: (smaller.serialized_length() + smallest.serialized_length()) as u32,
+        ..Default::default()
+    };
+    let mut deploy_buffer =
+        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
+    for deploy in [&too_large, &smaller, &smallest] {
+        deploy_buffer.register_deploy(deploy.clone());
+    }
+
+    let appendable_block = deploy_buffer.appendable_block(Timestamp::now());
+    let expected = HashSet::from([*smaller.hash(), *smallest.hash()]);
+    assert_eq!(appendable_block.deploy_and_transfer_set(), &expected);
+}
+
 /// Event for the mock reactor.
 #[derive(Debug)]
 enum ReactorEvent {
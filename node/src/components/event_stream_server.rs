@@ -21,6 +21,7 @@ mod config;
 mod event;
 mod event_indexer;
 mod http_server;
+mod metrics;
 mod sse_server;
 #[cfg(test)]
 mod tests;
@@ -28,6 +29,7 @@ mod tests;
 use std::{fmt::Debug, net::SocketAddr, path::PathBuf};
 
 use datasize::DataSize;
+use prometheus::Registry;
 use tokio::sync::{
     mpsc::{self, UnboundedSender},
     oneshot,
@@ -35,7 +37,7 @@ use tokio::sync::{
 use tracing::{error, info, warn};
 use warp::Filter;
 
-use casper_types::ProtocolVersion;
+use casper_types::{ExecutionResult, ProtocolVersion};
 
 use super::Component;
 use crate::{
@@ -49,6 +51,7 @@ use crate::{
 pub use config::Config;
 pub(crate) use event::Event;
 use event_indexer::{EventIndex, EventIndexer};
+use metrics::Metrics;
 use sse_server::ChannelsAndFilter;
 pub(crate) use sse_server::SseData;
 
@@ -87,17 +90,25 @@ pub(crate) struct EventStreamServer {
     storage_path: PathBuf,
     api_version: ProtocolVersion,
     sse_server: Option<InnerServer>,
+    #[data_size(skip)]
+    metrics: Metrics,
 }
 
 impl EventStreamServer {
-    pub(crate) fn new(config: Config, storage_path: PathBuf, api_version: ProtocolVersion) -> Self {
-        EventStreamServer {
+    pub(crate) fn new(
+        config: Config,
+        storage_path: PathBuf,
+        api_version: ProtocolVersion,
+        registry: &Registry,
+    ) -> Result<Self, prometheus::Error> {
+        Ok(EventStreamServer {
             state: ComponentState::Uninitialized,
             config,
             storage_path,
             api_version,
             sse_server: None,
-        }
+            metrics: Metrics::new(registry)?,
+        })
     }
 
     fn listen(&mut self) -> Result<(), ListeningError> {
@@ -122,6 +133,7 @@ impl EventStreamServer {
         } = ChannelsAndFilter::new(
             broadcast_channel_size as usize,
             self.config.max_concurrent_subscribers,
+            self.metrics.subscribers.clone(),
         );
 
         let (server_shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
@@ -295,15 +307,29 @@ where
                     deploy_header,
                     block_hash,
                     execution_result,
-                } => self.broadcast(SseData::DeployProcessed {
-                    deploy_hash: Box::new(deploy_hash),
-                    account: Box::new(deploy_header.account().clone()),
-                    timestamp: deploy_header.timestamp(),
-                    ttl: deploy_header.ttl(),
-                    dependencies: deploy_header.dependencies().clone(),
-                    block_hash: Box::new(block_hash),
-                    execution_result,
-                }),
+                } => {
+                    let contract_events = match &*execution_result {
+                        ExecutionResult::Success { events, .. } => events.clone(),
+                        ExecutionResult::Failure { .. } => vec![],
+                    };
+                    let mut effects = self.broadcast(SseData::DeployProcessed {
+                        deploy_hash: Box::new(deploy_hash),
+                        account: Box::new(deploy_header.account().clone()),
+                        timestamp: deploy_header.timestamp(),
+                        ttl: deploy_header.ttl(),
+                        dependencies: deploy_header.dependencies().clone(),
+                        block_hash: Box::new(block_hash),
+                        execution_result,
+                    });
+                    for event in contract_events {
+                        effects.extend(self.broadcast(SseData::ContractEvent {
+                            deploy_hash: Box::new(deploy_hash),
+                            block_hash: Box::new(block_hash),
+                            event: Box::new(event),
+                        }));
+                    }
+                    effects
+                }
                 Event::DeploysExpired(deploy_hashes) => deploy_hashes
                     .into_iter()
                     .flat_map(|deploy_hash| self.broadcast(SseData::DeployExpired { deploy_hash }))
@@ -0,0 +1,55 @@
+use prometheus::{IntGauge, IntGaugeVec, Opts, Registry};
+
+use crate::unregister_metric;
+
+const SUBSCRIBERS_NAME: &str = "event_stream_subscribers";
+const SUBSCRIBERS_HELP: &str = "number of clients connected to the event stream server, by filter";
+
+const FILTER_LABEL: &str = "filter";
+
+/// Metrics for the event stream server.
+#[derive(Debug)]
+pub(super) struct Metrics {
+    /// Number of connected subscribers, labelled by the path of the filter they subscribed to.
+    pub(super) subscribers: IntGaugeVec,
+
+    registry: Registry,
+}
+
+impl Metrics {
+    /// Creates and registers the event stream server metrics.
+    pub(super) fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let subscribers = IntGaugeVec::new(
+            Opts::new(SUBSCRIBERS_NAME, SUBSCRIBERS_HELP),
+            &[FILTER_LABEL],
+        )?;
+        registry.register(Box::new(subscribers.clone()))?;
+
+        Ok(Metrics {
+            subscribers,
+            registry: registry.clone(),
+        })
+    }
+}
+
+impl Drop for Metrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.subscribers);
+    }
+}
+
+/// Counts a connected subscriber in the given gauge for as long as it is alive.
+pub(super) struct SubscriberGuard(IntGauge);
+
+impl SubscriberGuard {
+    pub(super) fn new(gauge: IntGauge) -> Self {
+        gauge.inc();
+        SubscriberGuard(gauge)
+    }
+}
+
+impl Drop for SubscriberGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
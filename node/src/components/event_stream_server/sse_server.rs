@@ -2,6 +2,8 @@
 
 use std::{
     collections::{HashMap, HashSet},
+    hash::Hash,
+    iter,
     net::SocketAddr,
     sync::{Arc, RwLock},
 };
@@ -10,6 +12,7 @@ use datasize::DataSize;
 use futures::{future, Stream, StreamExt};
 use http::StatusCode;
 use hyper::Body;
+use prometheus::IntGaugeVec;
 #[cfg(test)]
 use rand::Rng;
 use schemars::JsonSchema;
@@ -32,12 +35,15 @@ use warp::{
     Filter, Reply,
 };
 
+use casper_hashing::Digest;
 #[cfg(test)]
 use casper_types::testing::TestRng;
 use casper_types::{
-    EraId, ExecutionEffect, ExecutionResult, ProtocolVersion, PublicKey, TimeDiff, Timestamp,
+    AsymmetricType, ContractEvent, EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion,
+    PublicKey, TimeDiff, Timestamp,
 };
 
+use super::metrics::SubscriberGuard;
 use crate::types::{BlockHash, Deploy, DeployHash, FinalitySignature, JsonBlock};
 #[cfg(test)]
 use crate::{testing, types::Block};
@@ -51,13 +57,24 @@ pub const SSE_API_MAIN_PATH: &str = "main";
 pub const SSE_API_DEPLOYS_PATH: &str = "deploys";
 /// The URL path part to subscribe to only `FinalitySignature` events.
 pub const SSE_API_SIGNATURES_PATH: &str = "sigs";
-/// The URL query string field name.
+/// The URL query string field name for the starting event ID.
 pub const QUERY_FIELD: &str = "start_from";
+/// The URL query string field name for restricting deploy events to those of the given accounts.
+pub const ACCOUNTS_QUERY_FIELD: &str = "accounts";
+/// The URL query string field name for restricting deploy events to those whose execution touched
+/// the given contract or contract package hashes, and contract events to those emitted by the given
+/// contracts.
+pub const CONTRACTS_QUERY_FIELD: &str = "contracts";
+/// The URL query string field name for restricting deploy events to the given deploy hashes.
+pub const DEPLOY_HASHES_QUERY_FIELD: &str = "deploy_hashes";
+/// The separator between the values of a list-valued URL query string field.
+const QUERY_LIST_SEPARATOR: char = ',';
 
 /// The filter associated with `/events/main` path.
-const MAIN_FILTER: [EventFilter; 5] = [
+const MAIN_FILTER: [EventFilter; 6] = [
     EventFilter::BlockAdded,
     EventFilter::DeployProcessed,
+    EventFilter::ContractEvent,
     EventFilter::DeployExpired,
     EventFilter::Fault,
     EventFilter::Step,
@@ -98,6 +115,14 @@ pub enum SseData {
         #[data_size(skip)]
         execution_result: Box<ExecutionResult>,
     },
+    /// The given event was emitted by a contract while executing the given deploy, which forms
+    /// part of the given block.
+    ContractEvent {
+        deploy_hash: Box<DeployHash>,
+        block_hash: Box<BlockHash>,
+        #[data_size(skip)]
+        event: Box<ContractEvent>,
+    },
     /// The given deploy has expired.
     DeployExpired { deploy_hash: DeployHash },
     /// Generic representation of validator's fault in an era.
@@ -125,6 +150,7 @@ impl SseData {
             SseData::BlockAdded { .. } => filter.contains(&EventFilter::BlockAdded),
             SseData::DeployAccepted { .. } => filter.contains(&EventFilter::DeployAccepted),
             SseData::DeployProcessed { .. } => filter.contains(&EventFilter::DeployProcessed),
+            SseData::ContractEvent { .. } => filter.contains(&EventFilter::ContractEvent),
             SseData::DeployExpired { .. } => filter.contains(&EventFilter::DeployExpired),
             SseData::Fault { .. } => filter.contains(&EventFilter::Fault),
             SseData::FinalitySignature(_) => filter.contains(&EventFilter::FinalitySignature),
@@ -177,6 +203,15 @@ impl SseData {
         }
     }
 
+    /// Returns a random `SseData::ContractEvent`.
+    pub(super) fn random_contract_event(rng: &mut TestRng) -> Self {
+        SseData::ContractEvent {
+            deploy_hash: Box::new(DeployHash::random(rng)),
+            block_hash: Box::new(BlockHash::random(rng)),
+            event: Box::new(rng.gen()),
+        }
+    }
+
     /// Returns a random `SseData::DeployExpired`
     pub(super) fn random_deploy_expired(rng: &mut TestRng) -> Self {
         let deploy = testing::create_expired_deploy(Timestamp::now(), rng);
@@ -269,18 +304,112 @@ pub(super) enum EventFilter {
     BlockAdded,
     DeployAccepted,
     DeployProcessed,
+    ContractEvent,
     DeployExpired,
     Fault,
     FinalitySignature,
     Step,
 }
 
+/// A filter on the deploy-related events a client has subscribed to receive, specified via the URL
+/// query string.
+///
+/// Each criterion which is set must be satisfied by an event for it to be sent, while any one of
+/// the values given for a criterion suffices.  Events unrelated to deploys are never filtered out.
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub(super) struct DeployFilter {
+    /// The deploy must have been created by one of these accounts.
+    accounts: Option<HashSet<PublicKey>>,
+    /// The deploy's execution must have touched one of these contract or contract package hashes,
+    /// or the contract event must have been emitted by one of these contracts, held as formatted
+    /// `Key::Hash` strings to match the keys of the execution transforms and the event emitters.
+    contracts: Option<HashSet<String>>,
+    /// The deploy must be one of these.
+    deploy_hashes: Option<HashSet<DeployHash>>,
+}
+
+impl DeployFilter {
+    /// Returns `true` if the given event satisfies this filter.
+    ///
+    /// Only `DeployProcessed` and `ContractEvent` events relate to contracts, so `DeployAccepted`
+    /// and `DeployExpired` events are filtered out if `contracts` is set.  Similarly,
+    /// `ContractEvent` and `DeployExpired` events are filtered out if `accounts` is set.
+    pub(super) fn should_include(&self, data: &SseData) -> bool {
+        match data {
+            SseData::DeployAccepted { deploy } => self.matches(
+                deploy.hash(),
+                Some(deploy.header().account()),
+                iter::empty(),
+            ),
+            SseData::DeployProcessed {
+                deploy_hash,
+                account,
+                execution_result,
+                ..
+            } => {
+                let effect = match &**execution_result {
+                    ExecutionResult::Success { effect, .. }
+                    | ExecutionResult::Failure { effect, .. } => effect,
+                };
+                let touched_keys = effect
+                    .transforms
+                    .iter()
+                    .map(|transform_entry| &transform_entry.key);
+                self.matches(deploy_hash, Some(&**account), touched_keys)
+            }
+            SseData::ContractEvent {
+                deploy_hash, event, ..
+            } => {
+                let emitter = event.emitter.to_formatted_string();
+                self.matches(deploy_hash, None, iter::once(&emitter))
+            }
+            SseData::DeployExpired { deploy_hash } => {
+                self.matches(deploy_hash, None, iter::empty())
+            }
+            SseData::ApiVersion(_)
+            | SseData::BlockAdded { .. }
+            | SseData::Fault { .. }
+            | SseData::FinalitySignature(_)
+            | SseData::Step { .. }
+            | SseData::Shutdown => true,
+        }
+    }
+
+    fn matches<'a>(
+        &self,
+        deploy_hash: &DeployHash,
+        maybe_account: Option<&PublicKey>,
+        mut contract_keys: impl Iterator<Item = &'a String>,
+    ) -> bool {
+        if let Some(deploy_hashes) = &self.deploy_hashes {
+            if !deploy_hashes.contains(deploy_hash) {
+                return false;
+            }
+        }
+
+        if let Some(accounts) = &self.accounts {
+            if !maybe_account.map_or(false, |account| accounts.contains(account)) {
+                return false;
+            }
+        }
+
+        if let Some(contracts) = &self.contracts {
+            if !contract_keys.any(|key| contracts.contains(key)) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
 /// Filters the `event`, mapping it to a warp event, or `None` if it should be filtered out.
 async fn filter_map_server_sent_event(
     event: &ServerSentEvent,
     event_filter: &[EventFilter],
+    deploy_filter: &DeployFilter,
 ) -> Option<Result<WarpServerSentEvent, RecvError>> {
-    if !event.data.should_include(event_filter) {
+    if !event.data.should_include(event_filter) || !deploy_filter.should_include(&event.data) {
         return None;
     }
 
@@ -311,6 +440,7 @@ async fn filter_map_server_sent_event(
 
         &SseData::BlockAdded { .. }
         | &SseData::DeployProcessed { .. }
+        | &SseData::ContractEvent { .. }
         | &SseData::DeployExpired { .. }
         | &SseData::Fault { .. }
         | &SseData::Step { .. }
@@ -345,26 +475,58 @@ pub(super) fn get_filter(path_param: &str) -> Option<&'static [EventFilter]> {
     }
 }
 
-/// Extracts the starting event ID from the provided query, or `None` if `query` is empty.
+/// Extracts the optional starting event ID and the deploy filter from the provided query.
 ///
-/// If `query` is not empty, returns a 422 response if `query` doesn't have exactly one entry,
-/// "starts_from" mapped to a value representing an event ID.
-fn parse_query(query: HashMap<String, String>) -> Result<Option<Id>, Response> {
-    if query.is_empty() {
-        return Ok(None);
-    }
+/// Returns a 422 response if `query` has any field other than "start_from" mapped to a value
+/// representing an event ID, or "accounts", "contracts" and "deploy_hashes" mapped to
+/// comma-separated lists of hex-encoded public keys, contract or contract package hashes and
+/// deploy hashes respectively.
+fn parse_query(mut query: HashMap<String, String>) -> Result<(Option<Id>, DeployFilter), Response> {
+    let start_from = match query.remove(QUERY_FIELD) {
+        Some(id_str) => Some(id_str.parse::<Id>().map_err(|_| create_422())?),
+        None => None,
+    };
 
-    if query.len() > 1 {
+    let deploy_filter = DeployFilter {
+        accounts: parse_query_list(query.remove(ACCOUNTS_QUERY_FIELD), |hex| {
+            PublicKey::from_hex(hex).ok()
+        })?,
+        contracts: parse_query_list(query.remove(CONTRACTS_QUERY_FIELD), |hex| {
+            Digest::from_hex(hex)
+                .ok()
+                .map(|digest| Key::Hash(digest.value()).to_formatted_string())
+        })?,
+        deploy_hashes: parse_query_list(query.remove(DEPLOY_HASHES_QUERY_FIELD), |hex| {
+            Digest::from_hex(hex).ok().map(DeployHash::new)
+        })?,
+    };
+
+    if !query.is_empty() {
         return Err(create_422());
     }
 
-    match query
-        .get(QUERY_FIELD)
-        .and_then(|id_str| id_str.parse::<Id>().ok())
-    {
-        Some(id) => Ok(Some(id)),
-        None => Err(create_422()),
-    }
+    Ok((start_from, deploy_filter))
+}
+
+/// Parses the comma-separated values of a list-valued query field, or returns `None` if the field
+/// was not provided.
+///
+/// Returns a 422 response if any of the values fails to parse.
+fn parse_query_list<T, F>(
+    maybe_list: Option<String>,
+    parse_value: F,
+) -> Result<Option<HashSet<T>>, Response>
+where
+    T: Eq + Hash,
+    F: Fn(&str) -> Option<T>,
+{
+    maybe_list
+        .map(|list| {
+            list.split(QUERY_LIST_SEPARATOR)
+                .map(|value| parse_value(value).ok_or_else(create_422))
+                .collect()
+        })
+        .transpose()
 }
 
 /// Creates a 404 response with a useful error message in the body.
@@ -384,8 +546,9 @@ fn create_404() -> Response {
 /// string.
 fn create_422() -> Response {
     let mut response = Response::new(Body::from(format!(
-        "invalid query: expected single field '{}=<EVENT ID>'\n",
-        QUERY_FIELD
+        "invalid query: expected only optional fields '{}=<EVENT ID>', '{}=<PUBLIC KEYS>', \
+        '{}=<CONTRACT HASHES>' and '{}=<DEPLOY HASHES>', with lists comma-separated\n",
+        QUERY_FIELD, ACCOUNTS_QUERY_FIELD, CONTRACTS_QUERY_FIELD, DEPLOY_HASHES_QUERY_FIELD
     )));
     *response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
     response
@@ -408,7 +571,13 @@ pub(super) struct ChannelsAndFilter {
 impl ChannelsAndFilter {
     /// Creates the message-passing channels required to run the event-stream server and the warp
     /// filter for the event-stream server.
-    pub(super) fn new(broadcast_channel_size: usize, max_concurrent_subscribers: u32) -> Self {
+    ///
+    /// Connected clients are counted in `subscribers`, labelled by the path they subscribed to.
+    pub(super) fn new(
+        broadcast_channel_size: usize,
+        max_concurrent_subscribers: u32,
+        subscribers: IntGaugeVec,
+    ) -> Self {
         // Create a channel to broadcast new events to all subscribed clients' streams.
         let (event_broadcaster, _) = broadcast::channel(broadcast_channel_size);
         let cloned_broadcaster = event_broadcaster.clone();
@@ -441,8 +610,8 @@ impl ChannelsAndFilter {
                 None => return create_404(),
             };
 
-            let start_from = match parse_query(query) {
-                Ok(maybe_id) => maybe_id,
+            let (start_from, deploy_filter) = match parse_query(query) {
+                Ok(start_from_and_deploy_filter) => start_from_and_deploy_filter,
                 Err(error_response) => return error_response,
             };
 
@@ -465,13 +634,23 @@ impl ChannelsAndFilter {
             // Create a channel for the client's handler to receive the stream of ongoing events.
             let ongoing_events_receiver = cloned_broadcaster.subscribe();
 
-            sse::reply(sse::keep_alive().stream(stream_to_client(
+            // Move the guard into the stream so that the client is counted for as long as it's
+            // connected.
+            let subscriber_guard =
+                SubscriberGuard::new(subscribers.with_label_values(&[path_param.as_str()]));
+            let stream = stream_to_client(
                 initial_events_receiver,
                 ongoing_events_receiver,
                 event_filter,
+                deploy_filter,
                 remote_address,
-            )))
-            .into_response()
+            )
+            .map(move |result| {
+                let _ = &subscriber_guard;
+                result
+            });
+
+            sse::reply(sse::keep_alive().stream(stream)).into_response()
         };
 
         let sse_filter = warp::get()
@@ -505,11 +684,13 @@ impl ChannelsAndFilter {
 /// subscribed to the server's event stream.
 ///
 /// It also takes an `EventFilter` which causes events to which the client didn't subscribe to be
+/// skipped, and a `DeployFilter` which causes deploy events not matching the client's query to be
 /// skipped.
 fn stream_to_client(
     initial_events: mpsc::UnboundedReceiver<ServerSentEvent>,
     ongoing_events: broadcast::Receiver<BroadcastChannelMessage>,
     event_filter: &'static [EventFilter],
+    deploy_filter: DeployFilter,
     remote_address: String,
 ) -> impl Stream<Item = Result<WarpServerSentEvent, RecvError>> + 'static {
     // Keep a record of the IDs of the events delivered via the `initial_events` receiver.
@@ -548,7 +729,8 @@ fn stream_to_client(
         .take_while(|result| future::ready(!matches!(result, Err(RecvError::Closed))));
 
     // Serve the initial events followed by the ongoing ones, filtering as dictated by the
-    // `event_filter`.
+    // `event_filter` and `deploy_filter`.
+    let deploy_filter = Arc::new(deploy_filter);
     UnboundedReceiverStream::new(initial_events)
         .map(move |event| {
             if let Some(id) = event.id {
@@ -557,10 +739,15 @@ fn stream_to_client(
             Ok(event)
         })
         .chain(ongoing_stream)
-        .filter_map(move |result| async move {
-            match result {
-                Ok(event) => filter_map_server_sent_event(&event, event_filter).await,
-                Err(error) => Some(Err(error)),
+        .filter_map(move |result| {
+            let deploy_filter = Arc::clone(&deploy_filter);
+            async move {
+                match result {
+                    Ok(event) => {
+                        filter_map_server_sent_event(&event, event_filter, &deploy_filter).await
+                    }
+                    Err(error) => Some(Err(error)),
+                }
             }
         })
 }
@@ -569,14 +756,16 @@ fn stream_to_client(
 mod tests {
     use std::iter;
 
-    use casper_types::testing::TestRng;
+    use casper_types::{testing::TestRng, Transform, TransformEntry};
 
     use super::*;
     use crate::logging;
 
     async fn should_filter_out(event: &ServerSentEvent, filter: &'static [EventFilter]) {
         assert!(
-            filter_map_server_sent_event(event, filter).await.is_none(),
+            filter_map_server_sent_event(event, filter, &DeployFilter::default())
+                .await
+                .is_none(),
             "should filter out {:?} with {:?}",
             event,
             filter
@@ -585,7 +774,9 @@ mod tests {
 
     async fn should_not_filter_out(event: &ServerSentEvent, filter: &'static [EventFilter]) {
         assert!(
-            filter_map_server_sent_event(event, filter).await.is_some(),
+            filter_map_server_sent_event(event, filter, &DeployFilter::default())
+                .await
+                .is_some(),
             "should not filter out {:?} with {:?}",
             event,
             filter
@@ -618,6 +809,10 @@ mod tests {
             id: Some(rng.gen()),
             data: SseData::random_deploy_processed(&mut rng),
         };
+        let contract_event = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: SseData::random_contract_event(&mut rng),
+        };
         let deploy_expired = ServerSentEvent {
             id: Some(rng.gen()),
             data: SseData::random_deploy_expired(&mut rng),
@@ -643,6 +838,7 @@ mod tests {
         should_not_filter_out(&api_version, &MAIN_FILTER[..]).await;
         should_not_filter_out(&block_added, &MAIN_FILTER[..]).await;
         should_not_filter_out(&deploy_processed, &MAIN_FILTER[..]).await;
+        should_not_filter_out(&contract_event, &MAIN_FILTER[..]).await;
         should_not_filter_out(&deploy_expired, &MAIN_FILTER[..]).await;
         should_not_filter_out(&fault, &MAIN_FILTER[..]).await;
         should_not_filter_out(&step, &MAIN_FILTER[..]).await;
@@ -659,6 +855,7 @@ mod tests {
 
         should_filter_out(&block_added, &DEPLOYS_FILTER[..]).await;
         should_filter_out(&deploy_processed, &DEPLOYS_FILTER[..]).await;
+        should_filter_out(&contract_event, &DEPLOYS_FILTER[..]).await;
         should_filter_out(&deploy_expired, &DEPLOYS_FILTER[..]).await;
         should_filter_out(&fault, &DEPLOYS_FILTER[..]).await;
         should_filter_out(&finality_signature, &DEPLOYS_FILTER[..]).await;
@@ -673,6 +870,7 @@ mod tests {
         should_filter_out(&block_added, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&deploy_accepted, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&deploy_processed, &SIGNATURES_FILTER[..]).await;
+        should_filter_out(&contract_event, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&deploy_expired, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&fault, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&step, &SIGNATURES_FILTER[..]).await;
@@ -704,6 +902,10 @@ mod tests {
             id: None,
             data: SseData::random_deploy_processed(&mut rng),
         };
+        let malformed_contract_event = ServerSentEvent {
+            id: None,
+            data: SseData::random_contract_event(&mut rng),
+        };
         let malformed_deploy_expired = ServerSentEvent {
             id: None,
             data: SseData::random_deploy_expired(&mut rng),
@@ -734,6 +936,7 @@ mod tests {
             should_filter_out(&malformed_block_added, filter).await;
             should_filter_out(&malformed_deploy_accepted, filter).await;
             should_filter_out(&malformed_deploy_processed, filter).await;
+            should_filter_out(&malformed_contract_event, filter).await;
             should_filter_out(&malformed_deploy_expired, filter).await;
             should_filter_out(&malformed_fault, filter).await;
             should_filter_out(&malformed_finality_signature, filter).await;
@@ -742,6 +945,138 @@ mod tests {
         }
     }
 
+    /// This test checks that deploy events are filtered according to the `DeployFilter` parsed
+    /// from the query string, and that events unrelated to deploys are never filtered out.
+    #[test]
+    fn should_filter_deploy_events_by_query() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+
+        let (deploy_accepted, deploy) = SseData::random_deploy_accepted(&mut rng);
+        let deploy_expired = SseData::random_deploy_expired(&mut rng);
+        let block_added = SseData::random_block_added(&mut rng);
+        let contract_hash: [u8; 32] = rng.gen();
+        let mut deploy_processed = SseData::random_deploy_processed(&mut rng);
+        let (processed_deploy_hash, processed_account) = match &mut deploy_processed {
+            SseData::DeployProcessed {
+                deploy_hash,
+                account,
+                execution_result,
+                ..
+            } => {
+                let effect = match execution_result.as_mut() {
+                    ExecutionResult::Success { effect, .. }
+                    | ExecutionResult::Failure { effect, .. } => effect,
+                };
+                effect.transforms.push(TransformEntry {
+                    key: Key::Hash(contract_hash).to_formatted_string(),
+                    transform: Transform::Identity,
+                });
+                (**deploy_hash, (**account).clone())
+            }
+            _ => unreachable!(),
+        };
+        let expired_deploy_hash = match &deploy_expired {
+            SseData::DeployExpired { deploy_hash } => *deploy_hash,
+            _ => unreachable!(),
+        };
+        let mut contract_event = SseData::random_contract_event(&mut rng);
+        let event_deploy_hash = match &mut contract_event {
+            SseData::ContractEvent {
+                deploy_hash, event, ..
+            } => {
+                event.emitter = Key::Hash(contract_hash);
+                **deploy_hash
+            }
+            _ => unreachable!(),
+        };
+
+        let filter_from = |fields: &[(&str, String)]| {
+            let query = fields
+                .iter()
+                .map(|(field, value)| (field.to_string(), value.clone()))
+                .collect();
+            parse_query(query).unwrap().1
+        };
+        let all_events = [
+            &deploy_accepted,
+            &deploy_processed,
+            &deploy_expired,
+            &block_added,
+            &contract_event,
+        ];
+        let included = |filter: &DeployFilter| -> Vec<bool> {
+            all_events
+                .iter()
+                .map(|event| filter.should_include(event))
+                .collect()
+        };
+
+        // No filter fields should include all events.
+        assert_eq!(
+            included(&filter_from(&[])),
+            vec![true, true, true, true, true]
+        );
+
+        // Filtering by account should only include the deploy events created by that account.
+        let filter = filter_from(&[(
+            ACCOUNTS_QUERY_FIELD,
+            format!(
+                "{},{}",
+                deploy.header().account().to_hex(),
+                PublicKey::random(&mut rng).to_hex()
+            ),
+        )]);
+        assert_eq!(included(&filter), vec![true, false, false, true, false]);
+        let filter = filter_from(&[(ACCOUNTS_QUERY_FIELD, processed_account.to_hex())]);
+        assert_eq!(included(&filter), vec![false, true, false, true, false]);
+
+        // Filtering by contract should only include processed deploys which touched the contract
+        // and the events emitted by the contract.
+        let filter = filter_from(&[(CONTRACTS_QUERY_FIELD, base16::encode_lower(&contract_hash))]);
+        assert_eq!(included(&filter), vec![false, true, false, true, true]);
+        let filter = filter_from(&[(
+            CONTRACTS_QUERY_FIELD,
+            base16::encode_lower(&rng.gen::<[u8; 32]>()),
+        )]);
+        assert_eq!(included(&filter), vec![false, false, false, true, false]);
+
+        // Filtering by deploy hash should only include the events of those deploys.
+        let filter = filter_from(&[(
+            DEPLOY_HASHES_QUERY_FIELD,
+            format!(
+                "{},{}",
+                base16::encode_lower(deploy.hash().inner()),
+                base16::encode_lower(expired_deploy_hash.inner())
+            ),
+        )]);
+        assert_eq!(included(&filter), vec![true, false, true, true, false]);
+        let filter = filter_from(&[(
+            DEPLOY_HASHES_QUERY_FIELD,
+            base16::encode_lower(event_deploy_hash.inner()),
+        )]);
+        assert_eq!(included(&filter), vec![false, false, false, true, true]);
+
+        // Multiple filter fields must all be satisfied.
+        let filter = filter_from(&[
+            (
+                DEPLOY_HASHES_QUERY_FIELD,
+                base16::encode_lower(processed_deploy_hash.inner()),
+            ),
+            (ACCOUNTS_QUERY_FIELD, deploy.header().account().to_hex()),
+        ]);
+        assert_eq!(included(&filter), vec![false, false, false, true, false]);
+        let filter = filter_from(&[
+            (
+                DEPLOY_HASHES_QUERY_FIELD,
+                base16::encode_lower(processed_deploy_hash.inner()),
+            ),
+            (CONTRACTS_QUERY_FIELD, base16::encode_lower(&contract_hash)),
+            (QUERY_FIELD, "1".to_string()),
+        ]);
+        assert_eq!(included(&filter), vec![false, true, false, true, false]);
+    }
+
     async fn should_filter_duplicate_events(path_filter: &str) {
         // Returns `count` random SSE events, all of a single variant defined by `path_filter`.  The
         // events will have sequential IDs starting from `start_id`, and if the path filter
@@ -855,6 +1190,7 @@ mod tests {
                 initial_events_receiver,
                 ongoing_events_receiver,
                 get_filter(path_filter).unwrap(),
+                DeployFilter::default(),
                 "127.0.0.1:3456".to_string(),
             )
             .collect()
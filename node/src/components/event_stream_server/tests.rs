@@ -27,9 +27,9 @@ use casper_types::testing::TestRng;
 use super::*;
 use crate::{logging, testing::assert_schema};
 use sse_server::{
-    DeployAccepted, Id, QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH,
-    SSE_API_MAIN_PATH as MAIN_PATH, SSE_API_ROOT_PATH as ROOT_PATH,
-    SSE_API_SIGNATURES_PATH as SIGS_PATH,
+    DeployAccepted, Id, ACCOUNTS_QUERY_FIELD, CONTRACTS_QUERY_FIELD, DEPLOY_HASHES_QUERY_FIELD,
+    QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH, SSE_API_MAIN_PATH as MAIN_PATH,
+    SSE_API_ROOT_PATH as ROOT_PATH, SSE_API_SIGNATURES_PATH as SIGS_PATH,
 };
 
 /// The total number of random events each `EventStreamServer` will emit by default, excluding the
@@ -199,7 +199,7 @@ struct TestFixture {
 impl TestFixture {
     /// Constructs a new `TestFixture` including `EVENT_COUNT` random events ready to be served.
     fn new(rng: &mut TestRng) -> Self {
-        const DISTINCT_EVENTS_COUNT: u32 = 7;
+        const DISTINCT_EVENTS_COUNT: u32 = 8;
 
         let _ = logging::init();
         let storage_dir = tempfile::tempdir().unwrap();
@@ -220,6 +220,7 @@ impl TestFixture {
                 4 => SseData::random_fault(rng),
                 5 => SseData::random_step(rng),
                 6 => SseData::random_finality_signature(rng),
+                7 => SseData::random_contract_event(rng),
                 _ => unreachable!(),
             })
             .collect();
@@ -268,7 +269,9 @@ impl TestFixture {
             config,
             self.storage_dir.path().to_path_buf(),
             self.protocol_version,
-        );
+            &Registry::new(),
+        )
+        .unwrap();
         server.listen().unwrap();
         assert!(server.sse_server.is_some());
 
@@ -955,11 +958,16 @@ async fn should_handle_bad_url_query() {
         format!("{}?{}=0&extra=1", main_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", deploys_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", sigs_url, QUERY_FIELD),
+        format!("{}?{}=not-a-public-key", main_url, ACCOUNTS_QUERY_FIELD),
+        format!("{}?{}=", deploys_url, ACCOUNTS_QUERY_FIELD),
+        format!("{}?{}=01,not-hex", main_url, CONTRACTS_QUERY_FIELD),
+        format!("{}?{}=0102", main_url, DEPLOY_HASHES_QUERY_FIELD),
     ];
 
     let expected_body = format!(
-        "invalid query: expected single field '{}=<EVENT ID>'",
-        QUERY_FIELD
+        "invalid query: expected only optional fields '{}=<EVENT ID>', '{}=<PUBLIC KEYS>', \
+        '{}=<CONTRACT HASHES>' and '{}=<DEPLOY HASHES>', with lists comma-separated",
+        QUERY_FIELD, ACCOUNTS_QUERY_FIELD, CONTRACTS_QUERY_FIELD, DEPLOY_HASHES_QUERY_FIELD
     );
     for url in &urls {
         let response = reqwest::get(url).await.unwrap();
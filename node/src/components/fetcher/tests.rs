@@ -221,6 +221,7 @@ impl ReactorTrait for Reactor {
                 deploy,
                 speculative_exec_at_block,
                 responder,
+                ..
             }) => {
                 assert!(speculative_exec_at_block.is_none());
                 let event = deploy_acceptor::Event::Accept {
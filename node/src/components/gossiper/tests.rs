@@ -265,6 +265,7 @@ impl reactor::Reactor for Reactor {
                 deploy,
                 speculative_exec_at_block,
                 responder,
+                ..
             }) => {
                 assert!(speculative_exec_at_block.is_none());
                 let event = deploy_acceptor::Event::Accept {
@@ -74,7 +74,7 @@ use tokio_openssl::SslStream;
 use tokio_util::codec::LengthDelimitedCodec;
 use tracing::{debug, error, info, trace, warn, Instrument, Span};
 
-use casper_types::{EraId, PublicKey, SecretKey};
+use casper_types::EraId;
 
 pub(crate) use self::{
     bincode_format::BincodeFormat,
@@ -111,6 +111,7 @@ use crate::{
         AutoClosingResponder, EffectBuilder, EffectExt, Effects, GossipTarget,
     },
     reactor::{Finalize, ReactorEvent},
+    signer::Signer,
     tls,
     types::{NodeId, ValidatorMatrix},
     utils::{self, display_error, Source},
@@ -242,7 +243,7 @@ where
     pub(crate) fn new<C: Into<ChainInfo>>(
         cfg: Config,
         our_identity: Identity,
-        node_key_pair: Option<(Arc<SecretKey>, PublicKey)>,
+        node_key_pair: Option<Signer>,
         registry: &Registry,
         chain_info_source: C,
         validator_matrix: ValidatorMatrix,
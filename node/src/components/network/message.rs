@@ -1,7 +1,6 @@
 use std::{
     fmt::{self, Debug, Display, Formatter},
     net::SocketAddr,
-    sync::Arc,
 };
 
 use datasize::DataSize;
@@ -11,16 +10,18 @@ use serde::{
     Deserialize, Deserializer, Serialize, Serializer,
 };
 use strum::EnumDiscriminants;
+use tracing::{debug, warn};
 
 use casper_hashing::Digest;
+use casper_types::{crypto, AsymmetricType, ProtocolVersion, PublicKey, Signature};
 #[cfg(test)]
-use casper_types::testing::TestRng;
-use casper_types::{crypto, AsymmetricType, ProtocolVersion, PublicKey, SecretKey, Signature};
+use casper_types::{testing::TestRng, SecretKey};
 
 use super::{counting_format::ConnectionId, health::Nonce, BincodeFormat};
 use crate::{
     effect::EffectBuilder,
     protocol,
+    signer::{self, Signer},
     types::{Chainspec, NodeId},
     utils::{
         opt_display::OptDisplay,
@@ -136,24 +137,15 @@ impl<P: Payload> Message<P> {
     }
 }
 
-/// A pair of secret keys used by consensus.
+/// The signer of the consensus key pair.
 pub(super) struct NodeKeyPair {
-    secret_key: Arc<SecretKey>,
-    public_key: PublicKey,
+    signer: Signer,
 }
 
 impl NodeKeyPair {
     /// Creates a new key pair for consensus signing.
-    pub(super) fn new(key_pair: (Arc<SecretKey>, PublicKey)) -> Self {
-        Self {
-            secret_key: key_pair.0,
-            public_key: key_pair.1,
-        }
-    }
-
-    /// Sign a value using this keypair.
-    fn sign<T: AsRef<[u8]>>(&self, value: T) -> Signature {
-        crypto::sign(value, &self.secret_key, &self.public_key)
+    pub(super) fn new(signer: Signer) -> Self {
+        Self { signer }
     }
 }
 
@@ -170,11 +162,31 @@ pub(crate) struct ConsensusCertificate {
 
 impl ConsensusCertificate {
     /// Creates a new consensus certificate from a connection ID and key pair.
-    pub(super) fn create(connection_id: ConnectionId, key_pair: &NodeKeyPair) -> Self {
-        let signature = key_pair.sign(connection_id.as_bytes());
-        ConsensusCertificate {
-            public_key: key_pair.public_key.clone(),
-            signature,
+    ///
+    /// The certificate is only valid for the connection whose ID it signs, so a new one is signed
+    /// for every handshake. Returns `None` if the connection ID couldn't be signed, e.g. because
+    /// the remote signer's handshake rate limit was exceeded.
+    pub(super) async fn create(
+        connection_id: ConnectionId,
+        key_pair: &NodeKeyPair,
+    ) -> Option<Self> {
+        match key_pair
+            .signer
+            .sign_handshake(connection_id.as_bytes())
+            .await
+        {
+            Ok(signature) => Some(ConsensusCertificate {
+                public_key: key_pair.signer.public_key().clone(),
+                signature,
+            }),
+            Err(signer::Error::RateLimited) => {
+                debug!("not signing consensus certificate, handshake rate limit exceeded");
+                None
+            }
+            Err(error) => {
+                warn!(%error, "failed to sign consensus certificate");
+                None
+            }
         }
     }
 
@@ -189,10 +201,15 @@ impl ConsensusCertificate {
     fn random(rng: &mut TestRng) -> Self {
         let secret_key = SecretKey::random(rng);
         let public_key = PublicKey::from(&secret_key);
-        ConsensusCertificate::create(
-            ConnectionId::random(rng),
-            &NodeKeyPair::new((Arc::new(secret_key), public_key)),
-        )
+        let signature = crypto::sign(
+            ConnectionId::random(rng).as_bytes(),
+            &secret_key,
+            &public_key,
+        );
+        ConsensusCertificate {
+            public_key,
+            signature,
+        }
     }
 }
 
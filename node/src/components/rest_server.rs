@@ -22,11 +22,13 @@ mod config;
 mod event;
 mod filters;
 mod http_server;
+mod metrics;
 
-use std::{fmt::Debug, time::Instant};
+use std::{fmt::Debug, sync::Arc, time::Instant};
 
 use datasize::DataSize;
 use futures::{future::BoxFuture, join, FutureExt};
+use prometheus::Registry;
 use tokio::{sync::oneshot, task::JoinHandle};
 use tracing::{debug, error, info, warn};
 
@@ -36,8 +38,8 @@ use casper_types::ProtocolVersion;
 use super::Component;
 use crate::{
     components::{
-        rpc_server::rpcs::docs::OPEN_RPC_SCHEMA, ComponentState, InitializedComponent,
-        PortBoundComponent,
+        rpc_server::{rpcs::docs::OPEN_RPC_SCHEMA, RateLimiter},
+        ComponentState, InitializedComponent, PortBoundComponent,
     },
     effect::{
         requests::{
@@ -54,6 +56,7 @@ use crate::{
 };
 pub use config::Config;
 pub(crate) use event::Event;
+use metrics::Metrics;
 
 const COMPONENT_NAME: &str = "rest_server";
 
@@ -113,6 +116,9 @@ pub(crate) struct RestServer {
     node_startup_instant: Instant,
     /// Inner server is present only when enabled in the config.
     inner_rest: Option<InnerRestServer>,
+    /// The metrics of the REST server.
+    #[data_size(skip)]
+    metrics: Arc<Metrics>,
 }
 
 impl RestServer {
@@ -121,15 +127,17 @@ impl RestServer {
         api_version: ProtocolVersion,
         network_name: String,
         node_startup_instant: Instant,
-    ) -> Self {
-        RestServer {
+        registry: &Registry,
+    ) -> Result<Self, prometheus::Error> {
+        Ok(RestServer {
             state: ComponentState::Uninitialized,
             config,
             api_version,
             network_name,
             node_startup_instant,
             inner_rest: None,
-        }
+            metrics: Arc::new(Metrics::new(registry)?),
+        })
     }
 }
 
@@ -297,6 +305,8 @@ where
                 self.api_version,
                 shutdown_receiver,
                 cfg.qps_limit,
+                Arc::clone(&self.metrics),
+                Arc::new(RateLimiter::new(&cfg.rate_limit)),
             ))),
             "*" => Some(tokio::spawn(http_server::run_with_cors(
                 builder,
@@ -304,6 +314,8 @@ where
                 self.api_version,
                 shutdown_receiver,
                 cfg.qps_limit,
+                Arc::clone(&self.metrics),
+                Arc::new(RateLimiter::new(&cfg.rate_limit)),
                 CorsOrigin::Any,
             ))),
             _ => Some(tokio::spawn(http_server::run_with_cors(
@@ -312,6 +324,8 @@ where
                 self.api_version,
                 shutdown_receiver,
                 cfg.qps_limit,
+                Arc::clone(&self.metrics),
+                Arc::new(RateLimiter::new(&cfg.rate_limit)),
                 CorsOrigin::Specified(cfg.cors_origin.clone()),
             ))),
         };
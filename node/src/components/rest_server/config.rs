@@ -1,6 +1,8 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use crate::components::rpc_server::RateLimitConfig;
+
 /// Default binding address for the REST HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -24,6 +26,9 @@ pub struct Config {
     /// Max rate limit in qps.
     pub qps_limit: u64,
 
+    /// Per-client rate limiting.
+    pub rate_limit: RateLimitConfig,
+
     /// CORS origin.
     pub cors_origin: String,
 }
@@ -35,6 +40,7 @@ impl Config {
             enable_server: true,
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
+            rate_limit: RateLimitConfig::default(),
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
         }
     }
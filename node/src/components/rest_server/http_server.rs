@@ -1,7 +1,10 @@
-use std::{convert::Infallible, time::Duration};
+use std::{convert::Infallible, sync::Arc, time::Duration};
 
 use futures::{future, TryFutureExt};
-use hyper::server::{conn::AddrIncoming, Builder};
+use hyper::server::{
+    conn::{AddrIncoming, AddrStream},
+    Builder,
+};
 use tokio::sync::oneshot;
 use tower::builder::ServiceBuilder;
 use tracing::{info, warn};
@@ -10,8 +13,8 @@ use warp::Filter;
 use casper_json_rpc::CorsOrigin;
 use casper_types::ProtocolVersion;
 
-use super::{filters, ReactorEventT};
-use crate::effect::EffectBuilder;
+use super::{filters, Metrics, ReactorEventT};
+use crate::{components::rpc_server::RateLimiter, effect::EffectBuilder};
 
 /// Run the REST HTTP server.
 ///
@@ -22,6 +25,8 @@ pub(super) async fn run<REv: ReactorEventT>(
     api_version: ProtocolVersion,
     shutdown_receiver: oneshot::Receiver<()>,
     qps_limit: u64,
+    metrics: Arc<Metrics>,
+    rate_limiter: Arc<RateLimiter>,
 ) {
     // REST filters.
     let rest_status = filters::create_status_filter(effect_builder, api_version);
@@ -36,12 +41,27 @@ pub(super) async fn run<REv: ReactorEventT>(
             .or(rest_metrics)
             .or(rest_open_rpc)
             .or(rest_validator_changes)
-            .or(rest_chainspec_filter),
+            .or(rest_chainspec_filter)
+            .with(warp::log::custom(move |info| {
+                metrics.observe_request(info.path(), info.status(), info.elapsed())
+            })),
     );
 
     // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
-    let make_svc =
-        hyper::service::make_service_fn(move |_| future::ok::<_, Infallible>(service.clone()));
+    let make_svc = hyper::service::make_service_fn(move |connection: &AddrStream| {
+        let service = service.clone();
+        let rate_limiter = Arc::clone(&rate_limiter);
+        let remote_address = connection.remote_addr();
+        future::ok::<_, Infallible>(hyper::service::service_fn(move |request| {
+            let service = service.clone();
+            let rate_limiter = Arc::clone(&rate_limiter);
+            async move {
+                rate_limiter
+                    .call_rest(service, remote_address, request)
+                    .await
+            }
+        }))
+    });
 
     let rate_limited_service = ServiceBuilder::new()
         .rate_limit(qps_limit, Duration::from_secs(1))
@@ -70,6 +90,8 @@ pub(super) async fn run_with_cors<REv: ReactorEventT>(
     api_version: ProtocolVersion,
     shutdown_receiver: oneshot::Receiver<()>,
     qps_limit: u64,
+    metrics: Arc<Metrics>,
+    rate_limiter: Arc<RateLimiter>,
     cors_origin: CorsOrigin,
 ) {
     // REST filters.
@@ -86,6 +108,9 @@ pub(super) async fn run_with_cors<REv: ReactorEventT>(
             .or(rest_open_rpc)
             .or(rest_validator_changes)
             .or(rest_chainspec_filter)
+            .with(warp::log::custom(move |info| {
+                metrics.observe_request(info.path(), info.status(), info.elapsed())
+            }))
             .with(match cors_origin {
                 CorsOrigin::Any => warp::cors().allow_any_origin(),
                 CorsOrigin::Specified(origin) => warp::cors().allow_origin(origin.as_str()),
@@ -93,8 +118,20 @@ pub(super) async fn run_with_cors<REv: ReactorEventT>(
     );
 
     // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
-    let make_svc =
-        hyper::service::make_service_fn(move |_| future::ok::<_, Infallible>(service.clone()));
+    let make_svc = hyper::service::make_service_fn(move |connection: &AddrStream| {
+        let service = service.clone();
+        let rate_limiter = Arc::clone(&rate_limiter);
+        let remote_address = connection.remote_addr();
+        future::ok::<_, Infallible>(hyper::service::service_fn(move |request| {
+            let service = service.clone();
+            let rate_limiter = Arc::clone(&rate_limiter);
+            async move {
+                rate_limiter
+                    .call_rest(service, remote_address, request)
+                    .await
+            }
+        }))
+    });
 
     let rate_limited_service = ServiceBuilder::new()
         .rate_limit(qps_limit, Duration::from_secs(1))
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry};
+use warp::http::StatusCode;
+
+use super::filters::{
+    CHAINSPEC_API_PATH, JSON_RPC_SCHEMA_API_PATH, METRICS_API_PATH, STATUS_API_PATH,
+    VALIDATOR_CHANGES_API_PATH,
+};
+use crate::unregister_metric;
+
+const REQUESTS_NAME: &str = "rest_requests_total";
+const REQUESTS_HELP: &str = "number of REST requests handled, by path and status code";
+
+const REQUEST_DURATION_NAME: &str = "rest_request_duration_seconds";
+const REQUEST_DURATION_HELP: &str = "time (in sec) taken to handle a REST request, by path";
+
+const PATH_LABEL: &str = "path";
+const STATUS_LABEL: &str = "status";
+
+/// The path label used for requests to paths not served by the REST server.
+const UNKNOWN_PATH: &str = "unknown";
+
+// We use exponential buckets to observe the time it takes to handle a request.  Buckets start at
+// 1ms and double in width, covering durations of up to ~16s.
+const EXPONENTIAL_BUCKET_START: f64 = 0.001;
+const EXPONENTIAL_BUCKET_FACTOR: f64 = 2.0;
+const EXPONENTIAL_BUCKET_COUNT: usize = 15;
+
+/// Metrics for the REST server, labelled by path.
+#[derive(Debug)]
+pub(super) struct Metrics {
+    /// Number of requests handled, also labelled by status code.
+    requests: IntCounterVec,
+    /// Time taken to handle a request.
+    request_duration: HistogramVec,
+
+    registry: Registry,
+}
+
+impl Metrics {
+    /// Creates and registers the REST server metrics.
+    pub(super) fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let buckets = prometheus::exponential_buckets(
+            EXPONENTIAL_BUCKET_START,
+            EXPONENTIAL_BUCKET_FACTOR,
+            EXPONENTIAL_BUCKET_COUNT,
+        )?;
+
+        let requests = IntCounterVec::new(
+            Opts::new(REQUESTS_NAME, REQUESTS_HELP),
+            &[PATH_LABEL, STATUS_LABEL],
+        )?;
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new(REQUEST_DURATION_NAME, REQUEST_DURATION_HELP).buckets(buckets),
+            &[PATH_LABEL],
+        )?;
+
+        registry.register(Box::new(requests.clone()))?;
+        registry.register(Box::new(request_duration.clone()))?;
+
+        Ok(Metrics {
+            requests,
+            request_duration,
+            registry: registry.clone(),
+        })
+    }
+
+    /// Records a handled request.
+    ///
+    /// Requests to paths not served by the REST server are all recorded under the same label to
+    /// bound the number of time series.
+    pub(super) fn observe_request(&self, path: &str, status: StatusCode, elapsed: Duration) {
+        let path = match path.trim_matches('/') {
+            STATUS_API_PATH => STATUS_API_PATH,
+            METRICS_API_PATH => METRICS_API_PATH,
+            JSON_RPC_SCHEMA_API_PATH => JSON_RPC_SCHEMA_API_PATH,
+            VALIDATOR_CHANGES_API_PATH => VALIDATOR_CHANGES_API_PATH,
+            CHAINSPEC_API_PATH => CHAINSPEC_API_PATH,
+            _ => UNKNOWN_PATH,
+        };
+        self.requests
+            .with_label_values(&[path, status.as_str()])
+            .inc();
+        self.request_duration
+            .with_label_values(&[path])
+            .observe(elapsed.as_secs_f64());
+    }
+}
+
+impl Drop for Metrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.requests);
+        unregister_metric!(self.registry, self.request_duration);
+    }
+}
@@ -14,14 +14,18 @@
 mod config;
 mod event;
 mod http_server;
+mod metrics;
+mod rate_limit_config;
+mod rate_limiter;
 pub mod rpcs;
 mod speculative_exec_config;
 mod speculative_exec_server;
 
-use std::{fmt::Debug, time::Instant};
+use std::{fmt::Debug, sync::Arc, time::Instant};
 
 use datasize::DataSize;
 use futures::join;
+use prometheus::Registry;
 use tracing::{error, info, warn};
 
 use casper_execution_engine::core::engine_state::{
@@ -52,6 +56,9 @@ use crate::{
 };
 pub use config::Config;
 pub(crate) use event::Event;
+use metrics::Metrics;
+pub use rate_limit_config::{ApiKeyConfig, Config as RateLimitConfig};
+pub(crate) use rate_limiter::RateLimiter;
 pub use speculative_exec_config::Config as SpeculativeExecConfig;
 
 const COMPONENT_NAME: &str = "rpc_server";
@@ -112,6 +119,9 @@ pub(crate) struct RpcServer {
     /// no fields and no methods because all that is needed to operate it is the
     /// spawned tokio task, so a unit struct will suffice here.
     speculative_exec: Option<()>,
+    /// The metrics of the JSON-RPC and speculative execution servers.
+    #[data_size(skip)]
+    metrics: Arc<Metrics>,
 }
 
 impl RpcServer {
@@ -121,8 +131,9 @@ impl RpcServer {
         api_version: ProtocolVersion,
         network_name: String,
         node_startup_instant: Instant,
-    ) -> Self {
-        RpcServer {
+        registry: &Registry,
+    ) -> Result<Self, prometheus::Error> {
+        Ok(RpcServer {
             state: ComponentState::Uninitialized,
             config,
             speculative_exec_config,
@@ -130,7 +141,8 @@ impl RpcServer {
             network_name,
             node_startup_instant,
             speculative_exec: None,
-        }
+            metrics: Arc::new(Metrics::new(registry)?),
+        })
     }
 }
 
@@ -451,8 +463,12 @@ where
                 builder,
                 effect_builder,
                 self.api_version,
+                Arc::clone(&self.metrics),
                 cfg.qps_limit,
+                Arc::new(RateLimiter::new(&cfg.rate_limit)),
                 cfg.max_body_bytes,
+                cfg.max_batch_size,
+                cfg.max_sequence_length,
                 cfg.cors_origin.clone(),
             ));
             Some(())
@@ -466,8 +482,11 @@ where
             builder,
             effect_builder,
             self.api_version,
+            Arc::clone(&self.metrics),
             cfg.qps_limit,
+            Arc::new(RateLimiter::new(&cfg.rate_limit)),
             cfg.max_body_bytes,
+            cfg.max_batch_size,
             cfg.cors_origin.clone(),
         ));
 
@@ -1,6 +1,8 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use super::RateLimitConfig;
+
 /// Default binding address for the JSON-RPC HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -10,6 +12,8 @@ const DEFAULT_QPS_LIMIT: u64 = 100;
 /// Default max body bytes.  This is 2.5MB which should be able to accommodate the largest valid
 /// JSON-RPC request, which would be an "account_put_deploy".
 const DEFAULT_MAX_BODY_BYTES: u32 = 2_621_440;
+/// Default maximum number of requests in a single JSON-RPC batch.
+const DEFAULT_MAX_BATCH_SIZE: usize = 100;
 /// Default CORS origin.
 const DEFAULT_CORS_ORIGIN: &str = "";
 
@@ -24,8 +28,12 @@ pub struct Config {
     pub address: String,
     /// Maximum rate limit in queries per second.
     pub qps_limit: u64,
+    /// Per-client rate limiting.
+    pub rate_limit: RateLimitConfig,
     /// Maximum number of bytes to accept in a single request body.
     pub max_body_bytes: u32,
+    /// Maximum number of requests to accept in a single JSON-RPC batch.
+    pub max_batch_size: usize,
     /// CORS origin.
     pub cors_origin: String,
 }
@@ -37,7 +45,9 @@ impl Config {
             enable_server: true,
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
+            rate_limit: RateLimitConfig::default(),
             max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
         }
     }
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use hyper::server::{conn::AddrIncoming, Builder};
 
 use casper_json_rpc::{CorsOrigin, RequestHandlersBuilder};
@@ -5,19 +7,20 @@ use casper_types::ProtocolVersion;
 
 use super::{
     rpcs::{
-        account::PutDeploy,
+        account::{GetAccountDeploys, PutDeploy},
         chain::{
-            GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetStateRootHash,
+            GetBlock, GetBlockTransfers, GetBlocksRange, GetEraInfoBySwitchBlock, GetEraRewards,
+            GetEraSummary, GetStateRootHash, GetTransfersByAccount,
         },
         docs::ListRpcs,
         info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
         state::{
-            GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, GetTrie,
-            QueryBalance, QueryGlobalState,
+            GetAccountInfo, GetAuctionInfo, GetBalance, GetContractSchema, GetDictionaryItem,
+            GetItem, GetTrie, QueryBalance, QueryGlobalState,
         },
         RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
     },
-    ReactorEventT,
+    Metrics, RateLimiter, ReactorEventT,
 };
 use crate::effect::EffectBuilder;
 
@@ -31,31 +34,49 @@ pub(super) async fn run<REv: ReactorEventT>(
     builder: Builder<AddrIncoming>,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
+    metrics: Arc<Metrics>,
     qps_limit: u64,
+    rate_limiter: Arc<RateLimiter>,
     max_body_bytes: u32,
+    max_batch_size: usize,
     cors_origin: String,
 ) {
     let mut handlers = RequestHandlersBuilder::new();
-    PutDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBlock::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBlockTransfers::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetStateRootHash::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetItem::register_as_handler(effect_builder, api_version, &mut handlers);
-    QueryGlobalState::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBalance::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetAccountInfo::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetPeers::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetStatus::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetEraInfoBySwitchBlock::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetEraSummary::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetAuctionInfo::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetTrie::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetValidatorChanges::register_as_handler(effect_builder, api_version, &mut handlers);
-    ListRpcs::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetDictionaryItem::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetChainspec::register_as_handler(effect_builder, api_version, &mut handlers);
-    QueryBalance::register_as_handler(effect_builder, api_version, &mut handlers);
+    PutDeploy::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetBlock::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetBlocksRange::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetBlockTransfers::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetTransfersByAccount::register_as_handler(
+        effect_builder,
+        api_version,
+        &metrics,
+        &mut handlers,
+    );
+    GetEraRewards::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetStateRootHash::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetItem::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    QueryGlobalState::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetBalance::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetAccountInfo::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetAccountDeploys::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetDeploy::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetPeers::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetStatus::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetEraInfoBySwitchBlock::register_as_handler(
+        effect_builder,
+        api_version,
+        &metrics,
+        &mut handlers,
+    );
+    GetEraSummary::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetAuctionInfo::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetTrie::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetValidatorChanges::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    ListRpcs::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetDictionaryItem::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetChainspec::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    QueryBalance::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    GetContractSchema::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
     let handlers = handlers.build();
 
     match cors_origin.as_str() {
@@ -64,7 +85,9 @@ pub(super) async fn run<REv: ReactorEventT>(
                 builder,
                 handlers,
                 qps_limit,
+                rate_limiter,
                 max_body_bytes,
+                max_batch_size,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
             )
@@ -75,7 +98,9 @@ pub(super) async fn run<REv: ReactorEventT>(
                 builder,
                 handlers,
                 qps_limit,
+                rate_limiter,
                 max_body_bytes,
+                max_batch_size,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
                 CorsOrigin::Any,
@@ -87,7 +112,9 @@ pub(super) async fn run<REv: ReactorEventT>(
                 builder,
                 handlers,
                 qps_limit,
+                rate_limiter,
                 max_body_bytes,
+                max_batch_size,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
                 CorsOrigin::Specified(cors_origin),
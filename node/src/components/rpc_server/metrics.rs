@@ -0,0 +1,107 @@
+use std::{future::Future, time::Instant};
+
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry};
+
+use casper_json_rpc::Error;
+
+use crate::unregister_metric;
+
+const REQUESTS_NAME: &str = "rpc_requests_total";
+const REQUESTS_HELP: &str = "number of JSON-RPC requests handled, by method";
+
+const REQUEST_DURATION_NAME: &str = "rpc_request_duration_seconds";
+const REQUEST_DURATION_HELP: &str = "time (in sec) taken to handle a JSON-RPC request, by method";
+
+const ERRORS_NAME: &str = "rpc_errors_total";
+const ERRORS_HELP: &str =
+    "number of JSON-RPC requests which resulted in an error, by method and error code";
+
+const METHOD_LABEL: &str = "method";
+const CODE_LABEL: &str = "code";
+
+// We use exponential buckets to observe the time it takes to handle a request.  Buckets start at
+// 1ms and double in width, covering durations of up to ~16s.
+const EXPONENTIAL_BUCKET_START: f64 = 0.001;
+const EXPONENTIAL_BUCKET_FACTOR: f64 = 2.0;
+const EXPONENTIAL_BUCKET_COUNT: usize = 15;
+
+/// Metrics for the JSON-RPC and speculative execution servers, labelled by JSON-RPC method.
+#[derive(Debug)]
+pub(super) struct Metrics {
+    /// Number of requests handled.
+    requests: IntCounterVec,
+    /// Time taken to handle a request.
+    request_duration: HistogramVec,
+    /// Number of requests which resulted in an error, also labelled by error code.
+    errors: IntCounterVec,
+
+    registry: Registry,
+}
+
+impl Metrics {
+    /// Creates and registers the JSON-RPC metrics.
+    pub(super) fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let buckets = prometheus::exponential_buckets(
+            EXPONENTIAL_BUCKET_START,
+            EXPONENTIAL_BUCKET_FACTOR,
+            EXPONENTIAL_BUCKET_COUNT,
+        )?;
+
+        let requests =
+            IntCounterVec::new(Opts::new(REQUESTS_NAME, REQUESTS_HELP), &[METHOD_LABEL])?;
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new(REQUEST_DURATION_NAME, REQUEST_DURATION_HELP).buckets(buckets),
+            &[METHOD_LABEL],
+        )?;
+        let errors = IntCounterVec::new(
+            Opts::new(ERRORS_NAME, ERRORS_HELP),
+            &[METHOD_LABEL, CODE_LABEL],
+        )?;
+
+        registry.register(Box::new(requests.clone()))?;
+        registry.register(Box::new(request_duration.clone()))?;
+        registry.register(Box::new(errors.clone()))?;
+
+        Ok(Metrics {
+            requests,
+            request_duration,
+            errors,
+            registry: registry.clone(),
+        })
+    }
+
+    /// Awaits `handle_request`, recording the request, its duration and any resulting error
+    /// against `method`.
+    pub(super) async fn observe_request<T, F>(
+        &self,
+        method: &'static str,
+        handle_request: F,
+    ) -> Result<T, Error>
+    where
+        F: Future<Output = Result<T, Error>>,
+    {
+        let start = Instant::now();
+        let result = handle_request.await;
+
+        self.requests.with_label_values(&[method]).inc();
+        self.request_duration
+            .with_label_values(&[method])
+            .observe(start.elapsed().as_secs_f64());
+        if let Err(error) = &result {
+            let code = error.code().to_string();
+            self.errors
+                .with_label_values(&[method, code.as_str()])
+                .inc();
+        }
+
+        result
+    }
+}
+
+impl Drop for Metrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.requests);
+        unregister_metric!(self.registry, self.request_duration);
+        unregister_metric!(self.registry, self.errors);
+    }
+}
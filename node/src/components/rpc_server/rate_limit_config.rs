@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use datasize::DataSize;
+use serde::{
+    de::{Deserializer, Error as SerdeError, Unexpected},
+    Deserialize, Serialize,
+};
+use tracing::error;
+
+/// Default number of tokens added to a client's bucket per second.
+const DEFAULT_TOKENS_PER_SECOND: u64 = 10;
+/// Default maximum number of tokens held in a client's bucket.
+const DEFAULT_BUCKET_SIZE: u64 = 20;
+
+/// Per-client rate limiting configuration, shared by the JSON-RPC, speculative execution and REST
+/// HTTP servers.
+///
+/// Each client has a bucket of tokens which is refilled at a constant rate up to a maximum size.
+/// Every request takes tokens from the bucket of the client making it, according to the cost of
+/// the requested JSON-RPC method or REST path, and is rejected if there are not enough tokens.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Setting to enable per-client rate limiting.
+    pub enable: bool,
+    /// Number of tokens added per second to the bucket of each client IP address.
+    #[serde(deserialize_with = "deserialize_non_zero")]
+    pub tokens_per_second: u64,
+    /// Maximum number of tokens held in the bucket of each client IP address.
+    #[serde(deserialize_with = "deserialize_non_zero")]
+    pub bucket_size: u64,
+    /// Cost in tokens of requests for the given JSON-RPC methods or REST paths.  Requests for any
+    /// other method or path cost one token.
+    pub costs: BTreeMap<String, u64>,
+    /// API keys which clients can provide via the "X-Api-Key" header to be rate limited separately
+    /// from their IP address, using the quota of the key.
+    pub api_keys: Vec<ApiKeyConfig>,
+}
+
+impl Config {
+    /// Creates a default instance, with rate limiting disabled.
+    pub fn new() -> Self {
+        Config {
+            enable: false,
+            tokens_per_second: DEFAULT_TOKENS_PER_SECOND,
+            bucket_size: DEFAULT_BUCKET_SIZE,
+            costs: BTreeMap::new(),
+            api_keys: vec![],
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::new()
+    }
+}
+
+/// An API key and its quota.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct ApiKeyConfig {
+    /// The API key.
+    pub key: String,
+    /// Number of tokens added per second to the bucket of this key.
+    #[serde(deserialize_with = "deserialize_non_zero")]
+    pub tokens_per_second: u64,
+    /// Maximum number of tokens held in the bucket of this key.
+    #[serde(deserialize_with = "deserialize_non_zero")]
+    pub bucket_size: u64,
+}
+
+/// Deserializes a `u64` but fails if it's zero: a bucket which is never refilled would lock its
+/// clients out for good, while an empty one would let every request through for free.
+fn deserialize_non_zero<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = u64::deserialize(deserializer)?;
+    if value == 0 {
+        error!("rate limit tokens_per_second and bucket_size must be greater than zero");
+        return Err(SerdeError::invalid_value(
+            Unexpected::Unsigned(value),
+            &"a value greater than zero",
+        ));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID_CONFIG: &str = r#"
+        enable = true
+        tokens_per_second = 10
+        bucket_size = 20
+        costs = { query_global_state = 2 }
+        api_keys = [{ key = 'abc', tokens_per_second = 30, bucket_size = 40 }]
+    "#;
+
+    #[test]
+    fn should_parse_valid_config() {
+        let config: Config = toml::from_str(VALID_CONFIG).expect("should parse");
+        assert_eq!(config.tokens_per_second, 10);
+        assert_eq!(config.bucket_size, 20);
+        assert_eq!(config.api_keys[0].tokens_per_second, 30);
+        assert_eq!(config.api_keys[0].bucket_size, 40);
+    }
+
+    #[test]
+    fn should_reject_zero_rates_and_sizes() {
+        for setting in [
+            "tokens_per_second = 10",
+            "bucket_size = 20",
+            "tokens_per_second = 30",
+            "bucket_size = 40",
+        ] {
+            let zero_setting = format!("{} = 0", setting.split(" = ").next().unwrap());
+            let invalid_config = VALID_CONFIG.replace(setting, &zero_setting);
+            assert!(
+                toml::from_str::<Config>(&invalid_config).is_err(),
+                "should reject {} in place of {}",
+                zero_setting,
+                setting
+            );
+        }
+    }
+}
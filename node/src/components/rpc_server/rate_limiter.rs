@@ -0,0 +1,581 @@
+//! Per-client rate limiting for the JSON-RPC, speculative execution and REST HTTP servers.
+//!
+//! Clients are identified by the API key given in their request's "X-Api-Key" header if any, or
+//! otherwise by their IPv4 address or the /64 prefix of their IPv6 address.  Each client has a
+//! token bucket, from which every request takes the configured cost of the requested JSON-RPC
+//! method or REST path.  The number of buckets of IP addresses is capped, the bucket of the least
+//! recently seen address being dropped to make room for a new one, while the buckets of the
+//! configured API keys are always kept.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use http::{header::RETRY_AFTER, HeaderMap, HeaderValue, Request, Response, StatusCode};
+use hyper::{service::Service, Body};
+use linked_hash_map::LinkedHashMap;
+use serde::Serialize;
+use thiserror::Error;
+use tracing::debug;
+
+use casper_json_rpc::Error;
+
+use super::{rpcs::ErrorCode, RateLimitConfig};
+
+/// The name of the header holding the client's API key.
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// The maximum number of buckets of IP addresses held at once.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// The cost of requests for methods or paths without a configured cost.
+const DEFAULT_COST: u64 = 1;
+
+tokio::task_local! {
+    /// The rate limiter and client of the JSON-RPC request currently being handled.
+    static CURRENT_REQUEST: (Arc<RateLimiter>, Client);
+}
+
+/// An error returned when a request is rejected by the rate limiter.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Error)]
+pub(crate) enum RateLimitError {
+    /// The API key provided by the client is not configured.
+    #[error("the API key provided via the X-Api-Key header is not recognized")]
+    InvalidApiKey,
+    /// The client has run out of tokens.
+    #[error("rate limit exceeded; retry after {} ms", retry_after.as_millis())]
+    Exceeded {
+        /// The time after which the client will have enough tokens to make the same request.
+        retry_after: Duration,
+    },
+}
+
+/// The "data" field of a JSON-RPC error caused by the client exceeding its rate limit.
+#[derive(Serialize)]
+struct RetryAfter {
+    /// The number of milliseconds after which the request can be retried.
+    retry_after_ms: u64,
+}
+
+impl From<RateLimitError> for Error {
+    fn from(error: RateLimitError) -> Self {
+        match error {
+            RateLimitError::InvalidApiKey => {
+                Error::new(ErrorCode::InvalidApiKey, error.to_string())
+            }
+            RateLimitError::Exceeded { retry_after } => Error::new(
+                ErrorCode::RateLimitExceeded,
+                RetryAfter {
+                    retry_after_ms: u64::try_from(retry_after.as_millis()).unwrap_or(u64::MAX),
+                },
+            ),
+        }
+    }
+}
+
+/// The identity of a client.
+#[derive(Clone, Debug)]
+struct Client {
+    ip_address: IpAddr,
+    api_key: Option<String>,
+}
+
+impl Client {
+    fn new(remote_address: SocketAddr, headers: &HeaderMap) -> Self {
+        let api_key = headers
+            .get(API_KEY_HEADER)
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned());
+        Client {
+            ip_address: remote_address.ip(),
+            api_key,
+        }
+    }
+}
+
+/// The key under which the bucket of a client without an API key is held.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum BucketKey {
+    Ipv4Address(Ipv4Addr),
+    /// The first 64 bits of an IPv6 address, since a single host is commonly assigned a whole /64
+    /// subnet.
+    Ipv6Prefix([u8; 8]),
+}
+
+impl BucketKey {
+    /// Returns the key of the bucket of a client with the given IP address.
+    fn for_ip_address(ip_address: IpAddr) -> Self {
+        let ipv6_address = match ip_address {
+            IpAddr::V4(ipv4_address) => return BucketKey::Ipv4Address(ipv4_address),
+            IpAddr::V6(ipv6_address) => ipv6_address,
+        };
+        if let Some(ipv4_address) = ipv6_address.to_ipv4_mapped() {
+            return BucketKey::Ipv4Address(ipv4_address);
+        }
+        let mut prefix = [0; 8];
+        prefix.copy_from_slice(&ipv6_address.octets()[..8]);
+        BucketKey::Ipv6Prefix(prefix)
+    }
+}
+
+/// The rate at which a bucket is refilled, and its size.
+#[derive(Clone, Copy, Debug)]
+struct Quota {
+    tokens_per_second: u64,
+    bucket_size: u64,
+}
+
+/// A token bucket.
+#[derive(Debug)]
+struct Bucket {
+    quota: Quota,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn new(quota: Quota, now: Instant) -> Self {
+        Bucket {
+            quota,
+            tokens: quota.bucket_size as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Returns the number of tokens in the bucket at `now`.
+    fn tokens_at(&self, now: Instant) -> f64 {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        (self.tokens + elapsed * self.quota.tokens_per_second as f64)
+            .min(self.quota.bucket_size as f64)
+    }
+
+    /// Takes `cost` tokens from the bucket if it holds enough of them, or returns the time after
+    /// which it will.
+    ///
+    /// The cost is capped at the size of the bucket, so that any request can eventually be made.
+    fn try_take(&mut self, cost: u64, now: Instant) -> Result<(), Duration> {
+        self.tokens = self.tokens_at(now);
+        self.last_refill = now;
+
+        let cost = cost.min(self.quota.bucket_size) as f64;
+        if self.tokens >= cost {
+            self.tokens -= cost;
+            return Ok(());
+        }
+        // The config ensures a bucket is refilled, but don't overflow if it isn't.
+        let retry_after_secs = (cost - self.tokens) / self.quota.tokens_per_second as f64;
+        if retry_after_secs.is_finite() && retry_after_secs < Duration::MAX.as_secs_f64() {
+            Err(Duration::from_secs_f64(retry_after_secs))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+}
+
+/// A per-client rate limiter.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    enabled: bool,
+    default_quota: Quota,
+    costs: BTreeMap<String, u64>,
+    /// The buckets of the configured API keys.
+    api_key_buckets: Mutex<HashMap<String, Bucket>>,
+    /// The buckets of the IP addresses of clients without an API key, least recently seen first.
+    buckets: Mutex<LinkedHashMap<BucketKey, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Creates a new rate limiter from the given config.
+    pub(crate) fn new(config: &RateLimitConfig) -> Self {
+        let now = Instant::now();
+        let api_key_buckets = config
+            .api_keys
+            .iter()
+            .map(|api_key| {
+                let quota = Quota {
+                    tokens_per_second: api_key.tokens_per_second,
+                    bucket_size: api_key.bucket_size,
+                };
+                (api_key.key.clone(), Bucket::new(quota, now))
+            })
+            .collect();
+        RateLimiter {
+            enabled: config.enable,
+            default_quota: Quota {
+                tokens_per_second: config.tokens_per_second,
+                bucket_size: config.bucket_size,
+            },
+            costs: config.costs.clone(),
+            api_key_buckets: Mutex::new(api_key_buckets),
+            buckets: Mutex::new(LinkedHashMap::new()),
+        }
+    }
+
+    /// Takes the cost of `method_or_path` from the bucket of `client`.
+    fn check_client(
+        &self,
+        client: &Client,
+        method_or_path: &str,
+        now: Instant,
+    ) -> Result<(), RateLimitError> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let cost = self
+            .costs
+            .get(method_or_path)
+            .copied()
+            .unwrap_or(DEFAULT_COST);
+
+        let result = match &client.api_key {
+            Some(api_key) => {
+                let mut api_key_buckets = self
+                    .api_key_buckets
+                    .lock()
+                    .expect("rate limiter mutex poisoned");
+                match api_key_buckets.get_mut(api_key) {
+                    Some(bucket) => bucket.try_take(cost, now),
+                    None => return Err(RateLimitError::InvalidApiKey),
+                }
+            }
+            None => {
+                let key = BucketKey::for_ip_address(client.ip_address);
+                let mut buckets = self.buckets.lock().expect("rate limiter mutex poisoned");
+                if buckets.get_refresh(&key).is_none() && buckets.len() >= MAX_TRACKED_CLIENTS {
+                    let _ = buckets.pop_front();
+                }
+                buckets
+                    .entry(key)
+                    .or_insert_with(|| Bucket::new(self.default_quota, now))
+                    .try_take(cost, now)
+            }
+        };
+        result.map_err(|retry_after| {
+            debug!(?client, %method_or_path, ?retry_after, "rate limit exceeded");
+            RateLimitError::Exceeded { retry_after }
+        })
+    }
+
+    /// Takes the cost of the requested path from the bucket of the client making `request`, and
+    /// if successful, passes the request to `service`.
+    ///
+    /// Otherwise responds with a "401 Unauthorized" if the client's API key is not recognized, or
+    /// with a "429 Too Many Requests" holding a "Retry-After" header if the client has exceeded
+    /// its rate limit.
+    pub(crate) async fn call_rest<S>(
+        &self,
+        mut service: S,
+        remote_address: SocketAddr,
+        request: Request<Body>,
+    ) -> Result<Response<Body>, S::Error>
+    where
+        S: Service<Request<Body>, Response = Response<Body>>,
+    {
+        let client = Client::new(remote_address, request.headers());
+        let path = request.uri().path().trim_matches('/');
+        if let Err(error) = self.check_client(&client, path, Instant::now()) {
+            return Ok(rate_limit_response(error));
+        }
+        service.call(request).await
+    }
+}
+
+/// Takes the cost of `method` from the bucket of the client making the JSON-RPC request currently
+/// being handled.
+///
+/// Requests not handled via [`call_with_rate_limiter`] are never limited.
+pub(super) fn check(method: &str) -> Result<(), RateLimitError> {
+    CURRENT_REQUEST
+        .try_with(|(rate_limiter, client)| {
+            rate_limiter.check_client(client, method, Instant::now())
+        })
+        .unwrap_or(Ok(()))
+}
+
+/// Passes the JSON-RPC `request` to `service`, making `rate_limiter` and the identity of the
+/// client available to [`check`] while the request is being handled.
+pub(super) async fn call_with_rate_limiter<S>(
+    mut service: S,
+    rate_limiter: Arc<RateLimiter>,
+    remote_address: SocketAddr,
+    request: Request<Body>,
+) -> Result<S::Response, S::Error>
+where
+    S: Service<Request<Body>>,
+{
+    let client = Client::new(remote_address, request.headers());
+    CURRENT_REQUEST
+        .scope((rate_limiter, client), service.call(request))
+        .await
+}
+
+/// Returns the HTTP response for a request rejected by the rate limiter.
+fn rate_limit_response(error: RateLimitError) -> Response<Body> {
+    let mut response = Response::new(Body::from(error.to_string()));
+    match error {
+        RateLimitError::InvalidApiKey => *response.status_mut() = StatusCode::UNAUTHORIZED,
+        RateLimitError::Exceeded { retry_after } => {
+            *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+            // The "Retry-After" header is given in whole seconds, so round up.
+            let retry_after_secs = retry_after
+                .as_secs()
+                .saturating_add(u64::from(retry_after.subsec_nanos() > 0));
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after_secs));
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::rpc_server::ApiKeyConfig;
+
+    const METHOD: &str = "info_get_status";
+    const EXPENSIVE_METHOD: &str = "query_global_state";
+    const API_KEY: &str = "abc";
+
+    fn rate_limiter() -> RateLimiter {
+        let mut config = RateLimitConfig {
+            enable: true,
+            tokens_per_second: 2,
+            bucket_size: 4,
+            ..Default::default()
+        };
+        config.costs.insert(EXPENSIVE_METHOD.to_string(), 3);
+        config.api_keys.push(ApiKeyConfig {
+            key: API_KEY.to_string(),
+            tokens_per_second: 10,
+            bucket_size: 10,
+        });
+        RateLimiter::new(&config)
+    }
+
+    fn client(last_octet: u8, api_key: Option<&str>) -> Client {
+        Client {
+            ip_address: IpAddr::from([127, 0, 0, last_octet]),
+            api_key: api_key.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn should_limit_each_client_separately() {
+        let rate_limiter = rate_limiter();
+        let now = Instant::now();
+        let client_1 = client(1, None);
+        let client_2 = client(2, None);
+
+        for _ in 0..4 {
+            assert!(rate_limiter.check_client(&client_1, METHOD, now).is_ok());
+        }
+        assert_eq!(
+            rate_limiter.check_client(&client_1, METHOD, now),
+            Err(RateLimitError::Exceeded {
+                retry_after: Duration::from_millis(500)
+            })
+        );
+        assert!(rate_limiter.check_client(&client_2, METHOD, now).is_ok());
+
+        // After half a second, one token has been added to the bucket.
+        let later = now + Duration::from_millis(500);
+        assert!(rate_limiter.check_client(&client_1, METHOD, later).is_ok());
+        assert!(rate_limiter.check_client(&client_1, METHOD, later).is_err());
+    }
+
+    #[test]
+    fn should_apply_method_costs() {
+        let rate_limiter = rate_limiter();
+        let now = Instant::now();
+        let client = client(1, None);
+
+        assert!(rate_limiter
+            .check_client(&client, EXPENSIVE_METHOD, now)
+            .is_ok());
+        assert_eq!(
+            rate_limiter.check_client(&client, EXPENSIVE_METHOD, now),
+            Err(RateLimitError::Exceeded {
+                retry_after: Duration::from_secs(1)
+            })
+        );
+        assert!(rate_limiter.check_client(&client, METHOD, now).is_ok());
+    }
+
+    #[test]
+    fn should_apply_api_key_quotas() {
+        let rate_limiter = rate_limiter();
+        let now = Instant::now();
+
+        // Requests with the API key use its quota, regardless of the client's IP address.
+        for last_octet in 0..10 {
+            assert!(rate_limiter
+                .check_client(&client(last_octet, Some(API_KEY)), METHOD, now)
+                .is_ok());
+        }
+        assert!(rate_limiter
+            .check_client(&client(10, Some(API_KEY)), METHOD, now)
+            .is_err());
+        assert!(rate_limiter
+            .check_client(&client(10, None), METHOD, now)
+            .is_ok());
+
+        assert_eq!(
+            rate_limiter.check_client(&client(10, Some("xyz")), METHOD, now),
+            Err(RateLimitError::InvalidApiKey)
+        );
+    }
+
+    #[test]
+    fn should_limit_ipv6_clients_by_prefix() {
+        let rate_limiter = rate_limiter();
+        let now = Instant::now();
+        let ipv6_client = |address: &str| Client {
+            ip_address: address.parse().unwrap(),
+            api_key: None,
+        };
+
+        for host in 0..4 {
+            let client = ipv6_client(&format!("2001:db8:0:1::{}", host));
+            assert!(rate_limiter.check_client(&client, METHOD, now).is_ok());
+        }
+        assert!(rate_limiter
+            .check_client(&ipv6_client("2001:db8:0:1::ffff"), METHOD, now)
+            .is_err());
+        assert!(rate_limiter
+            .check_client(&ipv6_client("2001:db8:0:2::1"), METHOD, now)
+            .is_ok());
+
+        // IPv4-mapped addresses share the bucket of the IPv4 address.
+        for _ in 0..4 {
+            assert!(rate_limiter
+                .check_client(&client(1, None), METHOD, now)
+                .is_ok());
+        }
+        assert!(rate_limiter
+            .check_client(&ipv6_client("::ffff:127.0.0.1"), METHOD, now)
+            .is_err());
+    }
+
+    #[test]
+    fn should_drop_bucket_of_least_recently_seen_client() {
+        let rate_limiter = rate_limiter();
+        let now = Instant::now();
+        let other_client = |index: usize| Client {
+            ip_address: IpAddr::from(Ipv4Addr::from(0x0a00_0000 + index as u32)),
+            api_key: None,
+        };
+        let exhaust = |client: &Client| {
+            while rate_limiter.check_client(client, METHOD, now).is_ok() {}
+        };
+
+        let client_1 = client(1, None);
+        let client_2 = client(2, None);
+        exhaust(&client_1);
+        exhaust(&client_2);
+        for index in 0..MAX_TRACKED_CLIENTS - 2 {
+            assert!(rate_limiter
+                .check_client(&other_client(index), METHOD, now)
+                .is_ok());
+        }
+        // Seeing the first client again makes the second one the least recently seen.
+        assert!(rate_limiter.check_client(&client_1, METHOD, now).is_err());
+        assert!(rate_limiter
+            .check_client(&other_client(MAX_TRACKED_CLIENTS), METHOD, now)
+            .is_ok());
+
+        assert_eq!(
+            rate_limiter.buckets.lock().unwrap().len(),
+            MAX_TRACKED_CLIENTS
+        );
+        assert!(rate_limiter.check_client(&client_1, METHOD, now).is_err());
+        assert!(rate_limiter.check_client(&client_2, METHOD, now).is_ok());
+    }
+
+    #[test]
+    fn should_keep_api_key_buckets() {
+        let rate_limiter = rate_limiter();
+        let now = Instant::now();
+        let api_key_client = client(1, Some(API_KEY));
+        while rate_limiter
+            .check_client(&api_key_client, METHOD, now)
+            .is_ok()
+        {}
+
+        // Clients without an API key don't push the API key's bucket out.
+        for index in 0..=MAX_TRACKED_CLIENTS {
+            let client = Client {
+                ip_address: IpAddr::from(Ipv4Addr::from(0x0a00_0000 + index as u32)),
+                api_key: None,
+            };
+            assert!(rate_limiter.check_client(&client, METHOD, now).is_ok());
+        }
+        assert!(rate_limiter
+            .check_client(&api_key_client, METHOD, now)
+            .is_err());
+    }
+
+    #[test]
+    fn should_not_overflow_retry_after() {
+        let now = Instant::now();
+        let quota = Quota {
+            tokens_per_second: 0,
+            bucket_size: 1,
+        };
+        let mut bucket = Bucket::new(quota, now);
+        assert!(bucket.try_take(1, now).is_ok());
+        assert_eq!(bucket.try_take(1, now), Err(Duration::MAX));
+
+        let error = RateLimitError::Exceeded {
+            retry_after: Duration::MAX,
+        };
+        let response = rate_limit_response(error);
+        assert_eq!(
+            response.headers().get(RETRY_AFTER),
+            Some(&HeaderValue::from(u64::MAX))
+        );
+        assert_eq!(
+            Error::from(error).code(),
+            ErrorCode::RateLimitExceeded as i64
+        );
+    }
+
+    #[test]
+    fn should_not_limit_when_disabled() {
+        let rate_limiter = RateLimiter::new(&RateLimitConfig::default());
+        let now = Instant::now();
+        let client = client(1, None);
+
+        for _ in 0..100 {
+            assert!(rate_limiter.check_client(&client, METHOD, now).is_ok());
+        }
+    }
+
+    #[test]
+    fn should_limit_current_request() {
+        let rate_limiter = Arc::new(rate_limiter());
+        let client = client(1, None);
+
+        CURRENT_REQUEST.sync_scope((rate_limiter, client), || {
+            for _ in 0..4 {
+                assert!(check(METHOD).is_ok());
+            }
+            assert!(check(METHOD).is_err());
+        });
+        // Outside the scope of a request, nothing is limited.
+        assert!(check(METHOD).is_ok());
+    }
+
+    #[test]
+    fn should_convert_to_json_rpc_error() {
+        let error = Error::from(RateLimitError::Exceeded {
+            retry_after: Duration::from_millis(1500),
+        });
+        assert_eq!(error.code(), ErrorCode::RateLimitExceeded as i64);
+    }
+}
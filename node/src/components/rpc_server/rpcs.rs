@@ -15,7 +15,10 @@ use std::{str, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use http::header::ACCEPT_ENCODING;
-use hyper::server::{conn::AddrIncoming, Builder};
+use hyper::server::{
+    conn::{AddrIncoming, AddrStream},
+    Builder,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -29,7 +32,10 @@ use casper_json_rpc::{
 };
 use casper_types::ProtocolVersion;
 
-use super::{ReactorEventT, RpcRequest};
+use super::{
+    rate_limiter::{self, call_with_rate_limiter},
+    Metrics, RateLimiter, ReactorEventT, RpcRequest,
+};
 use crate::effect::EffectBuilder;
 pub use common::ErrorData;
 use docs::DocExample;
@@ -84,15 +90,26 @@ pub(super) trait RpcWithParams {
     }
 
     /// Registers this RPC as the handler for JSON-RPC requests whose "method" field is the same as
-    /// `Self::METHOD`.
+    /// `Self::METHOD`, recording each request in `metrics` and applying the rate limit of the
+    /// server handling it.
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        metrics: &Arc<Metrics>,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            let params = Self::try_parse_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version, params).await
+        let metrics = Arc::clone(metrics);
+        let handler = move |maybe_params| {
+            let metrics = Arc::clone(&metrics);
+            async move {
+                metrics
+                    .observe_request(Self::METHOD, async move {
+                        rate_limiter::check(Self::METHOD)?;
+                        let params = Self::try_parse_params(maybe_params)?;
+                        Self::do_handle_request(effect_builder, api_version, params).await
+                    })
+                    .await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -143,15 +160,26 @@ pub(super) trait RpcWithoutParams {
     }
 
     /// Registers this RPC as the handler for JSON-RPC requests whose "method" field is the same as
-    /// `Self::METHOD`.
+    /// `Self::METHOD`, recording each request in `metrics` and applying the rate limit of the
+    /// server handling it.
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        metrics: &Arc<Metrics>,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            Self::check_no_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version).await
+        let metrics = Arc::clone(metrics);
+        let handler = move |maybe_params| {
+            let metrics = Arc::clone(&metrics);
+            async move {
+                metrics
+                    .observe_request(Self::METHOD, async move {
+                        rate_limiter::check(Self::METHOD)?;
+                        Self::check_no_params(maybe_params)?;
+                        Self::do_handle_request(effect_builder, api_version).await
+                    })
+                    .await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -223,15 +251,26 @@ pub(super) trait RpcWithOptionalParams {
     }
 
     /// Registers this RPC as the handler for JSON-RPC requests whose "method" field is the same as
-    /// `Self::METHOD`.
+    /// `Self::METHOD`, recording each request in `metrics` and applying the rate limit of the
+    /// server handling it.
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        metrics: &Arc<Metrics>,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            let params = Self::try_parse_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version, params).await
+        let metrics = Arc::clone(metrics);
+        let handler = move |maybe_params| {
+            let metrics = Arc::clone(&metrics);
+            async move {
+                metrics
+                    .observe_request(Self::METHOD, async move {
+                        rate_limiter::check(Self::METHOD)?;
+                        let params = Self::try_parse_params(maybe_params)?;
+                        Self::do_handle_request(effect_builder, api_version, params).await
+                    })
+                    .await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -259,17 +298,20 @@ pub(super) async fn run_with_cors(
     builder: Builder<AddrIncoming>,
     handlers: RequestHandlers,
     qps_limit: u64,
+    rate_limiter: Arc<RateLimiter>,
     max_body_bytes: u32,
+    max_batch_size: usize,
     api_path: &'static str,
     server_name: &'static str,
     cors_header: CorsOrigin,
 ) {
-    let make_svc = hyper::service::make_service_fn(move |_| {
+    let make_svc = hyper::service::make_service_fn(move |connection: &AddrStream| {
         let service_routes = casper_json_rpc::route_with_cors(
             api_path,
             max_body_bytes,
             handlers.clone(),
             ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+            max_batch_size,
             &cors_header,
         );
 
@@ -280,7 +322,17 @@ pub(super) async fn run_with_cors(
             .with(warp::compression::gzip());
 
         let service = warp::service(service_routes_gzip.or(service_routes));
-        async move { Ok::<_, Infallible>(service.clone()) }
+        let rate_limiter = Arc::clone(&rate_limiter);
+        let remote_address = connection.remote_addr();
+        let service = hyper::service::service_fn(move |request| {
+            call_with_rate_limiter(
+                service.clone(),
+                Arc::clone(&rate_limiter),
+                remote_address,
+                request,
+            )
+        });
+        async move { Ok::<_, Infallible>(service) }
     });
 
     let make_svc = ServiceBuilder::new()
@@ -305,16 +357,19 @@ pub(super) async fn run(
     builder: Builder<AddrIncoming>,
     handlers: RequestHandlers,
     qps_limit: u64,
+    rate_limiter: Arc<RateLimiter>,
     max_body_bytes: u32,
+    max_batch_size: usize,
     api_path: &'static str,
     server_name: &'static str,
 ) {
-    let make_svc = hyper::service::make_service_fn(move |_| {
+    let make_svc = hyper::service::make_service_fn(move |connection: &AddrStream| {
         let service_routes = casper_json_rpc::route(
             api_path,
             max_body_bytes,
             handlers.clone(),
             ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+            max_batch_size,
         );
 
         // Supports content negotiation for gzip responses. This is an interim fix until
@@ -324,7 +379,17 @@ pub(super) async fn run(
             .with(warp::compression::gzip());
 
         let service = warp::service(service_routes_gzip.or(service_routes));
-        async move { Ok::<_, Infallible>(service.clone()) }
+        let rate_limiter = Arc::clone(&rate_limiter);
+        let remote_address = connection.remote_addr();
+        let service = hyper::service::service_fn(move |request| {
+            call_with_rate_limiter(
+                service.clone(),
+                Arc::clone(&rate_limiter),
+                remote_address,
+                request,
+            )
+        });
+        async move { Ok::<_, Infallible>(service) }
     });
 
     let make_svc = ServiceBuilder::new()
@@ -356,6 +421,8 @@ mod tests {
     use super::*;
     use crate::types::DeployHash;
 
+    const MAX_BATCH_SIZE: usize = 10;
+
     async fn send_request(
         method: &str,
         maybe_params: Option<&str>,
@@ -392,9 +459,13 @@ mod tests {
             GetDeploy::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                MAX_BATCH_SIZE,
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
@@ -459,9 +530,13 @@ mod tests {
             GetPeers::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                MAX_BATCH_SIZE,
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
@@ -513,9 +588,13 @@ mod tests {
             GetBlock::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                MAX_BATCH_SIZE,
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
@@ -15,14 +15,18 @@ use casper_types::ProtocolVersion;
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
-    Error, ReactorEventT, RpcWithParams,
+    state::AccountIdentifier,
+    Error, ReactorEventT, ReservedErrorCode, RpcWithParams,
 };
 use crate::{
     components::rpc_server::rpcs::ErrorCode,
     effect::EffectBuilder,
-    types::{Deploy, DeployHash},
+    types::{Block, BlockHash, Deploy, DeployHash},
 };
 
+/// The maximum number of deploys which can be requested in a single "account_get_deploys" RPC.
+pub const MAX_ACCOUNT_DEPLOYS_LIMIT: u64 = 100;
+
 static PUT_DEPLOY_PARAMS: Lazy<PutDeployParams> = Lazy::new(|| PutDeployParams {
     deploy: Deploy::doc_example().clone(),
 });
@@ -30,6 +34,23 @@ static PUT_DEPLOY_RESULT: Lazy<PutDeployResult> = Lazy::new(|| PutDeployResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     deploy_hash: *Deploy::doc_example().hash(),
 });
+static GET_ACCOUNT_DEPLOYS_PARAMS: Lazy<GetAccountDeploysParams> =
+    Lazy::new(|| GetAccountDeploysParams {
+        account_identifier: AccountIdentifier::PublicKey(
+            Deploy::doc_example().header().account().clone(),
+        ),
+        offset: 0,
+        limit: 10,
+    });
+static GET_ACCOUNT_DEPLOYS_RESULT: Lazy<GetAccountDeploysResult> =
+    Lazy::new(|| GetAccountDeploysResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploys: vec![AccountDeploy {
+            deploy_hash: *Deploy::doc_example().hash(),
+            block_hash: *Block::doc_example().hash(),
+            block_height: Block::doc_example().header().height(),
+        }],
+    });
 
 /// Params for "account_put_deploy" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -102,3 +123,122 @@ impl RpcWithParams for PutDeploy {
         }
     }
 }
+
+/// Params for "account_get_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysParams {
+    /// The public key or account hash of the account which sent the deploys.
+    pub account_identifier: AccountIdentifier,
+    /// The number of deploys to skip, in order of block height. If omitted, no deploys are
+    /// skipped.
+    #[serde(default = "offset_default")]
+    pub offset: u64,
+    /// The maximum number of deploys to return, at most 100. If omitted, 100 deploys are
+    /// returned at most.
+    #[serde(default = "limit_default")]
+    pub limit: u64,
+}
+
+/// The default for `GetAccountDeploysParams::offset`.
+fn offset_default() -> u64 {
+    0
+}
+
+/// The default for `GetAccountDeploysParams::limit`.
+fn limit_default() -> u64 {
+    MAX_ACCOUNT_DEPLOYS_LIMIT
+}
+
+impl DocExample for GetAccountDeploysParams {
+    fn doc_example() -> &'static Self {
+        &GET_ACCOUNT_DEPLOYS_PARAMS
+    }
+}
+
+/// A deploy returned by the "account_get_deploys" RPC.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AccountDeploy {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The hash of the block in which the deploy was executed.
+    pub block_hash: BlockHash,
+    /// The height of the block in which the deploy was executed.
+    pub block_height: u64,
+}
+
+/// Result for "account_get_deploys" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The executed deploys sent by the account, ordered by block height.
+    pub deploys: Vec<AccountDeploy>,
+}
+
+impl DocExample for GetAccountDeploysResult {
+    fn doc_example() -> &'static Self {
+        &GET_ACCOUNT_DEPLOYS_RESULT
+    }
+}
+
+/// "account_get_deploys" RPC.
+pub struct GetAccountDeploys {}
+
+#[async_trait]
+impl RpcWithParams for GetAccountDeploys {
+    const METHOD: &'static str = "account_get_deploys";
+    type RequestParams = GetAccountDeploysParams;
+    type ResponseResult = GetAccountDeploysResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        if params.limit > MAX_ACCOUNT_DEPLOYS_LIMIT {
+            return Err(Error::new(
+                ReservedErrorCode::InvalidParams,
+                format!(
+                    "limit {} exceeds the maximum of {} deploys",
+                    params.limit, MAX_ACCOUNT_DEPLOYS_LIMIT
+                ),
+            ));
+        }
+
+        let account_hash = match params.account_identifier {
+            AccountIdentifier::PublicKey(public_key) => public_key.to_account_hash(),
+            AccountIdentifier::AccountHash(account_hash) => account_hash,
+        };
+        let account_deploys = match effect_builder
+            .get_account_deploys_from_storage(account_hash, params.offset, params.limit)
+            .await
+        {
+            Some(account_deploys) => account_deploys,
+            None => {
+                return Err(Error::new(
+                    ErrorCode::IndexDisabled,
+                    "the account deploys index is not enabled on this node",
+                ))
+            }
+        };
+
+        let deploys = account_deploys
+            .into_iter()
+            .map(|(deploy_hash, block_hash_and_height)| AccountDeploy {
+                deploy_hash,
+                block_hash: block_hash_and_height.block_hash,
+                block_height: block_hash_and_height.block_height,
+            })
+            .collect();
+
+        let result = Self::ResponseResult {
+            api_version,
+            deploys,
+        };
+        Ok(result)
+    }
+}
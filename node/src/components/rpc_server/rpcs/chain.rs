@@ -5,7 +5,12 @@
 
 mod era_summary;
 
-use std::{clone::Clone, num::ParseIntError, str};
+use std::{
+    clone::Clone,
+    collections::{BTreeMap, BTreeSet},
+    num::ParseIntError,
+    str,
+};
 
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
@@ -14,20 +19,40 @@ use serde::{Deserialize, Serialize};
 
 use casper_execution_engine::core::engine_state::{self, QueryResult};
 use casper_hashing::Digest;
-use casper_types::{Key, ProtocolVersion, Transfer};
+use casper_types::{
+    account::AccountHash,
+    system::auction::{EraInfo, SeigniorageAllocation},
+    EraId, Key, ProtocolVersion, PublicKey, StoredValue, Transfer, URef, U512,
+};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ErrorCode, ReactorEventT, ReservedErrorCode, RpcRequest, RpcWithOptionalParams,
+    RpcWithParams,
 };
 use crate::{
+    components::storage::TransferParty,
     effect::EffectBuilder,
     reactor::QueueKind,
     rpcs::{common, state},
-    types::{Block, BlockHash, BlockWithMetadata, JsonBlock},
+    types::{
+        AvailableBlockRange, Block, BlockHash, BlockHeader, BlockWithMetadata, JsonBlock,
+        JsonBlockBody, JsonBlockHeader,
+    },
 };
 pub use era_summary::EraSummary;
-use era_summary::ERA_SUMMARY;
+use era_summary::{ERA_SUMMARY, VALIDATOR_PUBLIC_KEY};
+
+/// The maximum number of blocks which can be requested in a single "chain_get_blocks_range" RPC.
+pub const MAX_BLOCKS_RANGE_LENGTH: u64 = 100;
+/// The maximum number of transfers which can be requested in a single
+/// "chain_get_transfers_by_account" RPC.
+pub const MAX_TRANSFERS_LIMIT: u64 = 100;
+/// The maximum number of eras which can be requested in a single "chain_get_era_rewards" RPC.
+pub const MAX_ERA_REWARDS_RANGE_LENGTH: u64 = 100;
+/// The maximum number of allocations which can be requested in a single "chain_get_era_rewards"
+/// RPC.
+pub const MAX_ERA_REWARDS_LIMIT: u64 = 100;
 
 static GET_BLOCK_PARAMS: Lazy<GetBlockParams> = Lazy::new(|| GetBlockParams {
     block_identifier: BlockIdentifier::Hash(*Block::doc_example().hash()),
@@ -69,6 +94,73 @@ static GET_ERA_SUMMARY_RESULT: Lazy<GetEraSummaryResult> = Lazy::new(|| GetEraSu
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     era_summary: ERA_SUMMARY.clone(),
 });
+static GET_BLOCKS_RANGE_PARAMS: Lazy<GetBlocksRangeParams> = Lazy::new(|| {
+    let height = Block::doc_example().header().height();
+    GetBlocksRangeParams {
+        start_height: height,
+        end_height: height + 1,
+        include_bodies: true,
+    }
+});
+static GET_BLOCKS_RANGE_RESULT: Lazy<GetBlocksRangeResult> = Lazy::new(|| {
+    let json_block = JsonBlock::doc_example();
+    let height = json_block.header.height;
+    GetBlocksRangeResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        available_block_range: AvailableBlockRange::new(0, height),
+        blocks: vec![BlockRangeEntry {
+            hash: json_block.hash,
+            header: json_block.header.clone(),
+            body: Some(json_block.body.clone()),
+        }],
+        missing_heights: vec![height + 1],
+    }
+});
+static GET_TRANSFERS_BY_ACCOUNT_PARAMS: Lazy<GetTransfersByAccountParams> =
+    Lazy::new(|| GetTransfersByAccountParams {
+        transfer_party_identifier: TransferPartyIdentifier::AccountHash(Transfer::default().from),
+        offset: 0,
+        limit: 10,
+    });
+static GET_TRANSFERS_BY_ACCOUNT_RESULT: Lazy<GetTransfersByAccountResult> =
+    Lazy::new(|| GetTransfersByAccountResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        transfers: vec![AccountTransfer {
+            block_hash: *Block::doc_example().hash(),
+            block_height: Block::doc_example().header().height(),
+            transfer: Transfer::default(),
+        }],
+    });
+static GET_ERA_REWARDS_PARAMS: Lazy<GetEraRewardsParams> = Lazy::new(|| {
+    let era_id = ERA_SUMMARY.era_id;
+    GetEraRewardsParams {
+        public_key: VALIDATOR_PUBLIC_KEY.clone(),
+        start_era_id: era_id,
+        end_era_id: era_id.successor(),
+        offset: 0,
+        limit: 10,
+    }
+});
+static GET_ERA_REWARDS_RESULT: Lazy<GetEraRewardsResult> = Lazy::new(|| {
+    let era_id = ERA_SUMMARY.era_id;
+    let allocation = SeigniorageAllocation::validator(VALIDATOR_PUBLIC_KEY.clone(), 2000.into());
+    GetEraRewardsResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        allocations: vec![EraRewardAllocation {
+            era_id,
+            block_hash: *Block::doc_example().hash(),
+            block_height: Block::doc_example().header().height(),
+            allocation: allocation.clone(),
+        }],
+        total_allocation_count: 1,
+        validator_totals: vec![ValidatorRewardTotal {
+            validator_public_key: VALIDATOR_PUBLIC_KEY.clone(),
+            amount: *allocation.amount(),
+        }],
+        total_amount: *allocation.amount(),
+        missing_era_ids: vec![era_id.successor()],
+    }
+});
 
 /// Identifier for possible ways to retrieve a block.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
@@ -185,6 +277,135 @@ impl RpcWithOptionalParams for GetBlock {
     }
 }
 
+/// Params for "chain_get_blocks_range" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlocksRangeParams {
+    /// The height of the first block in the range.
+    pub start_height: u64,
+    /// The height of the last block in the range (inclusive).
+    pub end_height: u64,
+    /// Whether to return the block bodies as well as the block headers. If `false` or omitted,
+    /// only the block headers are returned.
+    #[serde(default = "include_bodies_default")]
+    pub include_bodies: bool,
+}
+
+/// The default for `GetBlocksRangeParams::include_bodies`.
+fn include_bodies_default() -> bool {
+    false
+}
+
+impl DocExample for GetBlocksRangeParams {
+    fn doc_example() -> &'static Self {
+        &GET_BLOCKS_RANGE_PARAMS
+    }
+}
+
+/// A block returned by the "chain_get_blocks_range" RPC.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BlockRangeEntry {
+    /// The block hash.
+    pub hash: BlockHash,
+    /// JSON-friendly block header.
+    pub header: JsonBlockHeader,
+    /// JSON-friendly block body, if requested.
+    pub body: Option<JsonBlockBody>,
+}
+
+/// Result for "chain_get_blocks_range" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlocksRangeResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The height range (inclusive) of fully available blocks.
+    pub available_block_range: AvailableBlockRange,
+    /// The blocks in the requested range which are available on this node, ordered by height.
+    pub blocks: Vec<BlockRangeEntry>,
+    /// The heights in the requested range for which no block is available on this node.
+    pub missing_heights: Vec<u64>,
+}
+
+impl DocExample for GetBlocksRangeResult {
+    fn doc_example() -> &'static Self {
+        &GET_BLOCKS_RANGE_RESULT
+    }
+}
+
+/// "chain_get_blocks_range" RPC.
+pub struct GetBlocksRange {}
+
+#[async_trait]
+impl RpcWithParams for GetBlocksRange {
+    const METHOD: &'static str = "chain_get_blocks_range";
+    type RequestParams = GetBlocksRangeParams;
+    type ResponseResult = GetBlocksRangeResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let GetBlocksRangeParams {
+            start_height,
+            end_height,
+            include_bodies,
+        } = params;
+
+        if start_height > end_height {
+            return Err(Error::new(
+                ReservedErrorCode::InvalidParams,
+                format!(
+                    "start_height {} is greater than end_height {}",
+                    start_height, end_height
+                ),
+            ));
+        }
+        if end_height - start_height >= MAX_BLOCKS_RANGE_LENGTH {
+            return Err(Error::new(
+                ReservedErrorCode::InvalidParams,
+                format!(
+                    "requested range [{}, {}] exceeds the maximum of {} blocks",
+                    start_height, end_height, MAX_BLOCKS_RANGE_LENGTH
+                ),
+            ));
+        }
+
+        // This RPC request is restricted by the block availability index, which is enforced by
+        // storage.
+        let available_block_range = effect_builder
+            .get_available_block_range_from_storage()
+            .await;
+        let blocks: Vec<BlockRangeEntry> = effect_builder
+            .get_blocks_by_height_range_from_storage(start_height, end_height, include_bodies)
+            .await
+            .into_iter()
+            .map(|(block_header, maybe_block_body)| BlockRangeEntry {
+                hash: block_header.block_hash(),
+                header: JsonBlockHeader::from(block_header),
+                body: maybe_block_body.as_ref().map(JsonBlockBody::from),
+            })
+            .collect();
+
+        let returned_heights: BTreeSet<u64> =
+            blocks.iter().map(|block| block.header.height).collect();
+        let missing_heights = (start_height..=end_height)
+            .filter(|height| !returned_heights.contains(height))
+            .collect();
+
+        let result = Self::ResponseResult {
+            api_version,
+            available_block_range,
+            blocks,
+            missing_heights,
+        };
+        Ok(result)
+    }
+}
+
 /// Params for "chain_get_block_transfers" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -276,6 +497,354 @@ impl RpcWithOptionalParams for GetBlockTransfers {
     }
 }
 
+/// Identifier of an account or purse which took part in transfers.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum TransferPartyIdentifier {
+    /// The account identified by this public key.
+    PublicKey(PublicKey),
+    /// The account identified by this account hash.
+    AccountHash(AccountHash),
+    /// The purse identified by this URef.
+    PurseUref(URef),
+}
+
+impl From<TransferPartyIdentifier> for TransferParty {
+    fn from(identifier: TransferPartyIdentifier) -> Self {
+        match identifier {
+            TransferPartyIdentifier::PublicKey(public_key) => {
+                TransferParty::Account(public_key.to_account_hash())
+            }
+            TransferPartyIdentifier::AccountHash(account_hash) => {
+                TransferParty::Account(account_hash)
+            }
+            TransferPartyIdentifier::PurseUref(uref) => TransferParty::Purse(uref.addr()),
+        }
+    }
+}
+
+/// Params for "chain_get_transfers_by_account" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetTransfersByAccountParams {
+    /// The account or purse which sent or received the transfers.
+    pub transfer_party_identifier: TransferPartyIdentifier,
+    /// The number of transfers to skip, in order of block height. If omitted, no transfers are
+    /// skipped.
+    #[serde(default = "offset_default")]
+    pub offset: u64,
+    /// The maximum number of transfers to return, at most 100. If omitted, 100 transfers are
+    /// returned at most.
+    #[serde(default = "limit_default")]
+    pub limit: u64,
+}
+
+/// The default for `GetTransfersByAccountParams::offset`.
+fn offset_default() -> u64 {
+    0
+}
+
+/// The default for `GetTransfersByAccountParams::limit`.
+fn limit_default() -> u64 {
+    MAX_TRANSFERS_LIMIT
+}
+
+impl DocExample for GetTransfersByAccountParams {
+    fn doc_example() -> &'static Self {
+        &GET_TRANSFERS_BY_ACCOUNT_PARAMS
+    }
+}
+
+/// A transfer returned by the "chain_get_transfers_by_account" RPC.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AccountTransfer {
+    /// The hash of the block in which the transfer was executed.
+    pub block_hash: BlockHash,
+    /// The height of the block in which the transfer was executed.
+    pub block_height: u64,
+    /// The transfer.
+    pub transfer: Transfer,
+}
+
+/// Result for "chain_get_transfers_by_account" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetTransfersByAccountResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The transfers sent or received by the account or purse, ordered by block height.
+    pub transfers: Vec<AccountTransfer>,
+}
+
+impl DocExample for GetTransfersByAccountResult {
+    fn doc_example() -> &'static Self {
+        &GET_TRANSFERS_BY_ACCOUNT_RESULT
+    }
+}
+
+/// "chain_get_transfers_by_account" RPC.
+pub struct GetTransfersByAccount {}
+
+#[async_trait]
+impl RpcWithParams for GetTransfersByAccount {
+    const METHOD: &'static str = "chain_get_transfers_by_account";
+    type RequestParams = GetTransfersByAccountParams;
+    type ResponseResult = GetTransfersByAccountResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        if params.limit > MAX_TRANSFERS_LIMIT {
+            return Err(Error::new(
+                ReservedErrorCode::InvalidParams,
+                format!(
+                    "limit {} exceeds the maximum of {} transfers",
+                    params.limit, MAX_TRANSFERS_LIMIT
+                ),
+            ));
+        }
+
+        let party = TransferParty::from(params.transfer_party_identifier);
+        let transfers = match effect_builder
+            .get_transfers_by_party_from_storage(party, params.offset, params.limit)
+            .await
+        {
+            Some(transfers) => transfers,
+            None => {
+                return Err(Error::new(
+                    ErrorCode::IndexDisabled,
+                    "the transfer index is not enabled on this node",
+                ))
+            }
+        };
+
+        let transfers = transfers
+            .into_iter()
+            .map(|(transfer, block_hash_and_height)| AccountTransfer {
+                block_hash: block_hash_and_height.block_hash,
+                block_height: block_hash_and_height.block_height,
+                transfer,
+            })
+            .collect();
+
+        let result = Self::ResponseResult {
+            api_version,
+            transfers,
+        };
+        Ok(result)
+    }
+}
+
+/// Params for "chain_get_era_rewards" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEraRewardsParams {
+    /// The public key of the validator or delegator whose allocations are requested.
+    pub public_key: PublicKey,
+    /// The ID of the first era in the range.
+    pub start_era_id: EraId,
+    /// The ID of the last era in the range (inclusive).
+    pub end_era_id: EraId,
+    /// The number of allocations to skip, in order of era ID. If omitted, no allocations are
+    /// skipped.
+    #[serde(default = "offset_default")]
+    pub offset: u64,
+    /// The maximum number of allocations to return, at most 100. If omitted, 100 allocations are
+    /// returned at most.
+    #[serde(default = "era_rewards_limit_default")]
+    pub limit: u64,
+}
+
+/// The default for `GetEraRewardsParams::limit`.
+fn era_rewards_limit_default() -> u64 {
+    MAX_ERA_REWARDS_LIMIT
+}
+
+impl DocExample for GetEraRewardsParams {
+    fn doc_example() -> &'static Self {
+        &GET_ERA_REWARDS_PARAMS
+    }
+}
+
+/// A seigniorage allocation returned by the "chain_get_era_rewards" RPC.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct EraRewardAllocation {
+    /// The ID of the era for which the allocation was made.
+    pub era_id: EraId,
+    /// The hash of the switch block of the era.
+    pub block_hash: BlockHash,
+    /// The height of the switch block of the era.
+    pub block_height: u64,
+    /// The allocation.
+    pub allocation: SeigniorageAllocation,
+}
+
+/// The total amount allocated in connection with a single validator.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatorRewardTotal {
+    /// The public key of the validator.
+    pub validator_public_key: PublicKey,
+    /// The total amount allocated across the requested eras.
+    pub amount: U512,
+}
+
+/// Result for "chain_get_era_rewards" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEraRewardsResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The requested page of allocations, ordered by era ID.
+    pub allocations: Vec<EraRewardAllocation>,
+    /// The total number of allocations across all available eras in the range, regardless of
+    /// pagination.
+    pub total_allocation_count: u64,
+    /// The total amounts allocated across all available eras in the range, per validator.
+    pub validator_totals: Vec<ValidatorRewardTotal>,
+    /// The total amount allocated across all available eras in the range.
+    pub total_amount: U512,
+    /// The IDs of the eras in the range for which no switch block or era info is available on
+    /// this node.
+    pub missing_era_ids: Vec<EraId>,
+}
+
+impl DocExample for GetEraRewardsResult {
+    fn doc_example() -> &'static Self {
+        &GET_ERA_REWARDS_RESULT
+    }
+}
+
+/// "chain_get_era_rewards" RPC.
+pub struct GetEraRewards {}
+
+#[async_trait]
+impl RpcWithParams for GetEraRewards {
+    const METHOD: &'static str = "chain_get_era_rewards";
+    type RequestParams = GetEraRewardsParams;
+    type ResponseResult = GetEraRewardsResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let GetEraRewardsParams {
+            public_key,
+            start_era_id,
+            end_era_id,
+            offset,
+            limit,
+        } = params;
+
+        if start_era_id > end_era_id {
+            return Err(Error::new(
+                ReservedErrorCode::InvalidParams,
+                format!(
+                    "start_era_id {} is greater than end_era_id {}",
+                    start_era_id, end_era_id
+                ),
+            ));
+        }
+        if end_era_id.value() - start_era_id.value() >= MAX_ERA_REWARDS_RANGE_LENGTH {
+            return Err(Error::new(
+                ReservedErrorCode::InvalidParams,
+                format!(
+                    "requested range [{}, {}] exceeds the maximum of {} eras",
+                    start_era_id, end_era_id, MAX_ERA_REWARDS_RANGE_LENGTH
+                ),
+            ));
+        }
+        if limit > MAX_ERA_REWARDS_LIMIT {
+            return Err(Error::new(
+                ReservedErrorCode::InvalidParams,
+                format!(
+                    "limit {} exceeds the maximum of {} allocations",
+                    limit, MAX_ERA_REWARDS_LIMIT
+                ),
+            ));
+        }
+
+        // This RPC request is restricted by the block availability index, which is enforced by
+        // storage.
+        let switch_block_headers = effect_builder
+            .get_switch_block_headers_by_era_range_from_storage(start_era_id, end_era_id)
+            .await;
+
+        let mut all_allocations = vec![];
+        let mut available_era_ids = BTreeSet::new();
+        for block_header in switch_block_headers {
+            let era_info = match get_era_info(effect_builder, &block_header).await? {
+                Some(era_info) => era_info,
+                None => continue,
+            };
+            available_era_ids.insert(block_header.era_id());
+            let block_hash = block_header.block_hash();
+            all_allocations.extend(era_info.select(public_key.clone()).map(|allocation| {
+                EraRewardAllocation {
+                    era_id: block_header.era_id(),
+                    block_hash,
+                    block_height: block_header.height(),
+                    allocation: allocation.clone(),
+                }
+            }));
+        }
+
+        let mut totals: BTreeMap<PublicKey, U512> = BTreeMap::new();
+        for era_reward_allocation in &all_allocations {
+            let (validator_public_key, amount) = match &era_reward_allocation.allocation {
+                SeigniorageAllocation::Validator {
+                    validator_public_key,
+                    amount,
+                }
+                | SeigniorageAllocation::Delegator {
+                    validator_public_key,
+                    amount,
+                    ..
+                } => (validator_public_key, amount),
+            };
+            *totals.entry(validator_public_key.clone()).or_default() += *amount;
+        }
+        let total_amount = totals
+            .values()
+            .fold(U512::zero(), |sum, amount| sum + *amount);
+        let validator_totals = totals
+            .into_iter()
+            .map(|(validator_public_key, amount)| ValidatorRewardTotal {
+                validator_public_key,
+                amount,
+            })
+            .collect();
+
+        let total_allocation_count = all_allocations.len() as u64;
+        let allocations = all_allocations
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect();
+        let missing_era_ids = (start_era_id.value()..=end_era_id.value())
+            .map(EraId::from)
+            .filter(|era_id| !available_era_ids.contains(era_id))
+            .collect();
+
+        let result = Self::ResponseResult {
+            api_version,
+            allocations,
+            total_allocation_count,
+            validator_totals,
+            total_amount,
+            missing_era_ids,
+        };
+        Ok(result)
+    }
+}
+
 /// Params for "chain_get_state_root_hash" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -591,3 +1160,60 @@ async fn get_era_summary<REv: ReactorEventT>(
         .await;
     handle_query_result(effect_builder, block, era_info_query_result).await
 }
+
+/// Returns the `EraInfo` recorded at the state root of the given switch block, or `None` if it
+/// isn't present in global state.
+///
+/// As with `get_era_summary`, `Key::EraSummary` is tried first, falling back to
+/// `Key::EraInfo(era_id)` for blocks produced before the former was introduced.
+async fn get_era_info<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    block_header: &BlockHeader,
+) -> Result<Option<EraInfo>, Error> {
+    let state_root_hash = *block_header.state_root_hash();
+    for base_key in [Key::EraSummary, Key::EraInfo(block_header.era_id())] {
+        let query_result = effect_builder
+            .make_request(
+                |responder| RpcRequest::QueryGlobalState {
+                    state_root_hash,
+                    base_key,
+                    path: vec![],
+                    responder,
+                },
+                QueueKind::Api,
+            )
+            .await;
+        match query_result {
+            Ok(QueryResult::Success { value, .. }) => match *value {
+                StoredValue::EraInfo(era_info) => return Ok(Some(era_info)),
+                other => {
+                    return Err(Error::new(
+                        ReservedErrorCode::InternalError,
+                        format!(
+                            "expected era info under {} at state root {}, but found {}",
+                            base_key,
+                            state_root_hash,
+                            other.type_name()
+                        ),
+                    ))
+                }
+            },
+            Ok(QueryResult::ValueNotFound(_)) => continue,
+            // The state root has most likely been pruned; treat the era as unavailable.
+            Ok(QueryResult::RootNotFound) => return Ok(None),
+            Ok(query_result) => {
+                return Err(Error::new(
+                    ErrorCode::QueryFailed,
+                    format!("{:?}", query_result),
+                ))
+            }
+            Err(error) => {
+                return Err(Error::new(
+                    ErrorCode::QueryFailedToExecute,
+                    format!("{:?}", error),
+                ))
+            }
+        }
+    }
+    Ok(None)
+}
@@ -13,25 +13,23 @@ use crate::{
     types::{json_compatibility::StoredValue, Block, BlockHash},
 };
 
+pub(super) static VALIDATOR_PUBLIC_KEY: Lazy<PublicKey> = Lazy::new(|| {
+    PublicKey::from_hex("012a1732addc639ea43a89e25d3ad912e40232156dcaa4b9edfc709f43d2fb0876")
+        .unwrap()
+});
 pub(super) static ERA_SUMMARY: Lazy<EraSummary> = Lazy::new(|| {
     let delegator_amount = U512::from(1000);
     let validator_amount = U512::from(2000);
     let delegator_public_key =
         PublicKey::from_hex("01e1b46a25baa8a5c28beb3c9cfb79b572effa04076f00befa57eb70b016153f18")
             .unwrap();
-    let validator_public_key =
-        PublicKey::from_hex("012a1732addc639ea43a89e25d3ad912e40232156dcaa4b9edfc709f43d2fb0876")
-            .unwrap();
     let delegator = SeigniorageAllocation::delegator(
         delegator_public_key,
-        validator_public_key,
+        VALIDATOR_PUBLIC_KEY.clone(),
         delegator_amount,
     );
-    let validator = SeigniorageAllocation::validator(
-        PublicKey::from_hex("012a1732addc639ea43a89e25d3ad912e40232156dcaa4b9edfc709f43d2fb0876")
-            .unwrap(),
-        validator_amount,
-    );
+    let validator =
+        SeigniorageAllocation::validator(VALIDATOR_PUBLIC_KEY.clone(), validator_amount);
     let seigniorage_allocations = vec![delegator, validator];
     let mut era_info = EraInfo::new();
     *era_info.seigniorage_allocations_mut() = seigniorage_allocations;
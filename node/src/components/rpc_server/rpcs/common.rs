@@ -12,7 +12,7 @@ use casper_types::{bytesrepr::ToBytes, Key};
 
 use super::{
     chain::{self, BlockIdentifier},
-    state, Error, ReactorEventT, RpcRequest,
+    state, Error, ErrorCode, ReactorEventT, RpcRequest,
 };
 use crate::{
     effect::EffectBuilder,
@@ -126,6 +126,29 @@ pub(super) async fn missing_block_or_state_root_error<REv: ReactorEventT, E: Err
     Error::new(error_code, error_data)
 }
 
+/// Returns an `Error` with code `ErrorCode::StatePruned` if this node has pruned old global state,
+/// or `None` otherwise.
+///
+/// Intended to be called when a state root hash is not found in global state.
+pub(super) async fn state_pruned_error<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    error_message: &str,
+) -> Option<Error> {
+    let pruned_below_height = effect_builder
+        .get_state_pruned_below_height_from_storage()
+        .await?;
+    let error = missing_block_or_state_root_error(
+        effect_builder,
+        ErrorCode::StatePruned,
+        format!(
+            "{}: global state of blocks below height {} has been pruned",
+            error_message, pruned_below_height
+        ),
+    )
+    .await;
+    Some(error)
+}
+
 pub(super) async fn get_block<REv: ReactorEventT>(
     maybe_id: Option<BlockIdentifier>,
     only_from_available_block_range: bool,
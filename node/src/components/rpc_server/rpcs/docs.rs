@@ -16,14 +16,15 @@ use serde_json::{json, Value};
 use casper_types::ProtocolVersion;
 
 use super::{
-    account::PutDeploy,
+    account::{GetAccountDeploys, PutDeploy},
     chain::{
-        GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetStateRootHash,
+        GetBlock, GetBlockTransfers, GetBlocksRange, GetEraInfoBySwitchBlock, GetEraRewards,
+        GetEraSummary, GetStateRootHash, GetTransfersByAccount,
     },
     info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
     state::{
-        GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, QueryBalance,
-        QueryGlobalState,
+        GetAccountInfo, GetAuctionInfo, GetBalance, GetContractSchema, GetDictionaryItem, GetItem,
+        QueryBalance, QueryGlobalState,
     },
     Error, ReactorEventT, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
 };
@@ -109,6 +110,23 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
         "returns the era summary at either a specific block (by height or hash), or the most \
         recently added block",
     );
+    schema.push_with_params::<GetBlocksRange>(
+        "returns the headers, and optionally the bodies, of the Blocks in a range of heights",
+    );
+    schema.push_with_params::<GetAccountDeploys>(
+        "returns the hashes of executed Deploys sent by an Account, ordered by Block height",
+    );
+    schema.push_with_params::<GetTransfersByAccount>(
+        "returns the Transfers sent or received by an Account or purse, ordered by Block height",
+    );
+    schema.push_with_params::<GetEraRewards>(
+        "returns the seigniorage allocations of a validator or delegator across a range of eras, \
+        with totals per validator",
+    );
+    schema.push_with_params::<GetContractSchema>(
+        "returns the schema registered for a contract version, if one was provided when the \
+        version was added",
+    );
 
     schema
 });
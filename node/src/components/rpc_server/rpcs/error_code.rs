@@ -35,6 +35,16 @@ pub enum ErrorCode {
     FailedToGetTrie = -32011,
     /// The requested state root hash was not found.
     NoSuchStateRoot = -32012,
+    /// The index required to serve the request is not enabled on this node.
+    IndexDisabled = -32013,
+    /// The global state required to serve the request has been pruned from this node.
+    StatePruned = -32014,
+    /// The given state overrides for a speculative execution are invalid.
+    InvalidStateOverride = -32015,
+    /// The client has exceeded its rate limit.
+    RateLimitExceeded = -32016,
+    /// The API key provided by the client is not recognized.
+    InvalidApiKey = -32017,
 }
 
 impl From<ErrorCode> for (i64, &'static str) {
@@ -59,6 +69,11 @@ impl From<ErrorCode> for (i64, &'static str) {
             }
             ErrorCode::FailedToGetTrie => (error_code as i64, "Failed to get trie"),
             ErrorCode::NoSuchStateRoot => (error_code as i64, "No such state root"),
+            ErrorCode::IndexDisabled => (error_code as i64, "Index disabled"),
+            ErrorCode::StatePruned => (error_code as i64, "State pruned"),
+            ErrorCode::InvalidStateOverride => (error_code as i64, "Invalid state override"),
+            ErrorCode::RateLimitExceeded => (error_code as i64, "Rate limit exceeded"),
+            ErrorCode::InvalidApiKey => (error_code as i64, "Invalid API key"),
         }
     }
 }
@@ -3,16 +3,21 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::{str, sync::Arc};
+use std::{collections::BTreeMap, convert::TryFrom, str, sync::Arc};
 
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use casper_execution_engine::core::engine_state::Error as EngineStateError;
+use casper_execution_engine::core::{
+    engine_state::Error as EngineStateError, runtime::trace::ExecutionTrace,
+};
 use casper_json_rpc::ReservedErrorCode;
-use casper_types::{ExecutionResult, ProtocolVersion};
+use casper_types::{
+    CLValue, ExecutionEffect, ExecutionResult, Key, ProtocolVersion,
+    StoredValue as ExecutionEngineStoredValue, URef, U512,
+};
 
 use super::{
     chain::BlockIdentifier,
@@ -23,18 +28,37 @@ use super::{
 use crate::{
     components::contract_runtime::SpeculativeExecutionState,
     effect::EffectBuilder,
-    types::{Block, BlockHash, Deploy},
+    types::{json_compatibility::StoredValue, Block, BlockHash, Deploy, DeployHash},
 };
 
 static SPECULATIVE_EXEC_PARAMS: Lazy<SpeculativeExecParams> = Lazy::new(|| SpeculativeExecParams {
     block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
     deploy: Deploy::doc_example().clone(),
+    state_overrides: None,
+    trace: false,
 });
 static SPECULATIVE_EXEC_RESULT: Lazy<SpeculativeExecResult> = Lazy::new(|| SpeculativeExecResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     block_hash: *Block::doc_example().hash(),
     execution_result: ExecutionResult::example().clone(),
+    execution_trace: None,
 });
+static SPECULATIVE_EXEC_SEQUENCE_PARAMS: Lazy<SpeculativeExecSequenceParams> =
+    Lazy::new(|| SpeculativeExecSequenceParams {
+        block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
+        deploys: vec![Deploy::doc_example().clone()],
+        dry_run: false,
+    });
+static SPECULATIVE_EXEC_SEQUENCE_RESULT: Lazy<SpeculativeExecSequenceResult> =
+    Lazy::new(|| SpeculativeExecSequenceResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        block_hash: *Block::doc_example().hash(),
+        execution_results: vec![SpeculativeDeployExecutionResult {
+            deploy_hash: *Deploy::doc_example().hash(),
+            execution_result: ExecutionResult::example().clone(),
+        }],
+        effect: ExecutionEffect::default(),
+    });
 
 /// Params for "speculative_exec" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -44,6 +68,11 @@ pub struct SpeculativeExecParams {
     pub block_identifier: Option<BlockIdentifier>,
     /// Deploy to execute.
     pub deploy: Deploy,
+    /// Overrides of the global state of the block, applied before executing the deploy.
+    pub state_overrides: Option<StateOverrides>,
+    /// If `true`, the execution is traced and the trace is returned along with the result.
+    #[serde(default)]
+    pub trace: bool,
 }
 
 impl DocExample for SpeculativeExecParams {
@@ -52,6 +81,74 @@ impl DocExample for SpeculativeExecParams {
     }
 }
 
+/// Overrides of the global state on top of which a deploy is speculatively executed.
+#[derive(Serialize, Deserialize, Debug, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StateOverrides {
+    /// Values to store under the given keys.
+    #[serde(default)]
+    pub values: Vec<ValueOverride>,
+    /// Balances to set for the given purses.
+    #[serde(default)]
+    pub balances: Vec<BalanceOverride>,
+}
+
+impl StateOverrides {
+    /// Converts the overrides into the values to store under each key, balance overrides taking
+    /// precedence over value overrides of the same purse's balance.
+    fn into_values(self) -> Result<BTreeMap<Key, ExecutionEngineStoredValue>, Error> {
+        let mut values = BTreeMap::new();
+        for ValueOverride { key, value } in self.values {
+            let key = Key::from_formatted_str(&key).map_err(|error| {
+                Error::new(
+                    ErrorCode::InvalidStateOverride,
+                    format!("failed to parse key {}: {}", key, error),
+                )
+            })?;
+            let value = ExecutionEngineStoredValue::try_from(value).map_err(|error| {
+                Error::new(
+                    ErrorCode::InvalidStateOverride,
+                    format!("invalid value for key {}: {}", key, error),
+                )
+            })?;
+            let _ = values.insert(key, value);
+        }
+        for BalanceOverride { purse, balance } in self.balances {
+            let cl_value = CLValue::from_t(balance).map_err(|error| {
+                Error::new(
+                    ErrorCode::InvalidStateOverride,
+                    format!("invalid balance for purse {}: {}", purse, error),
+                )
+            })?;
+            let _ = values.insert(
+                Key::Balance(purse.addr()),
+                ExecutionEngineStoredValue::CLValue(cl_value),
+            );
+        }
+        Ok(values)
+    }
+}
+
+/// A value to store under a key before speculatively executing a deploy.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ValueOverride {
+    /// The formatted string of the key.
+    pub key: String,
+    /// The value to store under the key.
+    pub value: StoredValue,
+}
+
+/// A balance to set for a purse before speculatively executing a deploy.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BalanceOverride {
+    /// The purse.
+    pub purse: URef,
+    /// The balance to set, in motes.
+    pub balance: U512,
+}
+
 /// Result for "speculative_exec" RPC response.
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -63,6 +160,9 @@ pub struct SpeculativeExecResult {
     pub block_hash: BlockHash,
     /// Result of the execution.
     pub execution_result: ExecutionResult,
+    /// Trace of the execution, if requested.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub execution_trace: Option<ExecutionTrace>,
 }
 
 impl DocExample for SpeculativeExecResult {
@@ -88,8 +188,15 @@ impl RpcWithParams for SpeculativeExec {
         let SpeculativeExecParams {
             block_identifier: maybe_block_id,
             deploy,
+            state_overrides,
+            trace,
         } = params;
         let deploy = Arc::new(deploy);
+        let state_overrides = state_overrides.unwrap_or_default().into_values()?;
+        // The overrides may e.g. create or fund the deploy's account, so checking it against the
+        // block's global state could wrongly reject the deploy.  It is still checked during
+        // execution, against the overridden state.
+        let skip_account_checks = !state_overrides.is_empty();
         let only_from_available_block_range = true;
 
         let block = common::get_block(
@@ -106,7 +213,11 @@ impl RpcWithParams for SpeculativeExec {
         };
 
         let accept_deploy_result = effect_builder
-            .try_accept_deploy(Arc::clone(&deploy), Some(Box::new(block.take_header())))
+            .try_accept_speculative_deploy(
+                Arc::clone(&deploy),
+                Box::new(block.take_header()),
+                skip_account_checks,
+            )
             .await;
 
         if let Err(error) = accept_deploy_result {
@@ -114,15 +225,21 @@ impl RpcWithParams for SpeculativeExec {
         }
 
         let result = effect_builder
-            .speculative_execute_deploy(execution_prestate, Arc::clone(&deploy))
+            .speculative_execute_deploy(
+                execution_prestate,
+                Arc::clone(&deploy),
+                state_overrides,
+                trace,
+            )
             .await;
 
         match result {
-            Ok(Some(execution_result)) => {
+            Ok(Some((execution_result, execution_trace))) => {
                 let result = Self::ResponseResult {
                     api_version,
                     block_hash,
                     execution_result,
+                    execution_trace,
                 };
                 Ok(result)
             }
@@ -130,50 +247,340 @@ impl RpcWithParams for SpeculativeExec {
                 ErrorCode::NoSuchBlock,
                 "block hash not found".to_string(),
             )),
-            Err(error) => {
-                let rpc_error = match error {
-                    EngineStateError::RootNotFound(_) => Error::new(ErrorCode::NoSuchStateRoot, ""),
-                    EngineStateError::WasmPreprocessing(error) => {
-                        Error::new(ErrorCode::InvalidDeploy, error.to_string())
-                    }
-                    EngineStateError::InvalidDeployItemVariant(error) => {
-                        Error::new(ErrorCode::InvalidDeploy, error)
-                    }
-                    EngineStateError::InvalidProtocolVersion(_) => Error::new(
+            Err(error) => Err(engine_state_error_to_rpc_error(error)),
+        }
+    }
+}
+
+/// Params for "speculative_exec_sequence" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecSequenceParams {
+    /// Block hash on top of which to execute the deploys.
+    pub block_identifier: Option<BlockIdentifier>,
+    /// Deploys to execute, in order.  Each deploy is executed on top of the effects of the ones
+    /// before it.
+    pub deploys: Vec<Deploy>,
+    /// If `true`, the approvals of the deploys are not checked and their payment code is not run,
+    /// so that only the effects of their session code are returned.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl DocExample for SpeculativeExecSequenceParams {
+    fn doc_example() -> &'static Self {
+        &SPECULATIVE_EXEC_SEQUENCE_PARAMS
+    }
+}
+
+/// The result of executing a single deploy of a "speculative_exec_sequence" RPC request.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeDeployExecutionResult {
+    /// Hash of the deploy.
+    pub deploy_hash: DeployHash,
+    /// Result of the execution.
+    pub execution_result: ExecutionResult,
+}
+
+/// Result for "speculative_exec_sequence" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecSequenceResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// Hash of the block on top of which the deploys were executed.
+    pub block_hash: BlockHash,
+    /// Results of the execution of each deploy, in order.
+    pub execution_results: Vec<SpeculativeDeployExecutionResult>,
+    /// The combined effects of all of the deploys.
+    pub effect: ExecutionEffect,
+}
+
+impl DocExample for SpeculativeExecSequenceResult {
+    fn doc_example() -> &'static Self {
+        &SPECULATIVE_EXEC_SEQUENCE_RESULT
+    }
+}
+
+/// "speculative_exec_sequence" RPC
+pub struct SpeculativeExecSequence {}
+
+#[async_trait]
+impl RpcWithParams for SpeculativeExecSequence {
+    const METHOD: &'static str = "speculative_exec_sequence";
+    type RequestParams = SpeculativeExecSequenceParams;
+    type ResponseResult = SpeculativeExecSequenceResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let SpeculativeExecSequenceParams {
+            block_identifier: maybe_block_id,
+            deploys,
+            dry_run,
+        } = params;
+        if deploys.is_empty() {
+            return Err(Error::new(
+                ErrorCode::InvalidDeploy,
+                "no deploys to execute".to_string(),
+            ));
+        }
+        let deploys: Vec<_> = deploys.into_iter().map(Arc::new).collect();
+        let only_from_available_block_range = true;
+
+        let block = common::get_block(
+            maybe_block_id,
+            only_from_available_block_range,
+            effect_builder,
+        )
+        .await?;
+        let block_hash = *block.hash();
+        let execution_prestate = SpeculativeExecutionState {
+            state_root_hash: *block.state_root_hash(),
+            block_time: block.timestamp(),
+            protocol_version: block.protocol_version(),
+        };
+
+        if !dry_run {
+            let block_header = block.take_header();
+            for (index, deploy) in deploys.iter().enumerate() {
+                // Only the first deploy is executed on top of the block's global state.  The
+                // accounts of the later ones are checked during execution against the effects of
+                // the deploys before them, so only their other properties are checked here.
+                let skip_account_checks = index > 0;
+                let accept_deploy_result = effect_builder
+                    .try_accept_speculative_deploy(
+                        Arc::clone(deploy),
+                        Box::new(block_header.clone()),
+                        skip_account_checks,
+                    )
+                    .await;
+                if let Err(error) = accept_deploy_result {
+                    return Err(Error::new(
                         ErrorCode::InvalidDeploy,
-                        format!("deploy used invalid protocol version {}", error),
-                    ),
-                    EngineStateError::Deploy => Error::new(ErrorCode::InvalidDeploy, ""),
-                    EngineStateError::Genesis(_)
-                    | EngineStateError::WasmSerialization(_)
-                    | EngineStateError::Exec(_)
-                    | EngineStateError::Storage(_)
-                    | EngineStateError::Authorization
-                    | EngineStateError::InsufficientPayment
-                    | EngineStateError::GasConversionOverflow
-                    | EngineStateError::Finalization
-                    | EngineStateError::Bytesrepr(_)
-                    | EngineStateError::Mint(_)
-                    | EngineStateError::InvalidKeyVariant
-                    | EngineStateError::ProtocolUpgrade(_)
-                    | EngineStateError::CommitError(_)
-                    | EngineStateError::MissingSystemContractRegistry
-                    | EngineStateError::MissingSystemContractHash(_)
-                    | EngineStateError::RuntimeStackOverflow
-                    | EngineStateError::FailedToGetWithdrawKeys
-                    | EngineStateError::FailedToGetStoredWithdraws
-                    | EngineStateError::FailedToGetWithdrawPurses
-                    | EngineStateError::FailedToRetrieveUnbondingDelay
-                    | EngineStateError::FailedToRetrieveEraId => {
-                        Error::new(ReservedErrorCode::InternalError, error.to_string())
-                    }
-                    _ => Error::new(
-                        ReservedErrorCode::InternalError,
-                        format!("Unhandled engine state error: {}", error),
-                    ),
-                };
-                Err(rpc_error)
+                        format!("deploy {}: {}", deploy.hash(), error),
+                    ));
+                }
             }
         }
+
+        let deploy_hashes: Vec<DeployHash> = deploys.iter().map(|deploy| *deploy.hash()).collect();
+        let (execution_results, effect) = effect_builder
+            .speculative_execute_deploy_sequence(execution_prestate, deploys, dry_run)
+            .await
+            .map_err(engine_state_error_to_rpc_error)?;
+        let execution_results = deploy_hashes
+            .into_iter()
+            .zip(execution_results)
+            .map(
+                |(deploy_hash, execution_result)| SpeculativeDeployExecutionResult {
+                    deploy_hash,
+                    execution_result,
+                },
+            )
+            .collect();
+
+        Ok(Self::ResponseResult {
+            api_version,
+            block_hash,
+            execution_results,
+            effect,
+        })
+    }
+}
+
+/// Converts an error from the execution engine to an RPC error.
+fn engine_state_error_to_rpc_error(error: EngineStateError) -> Error {
+    match error {
+        EngineStateError::RootNotFound(_) => Error::new(ErrorCode::NoSuchStateRoot, ""),
+        EngineStateError::WasmPreprocessing(error) => {
+            Error::new(ErrorCode::InvalidDeploy, error.to_string())
+        }
+        EngineStateError::InvalidDeployItemVariant(error) => {
+            Error::new(ErrorCode::InvalidDeploy, error)
+        }
+        EngineStateError::InvalidProtocolVersion(_) => Error::new(
+            ErrorCode::InvalidDeploy,
+            format!("deploy used invalid protocol version {}", error),
+        ),
+        EngineStateError::Deploy => Error::new(ErrorCode::InvalidDeploy, ""),
+        EngineStateError::Genesis(_)
+        | EngineStateError::WasmSerialization(_)
+        | EngineStateError::Exec(_)
+        | EngineStateError::Storage(_)
+        | EngineStateError::Authorization
+        | EngineStateError::InsufficientPayment
+        | EngineStateError::GasConversionOverflow
+        | EngineStateError::Finalization
+        | EngineStateError::Bytesrepr(_)
+        | EngineStateError::Mint(_)
+        | EngineStateError::InvalidKeyVariant
+        | EngineStateError::ProtocolUpgrade(_)
+        | EngineStateError::CommitError(_)
+        | EngineStateError::MissingSystemContractRegistry
+        | EngineStateError::MissingSystemContractHash(_)
+        | EngineStateError::RuntimeStackOverflow
+        | EngineStateError::FailedToGetWithdrawKeys
+        | EngineStateError::FailedToGetStoredWithdraws
+        | EngineStateError::FailedToGetWithdrawPurses
+        | EngineStateError::FailedToRetrieveUnbondingDelay
+        | EngineStateError::FailedToRetrieveEraId => {
+            Error::new(ReservedErrorCode::InternalError, error.to_string())
+        }
+        _ => Error::new(
+            ReservedErrorCode::InternalError,
+            format!("Unhandled engine state error: {}", error),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use derive_more::From;
+    use rand::Rng;
+
+    use casper_types::{testing::TestRng, AccessRights};
+
+    use super::*;
+    use crate::{
+        components::rpc_server::Event as RpcServerEvent,
+        effect::requests::{
+            AcceptDeployRequest, BlockSynchronizerRequest, ChainspecRawBytesRequest,
+            ConsensusRequest, ContractRuntimeRequest, MetricsRequest, NetworkInfoRequest,
+            ReactorStatusRequest, RpcRequest, StorageRequest, UpgradeWatcherRequest,
+        },
+        reactor::{EventQueueHandle, QueueKind, Scheduler},
+        types::{BlockSignatures, BlockWithMetadata},
+        utils,
+    };
+
+    /// Event for the mock reactor.
+    #[derive(Debug, From)]
+    enum MockReactorEvent {
+        RpcServerEvent(RpcServerEvent),
+        RpcRequest(RpcRequest),
+        AcceptDeployRequest(AcceptDeployRequest),
+        ChainspecRawBytesRequest(ChainspecRawBytesRequest),
+        UpgradeWatcherRequest(UpgradeWatcherRequest),
+        ContractRuntimeRequest(ContractRuntimeRequest),
+        ConsensusRequest(ConsensusRequest),
+        MetricsRequest(MetricsRequest),
+        NetworkInfoRequest(NetworkInfoRequest),
+        StorageRequest(StorageRequest),
+        ReactorStatusRequest(ReactorStatusRequest),
+        BlockSynchronizerRequest(BlockSynchronizerRequest),
+    }
+
+    /// Handles a "speculative_exec" request with the given state overrides, answering the requests
+    /// it makes, and returns whether the account checks were skipped when accepting the deploy.
+    async fn speculative_exec_skipping_account_checks(
+        rng: &mut TestRng,
+        state_overrides: Option<StateOverrides>,
+    ) -> bool {
+        let scheduler = utils::leak(Scheduler::<MockReactorEvent>::new(
+            QueueKind::weights(),
+            None,
+        ));
+        let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+
+        let block = Block::random(rng);
+        let deploy = Deploy::random(rng);
+        let params = SpeculativeExecParams {
+            block_identifier: Some(BlockIdentifier::Hash(*block.hash())),
+            deploy: deploy.clone(),
+            state_overrides,
+            trace: false,
+        };
+        let handle = tokio::spawn(SpeculativeExec::do_handle_request(
+            effect_builder,
+            DOCS_EXAMPLE_PROTOCOL_VERSION,
+            params,
+        ));
+
+        let ((_ancestor, event), _) = scheduler.pop().await;
+        match event {
+            MockReactorEvent::StorageRequest(StorageRequest::GetBlockAndMetadataByHash {
+                block_hash,
+                responder,
+                ..
+            }) => {
+                assert_eq!(block_hash, *block.hash());
+                let block_signatures = BlockSignatures::new(*block.hash(), block.header().era_id());
+                responder
+                    .respond(Some(BlockWithMetadata {
+                        block: block.clone(),
+                        block_signatures,
+                    }))
+                    .await;
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let ((_ancestor, event), _) = scheduler.pop().await;
+        let skip_account_checks = match event {
+            MockReactorEvent::AcceptDeployRequest(AcceptDeployRequest {
+                deploy: accepted_deploy,
+                speculative_exec_at_block,
+                skip_account_checks,
+                responder,
+            }) => {
+                assert_eq!(*accepted_deploy, deploy);
+                assert_eq!(speculative_exec_at_block.as_deref(), Some(block.header()));
+                responder.respond(Ok(())).await;
+                skip_account_checks
+            }
+            other => panic!("unexpected event: {:?}", other),
+        };
+
+        let ((_ancestor, event), _) = scheduler.pop().await;
+        match event {
+            MockReactorEvent::ContractRuntimeRequest(
+                ContractRuntimeRequest::SpeculativeDeployExecution {
+                    state_overrides,
+                    responder,
+                    ..
+                },
+            ) => {
+                assert_eq!(state_overrides.is_empty(), !skip_account_checks);
+                responder
+                    .respond(Ok(Some((ExecutionResult::example().clone(), None))))
+                    .await;
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let result = handle
+            .await
+            .expect("should join")
+            .expect("should execute deploy");
+        assert_eq!(result.block_hash, *block.hash());
+        skip_account_checks
+    }
+
+    #[tokio::test]
+    async fn should_check_account_without_state_overrides() {
+        let mut rng = crate::new_rng();
+        assert!(!speculative_exec_skipping_account_checks(&mut rng, None).await);
+        assert!(
+            !speculative_exec_skipping_account_checks(&mut rng, Some(StateOverrides::default()))
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn should_skip_account_checks_with_state_overrides() {
+        let mut rng = crate::new_rng();
+        let state_overrides = StateOverrides {
+            values: vec![],
+            balances: vec![BalanceOverride {
+                purse: URef::new(rng.gen(), AccessRights::READ_ADD_WRITE),
+                balance: U512::from(rng.gen::<u64>()),
+            }],
+        };
+        assert!(speculative_exec_skipping_account_checks(&mut rng, Some(state_overrides)).await);
     }
 }
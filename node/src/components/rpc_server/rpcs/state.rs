@@ -20,8 +20,8 @@ use casper_json_rpc::ReservedErrorCode;
 use casper_types::{
     account::AccountHash,
     bytesrepr::{Bytes, ToBytes},
-    CLValue, Key, ProtocolVersion, PublicKey, SecretKey, StoredValue as DomainStoredValue, URef,
-    U512,
+    CLType, CLValue, ContractHash, ContractSchema, Key, Parameter, ProtocolVersion, PublicKey,
+    SecretKey, StoredValue as DomainStoredValue, URef, U512,
 };
 
 use crate::{
@@ -129,6 +129,29 @@ static QUERY_BALANCE_RESULT: Lazy<QueryBalanceResult> = Lazy::new(|| QueryBalanc
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     balance: U512::from(123_456),
 });
+static GET_CONTRACT_SCHEMA_PARAMS: Lazy<GetContractSchemaParams> =
+    Lazy::new(|| GetContractSchemaParams {
+        state_identifier: Some(GlobalStateIdentifier::BlockHash(
+            *Block::doc_example().hash(),
+        )),
+        contract_hash: ContractHash::new([11; 32]),
+    });
+static GET_CONTRACT_SCHEMA_RESULT: Lazy<GetContractSchemaResult> = Lazy::new(|| {
+    let mut schema = ContractSchema::new();
+    schema.add_entry_point(
+        "set_value",
+        vec![Parameter::new("value", CLType::U64)],
+        CLType::Unit,
+    );
+    schema.add_named_key("value", CLType::U64);
+    schema.add_dictionary("values", CLType::U64);
+    schema.add_event("value_set", CLType::U64);
+    GetContractSchemaResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        schema,
+        merkle_proof: MERKLE_PROOF.clone(),
+    }
+});
 
 /// Params for "state_get_item" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -401,18 +424,21 @@ impl RpcWithOptionalParams for GetAuctionInfo {
         let bids = match get_bids_result {
             Ok(GetBidsResult::Success { bids }) => bids,
             Ok(GetBidsResult::RootNotFound) => {
+                let error_message = format!(
+                    "root not found when getting bids at block {:?}",
+                    block.hash().inner()
+                );
+                if let Some(error) =
+                    common::state_pruned_error(effect_builder, &error_message).await
+                {
+                    return Err(error);
+                }
                 error!(
                     block_hash=?block.hash(),
                     ?state_root_hash,
                     "root not found while trying to get bids"
                 );
-                return Err(Error::new(
-                    ReservedErrorCode::InternalError,
-                    format!(
-                        "root not found when getting bids at block {:?}",
-                        block.hash().inner()
-                    ),
-                ));
+                return Err(Error::new(ReservedErrorCode::InternalError, error_message));
             }
             Err(error) => {
                 error!(
@@ -1004,13 +1030,16 @@ impl RpcWithParams for QueryBalance {
                     %purse_uref,
                     "query-balance failed: root not found"
                 );
-                return Err(Error::new(
-                    ErrorCode::FailedToGetBalance,
-                    format!(
-                        "root hash {} not found when querying for purse {}",
-                        state_root_hash, purse_uref
-                    ),
-                ));
+                let error_message = format!(
+                    "root hash {} not found when querying for purse {}",
+                    state_root_hash, purse_uref
+                );
+                if let Some(error) =
+                    common::state_pruned_error(effect_builder, &error_message).await
+                {
+                    return Err(error);
+                }
+                return Err(Error::new(ErrorCode::FailedToGetBalance, error_message));
             }
             Err(error) => {
                 info!("query-balance failed to execute: {}", error);
@@ -1029,6 +1058,103 @@ impl RpcWithParams for QueryBalance {
     }
 }
 
+/// Params for "state_get_contract_schema" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetContractSchemaParams {
+    /// The identifier used for the query. If none is passed
+    /// the tip of the chain will be used.
+    pub state_identifier: Option<GlobalStateIdentifier>,
+    /// The hash of the contract version whose schema is requested.
+    pub contract_hash: ContractHash,
+}
+
+impl DocExample for GetContractSchemaParams {
+    fn doc_example() -> &'static Self {
+        &GET_CONTRACT_SCHEMA_PARAMS
+    }
+}
+
+/// Result for "state_get_contract_schema" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetContractSchemaResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The schema of the contract.
+    pub schema: ContractSchema,
+    /// The Merkle proof.
+    pub merkle_proof: String,
+}
+
+impl DocExample for GetContractSchemaResult {
+    fn doc_example() -> &'static Self {
+        &GET_CONTRACT_SCHEMA_RESULT
+    }
+}
+
+/// "state_get_contract_schema" RPC.
+pub struct GetContractSchema {}
+
+#[async_trait]
+impl RpcWithParams for GetContractSchema {
+    const METHOD: &'static str = "state_get_contract_schema";
+    type RequestParams = GetContractSchemaParams;
+    type ResponseResult = GetContractSchemaResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let state_root_hash = match params.state_identifier {
+            None => match effect_builder
+                .get_highest_complete_block_header_from_storage()
+                .await
+            {
+                None => {
+                    return Err(Error::new(
+                        ErrorCode::NoSuchBlock,
+                        "get-contract-schema failed to retrieve highest block header",
+                    ))
+                }
+                Some(block_header) => *block_header.state_root_hash(),
+            },
+            Some(state_identifier) => {
+                let (state_root_hash, _) =
+                    get_state_root_hash_and_optional_header(effect_builder, state_identifier)
+                        .await?;
+                state_root_hash
+            }
+        };
+
+        let base_key = Key::contract_schema(params.contract_hash);
+        let (stored_value, merkle_proof) =
+            common::run_query_and_encode(effect_builder, state_root_hash, base_key, vec![]).await?;
+
+        let schema = match stored_value {
+            StoredValue::CLValue(cl_value) => cl_value.into_t::<ContractSchema>().ok(),
+            _ => None,
+        };
+        let schema = match schema {
+            Some(schema) => schema,
+            None => {
+                let error_msg = format!("failed to parse contract schema under {}", base_key);
+                warn!("{}", error_msg);
+                return Err(Error::new(ErrorCode::QueryFailed, error_msg));
+            }
+        };
+
+        let result = Self::ResponseResult {
+            api_version,
+            schema,
+            merkle_proof,
+        };
+        Ok(result)
+    }
+}
+
 /// Parameters for "state_get_trie" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct GetTrieParams {
@@ -1139,10 +1265,14 @@ pub(super) async fn handle_query_result<REv: ReactorEventT>(
         Ok(QueryResult::Success { value, proofs }) => Ok((*value, proofs)),
         Ok(QueryResult::RootNotFound) => {
             info!("query failed: root not found");
+            let error_message = format!("failed to get state root at {:?}", state_root_hash);
+            if let Some(error) = common::state_pruned_error(effect_builder, &error_message).await {
+                return Err(error);
+            }
             let error = common::missing_block_or_state_root_error(
                 effect_builder,
                 ErrorCode::NoSuchStateRoot,
-                format!("failed to get state root at {:?}", state_root_hash),
+                error_message,
             )
             .await;
             Err(error)
@@ -1,6 +1,8 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use super::RateLimitConfig;
+
 /// Default binding address for the speculative execution RPC HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -9,6 +11,10 @@ const DEFAULT_ADDRESS: &str = "0.0.0.0:1";
 const DEFAULT_QPS_LIMIT: u64 = 1;
 /// Default max body bytes (2.5MB).
 const DEFAULT_MAX_BODY_BYTES: u32 = 2_621_440;
+/// Default maximum number of requests in a single JSON-RPC batch.
+const DEFAULT_MAX_BATCH_SIZE: usize = 1;
+/// Default maximum number of deploys in a single speculative execution sequence.
+const DEFAULT_MAX_SEQUENCE_LENGTH: usize = 10;
 /// Default CORS origin.
 const DEFAULT_CORS_ORIGIN: &str = "";
 
@@ -23,8 +29,14 @@ pub struct Config {
     pub address: String,
     /// Maximum rate limit in queries per second.
     pub qps_limit: u64,
+    /// Per-client rate limiting.
+    pub rate_limit: RateLimitConfig,
     /// Maximum number of bytes to accept in a single request body.
     pub max_body_bytes: u32,
+    /// Maximum number of requests to accept in a single JSON-RPC batch.
+    pub max_batch_size: usize,
+    /// Maximum number of deploys to accept in a single "speculative_exec_sequence" request.
+    pub max_sequence_length: usize,
     /// CORS origin.
     pub cors_origin: String,
 }
@@ -36,7 +48,10 @@ impl Config {
             enable_server: false,
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
+            rate_limit: RateLimitConfig::default(),
             max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            max_sequence_length: DEFAULT_MAX_SEQUENCE_LENGTH,
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
         }
     }
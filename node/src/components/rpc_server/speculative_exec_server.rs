@@ -1,12 +1,17 @@
+use std::sync::Arc;
+
 use hyper::server::{conn::AddrIncoming, Builder};
 
-use casper_json_rpc::{CorsOrigin, RequestHandlersBuilder};
+use casper_json_rpc::{CorsOrigin, Error, RequestHandlersBuilder};
 use casper_types::ProtocolVersion;
 
-use super::ReactorEventT;
+use super::{rate_limiter, Metrics, RateLimiter, ReactorEventT};
 use crate::{
     effect::EffectBuilder,
-    rpcs::{speculative_exec::SpeculativeExec, RpcWithParams},
+    rpcs::{
+        speculative_exec::{SpeculativeExec, SpeculativeExecSequence},
+        ErrorCode, RpcWithParams,
+    },
 };
 
 /// The URL path for all JSON-RPC requests.
@@ -19,12 +24,23 @@ pub(super) async fn run<REv: ReactorEventT>(
     builder: Builder<AddrIncoming>,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
+    metrics: Arc<Metrics>,
     qps_limit: u64,
+    rate_limiter: Arc<RateLimiter>,
     max_body_bytes: u32,
+    max_batch_size: usize,
+    max_sequence_length: usize,
     cors_origin: String,
 ) {
     let mut handlers = RequestHandlersBuilder::new();
-    SpeculativeExec::register_as_handler(effect_builder, api_version, &mut handlers);
+    SpeculativeExec::register_as_handler(effect_builder, api_version, &metrics, &mut handlers);
+    register_speculative_exec_sequence(
+        effect_builder,
+        api_version,
+        max_sequence_length,
+        &metrics,
+        &mut handlers,
+    );
     let handlers = handlers.build();
 
     match cors_origin.as_str() {
@@ -33,7 +49,9 @@ pub(super) async fn run<REv: ReactorEventT>(
                 builder,
                 handlers,
                 qps_limit,
+                rate_limiter,
                 max_body_bytes,
+                max_batch_size,
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
             )
@@ -44,7 +62,9 @@ pub(super) async fn run<REv: ReactorEventT>(
                 builder,
                 handlers,
                 qps_limit,
+                rate_limiter,
                 max_body_bytes,
+                max_batch_size,
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
                 CorsOrigin::Any,
@@ -56,7 +76,9 @@ pub(super) async fn run<REv: ReactorEventT>(
                 builder,
                 handlers,
                 qps_limit,
+                rate_limiter,
                 max_body_bytes,
+                max_batch_size,
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
                 CorsOrigin::Specified(cors_origin),
@@ -65,3 +87,40 @@ pub(super) async fn run<REv: ReactorEventT>(
         }
     }
 }
+
+/// Registers the "speculative_exec_sequence" RPC like [`RpcWithParams::register_as_handler`], but
+/// rejecting requests with more than `max_sequence_length` deploys before any of them is checked
+/// or executed.
+fn register_speculative_exec_sequence<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    api_version: ProtocolVersion,
+    max_sequence_length: usize,
+    metrics: &Arc<Metrics>,
+    handlers_builder: &mut RequestHandlersBuilder,
+) {
+    let metrics = Arc::clone(metrics);
+    let handler = move |maybe_params| {
+        let metrics = Arc::clone(&metrics);
+        async move {
+            metrics
+                .observe_request(SpeculativeExecSequence::METHOD, async move {
+                    rate_limiter::check(SpeculativeExecSequence::METHOD)?;
+                    let params = SpeculativeExecSequence::try_parse_params(maybe_params)?;
+                    if params.deploys.len() > max_sequence_length {
+                        return Err(Error::new(
+                            ErrorCode::InvalidDeploy,
+                            format!(
+                                "too many deploys to execute: {} (maximum is {})",
+                                params.deploys.len(),
+                                max_sequence_length
+                            ),
+                        ));
+                    }
+                    SpeculativeExecSequence::do_handle_request(effect_builder, api_version, params)
+                        .await
+                })
+                .await
+        }
+    };
+    handlers_builder.register_handler(SpeculativeExecSequence::METHOD, Arc::new(handler))
+}
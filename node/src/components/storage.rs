@@ -32,11 +32,13 @@
 
 pub(crate) mod disjoint_sequences;
 mod error;
+mod inspect;
 mod lmdb_ext;
 mod metrics;
 mod object_pool;
 #[cfg(test)]
 mod tests;
+mod transfer_index;
 
 #[cfg(test)]
 use std::collections::BTreeSet;
@@ -70,7 +72,8 @@ use tracing::{debug, error, info, trace, warn};
 
 use casper_hashing::Digest;
 use casper_types::{
-    bytesrepr::{FromBytes, ToBytes},
+    account::AccountHash,
+    bytesrepr::{self, FromBytes, ToBytes, U64_SERIALIZED_LENGTH},
     EraId, ExecutionResult, ProtocolVersion, PublicKey, Timestamp, Transfer, Transform,
 };
 
@@ -103,9 +106,11 @@ use crate::{
 use disjoint_sequences::{DisjointSequences, Sequence};
 pub use error::FatalStorageError;
 use error::GetRequestError;
+pub(crate) use inspect::{DatabaseStats, IntegrityReport};
 use lmdb_ext::{BytesreprError, LmdbExtError, TransactionExt, WriteTransactionExt};
 use metrics::Metrics;
 use object_pool::ObjectPool;
+pub(crate) use transfer_index::TransferParty;
 
 const COMPONENT_NAME: &str = "storage";
 
@@ -128,11 +133,16 @@ const DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE: usize = 300 * GIB;
 /// Default max state store size.
 const DEFAULT_MAX_STATE_STORE_SIZE: usize = 10 * GIB;
 /// Maximum number of allowed dbs.
-const MAX_DB_COUNT: u32 = 9;
+const MAX_DB_COUNT: u32 = 11;
 /// Key under which completed blocks are to be stored.
 const COMPLETED_BLOCKS_STORAGE_KEY: &[u8] = b"completed_blocks_disjoint_sequences";
+/// Key under which the height below which global state has been pruned is to be stored.
+const STATE_PRUNED_BELOW_HEIGHT_STORAGE_KEY: &[u8] = b"state_pruned_below_height";
 /// Name of the file created when initializing a force resync.
 const FORCE_RESYNC_FILE_NAME: &str = "force_resync";
+/// Length of a value in the account deploys database: a big-endian block height followed by a
+/// deploy hash.
+const ACCOUNT_DEPLOYS_VALUE_LENGTH: usize = U64_SERIALIZED_LENGTH + Digest::LENGTH;
 
 /// OS-specific lmdb flags.
 #[cfg(not(target_os = "macos"))]
@@ -191,6 +201,15 @@ pub struct Storage {
     /// The finalized approvals database.
     #[data_size(skip)]
     finalized_approvals_db: Database,
+    /// The account deploys database, mapping account hashes to the heights and hashes of the
+    /// executed deploys sent by the account.  `None` if the index is disabled.
+    #[data_size(skip)]
+    account_deploys_db: Option<Database>,
+    /// The transfer index database, mapping the accounts and purses which took part in transfers
+    /// to the transfers and the heights of the blocks containing them.  `None` if the index is
+    /// disabled.
+    #[data_size(skip)]
+    transfer_index_db: Option<Database>,
     /// A map of block height to block ID.
     block_height_index: BTreeMap<u64, BlockHash>,
     /// A map of era ID to switch block ID.
@@ -199,6 +218,9 @@ pub struct Storage {
     deploy_hash_index: BTreeMap<DeployHash, BlockHashAndHeight>,
     /// Runs of completed blocks known in storage.
     completed_blocks: DisjointSequences,
+    /// The height below which the global state of blocks has been pruned by the contract runtime,
+    /// if any.
+    state_pruned_below_height: Option<u64>,
     /// The activation point era of the current protocol version.
     activation_era: EraId,
     /// The height of the final switch block of the previous protocol version.
@@ -416,6 +438,19 @@ impl Storage {
         let block_body_db = env.create_db(Some("block_body"), DatabaseFlags::empty())?;
         let approvals_hashes_db =
             env.create_db(Some("approvals_hashes"), DatabaseFlags::empty())?;
+        let account_deploys_db = if config.enable_account_deploys_index {
+            Some(env.create_db(
+                Some("account_deploys"),
+                DatabaseFlags::DUP_SORT | DatabaseFlags::DUP_FIXED,
+            )?)
+        } else {
+            None
+        };
+        let transfer_index_db = if config.enable_transfer_index {
+            Some(env.create_db(Some("transfer_index"), DatabaseFlags::DUP_SORT)?)
+        } else {
+            None
+        };
 
         // We now need to restore the block-height index. Log messages allow timing here.
         info!("indexing block store");
@@ -492,6 +527,26 @@ impl Storage {
         initialize_block_metadata_db(&env, &block_metadata_db, &deleted_block_hashes_raw)?;
         initialize_deploy_metadata_db(&env, &deploy_metadata_db, &deleted_deploy_hashes)?;
 
+        if let Some(account_deploys_db) = account_deploys_db {
+            initialize_account_deploys_db(
+                &env,
+                &account_deploys_db,
+                &block_header_db,
+                &block_body_db,
+                &deploy_db,
+                &block_height_index,
+            )?;
+        }
+
+        if let Some(transfer_index_db) = transfer_index_db {
+            initialize_transfer_index_db(
+                &env,
+                &transfer_index_db,
+                &transfer_db,
+                &block_height_index,
+            )?;
+        }
+
         let metrics = registry.map(Metrics::new).transpose()?;
 
         let mut component = Self {
@@ -506,10 +561,13 @@ impl Storage {
             transfer_db,
             state_store_db,
             finalized_approvals_db,
+            account_deploys_db,
+            transfer_index_db,
             block_height_index,
             switch_block_era_id_index,
             deploy_hash_index,
             completed_blocks: Default::default(),
+            state_pruned_below_height: None,
             activation_era,
             key_block_height_for_activation_point: None,
             enable_mem_deduplication: config.enable_mem_deduplication,
@@ -518,6 +576,7 @@ impl Storage {
             max_ttl,
             metrics,
         };
+        component.state_pruned_below_height = component.read_state_pruned_below_height()?;
 
         if force_resync {
             let force_resync_file_path = component.root_path().join(FORCE_RESYNC_FILE_NAME);
@@ -599,6 +658,122 @@ impl Storage {
         Ok(component)
     }
 
+    /// Opens an existing storage read-only, e.g. for inspection while the node is not running.
+    ///
+    /// Unlike [`Storage::new`], no data is migrated, purged or reindexed on disk, and the optional
+    /// indices are only available if they were already created by a node with them enabled.
+    pub(crate) fn open_read_only(
+        cfg: &WithDir<Config>,
+        activation_era: EraId,
+        network_name: &str,
+        max_ttl: MaxTtl,
+        recent_era_count: u64,
+    ) -> Result<Self, FatalStorageError> {
+        let config = cfg.value();
+        let root = cfg.with_dir(config.path.clone()).join(network_name);
+
+        let total_size = config
+            .max_block_store_size
+            .saturating_add(config.max_deploy_store_size)
+            .saturating_add(config.max_deploy_metadata_store_size);
+
+        let env = Environment::new()
+            .set_flags(
+                EnvironmentFlags::READ_ONLY
+                    | EnvironmentFlags::NO_SUB_DIR
+                    | EnvironmentFlags::NO_TLS
+                    | EnvironmentFlags::NO_READAHEAD,
+            )
+            .set_max_readers(MAX_TRANSACTIONS)
+            .set_max_dbs(MAX_DB_COUNT)
+            .set_map_size(total_size)
+            .open(&root.join(STORAGE_DB_FILENAME))?;
+
+        let block_header_db = env.open_db(Some("block_header"))?;
+        let block_metadata_db = env.open_db(Some("block_metadata"))?;
+        let deploy_db = env.open_db(Some("deploys"))?;
+        let deploy_metadata_db = env.open_db(Some("deploy_metadata"))?;
+        let transfer_db = env.open_db(Some("transfer"))?;
+        let state_store_db = env.open_db(Some("state_store"))?;
+        let finalized_approvals_db = env.open_db(Some("finalized_approvals"))?;
+        let block_body_db = env.open_db(Some("block_body"))?;
+        let approvals_hashes_db = env.open_db(Some("approvals_hashes"))?;
+        let account_deploys_db = open_optional_db(&env, "account_deploys")?;
+        let transfer_index_db = open_optional_db(&env, "transfer_index")?;
+
+        let mut block_height_index = BTreeMap::new();
+        let mut switch_block_era_id_index = BTreeMap::new();
+        let mut deploy_hash_index = BTreeMap::new();
+        let txn = env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(block_header_db)?;
+        for row in cursor.iter() {
+            let (_, raw_val) = row?;
+            let block_header: BlockHeader = lmdb_ext::deserialize(raw_val)?;
+            insert_to_block_header_indices(
+                &mut block_height_index,
+                &mut switch_block_era_id_index,
+                &block_header,
+            )?;
+
+            // Look up the body in the transaction the cursor is iterating over.
+            let maybe_raw_body = match txn.get(block_body_db, block_header.body_hash()) {
+                Ok(raw_body) => Some(raw_body),
+                Err(lmdb::Error::NotFound) => None,
+                Err(error) => return Err(error.into()),
+            };
+            if let Some(raw_body) = maybe_raw_body {
+                let block_body: BlockBody = lmdb_ext::deserialize(raw_body)?;
+                insert_to_deploy_index(
+                    &mut deploy_hash_index,
+                    block_header.block_hash(),
+                    &block_body,
+                    block_header.height(),
+                )?;
+            }
+        }
+        drop(cursor);
+        txn.commit()?;
+
+        let mut component = Self {
+            root,
+            env: Rc::new(env),
+            block_header_db,
+            block_body_db,
+            block_metadata_db,
+            approvals_hashes_db,
+            deploy_db,
+            deploy_metadata_db,
+            transfer_db,
+            state_store_db,
+            finalized_approvals_db,
+            account_deploys_db,
+            transfer_index_db,
+            block_height_index,
+            switch_block_era_id_index,
+            deploy_hash_index,
+            completed_blocks: Default::default(),
+            state_pruned_below_height: None,
+            activation_era,
+            key_block_height_for_activation_point: None,
+            enable_mem_deduplication: false,
+            serialized_item_pool: ObjectPool::new(config.mem_pool_prune_interval),
+            recent_era_count,
+            max_ttl,
+            metrics: None,
+        };
+
+        if let Some(raw) =
+            component.read_state_store(&Cow::Borrowed(COMPLETED_BLOCKS_STORAGE_KEY))?
+        {
+            let (sequences, _) = DisjointSequences::from_vec(raw)
+                .map_err(FatalStorageError::UnexpectedDeserializationFailure)?;
+            component.completed_blocks = sequences;
+        }
+        component.state_pruned_below_height = component.read_state_pruned_below_height()?;
+
+        Ok(component)
+    }
+
     /// Reads from the state storage database.
     ///
     /// If key is non-empty, returns bytes from under the key. Otherwise returns `Ok(None)`.
@@ -1154,6 +1329,41 @@ impl Storage {
                     .read_block_header_by_height(block_height, only_from_available_block_range)?;
                 responder.respond(maybe_header).ignore()
             }
+            StorageRequest::GetBlocksByHeightRange {
+                start_height,
+                end_height,
+                include_bodies,
+                responder,
+            } => {
+                let blocks =
+                    self.read_blocks_by_height_range(start_height, end_height, include_bodies)?;
+                responder.respond(blocks).ignore()
+            }
+            StorageRequest::GetSwitchBlockHeadersByEraRange {
+                start_era_id,
+                end_era_id,
+                responder,
+            } => {
+                let block_headers =
+                    self.read_switch_block_headers_by_era_range(start_era_id, end_era_id)?;
+                responder.respond(block_headers).ignore()
+            }
+            StorageRequest::GetAccountDeploys {
+                account_hash,
+                offset,
+                limit,
+                responder,
+            } => responder
+                .respond(self.read_account_deploys(account_hash, offset, limit)?)
+                .ignore(),
+            StorageRequest::GetTransfersByParty {
+                party,
+                offset,
+                limit,
+                responder,
+            } => responder
+                .respond(self.read_transfers_by_party(party, offset, limit)?)
+                .ignore(),
             StorageRequest::PutBlockHeader {
                 block_header,
                 responder,
@@ -1167,8 +1377,21 @@ impl Storage {
                     }
                 }
             }
-            StorageRequest::GetAvailableBlockRange { responder } => {
-                responder.respond(self.get_available_block_range()).ignore()
+            StorageRequest::GetAvailableBlockRange { responder } => responder
+                .respond(self.get_available_block_range_with_state())
+                .ignore(),
+            StorageRequest::GetStateRootHashesSinceSwitchBlock { era_id, responder } => responder
+                .respond(self.read_state_root_hashes_since_switch_block(era_id)?)
+                .ignore(),
+            StorageRequest::PutStatePrunedBelowHeight {
+                block_height,
+                responder,
+            } => {
+                self.put_state_pruned_below_height(block_height)?;
+                responder.respond(()).ignore()
+            }
+            StorageRequest::GetStatePrunedBelowHeight { responder } => {
+                responder.respond(self.state_pruned_below_height).ignore()
             }
             StorageRequest::StoreFinalizedApprovals {
                 ref deploy_hash,
@@ -1213,9 +1436,51 @@ impl Storage {
         }: MarkBlockCompletedRequest,
     ) -> Result<Effects<Event>, FatalStorageError> {
         let is_new = self.mark_block_complete(block_height)?;
+        if is_new {
+            self.index_account_deploys(block_height)?;
+        }
         Ok(responder.respond(is_new).ignore())
     }
 
+    /// Inserts the deploys of the stored block at height `block_height` into the account deploys
+    /// index, if it is enabled.
+    ///
+    /// Historically synced blocks are stored before their deploys, so unlike executed blocks they
+    /// can only be indexed once they are complete.
+    fn index_account_deploys(&self, block_height: u64) -> Result<(), FatalStorageError> {
+        let account_deploys_db = match self.account_deploys_db {
+            Some(account_deploys_db) => account_deploys_db,
+            None => return Ok(()),
+        };
+        let block_hash = match self.block_height_index.get(&block_height) {
+            Some(block_hash) => *block_hash,
+            None => {
+                debug!(
+                    block_height,
+                    "not indexing deploys by account as block is not stored"
+                );
+                return Ok(());
+            }
+        };
+        let mut txn = self.env.begin_rw_txn()?;
+        let block = match self.get_single_block(&mut txn, &block_hash)? {
+            Some(block) => block,
+            None => {
+                debug!(%block_hash, "not indexing deploys by account as block is not stored");
+                return Ok(());
+            }
+        };
+        insert_to_account_deploys_db(
+            &mut txn,
+            account_deploys_db,
+            self.deploy_db,
+            block_height,
+            block.body(),
+        )?;
+        txn.commit()?;
+        Ok(())
+    }
+
     /// Marks the block at height `block_height` as complete by inserting it
     /// into the `completed_blocks` index and storing it to disk.
     fn mark_block_complete(&mut self, block_height: u64) -> Result<bool, FatalStorageError> {
@@ -1246,6 +1511,38 @@ impl Storage {
         self.write_state_store(Cow::Borrowed(COMPLETED_BLOCKS_STORAGE_KEY), &serialized)
     }
 
+    /// Reads the height below which global state has been pruned from the database.
+    fn read_state_pruned_below_height(&self) -> Result<Option<u64>, FatalStorageError> {
+        self.read_state_store(&Cow::Borrowed(STATE_PRUNED_BELOW_HEIGHT_STORAGE_KEY))?
+            .map(bytesrepr::deserialize::<u64>)
+            .transpose()
+            .map_err(FatalStorageError::UnexpectedDeserializationFailure)
+    }
+
+    /// Records that the global state of all blocks below `block_height` has been pruned.
+    ///
+    /// The recorded height never decreases.
+    fn put_state_pruned_below_height(
+        &mut self,
+        block_height: u64,
+    ) -> Result<(), FatalStorageError> {
+        if self
+            .state_pruned_below_height
+            .map_or(false, |current_height| current_height >= block_height)
+        {
+            return Ok(());
+        }
+        let serialized = block_height
+            .to_bytes()
+            .map_err(FatalStorageError::UnexpectedSerializationFailure)?;
+        self.write_state_store(
+            Cow::Borrowed(STATE_PRUNED_BELOW_HEIGHT_STORAGE_KEY),
+            &serialized,
+        )?;
+        self.state_pruned_below_height = Some(block_height);
+        Ok(())
+    }
+
     /// Put a single deploy into storage.
     pub fn put_deploy(&self, deploy: &Deploy) -> Result<bool, FatalStorageError> {
         let mut txn = self.env.begin_rw_txn()?;
@@ -1275,6 +1572,15 @@ impl Storage {
 
         let _ = self.write_approvals_hashes(&mut txn, approvals_hashes)?;
         let _ = self.write_execution_results(&mut txn, block.hash(), execution_results)?;
+        if let Some(account_deploys_db) = self.account_deploys_db {
+            insert_to_account_deploys_db(
+                &mut txn,
+                account_deploys_db,
+                self.deploy_db,
+                block.height(),
+                block.body(),
+            )?;
+        }
         txn.commit()?;
 
         Ok(true)
@@ -1509,6 +1815,18 @@ impl Storage {
             }
         }
 
+        if let Some(transfer_index_db) = self.transfer_index_db {
+            match self.get_single_block_header(txn, block_hash)? {
+                Some(block_header) => insert_to_transfer_index_db(
+                    txn,
+                    transfer_index_db,
+                    block_header.height(),
+                    &transfers,
+                )?,
+                None => debug!(%block_hash, "not indexing transfers as block header is not stored"),
+            }
+        }
+
         let was_written = txn.put_value(self.transfer_db, block_hash, &transfers, true)?;
         if !was_written {
             error!(?block_hash, "failed to write transfers");
@@ -1689,6 +2007,190 @@ impl Storage {
         res
     }
 
+    /// Retrieves the block headers, and optionally the block bodies, of all blocks in the given
+    /// inclusive range of heights which are also within the available block range.
+    ///
+    /// The results are ordered by height.  Heights for which the block header (or the block body,
+    /// if requested) is not stored are omitted.
+    pub fn read_blocks_by_height_range(
+        &self,
+        start_height: u64,
+        end_height: u64,
+        include_bodies: bool,
+    ) -> Result<Vec<(BlockHeader, Option<BlockBody>)>, FatalStorageError> {
+        let available_block_range = self.get_available_block_range();
+        let start_height = start_height.max(available_block_range.low());
+        let end_height = end_height.min(available_block_range.high());
+        if start_height > end_height {
+            return Ok(vec![]);
+        }
+
+        let mut txn = self.env.begin_ro_txn()?;
+        let mut blocks = vec![];
+        for (_, block_hash) in self.block_height_index.range(start_height..=end_height) {
+            let block_header = match self.get_single_block_header(&mut txn, block_hash)? {
+                Some(block_header) => block_header,
+                None => {
+                    debug!(
+                        ?block_hash,
+                        "read_blocks_by_height_range: missing block header for {}", block_hash
+                    );
+                    continue;
+                }
+            };
+            let maybe_block_body = if include_bodies {
+                match get_body_for_block_header(
+                    &mut txn,
+                    block_header.body_hash(),
+                    self.block_body_db,
+                )? {
+                    Some(block_body) => Some(block_body),
+                    None => {
+                        debug!(
+                            ?block_header,
+                            "read_blocks_by_height_range: missing block body for {}",
+                            block_header.block_hash()
+                        );
+                        continue;
+                    }
+                }
+            } else {
+                None
+            };
+            blocks.push((block_header, maybe_block_body));
+        }
+        Ok(blocks)
+    }
+
+    /// Retrieves the headers of the switch blocks of all eras in the given inclusive range of era
+    /// IDs which are also within the available block range.
+    ///
+    /// The results are ordered by era ID.  Eras for which the switch block is not stored or not
+    /// available are omitted.
+    pub fn read_switch_block_headers_by_era_range(
+        &self,
+        start_era_id: EraId,
+        end_era_id: EraId,
+    ) -> Result<Vec<BlockHeader>, FatalStorageError> {
+        if start_era_id > end_era_id {
+            return Ok(vec![]);
+        }
+
+        let available_block_range = self.get_available_block_range();
+        let mut txn = self.env.begin_ro_txn()?;
+        let mut block_headers = vec![];
+        for (_, block_hash) in self
+            .switch_block_era_id_index
+            .range(start_era_id..=end_era_id)
+        {
+            let block_header = match self.get_single_block_header(&mut txn, block_hash)? {
+                Some(block_header) => block_header,
+                None => {
+                    debug!(
+                        ?block_hash,
+                        "read_switch_block_headers_by_era_range: missing block header for {}",
+                        block_hash
+                    );
+                    continue;
+                }
+            };
+            if available_block_range.contains(block_header.height()) {
+                block_headers.push(block_header);
+            }
+        }
+        Ok(block_headers)
+    }
+
+    /// Retrieves the hashes of executed deploys sent by the given account, along with the hashes
+    /// and heights of the blocks containing them, skipping the first `offset` and returning at
+    /// most `limit` entries.
+    ///
+    /// The results are ordered by block height.  Returns `None` if the account deploys index is
+    /// disabled.
+    pub fn read_account_deploys(
+        &self,
+        account_hash: AccountHash,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Option<Vec<(DeployHash, BlockHashAndHeight)>>, FatalStorageError> {
+        let account_deploys_db = match self.account_deploys_db {
+            Some(account_deploys_db) => account_deploys_db,
+            None => return Ok(None),
+        };
+
+        let txn = self.env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(account_deploys_db)?;
+        let mut account_deploys = vec![];
+        for row in cursor
+            .iter_dup_of(&account_hash)
+            .skip(offset as usize)
+            .take(limit as usize)
+        {
+            let (_, raw_val) = row?;
+            let (block_height, deploy_hash) = decode_account_deploys_value(raw_val)?;
+            match self.block_height_index.get(&block_height) {
+                Some(block_hash) => account_deploys.push((
+                    deploy_hash,
+                    BlockHashAndHeight::new(*block_hash, block_height),
+                )),
+                None => {
+                    debug!(
+                        %deploy_hash,
+                        "read_account_deploys: missing block at height {}", block_height
+                    );
+                }
+            }
+        }
+        drop(cursor);
+        txn.commit()?;
+
+        Ok(Some(account_deploys))
+    }
+
+    /// Retrieves the transfers in which the given account or purse took part, along with the
+    /// hashes and heights of the blocks containing them, skipping the first `offset` and returning
+    /// at most `limit` entries.
+    ///
+    /// The results are ordered by block height.  Returns `None` if the transfer index is disabled.
+    pub(crate) fn read_transfers_by_party(
+        &self,
+        party: TransferParty,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Option<Vec<(Transfer, BlockHashAndHeight)>>, FatalStorageError> {
+        let transfer_index_db = match self.transfer_index_db {
+            Some(transfer_index_db) => transfer_index_db,
+            None => return Ok(None),
+        };
+
+        let txn = self.env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(transfer_index_db)?;
+        let mut transfers = vec![];
+        for row in cursor
+            .iter_dup_of(&party.to_key())
+            .skip(offset as usize)
+            .take(limit as usize)
+        {
+            let (_, raw_val) = row?;
+            let (block_height, transfer) = transfer_index::decode_value(raw_val)?;
+            match self.block_height_index.get(&block_height) {
+                Some(block_hash) => {
+                    transfers.push((transfer, BlockHashAndHeight::new(*block_hash, block_height)))
+                }
+                None => {
+                    debug!(
+                        %party,
+                        "read_transfers_by_party: missing block at height {}", block_height
+                    );
+                }
+            }
+        }
+        drop(cursor);
+        txn.commit()?;
+
+        Ok(Some(transfers))
+    }
+
     /// Retrieves a single block header by hash.
     pub fn read_block_header(
         &self,
@@ -2463,6 +2965,51 @@ impl Storage {
         }
     }
 
+    /// Returns the height range of fully available blocks whose global state has not been pruned.
+    pub(crate) fn get_available_block_range_with_state(&self) -> AvailableBlockRange {
+        let available_block_range = self.get_available_block_range();
+        match self.state_pruned_below_height {
+            Some(pruned_below_height) if pruned_below_height > available_block_range.low() => {
+                AvailableBlockRange::new(
+                    pruned_below_height.min(available_block_range.high()),
+                    available_block_range.high(),
+                )
+            }
+            _ => available_block_range,
+        }
+    }
+
+    /// Retrieves the height of the switch block of the given era, along with the state root hashes
+    /// of it and all stored blocks above it.
+    ///
+    /// Returns `None` if the switch block is not stored.
+    fn read_state_root_hashes_since_switch_block(
+        &self,
+        era_id: EraId,
+    ) -> Result<Option<(u64, Vec<Digest>)>, FatalStorageError> {
+        let switch_block_hash = match self.switch_block_era_id_index.get(&era_id) {
+            Some(block_hash) => block_hash,
+            None => return Ok(None),
+        };
+        let mut txn = self.env.begin_ro_txn()?;
+        let switch_block_height = match self.get_single_block_header(&mut txn, switch_block_hash)? {
+            Some(block_header) => block_header.height(),
+            None => return Ok(None),
+        };
+        let mut state_root_hashes = vec![];
+        for block_hash in self
+            .block_height_index
+            .range(switch_block_height..)
+            .map(|(_, block_hash)| block_hash)
+        {
+            if let Some(block_header) = self.get_single_block_header(&mut txn, block_hash)? {
+                state_root_hashes.push(*block_header.state_root_hash());
+            }
+        }
+        txn.commit()?;
+        Ok(Some((switch_block_height, state_root_hashes)))
+    }
+
     pub(crate) fn get_highest_orphaned_block_header(&self) -> HighestOrphanedBlockResult {
         match self.completed_blocks.highest_sequence() {
             None => HighestOrphanedBlockResult::MissingHighestSequence,
@@ -2786,6 +3333,15 @@ pub struct Config {
     pub enable_mem_deduplication: bool,
     /// How many loads before memory duplication checks for dead references.
     pub mem_pool_prune_interval: u16,
+    /// Whether or not to maintain an index of the executed deploys sent by each account.
+    ///
+    /// Enabling the index requires all stored deploys to be read when the node starts.
+    pub enable_account_deploys_index: bool,
+    /// Whether or not to maintain an index of the transfers by the accounts and purses which took
+    /// part in them.
+    ///
+    /// Enabling the index requires all stored transfers to be read when the node starts.
+    pub enable_transfer_index: bool,
 }
 
 impl Default for Config {
@@ -2799,6 +3355,8 @@ impl Default for Config {
             max_state_store_size: DEFAULT_MAX_STATE_STORE_SIZE,
             enable_mem_deduplication: true,
             mem_pool_prune_interval: 4096,
+            enable_account_deploys_index: false,
+            enable_transfer_index: false,
         }
     }
 }
@@ -2975,6 +3533,15 @@ fn get_body_for_block_header<Tx: Transaction>(
     txn.get_value(block_body_db, block_body_hash)
 }
 
+/// Opens the named database if it exists, returning `None` otherwise.
+fn open_optional_db(env: &Environment, name: &str) -> Result<Option<Database>, FatalStorageError> {
+    match env.open_db(Some(name)) {
+        Ok(db) => Ok(Some(db)),
+        Err(lmdb::Error::NotFound) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
 /// Purges stale entries from the block metadata database.
 fn initialize_block_metadata_db(
     env: &Environment,
@@ -3019,3 +3586,148 @@ fn initialize_deploy_metadata_db(
     info!("deploy metadata database initialized");
     Ok(())
 }
+
+/// Rebuilds the account deploys database from the stored blocks and deploys.
+fn initialize_account_deploys_db(
+    env: &Environment,
+    account_deploys_db: &Database,
+    block_header_db: &Database,
+    block_body_db: &Database,
+    deploy_db: &Database,
+    block_height_index: &BTreeMap<u64, BlockHash>,
+) -> Result<(), FatalStorageError> {
+    info!("indexing account deploys");
+    let mut txn = env.begin_rw_txn()?;
+    txn.clear_db(*account_deploys_db)?;
+
+    for (block_height, block_hash) in block_height_index {
+        let maybe_block_header: Option<BlockHeader> =
+            txn.get_value(*block_header_db, block_hash)?;
+        let block_body = match maybe_block_header {
+            Some(block_header) => {
+                get_body_for_block_header(&mut txn, block_header.body_hash(), *block_body_db)?
+            }
+            None => None,
+        };
+        if let Some(block_body) = block_body {
+            insert_to_account_deploys_db(
+                &mut txn,
+                *account_deploys_db,
+                *deploy_db,
+                *block_height,
+                &block_body,
+            )?;
+        }
+    }
+
+    txn.commit()?;
+    info!("account deploys indexing complete");
+    Ok(())
+}
+
+/// Inserts the deploys and transfers of the given block into the account deploys database, keyed
+/// by the account hash of the deploy's sender.
+///
+/// Deploys which are not stored are skipped.
+fn insert_to_account_deploys_db(
+    txn: &mut RwTransaction,
+    account_deploys_db: Database,
+    deploy_db: Database,
+    block_height: u64,
+    block_body: &BlockBody,
+) -> Result<(), FatalStorageError> {
+    for deploy_hash in block_body.deploy_and_transfer_hashes() {
+        let deploy: Deploy = match txn.get_value(deploy_db, deploy_hash)? {
+            Some(deploy) => deploy,
+            None => {
+                debug!(%deploy_hash, "not indexing deploy by account as it is not stored");
+                continue;
+            }
+        };
+        let account_hash = deploy.header().account().to_account_hash();
+        txn.put(
+            account_deploys_db,
+            &account_hash,
+            &encode_account_deploys_value(block_height, deploy_hash),
+            WriteFlags::empty(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Rebuilds the transfer index database from the stored transfers.
+fn initialize_transfer_index_db(
+    env: &Environment,
+    transfer_index_db: &Database,
+    transfer_db: &Database,
+    block_height_index: &BTreeMap<u64, BlockHash>,
+) -> Result<(), FatalStorageError> {
+    info!("indexing transfers");
+    let mut txn = env.begin_rw_txn()?;
+    txn.clear_db(*transfer_index_db)?;
+
+    for (block_height, block_hash) in block_height_index {
+        let maybe_transfers: Option<Vec<Transfer>> = txn.get_value(*transfer_db, block_hash)?;
+        if let Some(transfers) = maybe_transfers {
+            insert_to_transfer_index_db(&mut txn, *transfer_index_db, *block_height, &transfers)?;
+        }
+    }
+
+    txn.commit()?;
+    info!("transfer indexing complete");
+    Ok(())
+}
+
+/// Inserts the given transfers executed in the block at the given height into the transfer index
+/// database, keyed by each account and purse which took part in them.
+fn insert_to_transfer_index_db(
+    txn: &mut RwTransaction,
+    transfer_index_db: Database,
+    block_height: u64,
+    transfers: &[Transfer],
+) -> Result<(), FatalStorageError> {
+    for transfer in transfers {
+        let value = transfer_index::encode_value(block_height, transfer)?;
+        for party in TransferParty::all_for(transfer) {
+            txn.put(
+                transfer_index_db,
+                &party.to_key(),
+                &value,
+                WriteFlags::empty(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Encodes a value of the account deploys database.
+///
+/// The block height is encoded big-endian so that the duplicate values of an account are sorted by
+/// block height.
+fn encode_account_deploys_value(
+    block_height: u64,
+    deploy_hash: &DeployHash,
+) -> [u8; ACCOUNT_DEPLOYS_VALUE_LENGTH] {
+    let mut value = [0; ACCOUNT_DEPLOYS_VALUE_LENGTH];
+    value[..U64_SERIALIZED_LENGTH].copy_from_slice(&block_height.to_be_bytes());
+    value[U64_SERIALIZED_LENGTH..].copy_from_slice(deploy_hash.inner().as_ref());
+    value
+}
+
+/// Decodes a value of the account deploys database.
+fn decode_account_deploys_value(raw_val: &[u8]) -> Result<(u64, DeployHash), LmdbExtError> {
+    if raw_val.len() != ACCOUNT_DEPLOYS_VALUE_LENGTH {
+        return Err(LmdbExtError::DataCorrupted(Box::new(BytesreprError(
+            bytesrepr::Error::Formatting,
+        ))));
+    }
+    let (raw_height, raw_deploy_hash) = raw_val.split_at(U64_SERIALIZED_LENGTH);
+    let mut height_bytes = [0; U64_SERIALIZED_LENGTH];
+    height_bytes.copy_from_slice(raw_height);
+    let deploy_hash = Digest::try_from(raw_deploy_hash)
+        .map_err(|err| LmdbExtError::DataCorrupted(Box::new(err)))?;
+    Ok((
+        u64::from_be_bytes(height_bytes),
+        DeployHash::new(deploy_hash),
+    ))
+}
@@ -0,0 +1,254 @@
+//! Offline inspection of the storage databases.
+//!
+//! These functions are intended to be used on a storage opened via [`Storage::open_read_only`]
+//! while the node is not running, e.g. by the `casper-node db` subcommand.
+
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+};
+
+use lmdb::{Cursor, Transaction};
+
+use casper_hashing::Digest;
+
+use super::{
+    disjoint_sequences::Sequence,
+    lmdb_ext::{self, LmdbExtError, TransactionExt},
+    FatalStorageError, Storage,
+};
+use crate::types::{ApprovalsHashes, BlockBody, BlockHash, BlockHeader};
+
+/// Statistics of a single named database.
+#[derive(Clone, Debug)]
+pub(crate) struct DatabaseStats {
+    /// The name of the database.
+    pub(crate) name: &'static str,
+    /// The number of entries in the database.
+    pub(crate) entries: usize,
+    /// The number of bytes used by the pages of the database.
+    pub(crate) size_bytes: usize,
+}
+
+impl Display for DatabaseStats {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{}: {} entries, {} bytes",
+            self.name, self.entries, self.size_bytes
+        )
+    }
+}
+
+/// The outcome of checking the consistency of the block header, block body and approvals hashes
+/// databases.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct IntegrityReport {
+    /// The number of block headers checked.
+    pub(crate) block_headers: usize,
+    /// The number of block bodies checked.
+    pub(crate) block_bodies: usize,
+    /// The number of approvals hashes checked.
+    pub(crate) approvals_hashes: usize,
+    /// Blocks whose header is stored, but not their body.
+    pub(crate) missing_bodies: Vec<BlockHash>,
+    /// Blocks whose header is stored, but not their approvals hashes.
+    pub(crate) missing_approvals_hashes: Vec<BlockHash>,
+    /// Blocks whose approvals hashes don't match the number of deploys in their body.
+    pub(crate) mismatched_approvals_hashes: Vec<BlockHash>,
+    /// Hashes of block bodies not referenced by any stored block header.
+    pub(crate) orphaned_bodies: Vec<Digest>,
+    /// Blocks whose approvals hashes are stored, but not their header.
+    pub(crate) orphaned_approvals_hashes: Vec<BlockHash>,
+    /// Blocks outside the available block range whose header is stored, but not their body.
+    ///
+    /// This is expected e.g. for headers fetched during a sync leap, so it is not considered an
+    /// inconsistency.
+    pub(crate) headers_only: Vec<BlockHash>,
+}
+
+impl IntegrityReport {
+    /// Returns `true` if no inconsistencies were found.
+    pub(crate) fn is_ok(&self) -> bool {
+        self.missing_bodies.is_empty()
+            && self.missing_approvals_hashes.is_empty()
+            && self.mismatched_approvals_hashes.is_empty()
+            && self.orphaned_bodies.is_empty()
+            && self.orphaned_approvals_hashes.is_empty()
+    }
+}
+
+impl Display for IntegrityReport {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            formatter,
+            "checked {} block headers, {} block bodies and {} approvals hashes",
+            self.block_headers, self.block_bodies, self.approvals_hashes
+        )?;
+        for block_hash in &self.missing_bodies {
+            writeln!(formatter, "missing body for block {}", block_hash)?;
+        }
+        for block_hash in &self.missing_approvals_hashes {
+            writeln!(
+                formatter,
+                "missing approvals hashes for block {}",
+                block_hash
+            )?;
+        }
+        for block_hash in &self.mismatched_approvals_hashes {
+            writeln!(
+                formatter,
+                "approvals hashes don't match the deploys of block {}",
+                block_hash
+            )?;
+        }
+        for body_hash in &self.orphaned_bodies {
+            writeln!(formatter, "orphaned block body {}", body_hash)?;
+        }
+        for block_hash in &self.orphaned_approvals_hashes {
+            writeln!(
+                formatter,
+                "orphaned approvals hashes for block {}",
+                block_hash
+            )?;
+        }
+        if !self.headers_only.is_empty() {
+            writeln!(
+                formatter,
+                "{} block headers without body outside the available block range",
+                self.headers_only.len()
+            )?;
+        }
+        if self.is_ok() {
+            writeln!(formatter, "no inconsistencies found")?;
+        }
+        Ok(())
+    }
+}
+
+impl Storage {
+    /// Returns the statistics of each named database.
+    pub(crate) fn database_stats(&self) -> Result<Vec<DatabaseStats>, FatalStorageError> {
+        let mut databases = vec![
+            ("block_header", self.block_header_db),
+            ("block_metadata", self.block_metadata_db),
+            ("deploys", self.deploy_db),
+            ("deploy_metadata", self.deploy_metadata_db),
+            ("transfer", self.transfer_db),
+            ("state_store", self.state_store_db),
+            ("finalized_approvals", self.finalized_approvals_db),
+            ("block_body", self.block_body_db),
+            ("approvals_hashes", self.approvals_hashes_db),
+        ];
+        if let Some(account_deploys_db) = self.account_deploys_db {
+            databases.push(("account_deploys", account_deploys_db));
+        }
+        if let Some(transfer_index_db) = self.transfer_index_db {
+            databases.push(("transfer_index", transfer_index_db));
+        }
+
+        let txn = self.env.begin_ro_txn()?;
+        let mut all_stats = vec![];
+        for (name, db) in databases {
+            let stat = txn.stat(db)?;
+            let pages = stat.branch_pages() + stat.leaf_pages() + stat.overflow_pages();
+            all_stats.push(DatabaseStats {
+                name,
+                entries: stat.entries(),
+                size_bytes: pages * stat.page_size() as usize,
+            });
+        }
+        txn.commit()?;
+        Ok(all_stats)
+    }
+
+    /// Returns the sequences of heights of the blocks marked as complete, highest first.
+    pub(crate) fn completed_block_sequences(&self) -> Vec<Sequence> {
+        self.completed_blocks.sequences().clone()
+    }
+
+    /// Checks that every stored block header within the available block range has a matching
+    /// block body and approvals hashes, and that no block bodies or approvals hashes are stored
+    /// without a block header.
+    ///
+    /// Headers outside the available block range without a body are only reported as
+    /// informational, since the node isn't expected to hold the full blocks there.
+    pub(crate) fn check_integrity(&self) -> Result<IntegrityReport, FatalStorageError> {
+        let mut report = IntegrityReport::default();
+        // Without any completed blocks, the available block range is a placeholder.
+        let available_block_range = self
+            .completed_blocks
+            .highest_sequence()
+            .map(|_| self.get_available_block_range());
+        let txn = self.env.begin_ro_txn()?;
+        // Used for lookups while iterating over `txn`.
+        let mut lookup_txn = self.env.begin_ro_txn()?;
+
+        let mut block_hashes = HashSet::new();
+        let mut body_hashes = HashSet::new();
+        let mut cursor = txn.open_ro_cursor(self.block_header_db)?;
+        for row in cursor.iter() {
+            let (_, raw_val) = row?;
+            report.block_headers += 1;
+            let block_header: BlockHeader = lmdb_ext::deserialize(raw_val)?;
+            let block_hash = block_header.block_hash();
+            let _ = block_hashes.insert(block_hash);
+            let _ = body_hashes.insert(*block_header.body_hash());
+
+            let maybe_body: Option<BlockBody> =
+                lookup_txn.get_value(self.block_body_db, block_header.body_hash())?;
+            let is_available =
+                available_block_range.map_or(false, |range| range.contains(block_header.height()));
+            if !is_available {
+                if maybe_body.is_none() {
+                    report.headers_only.push(block_hash);
+                }
+                continue;
+            }
+            let maybe_approvals_hashes: Option<ApprovalsHashes> =
+                lookup_txn.get_value(self.approvals_hashes_db, &block_hash)?;
+            match (maybe_body, maybe_approvals_hashes) {
+                (None, _) => report.missing_bodies.push(block_hash),
+                (Some(_), None) => report.missing_approvals_hashes.push(block_hash),
+                (Some(body), Some(approvals_hashes)) => {
+                    if body.deploy_and_transfer_hashes().count()
+                        != approvals_hashes.approvals_hashes().len()
+                    {
+                        report.mismatched_approvals_hashes.push(block_hash);
+                    }
+                }
+            }
+        }
+        drop(cursor);
+
+        let mut cursor = txn.open_ro_cursor(self.block_body_db)?;
+        for row in cursor.iter() {
+            let (raw_key, _) = row?;
+            report.block_bodies += 1;
+            let body_hash = Digest::try_from(raw_key)
+                .map_err(|error| LmdbExtError::DataCorrupted(Box::new(error)))?;
+            if !body_hashes.contains(&body_hash) {
+                report.orphaned_bodies.push(body_hash);
+            }
+        }
+        drop(cursor);
+
+        let mut cursor = txn.open_ro_cursor(self.approvals_hashes_db)?;
+        for row in cursor.iter() {
+            let (_, raw_val) = row?;
+            report.approvals_hashes += 1;
+            let approvals_hashes: ApprovalsHashes = lmdb_ext::deserialize(raw_val)?;
+            if !block_hashes.contains(approvals_hashes.block_hash()) {
+                report
+                    .orphaned_approvals_hashes
+                    .push(*approvals_hashes.block_hash());
+            }
+        }
+        drop(cursor);
+
+        lookup_txn.commit()?;
+        txn.commit()?;
+        Ok(report)
+    }
+}
@@ -1,7 +1,7 @@
 //! Unit tests for the storage component.
 
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, VecDeque},
     fs::{self, File},
     iter,
     rc::Rc,
@@ -12,14 +12,17 @@ use rand::{prelude::SliceRandom, Rng};
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
 
+use casper_execution_engine::storage::trie::merkle_proof::TrieMerkleProof;
 use casper_types::{
     generate_ed25519_keypair, system::auction::UnbondingPurse, testing::TestRng, AccessRights,
-    EraId, ExecutionResult, ProtocolVersion, PublicKey, SecretKey, TimeDiff, URef, U512,
+    CLValue, EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion, PublicKey, SecretKey,
+    StoredValue, TimeDiff, Timestamp, Transfer, TransferAddr, Transform, TransformEntry, URef,
+    U512,
 };
 
 use super::{
     move_storage_files_to_network_subdir, should_move_storage_files_to_network_subdir, Config,
-    Storage,
+    Storage, TransferParty,
 };
 use crate::{
     components::fetcher::{FetchItem, FetchResponse},
@@ -33,10 +36,11 @@ use crate::{
     },
     testing::{ComponentHarness, UnitTestEvent},
     types::{
-        sync_leap_validation_metadata::SyncLeapValidationMetaData, AvailableBlockRange, Block,
-        BlockHash, BlockHashAndHeight, BlockHeader, BlockHeaderWithMetadata, BlockSignatures,
-        Chainspec, ChainspecRawBytes, Deploy, DeployHash, DeployMetadata, DeployMetadataExt,
-        DeployWithFinalizedApprovals, FinalitySignature, LegacyDeploy, SyncLeapIdentifier,
+        sync_leap_validation_metadata::SyncLeapValidationMetaData, ApprovalsHashes,
+        AvailableBlockRange, Block, BlockHash, BlockHashAndHeight, BlockHeader,
+        BlockHeaderWithMetadata, BlockSignatures, Chainspec, ChainspecRawBytes, Deploy, DeployHash,
+        DeployMetadata, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
+        LegacyDeploy, SyncLeapIdentifier,
     },
     utils::{Loadable, WithDir},
 };
@@ -56,6 +60,8 @@ fn new_config(harness: &ComponentHarness<UnitTestEvent>) -> Config {
         max_state_store_size: 50 * MIB,
         enable_mem_deduplication: true,
         mem_pool_prune_interval: 4,
+        enable_account_deploys_index: false,
+        enable_transfer_index: false,
     }
 }
 
@@ -174,19 +180,7 @@ fn create_sync_leap_test_chain(
 ///
 /// Panics if setting up the storage fixture fails.
 fn storage_fixture(harness: &ComponentHarness<UnitTestEvent>) -> Storage {
-    let cfg = new_config(harness);
-    Storage::new(
-        &WithDir::new(harness.tmp.path(), cfg),
-        None,
-        ProtocolVersion::from_parts(1, 0, 0),
-        EraId::default(),
-        "test",
-        MAX_TTL.into(),
-        RECENT_ERA_COUNT,
-        None,
-        false,
-    )
-    .expect("could not create storage component fixture")
+    storage_fixture_with_config(harness, new_config(harness))
 }
 
 /// Storage component test fixture.
@@ -219,6 +213,28 @@ fn storage_fixture_from_parts(
     .expect("could not create storage component fixture from parts")
 }
 
+/// Storage component test fixture with the given configuration.
+///
+/// Creates a storage component in a temporary directory.
+///
+/// # Panics
+///
+/// Panics if setting up the storage fixture fails.
+fn storage_fixture_with_config(harness: &ComponentHarness<UnitTestEvent>, cfg: Config) -> Storage {
+    Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        EraId::default(),
+        "test",
+        MAX_TTL.into(),
+        RECENT_ERA_COUNT,
+        None,
+        false,
+    )
+    .expect("could not create storage component fixture")
+}
+
 /// Storage component test fixture with force resync enabled.
 ///
 /// Creates a storage component in a given temporary directory.
@@ -1721,6 +1737,588 @@ fn should_get_block_header_by_height() {
     assert_eq!(expected_header, maybe_block_header.unwrap());
 }
 
+#[test]
+fn should_get_blocks_by_height_range() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    // Create the following disjoint sequences: 1-2 4-6
+    let blocks: BTreeMap<u64, Block> = [1, 2, 4, 5, 6]
+        .iter()
+        .map(|height| {
+            let block = Block::random_with_specifics(
+                &mut harness.rng,
+                EraId::from(1),
+                *height,
+                ProtocolVersion::from_parts(1, 5, 0),
+                false,
+                None,
+            );
+            storage.write_block(&block).unwrap();
+            storage.completed_blocks.insert(*height);
+            (*height, block)
+        })
+        .collect();
+
+    // Only blocks in the available block range, i.e. 4-6, should be returned.
+    let headers_only = storage
+        .read_blocks_by_height_range(0, 10, false)
+        .expect("should read blocks");
+    assert_eq!(
+        headers_only
+            .iter()
+            .map(|(header, _)| header.height())
+            .collect::<Vec<_>>(),
+        vec![4, 5, 6]
+    );
+    for (header, maybe_body) in headers_only {
+        assert_eq!(&header, blocks[&header.height()].header());
+        assert!(maybe_body.is_none());
+    }
+
+    let with_bodies = storage
+        .read_blocks_by_height_range(2, 5, true)
+        .expect("should read blocks");
+    assert_eq!(with_bodies.len(), 2);
+    for (header, maybe_body) in with_bodies {
+        let block = &blocks[&header.height()];
+        assert_eq!(&header, block.header());
+        assert_eq!(maybe_body.as_ref(), Some(block.body()));
+    }
+
+    // Ranges outside the available block range, or with start > end, should be empty.
+    assert!(storage
+        .read_blocks_by_height_range(1, 2, true)
+        .expect("should read blocks")
+        .is_empty());
+    assert!(storage
+        .read_blocks_by_height_range(6, 4, false)
+        .expect("should read blocks")
+        .is_empty());
+}
+
+#[test]
+fn should_get_switch_block_headers_by_era_range() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    // Create a switch block at the end of each of the eras 0-3, and a non-switch block in era 4.
+    let blocks: BTreeMap<u64, Block> = (0..5)
+        .map(|height| {
+            let block = Block::random_with_specifics(
+                &mut harness.rng,
+                EraId::from(height),
+                height,
+                ProtocolVersion::from_parts(1, 5, 0),
+                height < 4,
+                None,
+            );
+            storage.write_block(&block).unwrap();
+            storage.completed_blocks.insert(height);
+            (height, block)
+        })
+        .collect();
+
+    let read_heights = |storage: &Storage, start_era_id: u64, end_era_id: u64| {
+        storage
+            .read_switch_block_headers_by_era_range(
+                EraId::from(start_era_id),
+                EraId::from(end_era_id),
+            )
+            .expect("should read switch block headers")
+            .iter()
+            .map(|header| {
+                assert_eq!(header, blocks[&header.height()].header());
+                header.height()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(read_heights(&storage, 1, 3), vec![1, 2, 3]);
+    assert_eq!(read_heights(&storage, 2, 10), vec![2, 3]);
+    assert!(read_heights(&storage, 3, 1).is_empty());
+
+    // Switch blocks outside the available block range should be omitted.
+    storage.completed_blocks = Default::default();
+    for height in 2..5 {
+        storage.completed_blocks.insert(height);
+    }
+    assert_eq!(read_heights(&storage, 0, 4), vec![2, 3]);
+}
+
+/// Creates approvals hashes for the given block with an arbitrary checksum registry proof.
+fn dummy_approvals_hashes(block: &Block) -> ApprovalsHashes {
+    ApprovalsHashes::new(
+        block.hash(),
+        vec![],
+        TrieMerkleProof::new(
+            URef::new([255; 32], AccessRights::NONE).into(),
+            StoredValue::CLValue(CLValue::from_t(()).unwrap()),
+            VecDeque::new(),
+        ),
+    )
+}
+
+/// Creates a random deploy sent by the account of the given secret key.
+fn random_deploy_from_account(rng: &mut TestRng, secret_key: &SecretKey) -> Deploy {
+    let deploy = Deploy::random(rng);
+    Deploy::new(
+        Timestamp::now(),
+        TimeDiff::from_seconds(60),
+        1,
+        vec![],
+        "test".to_string(),
+        deploy.payment().clone(),
+        deploy.session().clone(),
+        secret_key,
+        None,
+    )
+}
+
+#[test]
+fn should_index_account_deploys() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture_with_config(
+        &harness,
+        Config {
+            enable_account_deploys_index: true,
+            ..new_config(&harness)
+        },
+    );
+
+    let (alice_secret_key, alice_public_key) = generate_ed25519_keypair();
+    let (bob_secret_key, bob_public_key) = generate_ed25519_keypair();
+    let unknown_account_hash = PublicKey::random(&mut harness.rng).to_account_hash();
+    let alice_deploys: Vec<Deploy> =
+        iter::repeat_with(|| random_deploy_from_account(&mut harness.rng, &alice_secret_key))
+            .take(3)
+            .collect();
+    let bob_deploy = random_deploy_from_account(&mut harness.rng, &bob_secret_key);
+    for deploy in alice_deploys.iter().chain(iter::once(&bob_deploy)) {
+        put_deploy(&mut harness, &mut storage, Arc::new(deploy.clone()));
+    }
+
+    // Execute Alice's first deploy along with Bob's at height 1, and her others at height 2.
+    let block_1 = Block::random_with_specifics(
+        &mut harness.rng,
+        EraId::from(0),
+        1,
+        ProtocolVersion::V1_0_0,
+        false,
+        vec![&alice_deploys[0], &bob_deploy],
+    );
+    let block_2 = Block::random_with_specifics(
+        &mut harness.rng,
+        EraId::from(0),
+        2,
+        ProtocolVersion::V1_0_0,
+        false,
+        &alice_deploys[1..],
+    );
+    for block in [&block_1, &block_2].iter() {
+        storage
+            .put_executed_block(block, &dummy_approvals_hashes(block), HashMap::new())
+            .expect("should put executed block");
+    }
+
+    // Deploys in the same block are ordered by deploy hash.
+    let mut expected_alice_deploys = vec![(
+        *alice_deploys[0].hash(),
+        BlockHashAndHeight::new(*block_1.hash(), 1),
+    )];
+    let mut alice_deploys_at_height_2: Vec<_> = alice_deploys[1..]
+        .iter()
+        .map(|deploy| (*deploy.hash(), BlockHashAndHeight::new(*block_2.hash(), 2)))
+        .collect();
+    alice_deploys_at_height_2.sort_by_key(|(deploy_hash, _)| *deploy_hash);
+    expected_alice_deploys.extend(alice_deploys_at_height_2);
+    let expected_bob_deploys = vec![(
+        *bob_deploy.hash(),
+        BlockHashAndHeight::new(*block_1.hash(), 1),
+    )];
+
+    let check_index = |storage: &Storage| {
+        assert_eq!(
+            storage
+                .read_account_deploys(alice_public_key.to_account_hash(), 0, 10)
+                .unwrap(),
+            Some(expected_alice_deploys.clone())
+        );
+        assert_eq!(
+            storage
+                .read_account_deploys(alice_public_key.to_account_hash(), 1, 1)
+                .unwrap(),
+            Some(expected_alice_deploys[1..2].to_vec())
+        );
+        assert_eq!(
+            storage
+                .read_account_deploys(alice_public_key.to_account_hash(), 3, 10)
+                .unwrap(),
+            Some(vec![])
+        );
+        assert_eq!(
+            storage
+                .read_account_deploys(bob_public_key.to_account_hash(), 0, 10)
+                .unwrap(),
+            Some(expected_bob_deploys.clone())
+        );
+        assert_eq!(
+            storage
+                .read_account_deploys(unknown_account_hash, 0, 10)
+                .unwrap(),
+            Some(vec![])
+        );
+    };
+    check_index(&storage);
+
+    // The index should be rebuilt when the storage is reopened with the index enabled.
+    let (on_disk, rng) = harness.into_parts();
+    let harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let storage = storage_fixture_with_config(
+        &harness,
+        Config {
+            enable_account_deploys_index: true,
+            ..new_config(&harness)
+        },
+    );
+    check_index(&storage);
+
+    // The index should be unavailable when the storage is reopened with the index disabled.
+    let storage = storage_fixture(&harness);
+    assert_eq!(
+        storage
+            .read_account_deploys(alice_public_key.to_account_hash(), 0, 10)
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn should_index_account_deploys_of_synced_blocks() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture_with_config(
+        &harness,
+        Config {
+            enable_account_deploys_index: true,
+            ..new_config(&harness)
+        },
+    );
+
+    let (alice_secret_key, alice_public_key) = generate_ed25519_keypair();
+    let deploy = random_deploy_from_account(&mut harness.rng, &alice_secret_key);
+    let block = Block::random_with_specifics(
+        &mut harness.rng,
+        EraId::from(0),
+        1,
+        ProtocolVersion::V1_0_0,
+        false,
+        iter::once(&deploy),
+    );
+
+    // A synced block is stored before its deploys, and only indexed once marked complete.
+    assert!(storage.write_block(&block).unwrap());
+    put_deploy(&mut harness, &mut storage, Arc::new(deploy.clone()));
+    assert_eq!(
+        storage
+            .read_account_deploys(alice_public_key.to_account_hash(), 0, 10)
+            .unwrap(),
+        Some(vec![])
+    );
+
+    harness.send_request(&mut storage, |responder| {
+        MarkBlockCompletedRequest {
+            block_height: 1,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    assert_eq!(
+        storage
+            .read_account_deploys(alice_public_key.to_account_hash(), 0, 10)
+            .unwrap(),
+        Some(vec![(
+            *deploy.hash(),
+            BlockHashAndHeight::new(*block.hash(), 1)
+        )])
+    );
+}
+
+/// Creates a successful execution result which writes the given transfer.
+fn execution_result_with_transfer(transfer: Transfer) -> ExecutionResult {
+    ExecutionResult::Success {
+        effect: ExecutionEffect {
+            operations: vec![],
+            transforms: vec![TransformEntry {
+                key: Key::Transfer(TransferAddr::new([1; 32])).to_formatted_string(),
+                transform: Transform::WriteTransfer(transfer),
+            }],
+        },
+        transfers: vec![TransferAddr::new([1; 32])],
+        cost: U512::from(100),
+        events: vec![],
+    }
+}
+
+#[test]
+fn should_index_transfers() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture_with_config(
+        &harness,
+        Config {
+            enable_transfer_index: true,
+            ..new_config(&harness)
+        },
+    );
+
+    let alice = PublicKey::random(&mut harness.rng).to_account_hash();
+    let bob = PublicKey::random(&mut harness.rng).to_account_hash();
+    let alice_purse = URef::new(harness.rng.gen(), AccessRights::READ_ADD_WRITE);
+    let bob_purse = URef::new(harness.rng.gen(), AccessRights::READ_ADD_WRITE);
+    let other_purse = URef::new(harness.rng.gen(), AccessRights::READ_ADD_WRITE);
+
+    // Alice pays Bob at height 1, and Bob pays into a purse not owned by an account at height 2.
+    let block_1 = Block::random_with_specifics(
+        &mut harness.rng,
+        EraId::from(0),
+        1,
+        ProtocolVersion::V1_0_0,
+        false,
+        None,
+    );
+    let block_2 = Block::random_with_specifics(
+        &mut harness.rng,
+        EraId::from(0),
+        2,
+        ProtocolVersion::V1_0_0,
+        false,
+        None,
+    );
+    let transfer_1 = Transfer::new(
+        casper_types::DeployHash::new([1; 32]),
+        alice,
+        Some(bob),
+        alice_purse,
+        bob_purse,
+        U512::from(1_000),
+        U512::from(10),
+        Some(1),
+    );
+    let transfer_2 = Transfer::new(
+        casper_types::DeployHash::new([2; 32]),
+        bob,
+        None,
+        bob_purse,
+        other_purse,
+        U512::from(500),
+        U512::from(10),
+        None,
+    );
+    for (block, transfer) in [(&block_1, &transfer_1), (&block_2, &transfer_2)].iter() {
+        let mut execution_results = HashMap::new();
+        execution_results.insert(
+            DeployHash::random(&mut harness.rng),
+            execution_result_with_transfer((*transfer).clone()),
+        );
+        storage
+            .put_executed_block(block, &dummy_approvals_hashes(block), execution_results)
+            .expect("should put executed block");
+    }
+
+    let at_height_1 = BlockHashAndHeight::new(*block_1.hash(), 1);
+    let at_height_2 = BlockHashAndHeight::new(*block_2.hash(), 2);
+    let check_index = |storage: &Storage| {
+        let read = |party: TransferParty, offset: u64, limit: u64| {
+            storage
+                .read_transfers_by_party(party, offset, limit)
+                .unwrap()
+                .expect("index should be enabled")
+        };
+        assert_eq!(
+            read(TransferParty::Account(alice), 0, 10),
+            vec![(transfer_1.clone(), at_height_1)]
+        );
+        assert_eq!(
+            read(TransferParty::Account(bob), 0, 10),
+            vec![
+                (transfer_1.clone(), at_height_1),
+                (transfer_2.clone(), at_height_2)
+            ]
+        );
+        assert_eq!(
+            read(TransferParty::Account(bob), 1, 10),
+            vec![(transfer_2.clone(), at_height_2)]
+        );
+        assert_eq!(
+            read(TransferParty::Account(bob), 0, 1),
+            vec![(transfer_1.clone(), at_height_1)]
+        );
+        assert_eq!(
+            read(TransferParty::Purse(bob_purse.addr()), 0, 10),
+            vec![
+                (transfer_1.clone(), at_height_1),
+                (transfer_2.clone(), at_height_2)
+            ]
+        );
+        assert_eq!(
+            read(TransferParty::Purse(other_purse.addr()), 0, 10),
+            vec![(transfer_2.clone(), at_height_2)]
+        );
+        assert!(read(TransferParty::Purse(alice.value()), 0, 10).is_empty());
+    };
+    check_index(&storage);
+
+    // The index should be rebuilt when the storage is reopened with the index enabled.
+    let (on_disk, rng) = harness.into_parts();
+    let harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let storage = storage_fixture_with_config(
+        &harness,
+        Config {
+            enable_transfer_index: true,
+            ..new_config(&harness)
+        },
+    );
+    check_index(&storage);
+
+    // The index should be unavailable when the storage is reopened with the index disabled.
+    let storage = storage_fixture(&harness);
+    assert_eq!(
+        storage
+            .read_transfers_by_party(TransferParty::Account(alice), 0, 10)
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn should_inspect_read_only_storage() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    // Only block 1 has its approvals hashes stored, and only the header of block 0 is stored, as
+    // after a sync leap. Block 0 is outside the available block range.
+    let block_1 = Block::random_with_specifics(
+        &mut harness.rng,
+        EraId::from(0),
+        1,
+        ProtocolVersion::V1_0_0,
+        false,
+        None,
+    );
+    let block_2 = Block::random_with_specifics(
+        &mut harness.rng,
+        EraId::from(0),
+        2,
+        ProtocolVersion::V1_0_0,
+        false,
+        None,
+    );
+    let block_0 = Block::random_with_specifics(
+        &mut harness.rng,
+        EraId::from(0),
+        0,
+        ProtocolVersion::V1_0_0,
+        false,
+        None,
+    );
+    storage
+        .put_executed_block(&block_1, &dummy_approvals_hashes(&block_1), HashMap::new())
+        .expect("should put executed block");
+    storage.write_block(&block_2).expect("should write block");
+    storage
+        .put_block_headers(vec![block_0.header().clone()])
+        .expect("should put block header");
+    storage.completed_blocks.insert(1);
+    storage.completed_blocks.insert(2);
+    storage.persist_completed_blocks().unwrap();
+    drop(storage);
+
+    let cfg = WithDir::new(harness.tmp.path(), new_config(&harness));
+    let storage = Storage::open_read_only(
+        &cfg,
+        EraId::default(),
+        "test",
+        MAX_TTL.into(),
+        RECENT_ERA_COUNT,
+    )
+    .expect("should open storage read-only");
+
+    assert_eq!(storage.read_highest_block().unwrap(), Some(block_2.clone()));
+    assert_eq!(storage.read_block(block_1.hash()).unwrap(), Some(block_1));
+    assert_eq!(
+        storage
+            .completed_block_sequences()
+            .iter()
+            .map(|sequence| (sequence.low(), sequence.high()))
+            .collect::<Vec<_>>(),
+        vec![(1, 2)]
+    );
+
+    let block_header_stats = storage
+        .database_stats()
+        .unwrap()
+        .into_iter()
+        .find(|stats| stats.name == "block_header")
+        .expect("should have block header stats");
+    assert_eq!(block_header_stats.entries, 3);
+
+    let report = storage.check_integrity().unwrap();
+    assert_eq!(report.block_headers, 3);
+    assert_eq!(report.missing_approvals_hashes, vec![*block_2.hash()]);
+    assert!(report.missing_bodies.is_empty());
+    assert_eq!(report.headers_only, vec![*block_0.hash()]);
+    assert!(report.orphaned_bodies.is_empty());
+    assert!(report.orphaned_approvals_hashes.is_empty());
+    assert!(!report.is_ok());
+}
+
+#[test]
+fn should_track_pruned_global_state() {
+    let (mut storage, _, blocks) = create_sync_leap_test_chain(&[], false, None);
+
+    // S4 is the switch block of era 2.
+    let (switch_block_height, state_root_hashes) = storage
+        .read_state_root_hashes_since_switch_block(EraId::from(2))
+        .unwrap()
+        .expect("should have switch block of era 2");
+    assert_eq!(switch_block_height, 4);
+    let expected_state_root_hashes: Vec<_> = blocks[4..]
+        .iter()
+        .map(|block| *block.header().state_root_hash())
+        .collect();
+    assert_eq!(state_root_hashes, expected_state_root_hashes);
+    assert!(storage
+        .read_state_root_hashes_since_switch_block(EraId::from(100))
+        .unwrap()
+        .is_none());
+
+    assert_eq!(
+        storage.get_available_block_range_with_state(),
+        AvailableBlockRange::new(0, 12)
+    );
+    storage.put_state_pruned_below_height(4).unwrap();
+    assert_eq!(
+        storage.get_available_block_range_with_state(),
+        AvailableBlockRange::new(4, 12)
+    );
+    // The range of fully available blocks is unaffected.
+    assert_eq!(
+        storage.get_available_block_range(),
+        AvailableBlockRange::new(0, 12)
+    );
+
+    // The pruned height never decreases, and is persisted.
+    storage.put_state_pruned_below_height(2).unwrap();
+    assert_eq!(storage.state_pruned_below_height, Some(4));
+    assert_eq!(storage.read_state_pruned_below_height().unwrap(), Some(4));
+}
+
 #[ignore]
 #[test]
 fn check_force_resync_with_marker_file() {
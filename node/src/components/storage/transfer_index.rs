@@ -0,0 +1,101 @@
+//! Encoding of the keys and values of the transfer index database.
+//!
+//! The transfer index maps each account and purse which took part in a transfer to the executed
+//! transfers, along with the heights of the blocks in which they were executed.  The database
+//! allows duplicate keys, with the values of each key sorted by block height.
+
+use std::fmt::{self, Display, Formatter};
+
+use casper_types::{
+    account::AccountHash,
+    bytesrepr::{self, ToBytes, U64_SERIALIZED_LENGTH},
+    Transfer, URefAddr,
+};
+
+use super::lmdb_ext::{BytesreprError, LmdbExtError};
+
+/// Length of a key in the transfer index database: a tag byte followed by a 32-byte address.
+const TRANSFER_INDEX_KEY_LENGTH: usize = 33;
+/// Tag of keys identifying an account.
+const ACCOUNT_TAG: u8 = 0;
+/// Tag of keys identifying a purse.
+const PURSE_TAG: u8 = 1;
+
+/// An account or purse which took part in a transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TransferParty {
+    /// The account which sent or received the transfer.
+    Account(AccountHash),
+    /// The address of the purse from or to which the transfer was made.
+    Purse(URefAddr),
+}
+
+impl TransferParty {
+    /// Returns the key of this party in the transfer index database.
+    pub(super) fn to_key(self) -> [u8; TRANSFER_INDEX_KEY_LENGTH] {
+        let (tag, address) = match self {
+            TransferParty::Account(account_hash) => (ACCOUNT_TAG, account_hash.value()),
+            TransferParty::Purse(uref_addr) => (PURSE_TAG, uref_addr),
+        };
+        let mut key = [0; TRANSFER_INDEX_KEY_LENGTH];
+        key[0] = tag;
+        key[1..].copy_from_slice(&address);
+        key
+    }
+
+    /// Returns the parties to the given transfer: the sending and receiving accounts, if any, and
+    /// the source and target purses.
+    pub(super) fn all_for(transfer: &Transfer) -> impl Iterator<Item = TransferParty> {
+        let accounts = Some(transfer.from)
+            .into_iter()
+            .chain(transfer.to)
+            .map(TransferParty::Account);
+        let purses = vec![transfer.source.addr(), transfer.target.addr()]
+            .into_iter()
+            .map(TransferParty::Purse);
+        accounts.chain(purses)
+    }
+}
+
+impl Display for TransferParty {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferParty::Account(account_hash) => write!(formatter, "account {}", account_hash),
+            TransferParty::Purse(uref_addr) => {
+                write!(formatter, "purse {}", base16::encode_lower(uref_addr))
+            }
+        }
+    }
+}
+
+/// Encodes a value of the transfer index database.
+///
+/// The block height is encoded big-endian so that the duplicate values of a key are sorted by block
+/// height.
+pub(super) fn encode_value(
+    block_height: u64,
+    transfer: &Transfer,
+) -> Result<Vec<u8>, LmdbExtError> {
+    let serialized_transfer = transfer
+        .to_bytes()
+        .map_err(|error| LmdbExtError::Other(Box::new(BytesreprError(error))))?;
+    let mut value = Vec::with_capacity(U64_SERIALIZED_LENGTH + serialized_transfer.len());
+    value.extend_from_slice(&block_height.to_be_bytes());
+    value.extend(serialized_transfer);
+    Ok(value)
+}
+
+/// Decodes a value of the transfer index database.
+pub(super) fn decode_value(raw_val: &[u8]) -> Result<(u64, Transfer), LmdbExtError> {
+    if raw_val.len() < U64_SERIALIZED_LENGTH {
+        return Err(LmdbExtError::DataCorrupted(Box::new(BytesreprError(
+            bytesrepr::Error::EarlyEndOfStream,
+        ))));
+    }
+    let (raw_height, raw_transfer) = raw_val.split_at(U64_SERIALIZED_LENGTH);
+    let mut height_bytes = [0; U64_SERIALIZED_LENGTH];
+    height_bytes.copy_from_slice(raw_height);
+    let transfer = bytesrepr::deserialize_from_slice(raw_transfer)
+        .map_err(|error| LmdbExtError::DataCorrupted(Box::new(BytesreprError(error))))?;
+    Ok((u64::from_be_bytes(height_bytes), transfer))
+}
@@ -115,18 +115,23 @@ use tokio::{sync::Semaphore, time};
 use tracing::{debug, error, warn};
 
 use casper_execution_engine::{
-    core::engine_state::{
-        self, era_validators::GetEraValidatorsError, BalanceRequest, BalanceResult, GetBidsRequest,
-        GetBidsResult, QueryRequest, QueryResult,
+    core::{
+        engine_state::{
+            self, era_validators::GetEraValidatorsError, BalanceRequest, BalanceResult,
+            GetBidsRequest, GetBidsResult, QueryRequest, QueryResult,
+        },
+        runtime::trace::ExecutionTrace,
     },
     shared::execution_journal::ExecutionJournal,
     storage::trie::TrieRaw,
 };
 use casper_hashing::Digest;
 use casper_types::{
-    account::Account, bytesrepr::Bytes, system::auction::EraValidators, Contract, ContractPackage,
-    EraId, ExecutionEffect, ExecutionResult, Key, PublicKey, TimeDiff, Timestamp, Transfer, URef,
-    U512,
+    account::{Account, AccountHash},
+    bytesrepr::Bytes,
+    system::auction::EraValidators,
+    Contract, ContractPackage, EraId, ExecutionEffect, ExecutionResult, Key, PublicKey,
+    StoredValue, TimeDiff, Timestamp, Transfer, URef, U512,
 };
 
 use crate::{
@@ -142,17 +147,19 @@ use crate::{
         fetcher::{FetchItem, FetchResult},
         gossiper::GossipItem,
         network::{blocklist::BlocklistJustification, FromIncoming, NetworkInsights},
+        storage::TransferParty,
         upgrade_watcher::NextUpgrade,
     },
     contract_runtime::SpeculativeExecutionState,
     reactor::{main_reactor::ReactorState, EventQueueHandle, QueueKind},
     types::{
-        appendable_block::AppendableBlock, ApprovalsHashes, AvailableBlockRange, Block,
-        BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash, BlockHeader,
-        BlockSignatures, BlockWithMetadata, ChainspecRawBytes, Deploy, DeployHash, DeployHeader,
-        DeployId, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
-        FinalitySignatureId, FinalizedApprovals, FinalizedBlock, LegacyDeploy, MetaBlock,
-        MetaBlockState, NodeId, TrieOrChunk, TrieOrChunkId,
+        appendable_block::AppendableBlock, ApprovalsHashes, AvailableBlockRange, Block, BlockBody,
+        BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash,
+        BlockHashAndHeight, BlockHeader, BlockSignatures, BlockWithMetadata, ChainspecRawBytes,
+        Deploy, DeployHash, DeployHeader, DeployId, DeployMetadataExt,
+        DeployWithFinalizedApprovals, FinalitySignature, FinalitySignatureId, FinalizedApprovals,
+        FinalizedBlock, LegacyDeploy, MetaBlock, MetaBlockState, NodeId, TrieOrChunk,
+        TrieOrChunkId,
     },
     utils::{fmt_limit::FmtLimit, SharedFlag, Source},
 };
@@ -927,6 +934,33 @@ impl<REv> EffectBuilder<REv> {
             |responder| AcceptDeployRequest {
                 deploy,
                 speculative_exec_at_block,
+                skip_account_checks: false,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Checks a deploy to be speculatively executed on top of the given block.
+    ///
+    /// If `skip_account_checks` is `true`, the deploy's account is not checked against the block's
+    /// global state, e.g. as the deploy will be executed on top of modified state.  The account is
+    /// then only checked during execution.
+    pub(crate) async fn try_accept_speculative_deploy(
+        self,
+        deploy: Arc<Deploy>,
+        block_header: Box<BlockHeader>,
+        skip_account_checks: bool,
+    ) -> Result<(), deploy_acceptor::Error>
+    where
+        REv: From<AcceptDeployRequest>,
+    {
+        self.make_request(
+            |responder| AcceptDeployRequest {
+                deploy,
+                speculative_exec_at_block: Some(block_header),
+                skip_account_checks,
                 responder,
             },
             QueueKind::Api,
@@ -1200,6 +1234,100 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the headers, and optionally the bodies, of the blocks in the given inclusive range of
+    /// heights from the linear block store, restricted to the available block range.
+    pub(crate) async fn get_blocks_by_height_range_from_storage(
+        self,
+        start_height: u64,
+        end_height: u64,
+        include_bodies: bool,
+    ) -> Vec<(BlockHeader, Option<BlockBody>)>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlocksByHeightRange {
+                start_height,
+                end_height,
+                include_bodies,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Gets the headers of the switch blocks of the eras in the given inclusive range of era IDs
+    /// from the linear block store, restricted to the available block range.
+    pub(crate) async fn get_switch_block_headers_by_era_range_from_storage(
+        self,
+        start_era_id: EraId,
+        end_era_id: EraId,
+    ) -> Vec<BlockHeader>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetSwitchBlockHeadersByEraRange {
+                start_era_id,
+                end_era_id,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Gets the hashes of executed deploys sent by the given account, along with the hashes and
+    /// heights of the blocks containing them, from the account deploys index.
+    ///
+    /// Returns `None` if the account deploys index is disabled.
+    pub(crate) async fn get_account_deploys_from_storage(
+        self,
+        account_hash: AccountHash,
+        offset: u64,
+        limit: u64,
+    ) -> Option<Vec<(DeployHash, BlockHashAndHeight)>>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetAccountDeploys {
+                account_hash,
+                offset,
+                limit,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Gets the transfers in which the given account or purse took part, along with the hashes and
+    /// heights of the blocks containing them, from the transfer index.
+    ///
+    /// Returns `None` if the transfer index is disabled.
+    pub(crate) async fn get_transfers_by_party_from_storage(
+        self,
+        party: TransferParty,
+        offset: u64,
+        limit: u64,
+    ) -> Option<Vec<(Transfer, BlockHashAndHeight)>>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetTransfersByParty {
+                party,
+                offset,
+                limit,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
     /// Gets the requested signature for a given block hash.
     pub(crate) async fn get_signature_from_storage(
         self,
@@ -1359,6 +1487,49 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests the height of the switch block of the given era, along with the state root hashes
+    /// of it and all stored blocks above it.
+    pub(crate) async fn get_state_root_hashes_since_switch_block_from_storage(
+        self,
+        era_id: EraId,
+    ) -> Option<(u64, Vec<Digest>)>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetStateRootHashesSinceSwitchBlock { era_id, responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Records that the global state of all blocks below `block_height` has been pruned.
+    pub(crate) async fn put_state_pruned_below_height_to_storage(self, block_height: u64)
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutStatePrunedBelowHeight {
+                block_height,
+                responder,
+            },
+            QueueKind::ToStorage,
+        )
+        .await
+    }
+
+    /// Requests the height below which the global state of blocks has been pruned, if any.
+    pub(crate) async fn get_state_pruned_below_height_from_storage(self) -> Option<u64>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetStatePrunedBelowHeight { responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
     /// Synchronize global state under the given root hash.
     pub(crate) async fn sync_global_state(
         self,
@@ -1416,6 +1587,18 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Returns whether the block synchronizer is currently syncing a global state.
+    pub(crate) async fn is_syncing_global_state(self) -> bool
+    where
+        REv: From<BlockSynchronizerRequest>,
+    {
+        self.make_request(
+            |responder| BlockSynchronizerRequest::IsSyncingGlobalState { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Get a trie by its hash key.
     pub(crate) async fn get_trie_full(
         self,
@@ -1754,6 +1937,26 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Requests the contract runtime to prune the global state no longer retained after the given
+    /// switch block, in the background.
+    pub(crate) async fn collect_global_state_garbage(
+        self,
+        switch_block_header: BlockHeader,
+        pre_state_root_hash: Digest,
+    ) where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.event_queue
+            .schedule(
+                ContractRuntimeRequest::CollectGarbage {
+                    switch_block_header: Box::new(switch_block_header),
+                    pre_state_root_hash,
+                },
+                QueueKind::Regular,
+            )
+            .await
+    }
+
     /// Checks whether the deploys included in the block exist on the network and the block is
     /// valid.
     pub(crate) async fn validate_block(
@@ -2183,11 +2386,16 @@ impl<REv> EffectBuilder<REv> {
 
     /// Requests execution of a single deploy, without commiting its effects.
     /// Inteded to be used for debugging & discovery purposes.
+    ///
+    /// The given `state_overrides` are stored in a scratch copy of the global state before the
+    /// deploy is executed.
     pub(crate) async fn speculative_execute_deploy(
         self,
         execution_prestate: SpeculativeExecutionState,
         deploy: Arc<Deploy>,
-    ) -> Result<Option<ExecutionResult>, engine_state::Error>
+        state_overrides: BTreeMap<Key, StoredValue>,
+        trace: bool,
+    ) -> Result<Option<(ExecutionResult, Option<ExecutionTrace>)>, engine_state::Error>
     where
         REv: From<ContractRuntimeRequest>,
     {
@@ -2195,6 +2403,34 @@ impl<REv> EffectBuilder<REv> {
             |responder| ContractRuntimeRequest::SpeculativeDeployExecution {
                 execution_prestate,
                 deploy,
+                state_overrides,
+                trace,
+                responder,
+            },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+
+    /// Requests execution of a sequence of deploys, each on top of the effects of the ones before
+    /// it, without commiting their effects.
+    ///
+    /// If `dry_run` is `true`, the approvals of the deploys are not checked and their payment code
+    /// is not run.
+    pub(crate) async fn speculative_execute_deploy_sequence(
+        self,
+        execution_prestate: SpeculativeExecutionState,
+        deploys: Vec<Arc<Deploy>>,
+        dry_run: bool,
+    ) -> Result<(Vec<ExecutionResult>, ExecutionEffect), engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::SpeculativeDeploySequenceExecution {
+                execution_prestate,
+                deploys,
+                dry_run,
                 responder,
             },
             QueueKind::ContractRuntime,
@@ -16,19 +16,23 @@ use smallvec::SmallVec;
 use static_assertions::const_assert;
 
 use casper_execution_engine::{
-    core::engine_state::{
-        self,
-        balance::{BalanceRequest, BalanceResult},
-        era_validators::GetEraValidatorsError,
-        get_bids::{GetBidsRequest, GetBidsResult},
-        query::{QueryRequest, QueryResult},
+    core::{
+        engine_state::{
+            self,
+            balance::{BalanceRequest, BalanceResult},
+            era_validators::GetEraValidatorsError,
+            get_bids::{GetBidsRequest, GetBidsResult},
+            query::{QueryRequest, QueryResult},
+        },
+        runtime::trace::ExecutionTrace,
     },
     storage::trie::TrieRaw,
 };
 use casper_hashing::Digest;
 use casper_types::{
-    bytesrepr::Bytes, system::auction::EraValidators, EraId, ExecutionResult, Key, ProtocolVersion,
-    PublicKey, TimeDiff, Timestamp, Transfer, URef,
+    account::AccountHash, bytesrepr::Bytes, system::auction::EraValidators, EraId, ExecutionEffect,
+    ExecutionResult, Key, ProtocolVersion, PublicKey, StoredValue, TimeDiff, Timestamp, Transfer,
+    URef,
 };
 
 use crate::{
@@ -44,6 +48,7 @@ use crate::{
         fetcher::{FetchItem, FetchResult},
         gossiper::GossipItem,
         network::NetworkInsights,
+        storage::TransferParty,
         upgrade_watcher::NextUpgrade,
     },
     contract_runtime::{ContractRuntimeError, SpeculativeExecutionState},
@@ -51,12 +56,13 @@ use crate::{
     reactor::main_reactor::ReactorState,
     rpcs::docs::OpenRpcSchema,
     types::{
-        appendable_block::AppendableBlock, ApprovalsHashes, AvailableBlockRange, Block,
-        BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash, BlockHeader,
-        BlockSignatures, BlockWithMetadata, ChainspecRawBytes, Deploy, DeployHash, DeployHeader,
-        DeployId, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
-        FinalitySignatureId, FinalizedApprovals, FinalizedBlock, LegacyDeploy, MetaBlockState,
-        NodeId, StatusFeed, TrieOrChunk, TrieOrChunkId,
+        appendable_block::AppendableBlock, ApprovalsHashes, AvailableBlockRange, Block, BlockBody,
+        BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash,
+        BlockHashAndHeight, BlockHeader, BlockSignatures, BlockWithMetadata, ChainspecRawBytes,
+        Deploy, DeployHash, DeployHeader, DeployId, DeployMetadataExt,
+        DeployWithFinalizedApprovals, FinalitySignature, FinalitySignatureId, FinalizedApprovals,
+        FinalizedBlock, LegacyDeploy, MetaBlockState, NodeId, StatusFeed, TrieOrChunk,
+        TrieOrChunkId,
     },
     utils::{DisplayIter, Source},
 };
@@ -346,6 +352,58 @@ pub(crate) enum StorageRequest {
         /// local storage.
         responder: Responder<Option<BlockHeader>>,
     },
+    /// Retrieve the block headers, and optionally the block bodies, of all blocks in the given
+    /// inclusive range of heights which also lie within the available block range.
+    GetBlocksByHeightRange {
+        /// The inclusive lower bound of the range of heights.
+        start_height: u64,
+        /// The inclusive upper bound of the range of heights.
+        end_height: u64,
+        /// If true, the block bodies are retrieved as well as the block headers.
+        include_bodies: bool,
+        /// Responder to call with the result, ordered by height.  Heights for which the block
+        /// (or its body, if requested) is not stored are omitted.
+        responder: Responder<Vec<(BlockHeader, Option<BlockBody>)>>,
+    },
+    /// Retrieve the headers of the switch blocks of all eras in the given inclusive range of era
+    /// IDs which also lie within the available block range.
+    GetSwitchBlockHeadersByEraRange {
+        /// The inclusive lower bound of the range of era IDs.
+        start_era_id: EraId,
+        /// The inclusive upper bound of the range of era IDs.
+        end_era_id: EraId,
+        /// Responder to call with the result, ordered by era ID.  Eras for which the switch block
+        /// is not stored are omitted.
+        responder: Responder<Vec<BlockHeader>>,
+    },
+    /// Retrieve the hashes of executed deploys sent by the given account from the account deploys
+    /// index.
+    GetAccountDeploys {
+        /// The account hash of the deploys' sender.
+        account_hash: AccountHash,
+        /// The number of entries to skip.
+        offset: u64,
+        /// The maximum number of entries to retrieve.
+        limit: u64,
+        /// Responder to call with the deploy hashes along with the hashes and heights of the
+        /// blocks containing them, ordered by block height.  Returns `None` if the account
+        /// deploys index is disabled.
+        responder: Responder<Option<Vec<(DeployHash, BlockHashAndHeight)>>>,
+    },
+    /// Retrieve the transfers in which the given account or purse took part from the transfer
+    /// index.
+    GetTransfersByParty {
+        /// The account or purse which sent or received the transfers.
+        party: TransferParty,
+        /// The number of entries to skip.
+        offset: u64,
+        /// The maximum number of entries to retrieve.
+        limit: u64,
+        /// Responder to call with the transfers along with the hashes and heights of the blocks
+        /// containing them, ordered by block height.  Returns `None` if the transfer index is
+        /// disabled.
+        responder: Responder<Option<Vec<(Transfer, BlockHashAndHeight)>>>,
+    },
     /// Retrieve all transfers in a block with given hash.
     GetBlockTransfers {
         /// Hash of block to get transfers of.
@@ -484,10 +542,32 @@ pub(crate) enum StorageRequest {
     },
     /// Retrieve the height range of fully available blocks (not just block headers). Returns
     /// `[u64::MAX, u64::MAX]` when there are no sequences.
+    ///
+    /// Blocks whose global state has been pruned are excluded from the range.
     GetAvailableBlockRange {
         /// Responder to call with the result.
         responder: Responder<AvailableBlockRange>,
     },
+    /// Retrieve the height of the switch block of the given era, along with the state root hashes
+    /// of it and all stored blocks above it.
+    GetStateRootHashesSinceSwitchBlock {
+        /// The era whose switch block to start from.
+        era_id: EraId,
+        /// Responder to call with the result.  Returns `None` if the switch block is not stored.
+        responder: Responder<Option<(u64, Vec<Digest>)>>,
+    },
+    /// Record that the global state of all blocks below the given height has been pruned.
+    PutStatePrunedBelowHeight {
+        /// The height of the lowest block whose global state is retained.
+        block_height: u64,
+        /// Responder to call once the height has been recorded.
+        responder: Responder<()>,
+    },
+    /// Retrieve the height below which the global state of blocks has been pruned.
+    GetStatePrunedBelowHeight {
+        /// Responder to call with the result.  Returns `None` if no global state has been pruned.
+        responder: Responder<Option<u64>>,
+    },
     /// Store a set of finalized approvals for a specific deploy.
     StoreFinalizedApprovals {
         /// The deploy hash to store the finalized approvals for.
@@ -577,6 +657,53 @@ impl Display for StorageRequest {
                     block_hash
                 )
             }
+            StorageRequest::GetBlocksByHeightRange {
+                start_height,
+                end_height,
+                include_bodies,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "get blocks at heights [{}, {}] (include bodies: {})",
+                    start_height, end_height, include_bodies
+                )
+            }
+            StorageRequest::GetSwitchBlockHeadersByEraRange {
+                start_era_id,
+                end_era_id,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "get switch block headers for eras [{}, {}]",
+                    start_era_id, end_era_id
+                )
+            }
+            StorageRequest::GetAccountDeploys {
+                account_hash,
+                offset,
+                limit,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "get at most {} deploys of {} from offset {}",
+                    limit, account_hash, offset
+                )
+            }
+            StorageRequest::GetTransfersByParty {
+                party,
+                offset,
+                limit,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "get at most {} transfers of {} from offset {}",
+                    limit, party, offset
+                )
+            }
             StorageRequest::GetBlockAndMetadataByHeight { block_height, .. } => {
                 write!(
                     formatter,
@@ -610,6 +737,19 @@ impl Display for StorageRequest {
             StorageRequest::GetAvailableBlockRange { .. } => {
                 write!(formatter, "get available block range",)
             }
+            StorageRequest::GetStateRootHashesSinceSwitchBlock { era_id, .. } => {
+                write!(
+                    formatter,
+                    "get state root hashes since switch block of era {}",
+                    era_id
+                )
+            }
+            StorageRequest::PutStatePrunedBelowHeight { block_height, .. } => {
+                write!(formatter, "put state pruned below height {}", block_height)
+            }
+            StorageRequest::GetStatePrunedBelowHeight { .. } => {
+                write!(formatter, "get state pruned below height")
+            }
             StorageRequest::StoreFinalizedApprovals { deploy_hash, .. } => {
                 write!(formatter, "finalized approvals for deploy {}", deploy_hash)
             }
@@ -924,8 +1064,35 @@ pub(crate) enum ContractRuntimeRequest {
         execution_prestate: SpeculativeExecutionState,
         /// Deploy to execute.
         deploy: Arc<Deploy>,
-        /// Results
-        responder: Responder<Result<Option<ExecutionResult>, engine_state::Error>>,
+        /// Values to store under the given keys before executing the deploy.
+        state_overrides: BTreeMap<Key, StoredValue>,
+        /// Whether to trace the execution of the deploy.
+        trace: bool,
+        /// Results, along with the trace of the execution if requested.
+        responder: Responder<
+            Result<Option<(ExecutionResult, Option<ExecutionTrace>)>, engine_state::Error>,
+        >,
+    },
+    /// Delete the global state no longer retained after executing the given switch block, in the
+    /// background.
+    CollectGarbage {
+        /// The header of the executed switch block.
+        switch_block_header: Box<BlockHeader>,
+        /// The state root hash of the switch block's parent.
+        pre_state_root_hash: Digest,
+    },
+    /// Execute a sequence of deploys without commiting results, each on top of the effects of the
+    /// ones before it.
+    SpeculativeDeploySequenceExecution {
+        /// Hash of a block on top of which to execute the deploys.
+        execution_prestate: SpeculativeExecutionState,
+        /// Deploys to execute, in order.
+        deploys: Vec<Arc<Deploy>>,
+        /// Whether to skip the approval and payment checks of the deploys.
+        dry_run: bool,
+        /// Responder to call with the result of each deploy and the combined effects of all of
+        /// them.
+        responder: Responder<Result<(Vec<ExecutionResult>, ExecutionEffect), engine_state::Error>>,
     },
 }
 
@@ -981,6 +1148,28 @@ impl Display for ContractRuntimeRequest {
                     execution_prestate.state_root_hash
                 )
             }
+            ContractRuntimeRequest::CollectGarbage {
+                switch_block_header,
+                ..
+            } => write!(
+                formatter,
+                "collect global state garbage after switch block {}",
+                switch_block_header.height()
+            ),
+            ContractRuntimeRequest::SpeculativeDeploySequenceExecution {
+                execution_prestate,
+                deploys,
+                dry_run,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "Execute sequence of {} deploys on {} (dry run: {})",
+                    deploys.len(),
+                    execution_prestate.state_root_hash,
+                    dry_run
+                )
+            }
         }
     }
 }
@@ -1139,6 +1328,10 @@ pub(crate) enum BlockSynchronizerRequest {
     Status {
         responder: Responder<BlockSynchronizerStatus>,
     },
+    /// Whether the global state of a block is currently being synced.
+    IsSyncingGlobalState {
+        responder: Responder<bool>,
+    },
 }
 
 impl Display for BlockSynchronizerRequest {
@@ -1153,6 +1346,9 @@ impl Display for BlockSynchronizerRequest {
             BlockSynchronizerRequest::Status { .. } => {
                 write!(f, "block synchronizer request: status")
             }
+            BlockSynchronizerRequest::IsSyncingGlobalState { .. } => {
+                write!(f, "block synchronizer request: is syncing global state")
+            }
             BlockSynchronizerRequest::SyncGlobalStates(_) => {
                 write!(f, "request to sync global states")
             }
@@ -1185,6 +1381,8 @@ impl Display for SetNodeStopRequest {
 pub(crate) struct AcceptDeployRequest {
     pub(crate) deploy: Arc<Deploy>,
     pub(crate) speculative_exec_at_block: Option<Box<BlockHeader>>,
+    /// Whether to skip the checks of the deploy's account, only applicable to speculative exec.
+    pub(crate) skip_account_checks: bool,
     pub(crate) responder: Responder<Result<(), deploy_acceptor::Error>>,
 }
 
@@ -30,6 +30,7 @@ pub(crate) mod effect;
 pub mod logging;
 pub(crate) mod protocol;
 pub(crate) mod reactor;
+pub mod signer;
 #[cfg(test)]
 pub(crate) mod testing;
 pub(crate) mod tls;
@@ -241,6 +241,9 @@ impl reactor::Reactor for MainReactor {
             MainEvent::MetaBlockAnnouncement(MetaBlockAnnouncement(meta_block)) => {
                 self.handle_meta_block(effect_builder, rng, meta_block)
             }
+            MainEvent::CreatedFinalitySignature(finality_signature) => {
+                self.handle_created_finality_signature(effect_builder, rng, *finality_signature)
+            }
             MainEvent::UnexecutedBlockAnnouncement(UnexecutedBlockAnnouncement(block_height)) => {
                 let only_from_available_block_range = true;
                 if let Ok(Some(block_header)) = self
@@ -677,10 +680,14 @@ impl reactor::Reactor for MainReactor {
             MainEvent::AcceptDeployRequest(AcceptDeployRequest {
                 deploy,
                 speculative_exec_at_block,
+                skip_account_checks,
                 responder,
             }) => {
-                let source = if let Some(block) = speculative_exec_at_block {
-                    Source::SpeculativeExec(block)
+                let source = if let Some(block_header) = speculative_exec_at_block {
+                    Source::SpeculativeExec {
+                        block_header,
+                        skip_account_checks,
+                    }
                 } else {
                     Source::Client
                 };
@@ -732,7 +739,7 @@ impl reactor::Reactor for MainReactor {
                             ),
                         ));
                     }
-                    Source::SpeculativeExec(_) => {
+                    Source::SpeculativeExec { .. } => {
                         error!(
                             ?deploy,
                             "deploy acceptor should not announce speculative exec deploys"
@@ -1003,7 +1010,7 @@ impl reactor::Reactor for MainReactor {
 
         let trusted_hash = config.value().node.trusted_hash;
         let (root_dir, config) = config.into_parts();
-        let (our_secret_key, our_public_key) = config.consensus.load_keys(&root_dir)?;
+        let signer = config.consensus.load_signer(&root_dir)?;
         let validator_matrix = ValidatorMatrix::new(
             chainspec.core_config.finality_threshold_fraction,
             chainspec
@@ -1012,8 +1019,7 @@ impl reactor::Reactor for MainReactor {
                 .as_ref()
                 .and_then(|global_state_update| global_state_update.validators.clone()),
             chainspec.protocol_config.activation_point.era_id(),
-            our_secret_key.clone(),
-            our_public_key.clone(),
+            signer.clone(),
             chainspec.core_config.auction_delay,
         );
 
@@ -1059,12 +1065,13 @@ impl reactor::Reactor for MainReactor {
             chainspec.core_config.allow_unrestricted_transfers,
             chainspec.core_config.refund_handling,
             chainspec.core_config.fee_handling,
+            chainspec.core_config.deploy_validator_gas_limit,
         )?;
 
         let network = Network::new(
             config.network.clone(),
             network_identity,
-            Some((our_secret_key.clone(), our_public_key.clone())),
+            Some(signer.clone()),
             registry,
             chainspec.as_ref(),
             validator_matrix.clone(),
@@ -1082,18 +1089,21 @@ impl reactor::Reactor for MainReactor {
             protocol_version,
             chainspec.network_config.name.clone(),
             node_startup_instant,
-        );
+            registry,
+        )?;
         let rest_server = RestServer::new(
             config.rest_server.clone(),
             protocol_version,
             chainspec.network_config.name.clone(),
             node_startup_instant,
-        );
+            registry,
+        )?;
         let event_stream_server = EventStreamServer::new(
             config.event_stream_server.clone(),
             storage.root_path().to_path_buf(),
             protocol_version,
-        );
+            registry,
+        )?;
         let diagnostics_port =
             DiagnosticsPort::new(WithDir::new(&root_dir, config.diagnostics_port));
         let shutdown_trigger = ShutdownTrigger::new();
@@ -1123,8 +1133,7 @@ impl reactor::Reactor for MainReactor {
         // consensus
         let consensus = EraSupervisor::new(
             storage.root_path(),
-            our_secret_key,
-            our_public_key,
+            signer,
             config.consensus,
             chainspec.clone(),
             registry,
@@ -1333,43 +1342,22 @@ impl MainReactor {
 
         if state.register_we_have_tried_to_sign().was_updated() {
             // When this node is a validator in this era, sign and announce.
-            if let Some(finality_signature) = self
+            if let Some(sign) = self
                 .validator_matrix
                 .create_finality_signature(block.header())
             {
                 debug!(
-                    %finality_signature,
-                    "MetaBlock: registering finality signature: {} {}",
+                    "MetaBlock: signing block: {} {}",
                     block.height(),
                     block.hash(),
                 );
-
-                effects.extend(reactor::wrap_effects(
-                    MainEvent::Storage,
-                    effect_builder
-                        .put_finality_signature_to_storage(finality_signature.clone())
-                        .ignore(),
-                ));
-
-                effects.extend(reactor::wrap_effects(
-                    MainEvent::BlockAccumulator,
-                    self.block_accumulator.handle_event(
-                        effect_builder,
-                        rng,
-                        block_accumulator::Event::CreatedFinalitySignature {
-                            finality_signature: Box::new(finality_signature.clone()),
-                        },
-                    ),
-                ));
-
-                let era_id = finality_signature.era_id;
-                let payload = Message::FinalitySignature(Box::new(finality_signature));
-                effects.extend(reactor::wrap_effects(
-                    MainEvent::Network,
-                    effect_builder
-                        .broadcast_message_to_validators(payload, era_id)
-                        .ignore(),
-                ));
+                effects.extend(sign.events(|maybe_finality_signature| {
+                    maybe_finality_signature
+                        .map(|finality_signature| {
+                            MainEvent::CreatedFinalitySignature(Box::new(finality_signature))
+                        })
+                        .into_iter()
+                }));
             }
         }
 
@@ -1527,6 +1515,42 @@ impl MainReactor {
         effects
     }
 
+    fn handle_created_finality_signature(
+        &mut self,
+        effect_builder: EffectBuilder<MainEvent>,
+        rng: &mut NodeRng,
+        finality_signature: FinalitySignature,
+    ) -> Effects<MainEvent> {
+        debug!(%finality_signature, "registering our finality signature");
+        let mut effects = reactor::wrap_effects(
+            MainEvent::Storage,
+            effect_builder
+                .put_finality_signature_to_storage(finality_signature.clone())
+                .ignore(),
+        );
+
+        effects.extend(reactor::wrap_effects(
+            MainEvent::BlockAccumulator,
+            self.block_accumulator.handle_event(
+                effect_builder,
+                rng,
+                block_accumulator::Event::CreatedFinalitySignature {
+                    finality_signature: Box::new(finality_signature.clone()),
+                },
+            ),
+        ));
+
+        let era_id = finality_signature.era_id;
+        let payload = Message::FinalitySignature(Box::new(finality_signature));
+        effects.extend(reactor::wrap_effects(
+            MainEvent::Network,
+            effect_builder
+                .broadcast_message_to_validators(payload, era_id)
+                .ignore(),
+        ));
+        effects
+    }
+
     fn update_meta_block_gossip_state(
         &mut self,
         effect_builder: EffectBuilder<MainEvent>,
@@ -247,6 +247,9 @@ pub(crate) enum MainEvent {
 
     // Event related to figuring out validators for blocks after upgrades.
     GotBlockAfterUpgradeEraValidators(EraId, EraValidators, EraValidators),
+
+    /// Our own finality signature for an executed block has been created.
+    CreatedFinalitySignature(Box<FinalitySignature>),
 }
 
 impl ReactorEvent for MainEvent {
@@ -362,6 +365,7 @@ impl ReactorEvent for MainEvent {
             MainEvent::GotBlockAfterUpgradeEraValidators(_, _, _) => {
                 "GotImmediateSwitchBlockEraValidators"
             }
+            MainEvent::CreatedFinalitySignature(_) => "CreatedFinalitySignature",
         }
     }
 }
@@ -546,6 +550,9 @@ impl Display for MainEvent {
                     era_id
                 )
             }
+            MainEvent::CreatedFinalitySignature(finality_signature) => {
+                write!(f, "created finality signature: {}", finality_signature)
+            }
         }
     }
 }
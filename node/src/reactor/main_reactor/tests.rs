@@ -259,6 +259,7 @@ impl TestFixture {
                 .to_file(secret_key_path.clone())
                 .expect("could not write secret key");
             cfg.consensus.secret_key_path = External::Path(secret_key_path);
+            cfg.consensus.slashing_protection_path = temp_dir.path().join("slashing_protection.db");
         }
         cfg.storage = storage_cfg;
         cfg.node.trusted_hash = maybe_trusted_hash;
@@ -0,0 +1,501 @@
+//! Signing with the validator's consensus key.
+//!
+//! All consensus messages, finality signatures and consensus certificates are signed via a
+//! [`Signer`]. It either holds the secret key in-process, loaded from the node's secret key file,
+//! or forwards every request to an external signer process over a Unix domain or TCP socket, so
+//! that the secret key doesn't need to be stored on the node's host. The wire protocol is defined
+//! in the [`protocol`] module, and a [`StandInSigner`] implements the signer side of it for
+//! testing.
+//!
+//! Messages which could be evidence of equivocation are signed via [`Signer::sign_unique`], which
+//! checks and updates the [`slashing_protection`] database first.
+
+mod config;
+pub mod protocol;
+pub mod slashing_protection;
+mod stand_in;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    io::{self, Read, Write},
+    net::SocketAddr,
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use datasize::DataSize;
+use thiserror::Error;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpStream, UnixStream},
+    runtime::Handle,
+    sync::Mutex,
+    task, time,
+};
+use tracing::{debug, warn};
+
+use casper_hashing::Digest;
+use casper_types::{crypto, PublicKey, SecretKey, Signature};
+
+use crate::utils::{self, ResolveAddressError};
+pub use config::Config;
+use protocol::{Request, Response};
+use slashing_protection::{SlashingProtection, Slot};
+pub use stand_in::StandInSigner;
+
+/// The prefix of remote signer addresses denoting a Unix domain socket.
+const UNIX_ADDRESS_PREFIX: &str = "unix:";
+
+/// An error signing a message or connecting to a remote signer.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Invalid remote signer address.
+    #[error(transparent)]
+    InvalidAddress(#[from] ResolveAddressError),
+    /// I/O error communicating with the remote signer.
+    #[error("remote signer I/O error: {0}")]
+    Io(io::Error),
+    /// The remote signer didn't respond in time.
+    #[error("remote signer timed out")]
+    Timeout,
+    /// Too many handshakes were signed in the last second.
+    #[error("handshake signature rate limit exceeded")]
+    RateLimited,
+    /// The remote signer closed the connection.
+    #[error("remote signer closed the connection")]
+    ConnectionClosed,
+    /// A message couldn't be encoded.
+    #[error("failed to encode remote signer message: {0}")]
+    Encoding(bincode::Error),
+    /// A message couldn't be decoded.
+    #[error("failed to decode remote signer message: {0}")]
+    Decoding(bincode::Error),
+    /// A message exceeded the maximum frame size.
+    #[error("remote signer message of {0} bytes exceeds the maximum frame size")]
+    FrameTooLarge(usize),
+    /// The remote signer refused the request.
+    #[error("remote signer refused the request: {0}")]
+    Refused(String),
+    /// The remote signer's response doesn't match the request.
+    #[error("unexpected response from remote signer")]
+    UnexpectedResponse,
+    /// The remote signer returned an invalid signature.
+    #[error("remote signer returned an invalid signature: {0}")]
+    InvalidSignature(crypto::Error),
+    /// Slashing protection refused the request, or its database couldn't be updated.
+    #[error(transparent)]
+    SlashingProtection(#[from] slashing_protection::Error),
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => Error::Timeout,
+            io::ErrorKind::UnexpectedEof => Error::ConnectionClosed,
+            _ => Error::Io(error),
+        }
+    }
+}
+
+/// The address of a remote signer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Address {
+    /// A TCP socket address.
+    Tcp(SocketAddr),
+    /// The path of a Unix domain socket.
+    Unix(PathBuf),
+}
+
+impl FromStr for Address {
+    type Err = Error;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        match address.strip_prefix(UNIX_ADDRESS_PREFIX) {
+            Some(path) => Ok(Address::Unix(PathBuf::from(path))),
+            None => Ok(Address::Tcp(utils::resolve_address(address)?)),
+        }
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::Tcp(socket_addr) => write!(formatter, "{}", socket_addr),
+            Address::Unix(path) => write!(formatter, "{}{}", UNIX_ADDRESS_PREFIX, path.display()),
+        }
+    }
+}
+
+/// Signs messages with the validator's consensus key.
+///
+/// Requests to a remote signer use asynchronous I/O. They are sent via [`Signer::sign_async`] and
+/// [`Signer::sign_unique_async`] wherever the caller can await the signature. The synchronous
+/// [`Signer::sign`] and [`Signer::sign_unique`] wait for the signer via
+/// [`tokio::task::block_in_place`], for at most the configured timeout, so that the other tasks of
+/// the runtime keep running. They must be called from within a multi-threaded Tokio runtime if the
+/// signer is remote.
+#[derive(Clone, DataSize)]
+pub struct Signer {
+    public_key: PublicKey,
+    #[data_size(skip)]
+    backend: Backend,
+    #[data_size(skip)]
+    slashing_protection: Option<Arc<SlashingProtection>>,
+}
+
+#[derive(Clone)]
+enum Backend {
+    Local(Arc<SecretKey>),
+    Remote(Arc<RemoteSigner>),
+}
+
+impl Signer {
+    /// Creates a signer using the given secret key in-process.
+    pub fn local(secret_key: Arc<SecretKey>) -> Self {
+        let public_key = PublicKey::from(secret_key.as_ref());
+        Signer {
+            public_key,
+            backend: Backend::Local(secret_key),
+            slashing_protection: None,
+        }
+    }
+
+    /// Connects to the remote signer and requests the public key of the validator it signs for.
+    ///
+    /// This blocks the calling thread until the signer responds, for at most the configured
+    /// timeout, so it should only be called while the node is starting up.
+    pub fn remote(config: &Config) -> Result<Self, Error> {
+        let remote_signer = RemoteSigner::connect(config)?;
+        Ok(Signer {
+            public_key: remote_signer.public_key.clone(),
+            backend: Backend::Remote(Arc::new(remote_signer)),
+            slashing_protection: None,
+        })
+    }
+
+    /// Sets the slashing protection database checked by [`Signer::sign_unique`].
+    ///
+    /// It must have been opened for the public key of this signer.
+    pub fn with_slashing_protection(mut self, slashing_protection: SlashingProtection) -> Self {
+        self.slashing_protection = Some(Arc::new(slashing_protection));
+        self
+    }
+
+    /// Returns the public key of the validator this signer signs for.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Signs the given message.
+    pub fn sign<T: AsRef<[u8]>>(&self, message: T) -> Result<Signature, Error> {
+        match &self.backend {
+            Backend::Local(secret_key) => Ok(crypto::sign(message, secret_key, &self.public_key)),
+            Backend::Remote(remote_signer) => task::block_in_place(|| {
+                Handle::current().block_on(remote_signer.sign(message.as_ref()))
+            }),
+        }
+    }
+
+    /// Signs the given message, without blocking the calling thread.
+    pub async fn sign_async<T: AsRef<[u8]>>(&self, message: T) -> Result<Signature, Error> {
+        match &self.backend {
+            Backend::Local(secret_key) => Ok(crypto::sign(message, secret_key, &self.public_key)),
+            Backend::Remote(remote_signer) => remote_signer.sign(message.as_ref()).await,
+        }
+    }
+
+    /// Signs a consensus certificate for a network handshake, without blocking the calling thread.
+    ///
+    /// Handshakes are initiated by peers, so a remote signer is sent these requests on a separate
+    /// connection, and at most the configured number per second, so that a flood of incoming
+    /// connections can't delay signing consensus messages and finality signatures. Requests
+    /// exceeding the rate are refused with [`Error::RateLimited`].
+    pub async fn sign_handshake<T: AsRef<[u8]>>(&self, message: T) -> Result<Signature, Error> {
+        match &self.backend {
+            Backend::Local(secret_key) => Ok(crypto::sign(message, secret_key, &self.public_key)),
+            Backend::Remote(remote_signer) => remote_signer.sign_handshake(message.as_ref()).await,
+        }
+    }
+
+    /// Signs the given message, which must be the only one signed in the given slot.
+    ///
+    /// If slashing protection is enabled, the message is recorded in its database before it is
+    /// signed, and signing is refused if a different message was already recorded in the slot.
+    /// Should signing fail, the record is revoked again, so that the slot isn't used up.
+    pub fn sign_unique<T: AsRef<[u8]>>(&self, slot: Slot, message: T) -> Result<Signature, Error> {
+        self.check_and_record(slot, message.as_ref())?;
+        let result = self.sign(message);
+        self.finish_unique(slot, result.is_ok());
+        result
+    }
+
+    /// Signs the given message, which must be the only one signed in the given slot, without
+    /// blocking the calling thread on the remote signer.
+    ///
+    /// See [`Signer::sign_unique`].
+    pub async fn sign_unique_async<T: AsRef<[u8]>>(
+        &self,
+        slot: Slot,
+        message: T,
+    ) -> Result<Signature, Error> {
+        self.check_and_record(slot, message.as_ref())?;
+        let result = self.sign_async(message).await;
+        self.finish_unique(slot, result.is_ok());
+        result
+    }
+
+    /// Records the message in the slashing protection database, if enabled.
+    fn check_and_record(&self, slot: Slot, message: &[u8]) -> Result<(), Error> {
+        if let Some(slashing_protection) = &self.slashing_protection {
+            slashing_protection.check_and_record(slot, Digest::hash(message))?;
+        }
+        Ok(())
+    }
+
+    /// Confirms the record of the message in the given slot if it was signed, or revokes it
+    /// otherwise.
+    fn finish_unique(&self, slot: Slot, is_signed: bool) {
+        if let Some(slashing_protection) = &self.slashing_protection {
+            if is_signed {
+                slashing_protection.confirm(slot);
+            } else if let Err(error) = slashing_protection.revoke(slot) {
+                // The record is kept, which is safe, but the slot can't be used anymore.
+                warn!(%slot, %error, "failed to revoke slashing protection record");
+            }
+        }
+    }
+}
+
+impl Debug for Signer {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        let backend = match &self.backend {
+            Backend::Local(_) => "local".to_string(),
+            Backend::Remote(remote_signer) => remote_signer.address.to_string(),
+        };
+        formatter
+            .debug_struct("Signer")
+            .field("public_key", &self.public_key)
+            .field("backend", &backend)
+            .field("slashing_protection", &self.slashing_protection.is_some())
+            .finish()
+    }
+}
+
+/// A connection to a remote signer.
+enum Connection {
+    Tcp(TcpStream),
+    Unix(UnixStream),
+}
+
+impl Connection {
+    /// Connects to the remote signer.
+    async fn open(address: &Address) -> Result<Self, Error> {
+        match address {
+            Address::Tcp(socket_addr) => {
+                let stream = TcpStream::connect(socket_addr).await?;
+                stream.set_nodelay(true)?;
+                Ok(Connection::Tcp(stream))
+            }
+            Address::Unix(path) => Ok(Connection::Unix(UnixStream::connect(path).await?)),
+        }
+    }
+
+    /// Sends the request and waits for the response.
+    async fn exchange(&mut self, request: &Request) -> Result<Response, Error> {
+        match self {
+            Connection::Tcp(stream) => exchange(stream, request).await,
+            Connection::Unix(stream) => exchange(stream, request).await,
+        }
+    }
+}
+
+/// Sends the request on the stream and waits for the response.
+async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    request: &Request,
+) -> Result<Response, Error> {
+    protocol::write_frame_async(stream, request).await?;
+    protocol::read_frame_async(stream)
+        .await?
+        .ok_or(Error::ConnectionClosed)
+}
+
+/// Requests the public key from the remote signer on a new, blocking connection.
+fn fetch_public_key(address: &Address, timeout: Duration) -> Result<PublicKey, Error> {
+    let response = match address {
+        Address::Tcp(socket_addr) => {
+            let mut stream = std::net::TcpStream::connect_timeout(socket_addr, timeout)?;
+            stream.set_read_timeout(Some(timeout))?;
+            stream.set_write_timeout(Some(timeout))?;
+            exchange_blocking(&mut stream, &Request::PublicKey)?
+        }
+        Address::Unix(path) => {
+            let mut stream = std::os::unix::net::UnixStream::connect(path)?;
+            stream.set_read_timeout(Some(timeout))?;
+            stream.set_write_timeout(Some(timeout))?;
+            exchange_blocking(&mut stream, &Request::PublicKey)?
+        }
+    };
+    match response {
+        Response::PublicKey(public_key) => Ok(public_key),
+        Response::Refused(reason) => Err(Error::Refused(reason)),
+        Response::Signature(_) => Err(Error::UnexpectedResponse),
+    }
+}
+
+/// Sends the request on the stream and waits for the response, blocking the calling thread.
+fn exchange_blocking<S: Read + Write>(
+    stream: &mut S,
+    request: &Request,
+) -> Result<Response, Error> {
+    protocol::write_frame(stream, request)?;
+    protocol::read_frame(stream)?.ok_or(Error::ConnectionClosed)
+}
+
+/// Limits the rate of requests, allowing bursts of up to one second's worth of requests.
+struct RateLimiter {
+    /// The maximum number of requests per second, or `0` if unlimited.
+    rate: u32,
+    /// The number of requests that can currently be made.
+    tokens: f64,
+    /// The last time `tokens` was updated.
+    last_update: Instant,
+}
+
+impl RateLimiter {
+    fn new(rate: u32) -> Self {
+        RateLimiter {
+            rate,
+            tokens: f64::from(rate),
+            last_update: Instant::now(),
+        }
+    }
+
+    /// Returns `true` and takes a token if a request can be made now.
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        if self.rate == 0 {
+            return true;
+        }
+        let elapsed = now.saturating_duration_since(self.last_update);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * f64::from(self.rate)).min(f64::from(self.rate));
+        self.last_update = now;
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+/// A client of a remote signer, keeping one connection open for handshakes and one for all other
+/// requests.
+///
+/// Each connection is opened by the first signing request sent on it, dropped after any error, and
+/// reopened for the next request.
+struct RemoteSigner {
+    address: Address,
+    timeout: Duration,
+    public_key: PublicKey,
+    connection: Mutex<Option<Connection>>,
+    handshake_connection: Mutex<Option<Connection>>,
+    handshake_rate_limiter: std::sync::Mutex<RateLimiter>,
+}
+
+impl RemoteSigner {
+    fn connect(config: &Config) -> Result<Self, Error> {
+        let address: Address = config.address.parse()?;
+        let timeout = config.timeout.into();
+        let public_key = fetch_public_key(&address, timeout)?;
+        Ok(RemoteSigner {
+            address,
+            timeout,
+            public_key,
+            connection: Mutex::new(None),
+            handshake_connection: Mutex::new(None),
+            handshake_rate_limiter: std::sync::Mutex::new(RateLimiter::new(
+                config.max_handshake_signature_rate,
+            )),
+        })
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature, Error> {
+        self.sign_on(&self.connection, message).await
+    }
+
+    async fn sign_handshake(&self, message: &[u8]) -> Result<Signature, Error> {
+        let acquired = self
+            .handshake_rate_limiter
+            .lock()
+            .expect("handshake rate limiter lock poisoned")
+            .try_acquire(Instant::now());
+        if !acquired {
+            return Err(Error::RateLimited);
+        }
+        self.sign_on(&self.handshake_connection, message).await
+    }
+
+    /// Sends a signing request on the given connection, and waits for the signature.
+    async fn sign_on(
+        &self,
+        connection: &Mutex<Option<Connection>>,
+        message: &[u8],
+    ) -> Result<Signature, Error> {
+        let request = Request::Sign {
+            public_key: self.public_key.clone(),
+            message: message.to_vec(),
+        };
+        let mut connection = connection.lock().await;
+        // If the signer closed an existing connection, e.g. because it was restarted, retry once
+        // on a new one. Timeouts are not retried, since they already took as long as we can wait.
+        let reused = connection.is_some();
+        let response = match self.exchange(&mut connection, &request).await {
+            Err(error) if reused && !matches!(error, Error::Timeout) => {
+                debug!(%error, address = %self.address, "reconnecting to remote signer");
+                self.exchange(&mut connection, &request).await?
+            }
+            result => result?,
+        };
+        match response {
+            Response::Signature(signature) => {
+                crypto::verify(message, &signature, &self.public_key)
+                    .map_err(Error::InvalidSignature)?;
+                Ok(signature)
+            }
+            Response::Refused(reason) => Err(Error::Refused(reason)),
+            Response::PublicKey(_) => Err(Error::UnexpectedResponse),
+        }
+    }
+
+    /// Sends the request on the existing connection or on a new one, and waits for the response
+    /// for at most the timeout.
+    ///
+    /// Drops the connection if that fails: After a timeout, a late response could otherwise be
+    /// mistaken for the answer to the next request.
+    async fn exchange(
+        &self,
+        connection: &mut Option<Connection>,
+        request: &Request,
+    ) -> Result<Response, Error> {
+        let exchange = async {
+            if connection.is_none() {
+                *connection = Some(Connection::open(&self.address).await?);
+            }
+            match connection.as_mut() {
+                Some(connection) => connection.exchange(request).await,
+                None => Err(Error::ConnectionClosed),
+            }
+        };
+        let result = time::timeout(self.timeout, exchange)
+            .await
+            .unwrap_or(Err(Error::Timeout));
+        if result.is_err() {
+            *connection = None;
+        }
+        result
+    }
+}
@@ -0,0 +1,38 @@
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use casper_types::TimeDiff;
+
+/// Default timeout for connecting to the remote signer and for each request.
+const DEFAULT_TIMEOUT: TimeDiff = TimeDiff::from_seconds(2);
+
+/// Default maximum number of consensus certificates signed per second for handshakes.
+const DEFAULT_MAX_HANDSHAKE_SIGNATURE_RATE: u32 = 10;
+
+/// Remote signer configuration.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Setting to enable signing with a remote signer instead of the secret key file.
+    pub enable: bool,
+    /// Address of the remote signer: either `unix:<path>` for a Unix domain socket, or
+    /// `<host>:<port>` for a TCP socket.
+    pub address: String,
+    /// Timeout for connecting to the remote signer, and for each request sent to it.
+    pub timeout: TimeDiff,
+    /// Maximum number of consensus certificates signed per second for network handshakes. `0`
+    /// means unlimited.
+    pub max_handshake_signature_rate: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            enable: false,
+            address: String::new(),
+            timeout: DEFAULT_TIMEOUT,
+            max_handshake_signature_rate: DEFAULT_MAX_HANDSHAKE_SIGNATURE_RATE,
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! The protocol spoken between the node and a remote signer.
+//!
+//! Every message is sent as a frame: its length in bytes as a big-endian `u32`, followed by the
+//! `bincode`-encoded message itself. The node sends a [`Request`] and the signer answers every
+//! request with exactly one [`Response`], in order, on the same connection.
+
+use std::{
+    convert::TryFrom,
+    io::{self, Read, Write},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use casper_types::{PublicKey, Signature};
+
+use super::Error;
+
+/// The maximum size of a frame's payload, in bytes.
+pub const MAX_FRAME_SIZE: u32 = 64 * 1024;
+
+/// A request sent to a remote signer.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Request {
+    /// Requests the public key of the validator whose secret key the signer holds.
+    PublicKey,
+    /// Requests a signature of the given message by the validator with the given public key.
+    Sign {
+        /// The public key of the validator who is to sign the message.
+        public_key: PublicKey,
+        /// The message to be signed.
+        message: Vec<u8>,
+    },
+}
+
+/// A remote signer's response to a [`Request`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Response {
+    /// The public key of the validator whose secret key the signer holds.
+    PublicKey(PublicKey),
+    /// The requested signature.
+    Signature(Signature),
+    /// The signer refused the request, for the given reason.
+    Refused(String),
+}
+
+/// Writes the message as a single frame and flushes the writer.
+pub fn write_frame<W: Write, T: Serialize>(writer: &mut W, message: &T) -> Result<(), Error> {
+    writer.write_all(&encode_frame(message)?)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a single frame and decodes the message in it.
+///
+/// Returns `Ok(None)` if the connection was closed before the start of the frame.
+pub fn read_frame<R: Read, T: DeserializeOwned>(reader: &mut R) -> Result<Option<T>, Error> {
+    let mut size_buf = [0u8; 4];
+    match reader.read_exact(&mut size_buf) {
+        Ok(()) => (),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    }
+    let mut payload = vec![0; payload_size(size_buf)?];
+    reader.read_exact(&mut payload)?;
+    decode_payload(&payload).map(Some)
+}
+
+/// Writes the message as a single frame and flushes the writer, without blocking.
+pub(super) async fn write_frame_async<W: AsyncWrite + Unpin, T: Serialize>(
+    writer: &mut W,
+    message: &T,
+) -> Result<(), Error> {
+    writer.write_all(&encode_frame(message)?).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Reads a single frame and decodes the message in it, without blocking.
+///
+/// Returns `Ok(None)` if the connection was closed before the start of the frame.
+pub(super) async fn read_frame_async<R: AsyncRead + Unpin, T: DeserializeOwned>(
+    reader: &mut R,
+) -> Result<Option<T>, Error> {
+    let mut size_buf = [0u8; 4];
+    match reader.read_exact(&mut size_buf).await {
+        Ok(_) => (),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    }
+    let mut payload = vec![0; payload_size(size_buf)?];
+    reader.read_exact(&mut payload).await?;
+    decode_payload(&payload).map(Some)
+}
+
+/// Encodes the message as a frame, prefixed with its size.
+fn encode_frame<T: Serialize>(message: &T) -> Result<Vec<u8>, Error> {
+    let payload = bincode::serialize(message).map_err(Error::Encoding)?;
+    let size = u32::try_from(payload.len())
+        .ok()
+        .filter(|size| *size <= MAX_FRAME_SIZE)
+        .ok_or(Error::FrameTooLarge(payload.len()))?;
+    let mut frame = Vec::with_capacity(4 + payload.len());
+    frame.extend_from_slice(&size.to_be_bytes());
+    frame.extend_from_slice(&payload);
+    Ok(frame)
+}
+
+/// Returns the payload size given at the start of a frame, if it doesn't exceed the maximum.
+fn payload_size(size_buf: [u8; 4]) -> Result<usize, Error> {
+    let size = u32::from_be_bytes(size_buf);
+    if size > MAX_FRAME_SIZE {
+        return Err(Error::FrameTooLarge(size as usize));
+    }
+    Ok(size as usize)
+}
+
+/// Decodes the payload of a frame.
+fn decode_payload<T: DeserializeOwned>(payload: &[u8]) -> Result<T, Error> {
+    bincode::deserialize(payload).map_err(Error::Decoding)
+}
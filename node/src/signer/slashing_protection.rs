@@ -0,0 +1,804 @@
+//! Slashing protection: a persistent record of the messages signed by the validator.
+//!
+//! A validator equivocates if it signs two different messages of the same kind with the same round
+//! or sequence number in the same era: e.g. two Highway units with the same sequence number, two
+//! different Zug echoes or votes in the same round, or finality signatures for two different blocks
+//! at the same height. The consensus protocols prevent that as long as their own state is intact,
+//! but not if the node's storage is restored from a backup, or the validator is moved to another
+//! host. Before such a message is signed, the [`SlashingProtection`] database is checked for a
+//! different message in the same [`Slot`], and the new message is recorded on disk.
+//!
+//! If signing then fails, e.g. because the remote signer timed out or refused, the record is
+//! revoked again, so that a different message can be signed in the slot later: No signature of the
+//! message was obtained, so none can have been sent to peers.
+//!
+//! Records in eras more than [`RETAINED_ERAS`] before the latest recorded era are pruned, and
+//! signing any message in a pruned era is refused.
+//!
+//! Highway endorsements are not recorded. Endorsing two units on conflicting forks of the same
+//! creator is a fault as well, which can be proven by `Evidence::Endorsements`, but those units can
+//! have different sequence numbers, so such endorsements don't necessarily conflict in any single
+//! slot: Protecting them would require recording the endorsed forks of each creator. Endorsements
+//! are currently disabled in Highway, so this must be addressed before enabling them.
+//!
+//! # Interchange format
+//!
+//! To move a validator to another host, the database is exported on the old host and imported on
+//! the new one, using the `slashing-protection` subcommand of the node. An exported database is a
+//! JSON object of the form:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "public_key": "01d7e3...",
+//!   "min_era_id": 20,
+//!   "records": [
+//!     { "era_id": 29, "kind": "HighwayUnit", "number": 431, "digest": "5c3e91..." },
+//!     { "era_id": 29, "kind": "FinalitySignature", "number": 1203, "digest": "0d8a47..." }
+//!   ]
+//! }
+//! ```
+//!
+//! * `version` is the version of the interchange format, currently 1.
+//! * `public_key` is the hex-encoded public key of the validator.
+//! * `min_era_id` is the earliest era in which messages may be signed.
+//! * `records` holds one entry per signed message: its era, its [`MessageKind`], its round or
+//!   sequence number, or the block height for finality signatures, and the hex-encoded BLAKE2b-256
+//!   hash of the signed bytes.
+//!
+//! Importing merges the records into the database. If the database already has a record for the
+//! same slot with a different digest, the existing record is kept.
+//!
+//! While the database is open, it is locked via a `.lock` file next to it, so that records can't
+//! be lost by importing into the database of a running node. Opening a locked database fails.
+
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Read, Write},
+    mem,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use casper_hashing::Digest;
+use casper_types::{EraId, PublicKey};
+
+/// The number of eras before the latest recorded one whose records are retained.
+pub const RETAINED_ERAS: u64 = 10;
+
+/// The current version of the interchange format.
+pub const INTERCHANGE_VERSION: u32 = 1;
+
+/// The kind of a message which must be signed at most once per era and number.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum MessageKind {
+    /// A Highway unit, numbered by its sequence number.
+    HighwayUnit,
+    /// A Zug echo, numbered by its round ID.
+    ZugEcho,
+    /// A Zug vote, numbered by its round ID.
+    ZugVote,
+    /// A finality signature, numbered by the height of the signed block.
+    FinalitySignature,
+}
+
+/// A slot in which the validator may sign at most one message.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct Slot {
+    /// The era the message belongs to.
+    pub era_id: EraId,
+    /// The kind of message.
+    pub kind: MessageKind,
+    /// The round or sequence number of the message, or the block height for finality signatures.
+    pub number: u64,
+}
+
+impl Display for Slot {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{:?} {} in {}",
+            self.kind, self.number, self.era_id
+        )
+    }
+}
+
+/// An error reading or updating the slashing protection database.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// I/O error accessing the database file.
+    #[error("slashing protection database I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// The database is open in another process, e.g. a running node.
+    #[error("slashing protection database is locked by another process: {}", .0.display())]
+    Locked(PathBuf),
+    /// An entry couldn't be encoded.
+    #[error("failed to encode slashing protection entry: {0}")]
+    Encoding(bincode::Error),
+    /// An entry couldn't be decoded.
+    #[error("failed to decode slashing protection entry: {0}")]
+    Decoding(bincode::Error),
+    /// The database file doesn't start with exactly one header.
+    #[error("slashing protection database has an invalid header")]
+    InvalidHeader,
+    /// The records belong to a different validator.
+    #[error("slashing protection records belong to validator {0}")]
+    WrongPublicKey(Box<PublicKey>),
+    /// The interchange format version is not supported.
+    #[error("unsupported slashing protection interchange format version {0}")]
+    UnsupportedVersion(u32),
+    /// A different message was already signed in the slot.
+    #[error("refusing to sign: a different message was already signed for {0}")]
+    Conflict(Slot),
+    /// The records for the era were pruned.
+    #[error("refusing to sign: {era_id} is before the earliest retained {min_era_id}")]
+    Pruned {
+        /// The era of the message.
+        era_id: EraId,
+        /// The earliest era in which messages may be signed.
+        min_era_id: EraId,
+    },
+}
+
+/// An entry in the database file.
+///
+/// The file is a sequence of entries, each written as its size in bytes as a little-endian `u64`,
+/// followed by the `bincode`-encoded entry itself. It starts with a `Header` and is only appended
+/// to, except when it is compacted after pruning or an import, which replaces the whole file.
+#[derive(Debug, Serialize, Deserialize)]
+enum Entry {
+    /// The public key of the validator whose messages are recorded.
+    Header(PublicKey),
+    /// Messages in eras before the given one must not be signed.
+    MinEra(EraId),
+    /// A signed message, identified by the hash of the signed bytes.
+    Record(Slot, Digest),
+    /// The message recorded in the slot was not signed after all.
+    Revoked(Slot),
+}
+
+/// An exported slashing protection database, in the interchange format.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Interchange {
+    /// The version of the interchange format.
+    pub version: u32,
+    /// The public key of the validator whose messages are recorded.
+    pub public_key: PublicKey,
+    /// The earliest era in which messages may be signed.
+    pub min_era_id: EraId,
+    /// The signed messages.
+    pub records: Vec<InterchangeRecord>,
+}
+
+/// A signed message, in the interchange format.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InterchangeRecord {
+    /// The era the message belongs to.
+    pub era_id: EraId,
+    /// The kind of message.
+    pub kind: MessageKind,
+    /// The round or sequence number of the message, or the block height for finality signatures.
+    pub number: u64,
+    /// The hash of the signed bytes.
+    pub digest: Digest,
+}
+
+/// A persistent record of the messages signed by a validator.
+#[derive(Debug)]
+pub struct SlashingProtection {
+    path: PathBuf,
+    public_key: PublicKey,
+    state: Mutex<State>,
+    /// The lock file, exclusively locked until the database is dropped.
+    _lock_file: File,
+}
+
+#[derive(Debug)]
+struct State {
+    min_era_id: EraId,
+    records: BTreeMap<Slot, Digest>,
+    /// The slots recorded since the database was opened whose message has not been signed yet,
+    /// with the number of attempts to sign it still in progress.
+    unsigned: BTreeMap<Slot, usize>,
+    file: File,
+}
+
+impl SlashingProtection {
+    /// Opens the database at the given path for the validator with the given public key, creating
+    /// it if it doesn't exist.
+    ///
+    /// Fails with [`Error::Locked`] if the database is already open.
+    pub fn open(path: &Path, public_key: &PublicKey) -> Result<Self, Error> {
+        let lock_file = lock(path)?;
+        if !path.exists() {
+            info!(path = %path.display(), "creating slashing protection database");
+            write_file(path, public_key, EraId::default(), &BTreeMap::new())?;
+        }
+        let slashing_protection = Self::open_locked(path, lock_file)?;
+        if slashing_protection.public_key != *public_key {
+            return Err(Error::WrongPublicKey(Box::new(
+                slashing_protection.public_key,
+            )));
+        }
+        Ok(slashing_protection)
+    }
+
+    /// Opens the existing database at the given path.
+    ///
+    /// Fails with [`Error::Locked`] if the database is already open.
+    pub fn open_existing(path: &Path) -> Result<Self, Error> {
+        Self::open_locked(path, lock(path)?)
+    }
+
+    /// Opens the existing database at the given path, whose lock is held by `lock_file`.
+    fn open_locked(path: &Path, lock_file: File) -> Result<Self, Error> {
+        let mut file = OpenOptions::new().read(true).append(true).open(path)?;
+        let mut entries = read_entries(&mut file)?.into_iter();
+        let public_key = match entries.next() {
+            Some(Entry::Header(public_key)) => public_key,
+            _ => return Err(Error::InvalidHeader),
+        };
+        let mut min_era_id = EraId::default();
+        let mut records = BTreeMap::new();
+        for entry in entries {
+            match entry {
+                Entry::Header(_) => return Err(Error::InvalidHeader),
+                Entry::MinEra(era_id) => min_era_id = min_era_id.max(era_id),
+                Entry::Record(slot, digest) => {
+                    records.insert(slot, digest);
+                }
+                Entry::Revoked(slot) => {
+                    records.remove(&slot);
+                }
+            }
+        }
+        records.retain(|slot, _| slot.era_id >= min_era_id);
+        Ok(SlashingProtection {
+            path: path.to_path_buf(),
+            public_key,
+            state: Mutex::new(State {
+                min_era_id,
+                records,
+                unsigned: BTreeMap::new(),
+                file,
+            }),
+            _lock_file: lock_file,
+        })
+    }
+
+    /// Returns the public key of the validator whose messages are recorded.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Checks that no different message was signed in the given slot, and records the message
+    /// with the given digest in it.
+    ///
+    /// The record is on disk when this returns `Ok`, so the message can then be signed. Recording
+    /// the same message again is allowed, e.g. to retry signing it.
+    ///
+    /// Each successful call must be followed by [`SlashingProtection::confirm`] once the message is
+    /// signed, or by [`SlashingProtection::revoke`] if signing it failed.
+    pub fn check_and_record(&self, slot: Slot, digest: Digest) -> Result<(), Error> {
+        let mut state = self.lock();
+        if slot.era_id < state.min_era_id {
+            return Err(Error::Pruned {
+                era_id: slot.era_id,
+                min_era_id: state.min_era_id,
+            });
+        }
+        match state.records.get(&slot) {
+            Some(recorded) if *recorded == digest => {
+                if let Some(attempts) = state.unsigned.get_mut(&slot) {
+                    *attempts += 1;
+                }
+                return Ok(());
+            }
+            Some(_) => return Err(Error::Conflict(slot)),
+            None => (),
+        }
+        append_entry(&mut state.file, &Entry::Record(slot, digest))?;
+        state.records.insert(slot, digest);
+        state.unsigned.insert(slot, 1);
+        let min_era_id = slot.era_id.saturating_sub(RETAINED_ERAS);
+        if min_era_id > state.min_era_id {
+            self.compact(&mut state, min_era_id)?;
+        }
+        Ok(())
+    }
+
+    /// Marks the message recorded in the given slot as signed, so that its record is kept for good.
+    pub fn confirm(&self, slot: Slot) {
+        self.lock().unsigned.remove(&slot);
+    }
+
+    /// Revokes the record of the message in the given slot after an attempt to sign it failed.
+    ///
+    /// The record is only removed if the message was recorded since the database was opened, has
+    /// not been signed, and no other attempt to sign it is still in progress: A different message
+    /// can then be signed in the slot.
+    pub fn revoke(&self, slot: Slot) -> Result<(), Error> {
+        let mut state = self.lock();
+        match state.unsigned.get_mut(&slot) {
+            Some(attempts) if *attempts > 1 => {
+                *attempts -= 1;
+                return Ok(());
+            }
+            Some(_) => (),
+            None => return Ok(()),
+        }
+        append_entry(&mut state.file, &Entry::Revoked(slot))?;
+        state.unsigned.remove(&slot);
+        state.records.remove(&slot);
+        Ok(())
+    }
+
+    /// Returns all records, in the interchange format.
+    pub fn export(&self) -> Interchange {
+        let state = self.lock();
+        let records = state
+            .records
+            .iter()
+            .map(|(slot, digest)| InterchangeRecord {
+                era_id: slot.era_id,
+                kind: slot.kind,
+                number: slot.number,
+                digest: *digest,
+            })
+            .collect();
+        Interchange {
+            version: INTERCHANGE_VERSION,
+            public_key: self.public_key.clone(),
+            min_era_id: state.min_era_id,
+            records,
+        }
+    }
+
+    /// Merges the exported records into the database, and returns the number of new records.
+    pub fn import(&self, interchange: Interchange) -> Result<usize, Error> {
+        if interchange.version != INTERCHANGE_VERSION {
+            return Err(Error::UnsupportedVersion(interchange.version));
+        }
+        if interchange.public_key != self.public_key {
+            return Err(Error::WrongPublicKey(Box::new(interchange.public_key)));
+        }
+        let mut state = self.lock();
+        let mut imported = 0;
+        for record in interchange.records {
+            let slot = Slot {
+                era_id: record.era_id,
+                kind: record.kind,
+                number: record.number,
+            };
+            match state.records.get(&slot) {
+                None => {
+                    state.records.insert(slot, record.digest);
+                    imported += 1;
+                }
+                Some(digest) if *digest != record.digest => {
+                    warn!(%slot, "keeping existing record conflicting with imported one");
+                }
+                Some(_) => (),
+            }
+        }
+        let latest_era_id = state
+            .records
+            .keys()
+            .next_back()
+            .map_or_else(EraId::default, |slot| slot.era_id);
+        let min_era_id = interchange
+            .min_era_id
+            .max(latest_era_id.saturating_sub(RETAINED_ERAS));
+        self.compact(&mut state, min_era_id)?;
+        Ok(imported)
+    }
+
+    /// Raises the earliest era in which messages may be signed to `min_era_id`, removes the
+    /// records of earlier eras, and rewrites the database file.
+    fn compact(&self, state: &mut State, min_era_id: EraId) -> Result<(), Error> {
+        state.min_era_id = state.min_era_id.max(min_era_id);
+        let min_era_id = state.min_era_id;
+        state.records.retain(|slot, _| slot.era_id >= min_era_id);
+        state.unsigned.retain(|slot, _| slot.era_id >= min_era_id);
+        state.file = write_file(
+            &self.path,
+            &self.public_key,
+            state.min_era_id,
+            &state.records,
+        )?;
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .expect("slashing protection lock poisoned")
+    }
+}
+
+/// Exclusively locks the database at the given path, and returns the lock file holding the lock.
+///
+/// A separate file is locked, since the database file is replaced whenever it is compacted.
+fn lock(path: &Path) -> Result<File, Error> {
+    let lock_path = path.with_extension("lock");
+    let lock_file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .open(&lock_path)?;
+    match lock_file.try_lock_exclusive() {
+        Ok(()) => Ok(lock_file),
+        Err(error) if error.raw_os_error() == fs2::lock_contended_error().raw_os_error() => {
+            Err(Error::Locked(lock_path))
+        }
+        Err(error) => Err(Error::Io(error)),
+    }
+}
+
+/// Writes the entry's size and the encoded entry.
+fn write_entry<W: Write>(writer: &mut W, entry: &Entry) -> Result<(), Error> {
+    let payload = bincode::serialize(entry).map_err(Error::Encoding)?;
+    let mut bytes = (payload.len() as u64).to_le_bytes().to_vec();
+    bytes.extend(payload);
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Appends the entry to the file and waits until it is on disk.
+fn append_entry(file: &mut File, entry: &Entry) -> Result<(), Error> {
+    let length = file.metadata()?.len();
+    let result = write_entry(file, entry).and_then(|()| Ok(file.sync_data()?));
+    if result.is_err() {
+        // Don't leave an incomplete entry in the file, followed by later ones.
+        let _ = file.set_len(length);
+    }
+    result
+}
+
+/// Reads all entries from the file.
+///
+/// An incomplete entry at the end, left by a crash while it was being written, is removed: The
+/// message it was recorded for was not signed yet.
+fn read_entries(file: &mut File) -> Result<Vec<Entry>, Error> {
+    let mut bytes = vec![];
+    file.read_to_end(&mut bytes)?;
+    let mut entries = vec![];
+    let mut position = 0;
+    loop {
+        let payload_start = position + mem::size_of::<u64>();
+        let size = match bytes.get(position..payload_start) {
+            Some(size_bytes) => u64::from_le_bytes(
+                <[u8; 8]>::try_from(size_bytes).expect("should have exactly 8 bytes"),
+            ),
+            None => break,
+        };
+        let payload = usize::try_from(size)
+            .ok()
+            .and_then(|size| payload_start.checked_add(size))
+            .and_then(|payload_end| bytes.get(payload_start..payload_end));
+        let payload = match payload {
+            Some(payload) => payload,
+            None => break,
+        };
+        entries.push(bincode::deserialize(payload).map_err(Error::Decoding)?);
+        position = payload_start + payload.len();
+    }
+    if position < bytes.len() {
+        warn!("removing incomplete entry from slashing protection database");
+        file.set_len(position as u64)?;
+    }
+    Ok(entries)
+}
+
+/// Atomically replaces the database file with one holding the given records, and returns the new
+/// file, opened for appending.
+fn write_file(
+    path: &Path,
+    public_key: &PublicKey,
+    min_era_id: EraId,
+    records: &BTreeMap<Slot, Digest>,
+) -> Result<File, Error> {
+    let temp_path = path.with_extension("tmp");
+    let mut writer = BufWriter::new(File::create(&temp_path)?);
+    write_entry(&mut writer, &Entry::Header(public_key.clone()))?;
+    write_entry(&mut writer, &Entry::MinEra(min_era_id))?;
+    for (slot, digest) in records {
+        write_entry(&mut writer, &Entry::Record(*slot, *digest))?;
+    }
+    let file = writer.into_inner().map_err(|error| error.into_error())?;
+    file.sync_all()?;
+    fs::rename(&temp_path, path)?;
+    // Make sure the rename itself is on disk.
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(OpenOptions::new().append(true).open(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use assert_matches::assert_matches;
+
+    use casper_types::{testing::TestRng, SecretKey};
+
+    use super::*;
+
+    fn new_public_key(rng: &mut TestRng) -> PublicKey {
+        PublicKey::from(&SecretKey::random(rng))
+    }
+
+    fn slot(era_id: u64, kind: MessageKind, number: u64) -> Slot {
+        Slot {
+            era_id: EraId::new(era_id),
+            kind,
+            number,
+        }
+    }
+
+    #[test]
+    fn should_refuse_conflicting_messages() {
+        let mut rng = TestRng::new();
+        let tempdir = tempfile::tempdir().expect("should create temp dir");
+        let path = tempdir.path().join("slashing_protection.db");
+        let db = SlashingProtection::open(&path, &new_public_key(&mut rng)).expect("should open");
+
+        let unit = slot(5, MessageKind::HighwayUnit, 3);
+        db.check_and_record(unit, Digest::hash(b"unit"))
+            .expect("should record");
+        // Signing the same message again is allowed.
+        db.check_and_record(unit, Digest::hash(b"unit"))
+            .expect("should record");
+        assert_matches!(
+            db.check_and_record(unit, Digest::hash(b"other unit")),
+            Err(Error::Conflict(conflict)) if conflict == unit
+        );
+        // Different kinds, numbers and eras are different slots.
+        for other in [
+            slot(5, MessageKind::ZugEcho, 3),
+            slot(5, MessageKind::HighwayUnit, 4),
+            slot(6, MessageKind::HighwayUnit, 3),
+        ] {
+            db.check_and_record(other, Digest::hash(b"other unit"))
+                .expect("should record");
+        }
+    }
+
+    #[test]
+    fn should_persist_records() {
+        let mut rng = TestRng::new();
+        let tempdir = tempfile::tempdir().expect("should create temp dir");
+        let path = tempdir.path().join("slashing_protection.db");
+        let public_key = new_public_key(&mut rng);
+        let vote = slot(2, MessageKind::ZugVote, 7);
+        {
+            let db = SlashingProtection::open(&path, &public_key).expect("should open");
+            db.check_and_record(vote, Digest::hash(b"true"))
+                .expect("should record");
+        }
+
+        let db = SlashingProtection::open(&path, &public_key).expect("should reopen");
+        assert_matches!(
+            db.check_and_record(vote, Digest::hash(b"false")),
+            Err(Error::Conflict(_))
+        );
+        drop(db);
+        assert_matches!(
+            SlashingProtection::open(&path, &new_public_key(&mut rng)),
+            Err(Error::WrongPublicKey(_))
+        );
+    }
+
+    #[test]
+    fn should_lock_database_while_open() {
+        let mut rng = TestRng::new();
+        let tempdir = tempfile::tempdir().expect("should create temp dir");
+        let path = tempdir.path().join("slashing_protection.db");
+        let public_key = new_public_key(&mut rng);
+        let db = SlashingProtection::open(&path, &public_key).expect("should open");
+
+        assert_matches!(
+            SlashingProtection::open(&path, &public_key),
+            Err(Error::Locked(_))
+        );
+        assert_matches!(
+            SlashingProtection::open_existing(&path),
+            Err(Error::Locked(_))
+        );
+        // Compacting replaces the database file, but not the lock file.
+        db.check_and_record(
+            slot(RETAINED_ERAS + 1, MessageKind::FinalitySignature, 1),
+            Digest::hash(b"block 1"),
+        )
+        .expect("should record");
+        assert_matches!(
+            SlashingProtection::open_existing(&path),
+            Err(Error::Locked(_))
+        );
+
+        drop(db);
+        SlashingProtection::open_existing(&path).expect("should reopen");
+    }
+
+    #[test]
+    fn should_prune_old_eras() {
+        let mut rng = TestRng::new();
+        let tempdir = tempfile::tempdir().expect("should create temp dir");
+        let path = tempdir.path().join("slashing_protection.db");
+        let public_key = new_public_key(&mut rng);
+        {
+            let db = SlashingProtection::open(&path, &public_key).expect("should open");
+            db.check_and_record(
+                slot(1, MessageKind::FinalitySignature, 10),
+                Digest::hash(b"block 10"),
+            )
+            .expect("should record");
+            db.check_and_record(
+                slot(RETAINED_ERAS + 2, MessageKind::FinalitySignature, 90),
+                Digest::hash(b"block 90"),
+            )
+            .expect("should record");
+            assert_eq!(db.export().records.len(), 1);
+        }
+
+        let db = SlashingProtection::open(&path, &public_key).expect("should reopen");
+        assert_matches!(
+            db.check_and_record(
+                slot(1, MessageKind::FinalitySignature, 11),
+                Digest::hash(b"block 11"),
+            ),
+            Err(Error::Pruned { .. })
+        );
+        db.check_and_record(
+            slot(2, MessageKind::FinalitySignature, 20),
+            Digest::hash(b"block 20"),
+        )
+        .expect("should record");
+    }
+
+    #[test]
+    fn should_remove_incomplete_entry() {
+        let mut rng = TestRng::new();
+        let tempdir = tempfile::tempdir().expect("should create temp dir");
+        let path = tempdir.path().join("slashing_protection.db");
+        let public_key = new_public_key(&mut rng);
+        let unit = slot(3, MessageKind::HighwayUnit, 0);
+        {
+            let db = SlashingProtection::open(&path, &public_key).expect("should open");
+            db.check_and_record(unit, Digest::hash(b"unit"))
+                .expect("should record");
+        }
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("should open file");
+        file.write_all(&100u64.to_le_bytes()[..5])
+            .expect("should write");
+
+        for _ in 0..2 {
+            let db = SlashingProtection::open(&path, &public_key).expect("should reopen");
+            assert_matches!(
+                db.check_and_record(unit, Digest::hash(b"other unit")),
+                Err(Error::Conflict(_))
+            );
+            db.check_and_record(slot(3, MessageKind::HighwayUnit, 1), Digest::hash(b"next"))
+                .expect("should record");
+        }
+    }
+
+    #[test]
+    fn should_revoke_records_of_unsigned_messages() {
+        let mut rng = TestRng::new();
+        let tempdir = tempfile::tempdir().expect("should create temp dir");
+        let path = tempdir.path().join("slashing_protection.db");
+        let public_key = new_public_key(&mut rng);
+        let unit = slot(7, MessageKind::HighwayUnit, 2);
+        let signed_unit = slot(7, MessageKind::HighwayUnit, 3);
+        {
+            let db = SlashingProtection::open(&path, &public_key).expect("should open");
+            db.check_and_record(unit, Digest::hash(b"unit"))
+                .expect("should record");
+            db.revoke(unit).expect("should revoke");
+            db.check_and_record(unit, Digest::hash(b"other unit"))
+                .expect("should record");
+
+            // While another attempt to sign the message is in progress, its record is kept.
+            db.check_and_record(unit, Digest::hash(b"other unit"))
+                .expect("should record");
+            db.revoke(unit).expect("should revoke");
+            assert_matches!(
+                db.check_and_record(unit, Digest::hash(b"unit")),
+                Err(Error::Conflict(_))
+            );
+            db.confirm(unit);
+
+            // Once signed, the message's record is never revoked.
+            db.check_and_record(signed_unit, Digest::hash(b"signed unit"))
+                .expect("should record");
+            db.confirm(signed_unit);
+            db.check_and_record(signed_unit, Digest::hash(b"signed unit"))
+                .expect("should record");
+            db.revoke(signed_unit).expect("should revoke");
+            assert_matches!(
+                db.check_and_record(signed_unit, Digest::hash(b"unit")),
+                Err(Error::Conflict(_))
+            );
+        }
+
+        let db = SlashingProtection::open(&path, &public_key).expect("should reopen");
+        assert_matches!(
+            db.check_and_record(unit, Digest::hash(b"unit")),
+            Err(Error::Conflict(_))
+        );
+        db.check_and_record(unit, Digest::hash(b"other unit"))
+            .expect("should record");
+        // Records made before the database was reopened are never revoked.
+        db.revoke(unit).expect("should revoke");
+        assert_matches!(
+            db.check_and_record(unit, Digest::hash(b"unit")),
+            Err(Error::Conflict(_))
+        );
+    }
+
+    #[test]
+    fn should_export_and_import() {
+        let mut rng = TestRng::new();
+        let tempdir = tempfile::tempdir().expect("should create temp dir");
+        let public_key = new_public_key(&mut rng);
+        let old_db = SlashingProtection::open(&tempdir.path().join("old.db"), &public_key)
+            .expect("should open");
+        let echo = slot(4, MessageKind::ZugEcho, 1);
+        let vote = slot(4, MessageKind::ZugVote, 1);
+        old_db
+            .check_and_record(echo, Digest::hash(b"echo"))
+            .expect("should record");
+        old_db
+            .check_and_record(vote, Digest::hash(b"vote"))
+            .expect("should record");
+        let json = serde_json::to_string(&old_db.export()).expect("should serialize");
+        let interchange: Interchange = serde_json::from_str(&json).expect("should deserialize");
+        assert_eq!(interchange, old_db.export());
+
+        let new_db = SlashingProtection::open(&tempdir.path().join("new.db"), &public_key)
+            .expect("should open");
+        new_db
+            .check_and_record(echo, Digest::hash(b"conflicting echo"))
+            .expect("should record");
+        assert_eq!(
+            new_db.import(interchange.clone()).expect("should import"),
+            1
+        );
+        // The existing conflicting record is kept.
+        new_db
+            .check_and_record(echo, Digest::hash(b"conflicting echo"))
+            .expect("should record");
+        assert_matches!(
+            new_db.check_and_record(vote, Digest::hash(b"other vote")),
+            Err(Error::Conflict(_))
+        );
+
+        let wrong_version = Interchange {
+            version: INTERCHANGE_VERSION + 1,
+            ..interchange.clone()
+        };
+        assert_matches!(
+            new_db.import(wrong_version),
+            Err(Error::UnsupportedVersion(_))
+        );
+        let wrong_key = Interchange {
+            public_key: new_public_key(&mut rng),
+            ..interchange
+        };
+        assert_matches!(new_db.import(wrong_key), Err(Error::WrongPublicKey(_)));
+    }
+}
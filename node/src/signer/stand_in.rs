@@ -0,0 +1,121 @@
+//! A minimal remote signer holding a single secret key, for testing nodes that use a remote
+//! signer.
+
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    os::unix::net::UnixListener,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use tracing::{debug, info, warn};
+
+use casper_types::{crypto, PublicKey, SecretKey};
+
+use super::{
+    protocol::{self, Request, Response},
+    Address, Error,
+};
+
+/// A signer which answers requests for a single secret key.
+///
+/// It can be configured to refuse all signing requests, or to delay its responses, to simulate a
+/// misbehaving or unresponsive signer.
+#[derive(Clone)]
+pub struct StandInSigner {
+    secret_key: Arc<SecretKey>,
+    public_key: PublicKey,
+    refuse: bool,
+    delay: Duration,
+}
+
+impl StandInSigner {
+    /// Creates a new stand-in signer for the given secret key.
+    pub fn new(secret_key: Arc<SecretKey>) -> Self {
+        let public_key = PublicKey::from(secret_key.as_ref());
+        StandInSigner {
+            secret_key,
+            public_key,
+            refuse: false,
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Sets whether all signing requests are refused.
+    pub fn with_refuse(mut self, refuse: bool) -> Self {
+        self.refuse = refuse;
+        self
+    }
+
+    /// Sets the delay before each response to a signing request is sent.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Listens on the given address and serves every incoming connection on its own thread.
+    ///
+    /// Only returns if the listener fails.
+    pub fn serve(self, address: &Address) -> Result<(), Error> {
+        info!(%address, public_key = %self.public_key, "stand-in signer listening");
+        match address {
+            Address::Tcp(socket_addr) => {
+                let listener = TcpListener::bind(socket_addr)?;
+                for stream in listener.incoming() {
+                    let signer = self.clone();
+                    let stream = stream?;
+                    thread::spawn(move || signer.handle_connection(stream));
+                }
+            }
+            Address::Unix(path) => {
+                let listener = UnixListener::bind(path)?;
+                for stream in listener.incoming() {
+                    let signer = self.clone();
+                    let stream = stream?;
+                    thread::spawn(move || signer.handle_connection(stream));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Answers requests on the given connection until it is closed.
+    pub fn handle_connection<S: Read + Write>(&self, mut stream: S) {
+        loop {
+            let request = match protocol::read_frame(&mut stream) {
+                Ok(Some(request)) => request,
+                Ok(None) => return,
+                Err(error) => {
+                    warn!(%error, "failed to read request");
+                    return;
+                }
+            };
+            if matches!(request, Request::Sign { .. }) && !self.delay.is_zero() {
+                thread::sleep(self.delay);
+            }
+            let response = self.respond(request);
+            if let Err(error) = protocol::write_frame(&mut stream, &response) {
+                warn!(%error, "failed to send response");
+                return;
+            }
+        }
+    }
+
+    fn respond(&self, request: Request) -> Response {
+        match request {
+            Request::PublicKey => Response::PublicKey(self.public_key.clone()),
+            Request::Sign { public_key, .. } if public_key != self.public_key => {
+                Response::Refused(format!("unknown public key {}", public_key))
+            }
+            Request::Sign { .. } if self.refuse => {
+                debug!("refusing to sign");
+                Response::Refused("signing is disabled".to_string())
+            }
+            Request::Sign { message, .. } => {
+                Response::Signature(crypto::sign(message, &self.secret_key, &self.public_key))
+            }
+        }
+    }
+}
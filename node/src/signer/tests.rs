@@ -0,0 +1,329 @@
+use std::{
+    io::Cursor,
+    net::TcpListener,
+    os::unix::net::UnixListener,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use assert_matches::assert_matches;
+
+use casper_types::{crypto, testing::TestRng, EraId, SecretKey, TimeDiff};
+
+use super::{
+    slashing_protection::{self, MessageKind},
+    *,
+};
+
+const MESSAGE: &[u8] = b"block hash and era";
+
+fn new_secret_key(rng: &mut TestRng) -> Arc<SecretKey> {
+    Arc::new(SecretKey::random(rng))
+}
+
+/// Serves every connection accepted by the TCP listener with the stand-in signer, and returns the
+/// configuration to connect to it.
+fn spawn_tcp_signer(stand_in: StandInSigner, timeout: TimeDiff) -> Config {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("should bind");
+    let address = listener
+        .local_addr()
+        .expect("should have address")
+        .to_string();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stand_in = stand_in.clone();
+            let stream = stream.expect("should accept");
+            thread::spawn(move || stand_in.handle_connection(stream));
+        }
+    });
+    Config {
+        enable: true,
+        address,
+        timeout,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn should_sign_locally() {
+    let mut rng = TestRng::new();
+    let secret_key = new_secret_key(&mut rng);
+    let signer = Signer::local(Arc::clone(&secret_key));
+    assert_eq!(*signer.public_key(), PublicKey::from(secret_key.as_ref()));
+
+    let signature = signer.sign_async(MESSAGE).await.expect("should sign");
+    assert!(crypto::verify(MESSAGE, &signature, signer.public_key()).is_ok());
+}
+
+#[test]
+fn should_refuse_to_sign_conflicting_messages() {
+    let mut rng = TestRng::new();
+    let tempdir = tempfile::tempdir().expect("should create temp dir");
+    let secret_key = new_secret_key(&mut rng);
+    let public_key = PublicKey::from(secret_key.as_ref());
+    let slashing_protection =
+        SlashingProtection::open(&tempdir.path().join("slashing_protection.db"), &public_key)
+            .expect("should open slashing protection database");
+    let signer = Signer::local(secret_key).with_slashing_protection(slashing_protection);
+    let slot = Slot {
+        era_id: EraId::new(1),
+        kind: MessageKind::FinalitySignature,
+        number: 42,
+    };
+
+    let signature = signer.sign_unique(slot, MESSAGE).expect("should sign");
+    assert!(crypto::verify(MESSAGE, &signature, signer.public_key()).is_ok());
+    signer
+        .sign_unique(slot, MESSAGE)
+        .expect("should sign again");
+    assert_matches!(
+        signer.sign_unique(slot, b"another block hash"),
+        Err(Error::SlashingProtection(
+            slashing_protection::Error::Conflict(_)
+        ))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn should_sign_remotely_over_tcp() {
+    let mut rng = TestRng::new();
+    let secret_key = new_secret_key(&mut rng);
+    let config = spawn_tcp_signer(
+        StandInSigner::new(Arc::clone(&secret_key)),
+        TimeDiff::from_seconds(5),
+    );
+
+    let signer = Signer::remote(&config).expect("should connect");
+    assert_eq!(*signer.public_key(), PublicKey::from(secret_key.as_ref()));
+
+    for _ in 0..3 {
+        let signature = signer.sign_async(MESSAGE).await.expect("should sign");
+        assert!(crypto::verify(MESSAGE, &signature, signer.public_key()).is_ok());
+    }
+    // Synchronous signing waits for the same connection.
+    let signature = signer.sign(MESSAGE).expect("should sign");
+    assert!(crypto::verify(MESSAGE, &signature, signer.public_key()).is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn should_sign_remotely_over_unix_socket() {
+    let mut rng = TestRng::new();
+    let secret_key = new_secret_key(&mut rng);
+    let tempdir = tempfile::tempdir().expect("should create temp dir");
+    let path = tempdir.path().join("signer.sock");
+    let listener = UnixListener::bind(&path).expect("should bind");
+    let stand_in = StandInSigner::new(Arc::clone(&secret_key));
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            stand_in.handle_connection(stream.expect("should accept"));
+        }
+    });
+    let config = Config {
+        enable: true,
+        address: format!("unix:{}", path.display()),
+        timeout: TimeDiff::from_seconds(5),
+        ..Config::default()
+    };
+
+    let signer = Signer::remote(&config).expect("should connect");
+    assert_eq!(*signer.public_key(), PublicKey::from(secret_key.as_ref()));
+
+    let signature = signer.sign_async(MESSAGE).await.expect("should sign");
+    assert!(crypto::verify(MESSAGE, &signature, signer.public_key()).is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn should_report_refusal() {
+    let mut rng = TestRng::new();
+    let stand_in = StandInSigner::new(new_secret_key(&mut rng)).with_refuse(true);
+    let config = spawn_tcp_signer(stand_in, TimeDiff::from_seconds(5));
+
+    let signer = Signer::remote(&config).expect("should connect");
+    assert_matches!(signer.sign_async(MESSAGE).await, Err(Error::Refused(_)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn should_revoke_slashing_protection_record_if_signing_fails() {
+    let mut rng = TestRng::new();
+    let tempdir = tempfile::tempdir().expect("should create temp dir");
+    let path = tempdir.path().join("slashing_protection.db");
+    let secret_key = new_secret_key(&mut rng);
+    let public_key = PublicKey::from(secret_key.as_ref());
+    let slot = Slot {
+        era_id: EraId::new(1),
+        kind: MessageKind::HighwayUnit,
+        number: 7,
+    };
+
+    let stand_in = StandInSigner::new(Arc::clone(&secret_key)).with_refuse(true);
+    let config = spawn_tcp_signer(stand_in, TimeDiff::from_seconds(5));
+    let slashing_protection =
+        SlashingProtection::open(&path, &public_key).expect("should open database");
+    let signer = Signer::remote(&config)
+        .expect("should connect")
+        .with_slashing_protection(slashing_protection);
+    assert_matches!(
+        signer.sign_unique_async(slot, MESSAGE).await,
+        Err(Error::Refused(_))
+    );
+    drop(signer);
+
+    // The refused message wasn't signed, so a different one can be signed in its slot, even after
+    // the database is reopened.
+    let slashing_protection =
+        SlashingProtection::open(&path, &public_key).expect("should reopen database");
+    let signer = Signer::local(secret_key).with_slashing_protection(slashing_protection);
+    signer
+        .sign_unique_async(slot, b"another unit")
+        .await
+        .expect("should sign");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn should_time_out_and_reconnect() {
+    let mut rng = TestRng::new();
+    let stand_in =
+        StandInSigner::new(new_secret_key(&mut rng)).with_delay(Duration::from_millis(500));
+    let config = spawn_tcp_signer(stand_in, TimeDiff::from_millis(100));
+
+    let signer = Signer::remote(&config).expect("should connect");
+    assert_matches!(signer.sign_async(MESSAGE).await, Err(Error::Timeout));
+    // The late response must not be taken as the answer to the next request.
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_matches!(signer.sign_async(MESSAGE).await, Err(Error::Timeout));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn should_sign_handshakes_on_separate_connection() {
+    let mut rng = TestRng::new();
+    let stand_in = StandInSigner::new(new_secret_key(&mut rng)).with_delay(Duration::from_secs(1));
+    let config = spawn_tcp_signer(stand_in, TimeDiff::from_seconds(5));
+    let signer = Signer::remote(&config).expect("should connect");
+
+    // A handshake signed while another request is in flight doesn't wait for it.
+    let slow_signer = signer.clone();
+    let slow_request = tokio::spawn(async move { slow_signer.sign_async(MESSAGE).await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let start = Instant::now();
+    let signature = signer.sign_handshake(MESSAGE).await.expect("should sign");
+    assert!(crypto::verify(MESSAGE, &signature, signer.public_key()).is_ok());
+    // On a shared connection, this would have taken almost two delays.
+    assert!(start.elapsed() < Duration::from_millis(1500));
+    slow_request
+        .await
+        .expect("should not panic")
+        .expect("should sign");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn should_rate_limit_handshake_signatures() {
+    let mut rng = TestRng::new();
+    let mut config = spawn_tcp_signer(
+        StandInSigner::new(new_secret_key(&mut rng)),
+        TimeDiff::from_seconds(5),
+    );
+    config.max_handshake_signature_rate = 2;
+    let signer = Signer::remote(&config).expect("should connect");
+
+    signer.sign_handshake(MESSAGE).await.expect("should sign");
+    signer.sign_handshake(MESSAGE).await.expect("should sign");
+    assert_matches!(
+        signer.sign_handshake(MESSAGE).await,
+        Err(Error::RateLimited)
+    );
+    // Other requests are not limited.
+    signer.sign_async(MESSAGE).await.expect("should sign");
+
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    signer.sign_handshake(MESSAGE).await.expect("should sign");
+}
+
+#[test]
+fn should_refill_rate_limiter_over_time() {
+    let start = Instant::now();
+    let mut rate_limiter = RateLimiter::new(4);
+    for _ in 0..4 {
+        assert!(rate_limiter.try_acquire(start));
+    }
+    assert!(!rate_limiter.try_acquire(start));
+    assert!(rate_limiter.try_acquire(start + Duration::from_millis(250)));
+    assert!(!rate_limiter.try_acquire(start + Duration::from_millis(250)));
+    // No more than a second's worth of tokens is accumulated.
+    let later = start + Duration::from_secs(10);
+    for _ in 0..4 {
+        assert!(rate_limiter.try_acquire(later));
+    }
+    assert!(!rate_limiter.try_acquire(later));
+
+    let mut unlimited = RateLimiter::new(0);
+    for _ in 0..100 {
+        assert!(unlimited.try_acquire(start));
+    }
+}
+
+#[test]
+fn should_fail_to_connect_to_missing_signer() {
+    let tempdir = tempfile::tempdir().expect("should create temp dir");
+    let config = Config {
+        enable: true,
+        address: format!("unix:{}", tempdir.path().join("missing.sock").display()),
+        timeout: TimeDiff::from_seconds(1),
+        ..Config::default()
+    };
+    assert_matches!(Signer::remote(&config), Err(Error::Io(_)));
+}
+
+#[test]
+fn should_parse_addresses() {
+    assert_eq!(
+        "unix:/run/signer.sock".parse::<Address>().unwrap(),
+        Address::Unix(PathBuf::from("/run/signer.sock"))
+    );
+    assert_eq!(
+        "127.0.0.1:7777".parse::<Address>().unwrap(),
+        Address::Tcp(SocketAddr::from(([127, 0, 0, 1], 7777)))
+    );
+    assert_matches!(
+        "not an address".parse::<Address>(),
+        Err(Error::InvalidAddress(_))
+    );
+}
+
+#[test]
+fn should_roundtrip_frames() {
+    let mut rng = TestRng::new();
+    let public_key = PublicKey::from(new_secret_key(&mut rng).as_ref());
+    let request = Request::Sign {
+        public_key,
+        message: MESSAGE.to_vec(),
+    };
+
+    let mut buffer = vec![];
+    protocol::write_frame(&mut buffer, &request).expect("should write");
+    let mut reader = Cursor::new(buffer);
+    let decoded: Option<Request> = protocol::read_frame(&mut reader).expect("should read");
+    assert_eq!(decoded, Some(request));
+    // There are no more frames.
+    let decoded: Option<Request> = protocol::read_frame(&mut reader).expect("should read");
+    assert_eq!(decoded, None);
+}
+
+#[test]
+fn should_reject_oversized_frames() {
+    let mut rng = TestRng::new();
+    let request = Request::Sign {
+        public_key: PublicKey::from(new_secret_key(&mut rng).as_ref()),
+        message: vec![0; protocol::MAX_FRAME_SIZE as usize],
+    };
+    assert_matches!(
+        protocol::write_frame(&mut vec![], &request),
+        Err(Error::FrameTooLarge(_))
+    );
+
+    let mut reader = Cursor::new((protocol::MAX_FRAME_SIZE + 1).to_be_bytes().to_vec());
+    assert_matches!(
+        protocol::read_frame::<_, Request>(&mut reader),
+        Err(Error::FrameTooLarge(_))
+    );
+}
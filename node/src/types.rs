@@ -31,7 +31,7 @@ pub(crate) use block::{
     MetaBlockState,
 };
 pub use block::{
-    json_compatibility::{JsonBlock, JsonBlockHeader},
+    json_compatibility::{JsonBlock, JsonBlockBody, JsonBlockHeader},
     Block, BlockAndDeploys, BlockBody, BlockExecutionResultsOrChunk,
     BlockExecutionResultsOrChunkId, BlockExecutionResultsOrChunkIdDisplay, BlockHash, BlockHeader,
     BlockSignatures, FinalitySignature, FinalizedBlock,
@@ -34,7 +34,6 @@ impl AvailableBlockRange {
     pub const RANGE_0_0: AvailableBlockRange = AvailableBlockRange { low: 0, high: 0 };
 
     /// Constructs a new `AvailableBlockRange` with the given limits.
-    #[cfg(test)]
     pub fn new(low: u64, high: u64) -> Self {
         assert!(
             low <= high,
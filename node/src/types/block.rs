@@ -47,6 +47,11 @@ use crate::{
     },
     effect::GossipTarget,
     rpcs::docs::DocExample,
+    signer::{
+        self,
+        slashing_protection::{MessageKind, Slot},
+        Signer,
+    },
     types::{
         error::{BlockCreationError, BlockHeaderWithMetadataValidationError, BlockValidationError},
         Approval, Chunkable, Deploy, DeployHash, DeployHashWithApprovals, DeployId,
@@ -2402,8 +2407,7 @@ impl FinalitySignature {
         secret_key: &SecretKey,
         public_key: PublicKey,
     ) -> Self {
-        let mut bytes = block_hash.inner().into_vec();
-        bytes.extend_from_slice(&era_id.to_le_bytes());
+        let bytes = Self::bytes_to_sign(&block_hash, era_id);
         let signature = crypto::sign(bytes, secret_key, &public_key);
         FinalitySignature {
             block_hash,
@@ -2414,6 +2418,32 @@ impl FinalitySignature {
         }
     }
 
+    /// Create an instance of `FinalitySignature`, signed by the given signer.
+    ///
+    /// Slashing protection refuses to sign a different block at the same height in the same era.
+    pub(crate) async fn sign(
+        block_hash: BlockHash,
+        block_height: u64,
+        era_id: EraId,
+        signer: &Signer,
+    ) -> Result<Self, signer::Error> {
+        let slot = Slot {
+            era_id,
+            kind: MessageKind::FinalitySignature,
+            number: block_height,
+        };
+        let signature = signer
+            .sign_unique_async(slot, Self::bytes_to_sign(&block_hash, era_id))
+            .await?;
+        Ok(FinalitySignature {
+            block_hash,
+            era_id,
+            signature,
+            public_key: signer.public_key().clone(),
+            is_verified: OnceCell::with_value(Ok(())),
+        })
+    }
+
     /// Create an instance of `FinalitySignature`.
     pub fn new(
         block_hash: BlockHash,
@@ -2434,14 +2464,19 @@ impl FinalitySignature {
     pub fn is_verified(&self) -> Result<(), crypto::Error> {
         self.is_verified
             .get_or_init(|| {
-                // NOTE: This needs to be in sync with the `new` constructor.
-                let mut bytes = self.block_hash.inner().into_vec();
-                bytes.extend_from_slice(&self.era_id.to_le_bytes());
+                let bytes = Self::bytes_to_sign(&self.block_hash, self.era_id);
                 crypto::verify(bytes, &self.signature, &self.public_key)
             })
             .clone()
     }
 
+    /// Returns the bytes covered by the signature.
+    fn bytes_to_sign(block_hash: &BlockHash, era_id: EraId) -> Vec<u8> {
+        let mut bytes = block_hash.inner().into_vec();
+        bytes.extend_from_slice(&era_id.to_le_bytes());
+        bytes
+    }
+
     /// Returns a random `FinalitySignature` for the provided `block_hash` and `era_id`.
     #[cfg(any(feature = "testing", test))]
     pub fn random_for_block(block_hash: BlockHash, era_id: u64) -> Self {
@@ -112,6 +112,8 @@ pub struct CoreConfig {
     pub(crate) refund_handling: RefundHandling,
     /// Fee handling.
     pub(crate) fee_handling: FeeHandling,
+    /// The maximum amount of gas an account's deploy validator can use when authorizing a deploy.
+    pub(crate) deploy_validator_gas_limit: u64,
 }
 
 impl CoreConfig {
@@ -209,6 +211,7 @@ impl CoreConfig {
         } else {
             FeeHandling::Accumulate
         };
+        let deploy_validator_gas_limit = rng.gen_range(0..10_000_000_000);
 
         CoreConfig {
             era_duration,
@@ -237,6 +240,7 @@ impl CoreConfig {
             compute_rewards,
             refund_handling,
             fee_handling,
+            deploy_validator_gas_limit,
         }
     }
 }
@@ -273,6 +277,7 @@ impl ToBytes for CoreConfig {
         buffer.extend(self.administrators.to_bytes()?);
         buffer.extend(self.refund_handling.to_bytes()?);
         buffer.extend(self.fee_handling.to_bytes()?);
+        buffer.extend(self.deploy_validator_gas_limit.to_bytes()?);
         Ok(buffer)
     }
 
@@ -305,6 +310,7 @@ impl ToBytes for CoreConfig {
             + self.administrators.serialized_length()
             + self.refund_handling.serialized_length()
             + self.fee_handling.serialized_length()
+            + self.deploy_validator_gas_limit.serialized_length()
     }
 }
 
@@ -337,6 +343,7 @@ impl FromBytes for CoreConfig {
         let (administrative_accounts, remainder) = FromBytes::from_bytes(remainder)?;
         let (refund_handling, remainder) = FromBytes::from_bytes(remainder)?;
         let (fee_handling, remainder) = FromBytes::from_bytes(remainder)?;
+        let (deploy_validator_gas_limit, remainder) = u64::from_bytes(remainder)?;
         let config = CoreConfig {
             era_duration,
             minimum_era_height,
@@ -364,6 +371,7 @@ impl FromBytes for CoreConfig {
             administrators: administrative_accounts,
             refund_handling,
             fee_handling,
+            deploy_validator_gas_limit,
         };
         Ok((config, remainder))
     }
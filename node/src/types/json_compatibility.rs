@@ -5,7 +5,9 @@ mod auction_state;
 mod contracts;
 mod stored_value;
 
-use casper_types::{contracts::NamedKeys, NamedKey};
+use thiserror::Error;
+
+use casper_types::{contracts::NamedKeys, Key, NamedKey};
 
 pub use account::Account;
 pub use auction_state::AuctionState;
@@ -23,3 +25,38 @@ pub fn vectorize(keys: &NamedKeys) -> Vec<NamedKey> {
         .collect();
     named_keys
 }
+
+/// A helper function to change a `Vec<NamedKey>` back into NamedKeys.
+pub fn unvectorize(named_keys: &[NamedKey]) -> Result<NamedKeys, ConversionError> {
+    named_keys
+        .iter()
+        .map(|named_key| {
+            let key = Key::from_formatted_str(&named_key.key).map_err(|_| {
+                ConversionError::InvalidNamedKey {
+                    name: named_key.name.clone(),
+                    key: named_key.key.clone(),
+                }
+            })?;
+            Ok((named_key.name.clone(), key))
+        })
+        .collect()
+}
+
+/// Error converting a type defined in this module back into the type it maps to.
+#[derive(Debug, Error)]
+pub enum ConversionError {
+    /// The key of a named key could not be parsed.
+    #[error("invalid key {key} of named key {name}")]
+    InvalidNamedKey {
+        /// The name of the named key.
+        name: String,
+        /// The unparsable key.
+        key: String,
+    },
+    /// The deployment threshold of an account exceeds its key management threshold.
+    #[error("deployment threshold exceeds key management threshold")]
+    InvalidActionThresholds,
+    /// The contract Wasm could not be decoded.
+    #[error("invalid contract wasm: {0}")]
+    InvalidContractWasm(String),
+}
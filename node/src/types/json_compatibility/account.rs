@@ -1,14 +1,22 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
+use std::{collections::BTreeMap, convert::TryFrom};
+
 use datasize::DataSize;
 use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::{rpcs::docs::DocExample, types::json_compatibility::vectorize};
+use crate::{
+    rpcs::docs::DocExample,
+    types::json_compatibility::{unvectorize, vectorize, ConversionError},
+};
 use casper_types::{
-    account::{Account as ExecutionEngineAccount, AccountHash},
+    account::{
+        Account as ExecutionEngineAccount, AccountHash,
+        ActionThresholds as ExecutionEngineActionThresholds, AssociatedKeys, Weight,
+    },
     NamedKey, PublicKey, SecretKey, URef,
 };
 
@@ -92,6 +100,35 @@ impl From<&ExecutionEngineAccount> for Account {
     }
 }
 
+impl TryFrom<Account> for ExecutionEngineAccount {
+    type Error = ConversionError;
+
+    fn try_from(account: Account) -> Result<Self, Self::Error> {
+        let associated_keys: BTreeMap<AccountHash, Weight> = account
+            .associated_keys
+            .iter()
+            .map(|associated_key| {
+                (
+                    associated_key.account_hash,
+                    Weight::new(associated_key.weight),
+                )
+            })
+            .collect();
+        let action_thresholds = ExecutionEngineActionThresholds::new(
+            Weight::new(account.action_thresholds.deployment),
+            Weight::new(account.action_thresholds.key_management),
+        )
+        .map_err(|_| ConversionError::InvalidActionThresholds)?;
+        Ok(ExecutionEngineAccount::new(
+            account.account_hash,
+            unvectorize(&account.named_keys)?,
+            account.main_purse,
+            AssociatedKeys::from(associated_keys),
+            action_thresholds,
+        ))
+    }
+}
+
 impl DocExample for Account {
     fn doc_example() -> &'static Self {
         &ACCOUNT
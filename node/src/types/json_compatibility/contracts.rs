@@ -1,15 +1,18 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
+use std::convert::TryFrom;
+
 use datasize::DataSize;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::types::json_compatibility::vectorize;
+use crate::types::json_compatibility::{unvectorize, vectorize, ConversionError};
 use casper_types::{
-    contracts::ContractPackageStatus, Contract as DomainContract, ContractHash,
-    ContractPackage as DomainContractPackage, ContractPackageHash, ContractWasmHash, EntryPoint,
-    NamedKey, ProtocolVersion, URef,
+    contracts::{ContractPackageStatus, ContractVersionKey},
+    Contract as DomainContract, ContractHash, ContractPackage as DomainContractPackage,
+    ContractPackageHash, ContractWasmHash, EntryPoint, EntryPoints, Group, NamedKey,
+    ProtocolVersion, URef,
 };
 
 #[derive(
@@ -63,6 +66,20 @@ impl From<&DomainContract> for Contract {
     }
 }
 
+impl TryFrom<Contract> for DomainContract {
+    type Error = ConversionError;
+
+    fn try_from(contract: Contract) -> Result<Self, Self::Error> {
+        Ok(DomainContract::new(
+            contract.contract_package_hash,
+            contract.contract_wasm_hash,
+            unvectorize(&contract.named_keys)?,
+            EntryPoints::from(contract.entry_points),
+            contract.protocol_version,
+        ))
+    }
+}
+
 /// Contract definition, metadata, and security container.
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, DataSize, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -114,3 +131,43 @@ impl From<&DomainContractPackage> for ContractPackage {
         }
     }
 }
+
+impl From<ContractPackage> for DomainContractPackage {
+    fn from(contract_package: ContractPackage) -> Self {
+        let versions = contract_package
+            .versions
+            .iter()
+            .map(|version| {
+                (
+                    ContractVersionKey::new(
+                        version.protocol_version_major,
+                        version.contract_version,
+                    ),
+                    version.contract_hash,
+                )
+            })
+            .collect();
+
+        let disabled_versions = contract_package
+            .disabled_versions
+            .iter()
+            .map(|version| {
+                ContractVersionKey::new(version.protocol_version_major, version.contract_version)
+            })
+            .collect();
+
+        let groups = contract_package
+            .groups
+            .into_iter()
+            .map(|groups| (Group::new(groups.group), groups.keys.into_iter().collect()))
+            .collect();
+
+        DomainContractPackage::new(
+            contract_package.access_key,
+            versions,
+            disabled_versions,
+            groups,
+            contract_package.lock_status,
+        )
+    }
+}
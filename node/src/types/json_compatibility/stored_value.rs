@@ -13,10 +13,10 @@ use serde::{Deserialize, Serialize};
 use casper_types::{
     bytesrepr::{self, ToBytes},
     system::auction::{Bid, EraInfo, UnbondingPurse, WithdrawPurse},
-    CLValue, DeployInfo, StoredValue as ExecutionEngineStoredValue, Transfer,
+    CLValue, ContractWasm, DeployInfo, StoredValue as ExecutionEngineStoredValue, Transfer,
 };
 
-use super::{Account, Contract, ContractPackage};
+use super::{Account, Contract, ContractPackage, ConversionError};
 
 /// Representation of a value stored in global state.
 ///
@@ -82,3 +82,46 @@ impl TryFrom<ExecutionEngineStoredValue> for StoredValue {
         Ok(stored_value)
     }
 }
+
+impl TryFrom<StoredValue> for ExecutionEngineStoredValue {
+    type Error = ConversionError;
+
+    fn try_from(stored_value: StoredValue) -> Result<Self, Self::Error> {
+        let ee_stored_value = match stored_value {
+            StoredValue::CLValue(cl_value) => ExecutionEngineStoredValue::CLValue(cl_value),
+            StoredValue::Account(account) => {
+                ExecutionEngineStoredValue::Account(TryFrom::try_from(account)?)
+            }
+            StoredValue::ContractWasm(hex_contract_wasm) => {
+                let contract_wasm = base16::decode(&hex_contract_wasm)
+                    .map_err(|error| ConversionError::InvalidContractWasm(error.to_string()))
+                    .and_then(|bytes| {
+                        bytesrepr::deserialize::<ContractWasm>(bytes).map_err(|error| {
+                            ConversionError::InvalidContractWasm(error.to_string())
+                        })
+                    })?;
+                ExecutionEngineStoredValue::ContractWasm(contract_wasm)
+            }
+            StoredValue::Contract(contract) => {
+                ExecutionEngineStoredValue::Contract(TryFrom::try_from(contract)?)
+            }
+            StoredValue::ContractPackage(contract_package) => {
+                ExecutionEngineStoredValue::ContractPackage(contract_package.into())
+            }
+            StoredValue::Transfer(transfer) => ExecutionEngineStoredValue::Transfer(transfer),
+            StoredValue::DeployInfo(deploy_info) => {
+                ExecutionEngineStoredValue::DeployInfo(deploy_info)
+            }
+            StoredValue::EraInfo(era_info) => ExecutionEngineStoredValue::EraInfo(era_info),
+            StoredValue::Bid(bid) => ExecutionEngineStoredValue::Bid(bid),
+            StoredValue::Withdraw(withdraw_purses) => {
+                ExecutionEngineStoredValue::Withdraw(withdraw_purses)
+            }
+            StoredValue::Unbonding(unbonding_purses) => {
+                ExecutionEngineStoredValue::Unbonding(unbonding_purses)
+            }
+        };
+
+        Ok(ee_stored_value)
+    }
+}
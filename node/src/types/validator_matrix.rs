@@ -3,6 +3,7 @@ use std::iter;
 use std::{
     collections::{BTreeMap, HashSet},
     fmt::{self, Debug, Formatter},
+    future::Future,
     sync::{Arc, RwLock, RwLockReadGuard},
 };
 
@@ -11,11 +12,14 @@ use itertools::Itertools;
 use num_rational::Ratio;
 use serde::Serialize;
 use static_assertions::const_assert;
-use tracing::info;
+use tracing::{error, info};
 
-use casper_types::{EraId, PublicKey, SecretKey, U512};
+#[cfg(test)]
+use casper_types::SecretKey;
+use casper_types::{EraId, PublicKey, U512};
 
 use super::{BlockHeader, FinalitySignature};
+use crate::signer::Signer;
 
 const MAX_VALIDATOR_MATRIX_ENTRIES: usize = 6;
 const_assert!(MAX_VALIDATOR_MATRIX_ENTRIES % 2 == 0);
@@ -47,7 +51,7 @@ pub(crate) struct ValidatorMatrix {
     chainspec_activation_era: EraId,
     #[data_size(skip)]
     finality_threshold_fraction: Ratio<u64>,
-    secret_signing_key: Arc<SecretKey>,
+    signer: Signer,
     public_signing_key: PublicKey,
     auction_delay: u64,
     retrograde_latch: Option<EraId>,
@@ -58,17 +62,17 @@ impl ValidatorMatrix {
         finality_threshold_fraction: Ratio<u64>,
         chainspec_validators: Option<BTreeMap<PublicKey, U512>>,
         chainspec_activation_era: EraId,
-        secret_signing_key: Arc<SecretKey>,
-        public_signing_key: PublicKey,
+        signer: Signer,
         auction_delay: u64,
     ) -> Self {
         let inner = Arc::new(RwLock::new(BTreeMap::new()));
+        let public_signing_key = signer.public_key().clone();
         ValidatorMatrix {
             inner,
             finality_threshold_fraction,
             chainspec_validators: chainspec_validators.map(Arc::new),
             chainspec_activation_era,
-            secret_signing_key,
+            signer,
             public_signing_key,
             auction_delay,
             retrograde_latch: None,
@@ -92,7 +96,7 @@ impl ValidatorMatrix {
             chainspec_activation_era: EraId::from(0),
             finality_threshold_fraction,
             public_signing_key,
-            secret_signing_key,
+            signer: Signer::local(secret_signing_key),
             auction_delay: 1,
             retrograde_latch: None,
         }
@@ -262,22 +266,34 @@ impl ValidatorMatrix {
             .any(|validator_weights| validator_weights.is_validator(public_key))
     }
 
+    /// Returns a future signing the block, if this node is a validator in the block's era.
+    ///
+    /// The future resolves to `None` if the block couldn't be signed. It doesn't borrow the
+    /// validator matrix, so that it can be run as an effect instead of blocking the reactor while
+    /// waiting for a remote signer.
     pub(crate) fn create_finality_signature(
         &self,
         block_header: &BlockHeader,
-    ) -> Option<FinalitySignature> {
-        if self
+    ) -> Option<impl Future<Output = Option<FinalitySignature>> + Send + 'static> {
+        if !self
             .is_self_validator_in_era(block_header.era_id())
             .unwrap_or(false)
         {
-            return Some(FinalitySignature::create(
-                block_header.block_hash(),
-                block_header.era_id(),
-                &self.secret_signing_key,
-                self.public_signing_key.clone(),
-            ));
+            return None;
         }
-        None
+        let signer = self.signer.clone();
+        let block_hash = block_header.block_hash();
+        let block_height = block_header.height();
+        let era_id = block_header.era_id();
+        Some(async move {
+            match FinalitySignature::sign(block_hash, block_height, era_id, &signer).await {
+                Ok(finality_signature) => Some(finality_signature),
+                Err(err) => {
+                    error!(%err, %block_hash, "failed to sign block");
+                    None
+                }
+            }
+        })
     }
 
     fn read_inner(&self) -> RwLockReadGuard<BTreeMap<EraId, EraValidatorWeights>> {
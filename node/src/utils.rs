@@ -290,7 +290,13 @@ pub(crate) enum Source {
     /// A client.
     Client,
     /// A client via the speculative_exec server.
-    SpeculativeExec(Box<BlockHeader>),
+    SpeculativeExec {
+        /// The block on top of which the deploy is to be executed.
+        block_header: Box<BlockHeader>,
+        /// Whether to skip the checks of the deploy's account against the block's global state,
+        /// as the deploy is executed on top of different state.
+        skip_account_checks: bool,
+    },
     /// This node.
     Ourself,
 }
@@ -299,7 +305,7 @@ impl Source {
     #[allow(clippy::wrong_self_convention)]
     pub(crate) fn is_client(&self) -> bool {
         match self {
-            Source::Client | Source::SpeculativeExec(_) => true,
+            Source::Client | Source::SpeculativeExec { .. } => true,
             Source::PeerGossiped(_) | Source::Peer(_) | Source::Ourself => false,
         }
     }
@@ -308,7 +314,7 @@ impl Source {
     pub(crate) fn node_id(&self) -> Option<NodeId> {
         match self {
             Source::Peer(node_id) | Source::PeerGossiped(node_id) => Some(*node_id),
-            Source::Client | Source::SpeculativeExec(_) | Source::Ourself => None,
+            Source::Client | Source::SpeculativeExec { .. } | Source::Ourself => None,
         }
     }
 }
@@ -319,7 +325,7 @@ impl Display for Source {
             Source::PeerGossiped(node_id) => Display::fmt(node_id, formatter),
             Source::Peer(node_id) => Display::fmt(node_id, formatter),
             Source::Client => write!(formatter, "client"),
-            Source::SpeculativeExec(_) => write!(formatter, "client (speculative exec)"),
+            Source::SpeculativeExec { .. } => write!(formatter, "client (speculative exec)"),
             Source::Ourself => write!(formatter, "ourself"),
         }
     }
@@ -6,11 +6,12 @@ use core::mem::MaybeUninit;
 use casper_types::{
     account::AccountHash,
     api_error,
-    bytesrepr::{self, FromBytes},
+    bytesrepr::{self, FromBytes, ToBytes},
     contracts::{ContractVersion, NamedKeys},
     system::CallStackElement,
     ApiError, BlockTime, CLTyped, CLValue, ContractHash, ContractPackageHash, Key, Phase,
-    RuntimeArgs, URef, BLAKE2B_DIGEST_LENGTH, BLOCKTIME_SERIALIZED_LENGTH, PHASE_SERIALIZED_LENGTH,
+    PublicKey, RuntimeArgs, Signature, URef, BLAKE2B_DIGEST_LENGTH, BLOCKTIME_SERIALIZED_LENGTH,
+    PHASE_SERIALIZED_LENGTH,
 };
 
 use crate::{contract_api, ext_ffi, unwrap_or_revert::UnwrapOrRevert};
@@ -359,6 +360,44 @@ pub fn random_bytes() -> [u8; RANDOM_BYTES_COUNT] {
     ret
 }
 
+/// Returns `true` if `signature` is a valid signature of `message` by the holder of the secret key
+/// corresponding to `public_key`.
+///
+/// Both Ed25519 and secp256k1 signatures are supported.
+pub fn verify_signature<T: AsRef<[u8]>>(
+    message: T,
+    signature: &Signature,
+    public_key: &PublicKey,
+) -> bool {
+    let message = message.as_ref();
+    let (signature_ptr, signature_size, _bytes1) = contract_api::to_ptr(signature);
+    let (public_key_ptr, public_key_size, _bytes2) = contract_api::to_ptr(public_key);
+    let result = unsafe {
+        ext_ffi::casper_verify_signature(
+            message.as_ptr(),
+            message.len(),
+            signature_ptr,
+            signature_size,
+            public_key_ptr,
+            public_key_size,
+        )
+    };
+    result == 0
+}
+
+/// Emits an event with the given `topic` and `payload`.
+///
+/// The event is recorded in the execution result of the deploy, attributed to the current context,
+/// i.e. either the caller's account or a stored contract depending on whether the
+/// currently-executing module is a direct call or a sub-call respectively.  Events are discarded if
+/// the deploy's execution fails.
+pub fn emit_event<T: CLTyped + ToBytes>(topic: &str, payload: T) {
+    let payload = CLValue::from_t(payload).unwrap_or_revert();
+    let (topic_ptr, topic_size, _bytes1) = contract_api::to_ptr(topic);
+    let (payload_ptr, payload_size, _bytes2) = contract_api::to_ptr(payload);
+    unsafe { ext_ffi::casper_emit_event(topic_ptr, topic_size, payload_ptr, payload_size) }
+}
+
 fn read_host_buffer_into(dest: &mut [u8]) -> Result<usize, ApiError> {
     let mut bytes_written = MaybeUninit::uninit();
     let ret = unsafe {
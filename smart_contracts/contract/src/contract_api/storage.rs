@@ -7,8 +7,8 @@ use casper_types::{
     api_error,
     bytesrepr::{self, FromBytes, ToBytes},
     contracts::{ContractVersion, EntryPoints, NamedKeys},
-    AccessRights, ApiError, CLTyped, CLValue, ContractHash, ContractPackageHash, HashAddr, Key,
-    URef, DICTIONARY_ITEM_KEY_MAX_LENGTH, UREF_SERIALIZED_LENGTH,
+    AccessRights, ApiError, CLTyped, CLValue, ContractHash, ContractPackageHash, ContractSchema,
+    HashAddr, Key, URef, DICTIONARY_ITEM_KEY_MAX_LENGTH, UREF_SERIALIZED_LENGTH,
 };
 
 use crate::{
@@ -317,6 +317,52 @@ pub fn add_contract_version(
     (contract_hash, contract_version)
 }
 
+/// Add a new version of a contract to the contract stored at the given `Key`, along with a
+/// `schema` describing its interface.  The schema is stored in global state under
+/// [`Key::ContractSchema`] for the new contract hash.
+///
+/// Reverts with [`casper_types::contracts::Error::ContractSchemaMismatch`] if the schema does not
+/// describe exactly the given `entry_points`.  Note that this contract must have been created by
+/// `create_contract` or `create_contract_package_at_hash` first.
+pub fn add_contract_version_with_schema(
+    contract_package_hash: ContractPackageHash,
+    entry_points: EntryPoints,
+    named_keys: NamedKeys,
+    schema: ContractSchema,
+) -> (ContractHash, ContractVersion) {
+    let (contract_package_hash_ptr, contract_package_hash_size, _bytes1) =
+        contract_api::to_ptr(contract_package_hash);
+    let (entry_points_ptr, entry_points_size, _bytes2) = contract_api::to_ptr(entry_points);
+    let (named_keys_ptr, named_keys_size, _bytes3) = contract_api::to_ptr(named_keys);
+    let (schema_ptr, schema_size, _bytes4) = contract_api::to_ptr(schema);
+
+    let mut output_ptr = vec![0u8; Key::max_serialized_length()];
+    let mut total_bytes: usize = 0;
+
+    let mut contract_version: ContractVersion = 0;
+
+    let ret = unsafe {
+        ext_ffi::casper_add_contract_version_with_schema(
+            contract_package_hash_ptr,
+            contract_package_hash_size,
+            &mut contract_version as *mut ContractVersion,
+            entry_points_ptr,
+            entry_points_size,
+            named_keys_ptr,
+            named_keys_size,
+            schema_ptr,
+            schema_size,
+            output_ptr.as_mut_ptr(),
+            output_ptr.len(),
+            &mut total_bytes as *mut usize,
+        )
+    };
+    api_error::result_from(ret).unwrap_or_revert();
+    output_ptr.truncate(total_bytes);
+    let contract_hash = bytesrepr::deserialize(output_ptr).unwrap_or_revert();
+    (contract_hash, contract_version)
+}
+
 /// Disable a version of a contract from the contract stored at the given
 /// `Key`. That version of the contract will no longer be callable by
 /// `call_versioned_contract`. Note that this contract must have been created by
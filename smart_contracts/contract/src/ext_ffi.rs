@@ -805,4 +805,76 @@ extern "C" {
         contract_hash_ptr: *const u8,
         contract_hash_size: usize,
     ) -> i32;
+    /// Verifies the signature of the given message against the given public key.  Returns zero if
+    /// the signature is valid, otherwise a non-zero value.
+    ///
+    /// Both Ed25519 and secp256k1 signatures are supported.  Execution is reverted if the signature
+    /// or public key cannot be deserialized.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_ptr` - pointer to the message bytes.
+    /// * `message_size` - size of the message.
+    /// * `signature_ptr` - pointer to serialized signature.
+    /// * `signature_size` - size of signature in serialized form.
+    /// * `public_key_ptr` - pointer to serialized public key.
+    /// * `public_key_size` - size of public key in serialized form.
+    pub fn casper_verify_signature(
+        message_ptr: *const u8,
+        message_size: usize,
+        signature_ptr: *const u8,
+        signature_size: usize,
+        public_key_ptr: *const u8,
+        public_key_size: usize,
+    ) -> i32;
+    /// Emits an event with the given topic and payload, recorded in the execution result of the
+    /// deploy along with the key of the current context.
+    ///
+    /// Events are discarded if the deploy's execution fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic_ptr` - pointer to serialized topic.
+    /// * `topic_size` - size of topic in serialized form.
+    /// * `payload_ptr` - pointer to serialized payload `CLValue`.
+    /// * `payload_size` - size of payload in serialized form.
+    pub fn casper_emit_event(
+        topic_ptr: *const u8,
+        topic_size: usize,
+        payload_ptr: *const u8,
+        payload_size: usize,
+    );
+    /// Adds new contract version to a contract package, along with a schema describing its
+    /// interface.  The schema must describe exactly the given entry points, otherwise the
+    /// [`casper_types::contracts::Error::ContractSchemaMismatch`] error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract_package_hash_ptr` - pointer to serialized contract package hash.
+    /// * `contract_package_hash_size` - size of contract package hash in serialized form.
+    /// * `version_ptr` - output parameter where new version assigned by host is set
+    /// * `entry_points_ptr` - pointer to serialized [`casper_types::EntryPoints`]
+    /// * `entry_points_size` - size of serialized [`casper_types::EntryPoints`]
+    /// * `named_keys_ptr` - pointer to serialized [`casper_types::contracts::NamedKeys`]
+    /// * `named_keys_size` - size of serialized [`casper_types::contracts::NamedKeys`]
+    /// * `schema_ptr` - pointer to serialized [`casper_types::ContractSchema`]
+    /// * `schema_size` - size of serialized [`casper_types::ContractSchema`]
+    /// * `output_ptr` - pointer to a memory where host assigned contract hash is set to
+    /// * `output_size` - size of memory area that host can write to
+    /// * `bytes_written_ptr` - pointer to a value where host will set a number of bytes written to
+    ///   the `output_size` pointer
+    pub fn casper_add_contract_version_with_schema(
+        contract_package_hash_ptr: *const u8,
+        contract_package_hash_size: usize,
+        version_ptr: *const u32,
+        entry_points_ptr: *const u8,
+        entry_points_size: usize,
+        named_keys_ptr: *const u8,
+        named_keys_size: usize,
+        schema_ptr: *const u8,
+        schema_size: usize,
+        output_ptr: *mut u8,
+        output_size: usize,
+        bytes_written_ptr: *mut usize,
+    ) -> i32;
 }
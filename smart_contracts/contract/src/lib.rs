@@ -66,6 +66,10 @@ pub mod ext_ffi;
 mod no_std_handlers;
 pub mod unwrap_or_revert;
 
+/// Derive macros for storing user-defined structs and enums as `CLValue`s of type
+/// `CLType::Struct` and `CLType::Enum`.
+pub use casper_contract_derive::{CLTyped, FromBytes, ToBytes};
+
 /// An instance of [`WeeAlloc`](https://docs.rs/wee_alloc) which allows contracts built as `no_std`
 /// to avoid having to provide a global allocator themselves.
 #[cfg(all(not(test), feature = "no-std-helpers"))]
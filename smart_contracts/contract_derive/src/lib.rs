@@ -0,0 +1,379 @@
+//! Derive macros for the [`CLTyped`], [`ToBytes`] and [`FromBytes`] traits of `casper-types`.
+//!
+//! Deriving all three for a struct or enum allows it to be stored as a `CLValue` whose `CLType`
+//! describes its layout by name, i.e. a `CLType::Struct` or a `CLType::Enum`, so that tooling can
+//! decode it.
+//!
+//! * A struct is serialized as its fields in declaration order.  Fields of tuple structs are named
+//!   by their index.
+//! * An enum is serialized as the index of its variant as a `u8`, followed by the variant's
+//!   payload.  The payload of a unit variant is a `CLType::Unit`, that of a variant with a single
+//!   unnamed field is the field itself, and that of any other variant is a `CLType::Struct` named
+//!   after the variant.
+//!
+//! # Example
+//!
+//! ```
+//! use casper_contract_derive::{CLTyped, FromBytes, ToBytes};
+//!
+//! #[derive(CLTyped, ToBytes, FromBytes)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! #[derive(CLTyped, ToBytes, FromBytes)]
+//! enum Shape {
+//!     Empty,
+//!     Circle(u64),
+//!     Polygon { vertices: Vec<Point> },
+//! }
+//! ```
+//!
+//! [`CLTyped`]: https://docs.rs/casper-types/latest/casper_types/trait.CLTyped.html
+//! [`ToBytes`]: https://docs.rs/casper-types/latest/casper_types/bytesrepr/trait.ToBytes.html
+//! [`FromBytes`]: https://docs.rs/casper-types/latest/casper_types/bytesrepr/trait.FromBytes.html
+
+#![doc(html_root_url = "https://docs.rs/casper-contract-derive/0.1.0")]
+#![doc(
+    html_favicon_url = "https://raw.githubusercontent.com/casper-network/casper-node/blob/dev/images/Casper_Logo_Favicon_48.png",
+    html_logo_url = "https://raw.githubusercontent.com/casper-network/casper-node/blob/dev/images/Casper_Logo_Favicon.png"
+)]
+#![warn(missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    ext::IdentExt, parse_macro_input, parse_quote, Data, DataEnum, DeriveInput, Error, Fields,
+    Generics, Ident, Index, Member, Path,
+};
+
+/// The maximum number of variants of an enum, as the index of a variant is serialized as a `u8`.
+const MAX_ENUM_VARIANTS: usize = u8::MAX as usize + 1;
+
+/// Derives `casper_types::CLTyped`, describing the type as a `CLType::Struct` or `CLType::Enum`.
+#[proc_macro_derive(CLTyped)]
+pub fn derive_cl_typed(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_cl_typed(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `casper_types::bytesrepr::ToBytes`, consistent with the derived `CLTyped` impl.
+#[proc_macro_derive(ToBytes)]
+pub fn derive_to_bytes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_to_bytes(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `casper_types::bytesrepr::FromBytes`, consistent with the derived `CLTyped` impl.
+#[proc_macro_derive(FromBytes)]
+pub fn derive_from_bytes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_bytes(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Returns `generics` with `trait_path` added as a bound to each type parameter.
+fn add_trait_bounds(mut generics: Generics, trait_path: &Path) -> Generics {
+    for type_param in generics.type_params_mut() {
+        type_param.bounds.push(parse_quote!(#trait_path));
+    }
+    generics
+}
+
+/// Returns an error if `input` is a union, or an enum with no variants or with too many variants
+/// for their index to be serialized as a `u8`.
+fn check_supported(input: &DeriveInput) -> Result<(), Error> {
+    match &input.data {
+        Data::Struct(_) => Ok(()),
+        Data::Enum(DataEnum { variants, .. }) if variants.is_empty() => Err(Error::new_spanned(
+            &input.ident,
+            "enums without variants are not supported",
+        )),
+        Data::Enum(DataEnum { variants, .. }) if variants.len() > MAX_ENUM_VARIANTS => {
+            Err(Error::new_spanned(
+                &input.ident,
+                format!(
+                    "enums with more than {} variants are not supported",
+                    MAX_ENUM_VARIANTS
+                ),
+            ))
+        }
+        Data::Enum(_) => Ok(()),
+        Data::Union(_) => Err(Error::new_spanned(&input.ident, "unions are not supported")),
+    }
+}
+
+/// Returns the `CLType::Struct` with the given name describing `fields`.
+fn struct_cl_type(name: &Ident, fields: &Fields) -> TokenStream2 {
+    let name = name.unraw().to_string();
+    let field_count = fields.len();
+    let field_names = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match &field.ident {
+            Some(ident) => ident.unraw().to_string(),
+            None => index.to_string(),
+        });
+    let field_types = fields.iter().map(|field| &field.ty);
+    quote! {
+        {
+            let fields: [(&str, ::casper_types::CLType); #field_count] = [
+                #( (#field_names, <#field_types as ::casper_types::CLTyped>::cl_type()) ),*
+            ];
+            ::casper_types::CLType::new_struct(#name, fields)
+        }
+    }
+}
+
+/// Returns the `CLType` of the payload of an enum variant with the given name and fields.
+fn variant_payload_cl_type(name: &Ident, fields: &Fields) -> TokenStream2 {
+    match fields {
+        Fields::Unit => quote!(::casper_types::CLType::Unit),
+        Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+            let field_type = &unnamed.unnamed[0].ty;
+            quote!(<#field_type as ::casper_types::CLTyped>::cl_type())
+        }
+        _ => struct_cl_type(name, fields),
+    }
+}
+
+fn expand_cl_typed(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    check_supported(input)?;
+
+    let ident = &input.ident;
+    let cl_type = match &input.data {
+        Data::Struct(data) => struct_cl_type(ident, &data.fields),
+        Data::Enum(data) => {
+            let name = ident.unraw().to_string();
+            let variant_count = data.variants.len();
+            let variant_names = data
+                .variants
+                .iter()
+                .map(|variant| variant.ident.unraw().to_string());
+            let payload_cl_types = data
+                .variants
+                .iter()
+                .map(|variant| variant_payload_cl_type(&variant.ident, &variant.fields));
+            quote! {
+                {
+                    let variants: [(&str, ::casper_types::CLType); #variant_count] = [
+                        #( (#variant_names, #payload_cl_types) ),*
+                    ];
+                    ::casper_types::CLType::new_enum(#name, variants)
+                }
+            }
+        }
+        Data::Union(_) => unreachable!("checked above"),
+    };
+
+    let generics = add_trait_bounds(
+        input.generics.clone(),
+        &parse_quote!(::casper_types::CLTyped),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::casper_types::CLTyped for #ident #ty_generics #where_clause {
+            fn cl_type() -> ::casper_types::CLType {
+                #cl_type
+            }
+        }
+    })
+}
+
+/// Returns the members of `fields`, i.e. the names of named fields or indices of unnamed ones.
+fn members(fields: &Fields) -> Vec<Member> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        })
+        .collect()
+}
+
+/// Returns the identifiers to which the fields of an enum variant are bound in a match arm.
+fn bindings(fields: &Fields) -> Vec<Ident> {
+    (0..fields.len())
+        .map(|index| format_ident!("field_{}", index))
+        .collect()
+}
+
+/// Returns the pattern destructuring a value of type `path` with `fields` into `bindings`, or the
+/// expression constructing one from them.
+fn destructure(path: TokenStream2, fields: &Fields, bindings: &[Ident]) -> TokenStream2 {
+    let members = members(fields);
+    match fields {
+        Fields::Named(_) => quote!(#path { #( #members: #bindings ),* }),
+        Fields::Unnamed(_) => quote!(#path( #( #bindings ),* )),
+        Fields::Unit => path,
+    }
+}
+
+fn expand_to_bytes(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    check_supported(input)?;
+
+    let (serialized_length, write_bytes) = match &input.data {
+        Data::Struct(data) => {
+            let members = members(&data.fields);
+            let serialized_length = quote! {
+                0 #( + ::casper_types::bytesrepr::ToBytes::serialized_length(&self.#members) )*
+            };
+            let write_bytes = if members.is_empty() {
+                quote!(let _ = writer;)
+            } else {
+                quote! {
+                    #( ::casper_types::bytesrepr::ToBytes::write_bytes(&self.#members, writer)?; )*
+                }
+            };
+            (serialized_length, write_bytes)
+        }
+        Data::Enum(data) => {
+            let mut length_arms = Vec::new();
+            let mut write_arms = Vec::new();
+            for (index, variant) in data.variants.iter().enumerate() {
+                let index = index as u8;
+                let variant_ident = &variant.ident;
+                let bindings = bindings(&variant.fields);
+                let pattern = destructure(quote!(Self::#variant_ident), &variant.fields, &bindings);
+                length_arms.push(quote! {
+                    #pattern => {
+                        ::casper_types::bytesrepr::U8_SERIALIZED_LENGTH
+                            #( + ::casper_types::bytesrepr::ToBytes::serialized_length(#bindings) )*
+                    }
+                });
+                write_arms.push(quote! {
+                    #pattern => {
+                        writer.push(#index);
+                        #( ::casper_types::bytesrepr::ToBytes::write_bytes(#bindings, writer)?; )*
+                    }
+                });
+            }
+            let serialized_length = quote! {
+                match self {
+                    #( #length_arms )*
+                }
+            };
+            let write_bytes = quote! {
+                match self {
+                    #( #write_arms )*
+                }
+            };
+            (serialized_length, write_bytes)
+        }
+        Data::Union(_) => unreachable!("checked above"),
+    };
+
+    let ident = &input.ident;
+    let generics = add_trait_bounds(
+        input.generics.clone(),
+        &parse_quote!(::casper_types::bytesrepr::ToBytes),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        const _: () = {
+            extern crate alloc;
+
+            impl #impl_generics ::casper_types::bytesrepr::ToBytes for #ident #ty_generics
+                #where_clause
+            {
+                fn to_bytes(
+                    &self,
+                ) -> ::core::result::Result<alloc::vec::Vec<u8>, ::casper_types::bytesrepr::Error>
+                {
+                    let mut buffer = ::casper_types::bytesrepr::allocate_buffer(self)?;
+                    ::casper_types::bytesrepr::ToBytes::write_bytes(self, &mut buffer)?;
+                    ::core::result::Result::Ok(buffer)
+                }
+
+                fn serialized_length(&self) -> usize {
+                    #serialized_length
+                }
+
+                fn write_bytes(
+                    &self,
+                    writer: &mut alloc::vec::Vec<u8>,
+                ) -> ::core::result::Result<(), ::casper_types::bytesrepr::Error> {
+                    #write_bytes
+                    ::core::result::Result::Ok(())
+                }
+            }
+        };
+    })
+}
+
+/// Returns the statements parsing each of `bindings` in turn from `remainder`.
+fn parse_bindings(bindings: &[Ident]) -> TokenStream2 {
+    quote! {
+        #(
+            let (#bindings, remainder) =
+                ::casper_types::bytesrepr::FromBytes::from_bytes(remainder)?;
+        )*
+    }
+}
+
+fn expand_from_bytes(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    check_supported(input)?;
+
+    let from_bytes = match &input.data {
+        Data::Struct(data) => {
+            let bindings = bindings(&data.fields);
+            let parse_bindings = parse_bindings(&bindings);
+            let value = destructure(quote!(Self), &data.fields, &bindings);
+            quote! {
+                let remainder = bytes;
+                #parse_bindings
+                ::core::result::Result::Ok((#value, remainder))
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data.variants.iter().enumerate().map(|(index, variant)| {
+                let index = index as u8;
+                let variant_ident = &variant.ident;
+                let bindings = bindings(&variant.fields);
+                let parse_bindings = parse_bindings(&bindings);
+                let value = destructure(quote!(Self::#variant_ident), &variant.fields, &bindings);
+                quote! {
+                    #index => {
+                        #parse_bindings
+                        ::core::result::Result::Ok((#value, remainder))
+                    }
+                }
+            });
+            quote! {
+                let (index, remainder) =
+                    <u8 as ::casper_types::bytesrepr::FromBytes>::from_bytes(bytes)?;
+                match index {
+                    #( #arms )*
+                    _ => ::core::result::Result::Err(::casper_types::bytesrepr::Error::Formatting),
+                }
+            }
+        }
+        Data::Union(_) => unreachable!("checked above"),
+    };
+
+    let ident = &input.ident;
+    let generics = add_trait_bounds(
+        input.generics.clone(),
+        &parse_quote!(::casper_types::bytesrepr::FromBytes),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::casper_types::bytesrepr::FromBytes for #ident #ty_generics
+            #where_clause
+        {
+            fn from_bytes(
+                bytes: &[u8],
+            ) -> ::core::result::Result<(Self, &[u8]), ::casper_types::bytesrepr::Error> {
+                #from_bytes
+            }
+        }
+    })
+}
@@ -0,0 +1,111 @@
+use std::fmt::Debug;
+
+use casper_contract_derive::{CLTyped, FromBytes, ToBytes};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped, CLValue,
+};
+
+#[derive(CLTyped, ToBytes, FromBytes, PartialEq, Debug, Clone)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(CLTyped, ToBytes, FromBytes, PartialEq, Debug, Clone)]
+struct Pair<T>(T, String);
+
+#[derive(CLTyped, ToBytes, FromBytes, PartialEq, Debug, Clone)]
+struct Marker;
+
+#[derive(CLTyped, ToBytes, FromBytes, PartialEq, Debug, Clone)]
+enum Shape {
+    Empty,
+    Circle(u64),
+    Polygon { vertices: Vec<Point>, closed: bool },
+}
+
+fn round_trip<T: CLTyped + FromBytes + ToBytes + PartialEq + Debug + Clone>(value: &T) {
+    let cl_value = CLValue::from_t(value.clone()).unwrap();
+
+    let serialized_cl_value = cl_value.to_bytes().unwrap();
+    assert_eq!(serialized_cl_value.len(), cl_value.serialized_length());
+    let parsed_cl_value: CLValue = bytesrepr::deserialize(serialized_cl_value).unwrap();
+    assert_eq!(cl_value, parsed_cl_value);
+
+    let parsed_value = CLValue::into_t(cl_value).unwrap();
+    assert_eq!(*value, parsed_value);
+}
+
+#[test]
+fn should_derive_struct_cl_type() {
+    assert_eq!(
+        Point::cl_type(),
+        CLType::new_struct("Point", [("x", CLType::I32), ("y", CLType::I32)])
+    );
+    assert_eq!(
+        Pair::<u8>::cl_type(),
+        CLType::new_struct("Pair", [("0", CLType::U8), ("1", CLType::String)])
+    );
+    assert_eq!(
+        Marker::cl_type(),
+        CLType::new_struct("Marker", Vec::<(String, CLType)>::new())
+    );
+}
+
+#[test]
+fn should_derive_enum_cl_type() {
+    let polygon = CLType::new_struct(
+        "Polygon",
+        [
+            ("vertices", CLType::List(Box::new(Point::cl_type()))),
+            ("closed", CLType::Bool),
+        ],
+    );
+    assert_eq!(
+        Shape::cl_type(),
+        CLType::new_enum(
+            "Shape",
+            [
+                ("Empty", CLType::Unit),
+                ("Circle", CLType::U64),
+                ("Polygon", polygon),
+            ]
+        )
+    );
+}
+
+#[test]
+fn should_serialize_structs_as_their_fields() {
+    let point = Point { x: 1, y: -1 };
+    assert_eq!(point.to_bytes().unwrap(), (1i32, -1i32).to_bytes().unwrap());
+    round_trip(&point);
+
+    round_trip(&Pair(7u64, String::from("seven")));
+    round_trip(&Marker);
+    assert!(Marker.to_bytes().unwrap().is_empty());
+}
+
+#[test]
+fn should_serialize_enums_as_variant_index_and_payload() {
+    assert_eq!(Shape::Empty.to_bytes().unwrap(), vec![0]);
+    assert_eq!(
+        Shape::Circle(7).to_bytes().unwrap(),
+        (1u8, 7u64).to_bytes().unwrap()
+    );
+
+    round_trip(&Shape::Empty);
+    round_trip(&Shape::Circle(7));
+    round_trip(&Shape::Polygon {
+        vertices: vec![Point { x: 0, y: 0 }, Point { x: 1, y: 1 }],
+        closed: true,
+    });
+}
+
+#[test]
+fn should_fail_to_parse_unknown_enum_variant() {
+    assert_eq!(
+        bytesrepr::deserialize::<Shape>(vec![3]),
+        Err(bytesrepr::Error::Formatting)
+    );
+}
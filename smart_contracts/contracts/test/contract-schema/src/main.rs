@@ -0,0 +1,80 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{string::ToString, vec};
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    contracts::{EntryPoint, EntryPoints, NamedKeys},
+    CLType, ContractSchema, EntryPointAccess, EntryPointType, Parameter,
+};
+
+const ENTRY_POINT_SET_VALUE: &str = "set_value";
+const ARG_VALUE: &str = "value";
+const ARG_MISMATCH: &str = "mismatch";
+const VALUE_KEY_NAME: &str = "value";
+const DICTIONARY_NAME: &str = "values";
+const VALUE_SET_TOPIC: &str = "value_set";
+const HASH_KEY_NAME: &str = "contract_schema_hash";
+
+#[no_mangle]
+pub extern "C" fn set_value() {
+    let value: u64 = runtime::get_named_arg(ARG_VALUE);
+    let uref = runtime::get_key(VALUE_KEY_NAME)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert();
+    storage::write(uref, value);
+    let dictionary_uref = runtime::get_key(DICTIONARY_NAME)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert();
+    storage::dictionary_put(dictionary_uref, &value.to_string(), value);
+    runtime::emit_event(VALUE_SET_TOPIC, value);
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let mismatch: bool = runtime::get_named_arg(ARG_MISMATCH);
+
+    let entry_points = {
+        let mut entry_points = EntryPoints::new();
+        let entry_point = EntryPoint::new(
+            ENTRY_POINT_SET_VALUE,
+            vec![Parameter::new(ARG_VALUE, CLType::U64)],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        );
+        entry_points.add_entry_point(entry_point);
+        entry_points
+    };
+
+    let mut schema = ContractSchema::from(&entry_points);
+    schema.add_named_key(VALUE_KEY_NAME, CLType::U64);
+    schema.add_dictionary(DICTIONARY_NAME, CLType::U64);
+    schema.add_event(VALUE_SET_TOPIC, CLType::U64);
+    if mismatch {
+        schema.add_entry_point(ENTRY_POINT_SET_VALUE, vec![], CLType::Unit);
+    }
+
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(VALUE_KEY_NAME.to_string(), storage::new_uref(0u64).into());
+    let dictionary_uref = storage::new_dictionary(DICTIONARY_NAME).unwrap_or_revert();
+    runtime::remove_key(DICTIONARY_NAME);
+    named_keys.insert(DICTIONARY_NAME.to_string(), dictionary_uref.into());
+
+    let (contract_package_hash, _access_uref) = storage::create_contract_package_at_hash();
+    let (contract_hash, _contract_version) = storage::add_contract_version_with_schema(
+        contract_package_hash,
+        entry_points,
+        named_keys,
+        schema,
+    );
+    runtime::put_key(HASH_KEY_NAME, contract_hash.into());
+}
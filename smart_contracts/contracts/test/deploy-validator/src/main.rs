@@ -0,0 +1,71 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{collections::BTreeSet, string::ToString, vec};
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    account::{
+        AccountHash, ARG_AUTHORIZATION_KEYS, ARG_DEPLOY_HASH, DEPLOY_VALIDATOR_ENTRY_POINT,
+        DEPLOY_VALIDATOR_KEY,
+    },
+    contracts::{EntryPoint, EntryPoints, NamedKeys},
+    ApiError, CLType, CLTyped, EntryPointAccess, EntryPointType, Parameter,
+};
+
+const ARG_REQUIRED_KEY: &str = "required_key";
+const REQUIRED_KEY_NAME: &str = "required_key";
+
+/// Accepts a deploy only if it was approved by the key the validator was installed with.
+#[no_mangle]
+pub extern "C" fn validate_deploy() {
+    let authorization_keys: BTreeSet<AccountHash> = runtime::get_named_arg(ARG_AUTHORIZATION_KEYS);
+    let _deploy_hash: [u8; 32] = runtime::get_named_arg(ARG_DEPLOY_HASH);
+
+    let required_key_uref = runtime::get_key(REQUIRED_KEY_NAME)
+        .and_then(|key| key.into_uref())
+        .unwrap_or_revert_with(ApiError::MissingKey);
+    let required_key: AccountHash = storage::read(required_key_uref)
+        .unwrap_or_revert()
+        .unwrap_or_revert_with(ApiError::ValueNotFound);
+
+    if !authorization_keys.contains(&required_key) {
+        runtime::revert(ApiError::User(0))
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let required_key: AccountHash = runtime::get_named_arg(ARG_REQUIRED_KEY);
+
+    let entry_points = {
+        let mut entry_points = EntryPoints::new();
+        let entry_point = EntryPoint::new(
+            DEPLOY_VALIDATOR_ENTRY_POINT,
+            vec![
+                Parameter::new(ARG_AUTHORIZATION_KEYS, <BTreeSet<AccountHash>>::cl_type()),
+                Parameter::new(ARG_DEPLOY_HASH, CLType::ByteArray(32)),
+            ],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        );
+        entry_points.add_entry_point(entry_point);
+        entry_points
+    };
+
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(
+        REQUIRED_KEY_NAME.to_string(),
+        storage::new_uref(required_key).into(),
+    );
+
+    let (contract_hash, _contract_version) =
+        storage::new_contract(entry_points, Some(named_keys), None, None);
+    runtime::put_key(DEPLOY_VALIDATOR_KEY, contract_hash.into());
+}
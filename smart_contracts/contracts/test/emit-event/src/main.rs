@@ -0,0 +1,57 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{string::String, vec};
+
+use casper_contract::contract_api::{runtime, storage};
+use casper_types::{
+    contracts::{EntryPoint, EntryPoints},
+    ApiError, CLType, EntryPointAccess, EntryPointType, Parameter,
+};
+
+const ENTRY_POINT_EMIT: &str = "emit";
+const ENTRY_POINT_EMIT_AND_REVERT: &str = "emit_and_revert";
+const HASH_KEY_NAME: &str = "emit_event_hash";
+const ARG_TOPIC: &str = "topic";
+const ARG_MESSAGE: &str = "message";
+const INSTALLED_TOPIC: &str = "installed";
+
+#[no_mangle]
+pub extern "C" fn emit() {
+    let topic: String = runtime::get_named_arg(ARG_TOPIC);
+    let message: String = runtime::get_named_arg(ARG_MESSAGE);
+    runtime::emit_event(&topic, message);
+}
+
+#[no_mangle]
+pub extern "C" fn emit_and_revert() {
+    emit();
+    runtime::revert(ApiError::User(0))
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let entry_points = {
+        let mut entry_points = EntryPoints::new();
+        for name in [ENTRY_POINT_EMIT, ENTRY_POINT_EMIT_AND_REVERT] {
+            let entry_point = EntryPoint::new(
+                name,
+                vec![
+                    Parameter::new(ARG_TOPIC, CLType::String),
+                    Parameter::new(ARG_MESSAGE, CLType::String),
+                ],
+                CLType::Unit,
+                EntryPointAccess::Public,
+                EntryPointType::Contract,
+            );
+            entry_points.add_entry_point(entry_point);
+        }
+        entry_points
+    };
+
+    let (contract_hash, _contract_version) = storage::new_contract(entry_points, None, None, None);
+    runtime::put_key(HASH_KEY_NAME, contract_hash.into());
+    runtime::emit_event(INSTALLED_TOPIC, contract_hash);
+}
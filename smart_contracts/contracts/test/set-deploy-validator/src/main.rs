@@ -0,0 +1,18 @@
+#![no_std]
+#![no_main]
+
+use casper_contract::contract_api::runtime;
+use casper_types::{account::DEPLOY_VALIDATOR_KEY, Key};
+
+const ARG_VALIDATOR_KEY: &str = "validator_key";
+
+/// Puts the given key under the account's deploy validator named key, or removes the deploy
+/// validator if no key is given.
+#[no_mangle]
+pub extern "C" fn call() {
+    let maybe_validator_key: Option<Key> = runtime::get_named_arg(ARG_VALIDATOR_KEY);
+    match maybe_validator_key {
+        Some(validator_key) => runtime::put_key(DEPLOY_VALIDATOR_KEY, validator_key),
+        None => runtime::remove_key(DEPLOY_VALIDATOR_KEY),
+    }
+}
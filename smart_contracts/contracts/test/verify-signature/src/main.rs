@@ -0,0 +1,31 @@
+#![no_std]
+#![no_main]
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    bytesrepr::{self, Bytes},
+    ApiError, PublicKey, Signature,
+};
+
+const ARG_MESSAGE: &str = "message";
+const ARG_SIGNATURE: &str = "signature";
+const ARG_PUBLIC_KEY: &str = "public_key";
+
+const VERIFY_SIGNATURE_RESULT: &str = "verify_signature_result";
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let message: Bytes = runtime::get_named_arg(ARG_MESSAGE);
+    let signature_bytes: Bytes = runtime::get_named_arg(ARG_SIGNATURE);
+    let public_key: PublicKey = runtime::get_named_arg(ARG_PUBLIC_KEY);
+
+    let signature: Signature =
+        bytesrepr::deserialize(signature_bytes.into()).unwrap_or_revert_with(ApiError::Deserialize);
+
+    let is_valid = runtime::verify_signature(message, &signature, &public_key);
+    let uref = storage::new_uref(is_valid);
+    runtime::put_key(VERIFY_SIGNATURE_RESULT, uref.into())
+}
@@ -33,6 +33,22 @@ use crate::{
     crypto, AccessRights, ContextAccessRights, Key, URef, BLAKE2B_DIGEST_LENGTH,
 };
 
+/// The name of the named key under which an account can install a deploy validator.
+///
+/// The named key should hold the [`Key::Hash`] of a contract exposing a
+/// [`DEPLOY_VALIDATOR_ENTRY_POINT`] entry point.  When installed, the deploy validator is called
+/// in place of the deploy threshold check whenever the account sends a deploy, and the deploy is
+/// accepted only if the call succeeds.
+pub const DEPLOY_VALIDATOR_KEY: &str = "deploy_validator";
+/// The name of the entry point called on an account's deploy validator.
+pub const DEPLOY_VALIDATOR_ENTRY_POINT: &str = "validate_deploy";
+/// The argument of the deploy validator entry point holding the account hashes of the keys which
+/// approved the deploy, as a `BTreeSet<AccountHash>`.
+pub const ARG_AUTHORIZATION_KEYS: &str = "authorization_keys";
+/// The argument of the deploy validator entry point holding the hash of the deploy, as a
+/// `[u8; 32]`.
+pub const ARG_DEPLOY_HASH: &str = "deploy_hash";
+
 /// Represents an Account in the global state.
 #[derive(PartialEq, Eq, Clone, Debug, Serialize)]
 #[cfg_attr(feature = "datasize", derive(DataSize))]
@@ -7,7 +7,7 @@ use alloc::{
     string::String,
     vec::Vec,
 };
-use core::mem;
+use core::{convert::TryInto, mem};
 
 #[cfg(feature = "datasize")]
 use datasize::DataSize;
@@ -47,6 +47,12 @@ const CL_TYPE_TAG_TUPLE2: u8 = 19;
 const CL_TYPE_TAG_TUPLE3: u8 = 20;
 const CL_TYPE_TAG_ANY: u8 = 21;
 const CL_TYPE_TAG_PUBLIC_KEY: u8 = 22;
+const CL_TYPE_TAG_STRUCT: u8 = 23;
+const CL_TYPE_TAG_ENUM: u8 = 24;
+
+/// The maximum number of variants of a [`CLType::Enum`], as the index of a value's variant is
+/// serialized as a `u8`.
+pub(crate) const MAX_CL_ENUM_VARIANTS: usize = u8::MAX as usize + 1;
 
 /// Casper types, i.e. types which can be stored and manipulated by smart contracts.
 ///
@@ -114,6 +120,46 @@ pub enum CLType {
     Tuple3([Box<CLType>; 3]),
     /// Unspecified type.
     Any,
+    /// Struct with named fields, serialized as its fields in order.
+    #[cfg_attr(feature = "datasize", data_size(skip))]
+    Struct {
+        /// The name of the struct.
+        name: String,
+        /// The fields of the struct, in order.
+        fields: Vec<NamedCLType>,
+    },
+    /// Enum with named variants, each holding a payload of a given `CLType`.  Serialized as the
+    /// index of the variant as a `u8` followed by the payload.
+    #[cfg_attr(feature = "datasize", data_size(skip))]
+    Enum {
+        /// The name of the enum.
+        name: String,
+        /// The variants of the enum, in order.
+        variants: Vec<NamedCLType>,
+    },
+}
+
+/// A named `CLType`, i.e. a field of a [`CLType::Struct`] or a variant of a [`CLType::Enum`].
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "datasize", derive(DataSize))]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct NamedCLType {
+    /// The name of the field or variant.
+    pub name: String,
+    /// The type of the field, or of the variant's payload.
+    #[cfg_attr(feature = "datasize", data_size(skip))]
+    pub cl_type: CLType,
+}
+
+impl NamedCLType {
+    /// Constructs a new `NamedCLType`.
+    pub fn new<T: Into<String>>(name: T, cl_type: CLType) -> Self {
+        NamedCLType {
+            name: name.into(),
+            cl_type,
+        }
+    }
 }
 
 impl CLType {
@@ -143,6 +189,12 @@ impl CLType {
                 CLType::Tuple1(cl_type_array) => serialized_length_of_cl_tuple_type(cl_type_array),
                 CLType::Tuple2(cl_type_array) => serialized_length_of_cl_tuple_type(cl_type_array),
                 CLType::Tuple3(cl_type_array) => serialized_length_of_cl_tuple_type(cl_type_array),
+                CLType::Struct { name, fields } => {
+                    name.serialized_length() + serialized_length_of_named_cl_types(fields)
+                }
+                CLType::Enum { name, variants } => {
+                    name.serialized_length() + serialized_length_of_named_cl_types(variants)
+                }
             }
     }
 
@@ -150,6 +202,38 @@ impl CLType {
     pub fn is_option(&self) -> bool {
         matches!(self, Self::Option(..))
     }
+
+    /// Constructs a [`CLType::Struct`] with the given name and fields.
+    pub fn new_struct<T, I, U>(name: T, fields: I) -> Self
+    where
+        T: Into<String>,
+        I: IntoIterator<Item = (U, CLType)>,
+        U: Into<String>,
+    {
+        CLType::Struct {
+            name: name.into(),
+            fields: fields
+                .into_iter()
+                .map(|(name, cl_type)| NamedCLType::new(name, cl_type))
+                .collect(),
+        }
+    }
+
+    /// Constructs a [`CLType::Enum`] with the given name and variants.
+    pub fn new_enum<T, I, U>(name: T, variants: I) -> Self
+    where
+        T: Into<String>,
+        I: IntoIterator<Item = (U, CLType)>,
+        U: Into<String>,
+    {
+        CLType::Enum {
+            name: name.into(),
+            variants: variants
+                .into_iter()
+                .map(|(name, cl_type)| NamedCLType::new(name, cl_type))
+                .collect(),
+        }
+    }
 }
 
 /// Returns the `CLType` describing a "named key" on the system, i.e. a `(String, Key)`.
@@ -206,6 +290,19 @@ impl CLType {
                 serialize_cl_tuple_type(CL_TYPE_TAG_TUPLE3, cl_type_array, stream)?
             }
             CLType::Any => stream.push(CL_TYPE_TAG_ANY),
+            CLType::Struct { name, fields } => {
+                stream.push(CL_TYPE_TAG_STRUCT);
+                name.write_bytes(stream)?;
+                serialize_named_cl_types(fields, stream)?;
+            }
+            CLType::Enum { name, variants } => {
+                if variants.len() > MAX_CL_ENUM_VARIANTS {
+                    return Err(bytesrepr::Error::NotRepresentable);
+                }
+                stream.push(CL_TYPE_TAG_ENUM);
+                name.write_bytes(stream)?;
+                serialize_named_cl_types(variants, stream)?;
+            }
         }
         Ok(())
     }
@@ -300,6 +397,21 @@ fn depth_limited_from_bytes(depth: u8, bytes: &[u8]) -> Result<(CLType, &[u8]),
             Ok((cl_type, remainder))
         }
         CL_TYPE_TAG_ANY => Ok((CLType::Any, remainder)),
+        CL_TYPE_TAG_STRUCT => {
+            let (name, remainder) = String::from_bytes(remainder)?;
+            let (fields, remainder) = parse_named_cl_types(depth, remainder)?;
+            let cl_type = CLType::Struct { name, fields };
+            Ok((cl_type, remainder))
+        }
+        CL_TYPE_TAG_ENUM => {
+            let (name, remainder) = String::from_bytes(remainder)?;
+            let (variants, remainder) = parse_named_cl_types(depth, remainder)?;
+            if variants.len() > MAX_CL_ENUM_VARIANTS {
+                return Err(bytesrepr::Error::Formatting);
+            }
+            let cl_type = CLType::Enum { name, variants };
+            Ok((cl_type, remainder))
+        }
         _ => Err(bytesrepr::Error::Formatting),
     }
 }
@@ -340,6 +452,47 @@ fn serialized_length_of_cl_tuple_type<'a, T: IntoIterator<Item = &'a Box<CLType>
         .sum()
 }
 
+fn serialize_named_cl_types(
+    named_cl_types: &[NamedCLType],
+    stream: &mut Vec<u8>,
+) -> Result<(), bytesrepr::Error> {
+    let count: u32 = named_cl_types
+        .len()
+        .try_into()
+        .map_err(|_| bytesrepr::Error::NotRepresentable)?;
+    count.write_bytes(stream)?;
+    for named_cl_type in named_cl_types {
+        named_cl_type.name.write_bytes(stream)?;
+        named_cl_type.cl_type.append_bytes(stream)?;
+    }
+    Ok(())
+}
+
+fn parse_named_cl_types(
+    depth: u8,
+    bytes: &[u8],
+) -> Result<(Vec<NamedCLType>, &[u8]), bytesrepr::Error> {
+    let (count, mut bytes) = u32::from_bytes(bytes)?;
+    let mut named_cl_types = Vec::new();
+    for _ in 0..count {
+        let (name, remainder) = String::from_bytes(bytes)?;
+        let (cl_type, remainder) = depth_limited_from_bytes(depth, remainder)?;
+        named_cl_types.push(NamedCLType { name, cl_type });
+        bytes = remainder;
+    }
+    Ok((named_cl_types, bytes))
+}
+
+fn serialized_length_of_named_cl_types(named_cl_types: &[NamedCLType]) -> usize {
+    bytesrepr::U32_SERIALIZED_LENGTH
+        + named_cl_types
+            .iter()
+            .map(|named_cl_type| {
+                named_cl_type.name.serialized_length() + named_cl_type.cl_type.serialized_length()
+            })
+            .sum::<usize>()
+}
+
 /// A type which can be described as a [`CLType`].
 pub trait CLTyped {
     /// The `CLType` of `Self`.
@@ -772,6 +925,83 @@ mod tests {
         round_trip(&any);
     }
 
+    fn cl_type_round_trip(cl_type: &CLType) {
+        let mut serialized_cl_type = Vec::new();
+        cl_type.append_bytes(&mut serialized_cl_type).unwrap();
+        assert_eq!(serialized_cl_type.len(), cl_type.serialized_length());
+        let parsed_cl_type: CLType = bytesrepr::deserialize(serialized_cl_type).unwrap();
+        assert_eq!(*cl_type, parsed_cl_type);
+    }
+
+    #[test]
+    fn struct_and_enum_cl_types_should_round_trip() {
+        let struct_type = CLType::new_struct("Point", [("x", CLType::I32), ("y", CLType::I32)]);
+        cl_type_round_trip(&struct_type);
+
+        let enum_type = CLType::new_enum(
+            "Shape",
+            [
+                ("Empty", CLType::Unit),
+                ("Circle", CLType::U64),
+                ("Polygon", CLType::List(Box::new(struct_type))),
+            ],
+        );
+        cl_type_round_trip(&enum_type);
+
+        let empty_struct_type = CLType::new_struct("Empty", Vec::<(String, CLType)>::new());
+        cl_type_round_trip(&empty_struct_type);
+    }
+
+    #[test]
+    fn enum_cl_type_with_too_many_variants_should_not_serialize() {
+        let variants = (0..=MAX_CL_ENUM_VARIANTS).map(|index| (index.to_string(), CLType::Unit));
+        let enum_type = CLType::new_enum("Large", variants);
+        assert_eq!(
+            enum_type.append_bytes(&mut Vec::new()),
+            Err(bytesrepr::Error::NotRepresentable)
+        );
+    }
+
+    #[test]
+    fn struct_value_should_work() {
+        #[derive(PartialEq, Debug, Clone)]
+        struct Point {
+            x: i32,
+            label: String,
+        }
+
+        impl CLTyped for Point {
+            fn cl_type() -> CLType {
+                CLType::new_struct("Point", [("x", CLType::I32), ("label", CLType::String)])
+            }
+        }
+
+        impl ToBytes for Point {
+            fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+                let mut result = self.x.to_bytes()?;
+                result.append(&mut self.label.to_bytes()?);
+                Ok(result)
+            }
+
+            fn serialized_length(&self) -> usize {
+                self.x.serialized_length() + self.label.serialized_length()
+            }
+        }
+
+        impl FromBytes for Point {
+            fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+                let (x, remainder) = i32::from_bytes(bytes)?;
+                let (label, remainder) = String::from_bytes(remainder)?;
+                Ok((Point { x, label }, remainder))
+            }
+        }
+
+        round_trip(&Point {
+            x: -1,
+            label: String::from("a"),
+        });
+    }
+
     #[test]
     fn should_have_cltype_of_ref_to_cltyped() {
         assert_eq!(<Vec<&u64>>::cl_type(), <Vec<u64>>::cl_type())
@@ -1,7 +1,7 @@
 use alloc::{string::String, vec, vec::Vec};
 
 use serde::Serialize;
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
 
 use crate::{
     bytesrepr::{self, FromBytes, OPTION_NONE_TAG, OPTION_SOME_TAG, RESULT_ERR_TAG, RESULT_OK_TAG},
@@ -111,6 +111,24 @@ fn depth_limited_to_json<'a>(
             Some((json!([t1, t2, t3]), remainder))
         }
         CLType::Any => None,
+        CLType::Struct { fields, .. } => {
+            let mut result = Map::new();
+            let mut stream = bytes;
+            for field in fields {
+                let (value, remainder) = depth_limited_to_json(depth, &field.cl_type, stream)?;
+                result.insert(field.name.clone(), value);
+                stream = remainder;
+            }
+            Some((Value::Object(result), stream))
+        }
+        CLType::Enum { variants, .. } => {
+            let (index, remainder) = u8::from_bytes(bytes).ok()?;
+            let variant = variants.get(index as usize)?;
+            let (value, remainder) = depth_limited_to_json(depth, &variant.cl_type, remainder)?;
+            let mut result = Map::new();
+            result.insert(variant.name.clone(), value);
+            Some((Value::Object(result), remainder))
+        }
     }
 }
 
@@ -242,6 +260,37 @@ mod tests {
         test_value((v1, v2, v3));
     }
 
+    #[test]
+    fn struct_to_json_value() {
+        let cl_type = CLType::new_struct(
+            "Point",
+            [
+                ("x", CLType::I32),
+                ("label", CLType::Option(Box::new(CLType::String))),
+            ],
+        );
+        let mut bytes = (-3i32).to_bytes().unwrap();
+        bytes.append(&mut Some(String::from("origin")).to_bytes().unwrap());
+        let cl_value = CLValue::from_components(cl_type, bytes);
+        let cl_value_as_json = cl_value_to_json(&cl_value).unwrap();
+        let expected = json!({ "x": -3, "label": "origin" });
+        assert_eq!(cl_value_as_json, expected);
+    }
+
+    #[test]
+    fn enum_to_json_value() {
+        let cl_type = CLType::new_enum("Shape", [("Empty", CLType::Unit), ("Circle", CLType::U64)]);
+        let mut bytes = vec![1u8];
+        bytes.append(&mut 7u64.to_bytes().unwrap());
+        let cl_value = CLValue::from_components(cl_type.clone(), bytes);
+        let cl_value_as_json = cl_value_to_json(&cl_value).unwrap();
+        assert_eq!(cl_value_as_json, json!({ "Circle": 7 }));
+
+        // An out-of-range variant index cannot be represented.
+        let cl_value = CLValue::from_components(cl_type, vec![2u8]);
+        assert!(cl_value_to_json(&cl_value).is_none());
+    }
+
     #[test]
     fn json_encoding_nested_tuple_1_value_should_not_stack_overflow() {
         // Returns a CLType corresponding to (((...(cl_type,),...),),) nested in tuples to
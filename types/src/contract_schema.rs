@@ -0,0 +1,353 @@
+//! Data types describing the interface of a contract.
+// TODO - remove once schemars stops causing warning.
+#![allow(clippy::field_reassign_with_default)]
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::convert::TryInto;
+
+#[cfg(feature = "json-schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bytesrepr::{self, FromBytes, ToBytes, U32_SERIALIZED_LENGTH},
+    contracts::{self, Parameters},
+    CLType, CLTyped, EntryPoints,
+};
+
+/// The signature of an entry point as described by a [`ContractSchema`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct EntryPointSchema {
+    /// The parameters of the entry point, in order.
+    args: Parameters,
+    /// The return type of the entry point.
+    ret: CLType,
+}
+
+impl EntryPointSchema {
+    /// Constructs a new `EntryPointSchema`.
+    pub fn new(args: Parameters, ret: CLType) -> Self {
+        EntryPointSchema { args, ret }
+    }
+
+    /// Returns the parameters of the entry point.
+    pub fn args(&self) -> &Parameters {
+        &self.args
+    }
+
+    /// Returns the return type of the entry point.
+    pub fn ret(&self) -> &CLType {
+        &self.ret
+    }
+}
+
+impl ToBytes for EntryPointSchema {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        self.write_bytes(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.args.serialized_length() + self.ret.serialized_length()
+    }
+
+    fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
+        self.args.write_bytes(writer)?;
+        self.ret.append_bytes(writer)
+    }
+}
+
+impl FromBytes for EntryPointSchema {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (args, remainder) = Parameters::from_bytes(bytes)?;
+        let (ret, remainder) = CLType::from_bytes(remainder)?;
+        Ok((EntryPointSchema { args, ret }, remainder))
+    }
+}
+
+/// A description of the interface of a single contract version.
+///
+/// A schema can optionally be provided when adding a new version to a contract package, in which
+/// case it is validated against the entry points of that version and stored in global state under
+/// [`Key::ContractSchema`](crate::Key::ContractSchema).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ContractSchema {
+    /// The signatures of the entry points of the contract, by name.
+    entry_points: BTreeMap<String, EntryPointSchema>,
+    /// The types of the values stored under the named keys of the contract, by name.
+    named_keys: BTreeMap<String, CLType>,
+    /// The types of the values stored in the dictionaries of the contract, by the name of the
+    /// named key holding the dictionary's seed `URef`.
+    dictionaries: BTreeMap<String, CLType>,
+    /// The types of the payloads of the events emitted by the contract, by topic.
+    events: BTreeMap<String, CLType>,
+}
+
+impl ContractSchema {
+    /// Constructs a new, empty `ContractSchema`.
+    pub fn new() -> Self {
+        ContractSchema::default()
+    }
+
+    /// Adds the signature of an entry point, replacing any previous one with the same name.
+    pub fn add_entry_point<T: Into<String>>(&mut self, name: T, args: Parameters, ret: CLType) {
+        self.entry_points
+            .insert(name.into(), EntryPointSchema::new(args, ret));
+    }
+
+    /// Adds the type of the value stored under a named key, replacing any previous one with the
+    /// same name.
+    pub fn add_named_key<T: Into<String>>(&mut self, name: T, cl_type: CLType) {
+        self.named_keys.insert(name.into(), cl_type);
+    }
+
+    /// Adds the type of the values stored in a dictionary, replacing any previous one with the
+    /// same name.
+    pub fn add_dictionary<T: Into<String>>(&mut self, name: T, value_type: CLType) {
+        self.dictionaries.insert(name.into(), value_type);
+    }
+
+    /// Adds the type of the payload of an event, replacing any previous one with the same topic.
+    pub fn add_event<T: Into<String>>(&mut self, topic: T, payload_type: CLType) {
+        self.events.insert(topic.into(), payload_type);
+    }
+
+    /// Returns the signatures of the entry points of the contract.
+    pub fn entry_points(&self) -> &BTreeMap<String, EntryPointSchema> {
+        &self.entry_points
+    }
+
+    /// Returns the types of the values stored under the named keys of the contract.
+    pub fn named_keys(&self) -> &BTreeMap<String, CLType> {
+        &self.named_keys
+    }
+
+    /// Returns the types of the values stored in the dictionaries of the contract.
+    pub fn dictionaries(&self) -> &BTreeMap<String, CLType> {
+        &self.dictionaries
+    }
+
+    /// Returns the types of the payloads of the events emitted by the contract.
+    pub fn events(&self) -> &BTreeMap<String, CLType> {
+        &self.events
+    }
+
+    /// Checks that the schema describes exactly the given entry points, with matching parameters
+    /// and return types.
+    pub fn validate(&self, entry_points: &EntryPoints) -> Result<(), contracts::Error> {
+        if self.entry_points.len() != entry_points.len() {
+            return Err(contracts::Error::ContractSchemaMismatch);
+        }
+        for (name, entry_point_schema) in &self.entry_points {
+            match entry_points.get(name) {
+                Some(entry_point)
+                    if entry_point.args() == entry_point_schema.args.as_slice()
+                        && *entry_point.ret() == entry_point_schema.ret => {}
+                _ => return Err(contracts::Error::ContractSchemaMismatch),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&EntryPoints> for ContractSchema {
+    fn from(entry_points: &EntryPoints) -> Self {
+        let mut schema = ContractSchema::new();
+        for entry_point in entry_points.clone().take_entry_points() {
+            let (name, args, ret, _access, _entry_point_type) = entry_point.into();
+            schema.add_entry_point(name, args, ret);
+        }
+        schema
+    }
+}
+
+impl CLTyped for ContractSchema {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for ContractSchema {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        self.write_bytes(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.entry_points.serialized_length()
+            + serialized_length_of_cl_types(&self.named_keys)
+            + serialized_length_of_cl_types(&self.dictionaries)
+            + serialized_length_of_cl_types(&self.events)
+    }
+
+    fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
+        self.entry_points.write_bytes(writer)?;
+        write_cl_types(&self.named_keys, writer)?;
+        write_cl_types(&self.dictionaries, writer)?;
+        write_cl_types(&self.events, writer)
+    }
+}
+
+impl FromBytes for ContractSchema {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (entry_points, remainder) = BTreeMap::<String, EntryPointSchema>::from_bytes(bytes)?;
+        let (named_keys, remainder) = cl_types_from_bytes(remainder)?;
+        let (dictionaries, remainder) = cl_types_from_bytes(remainder)?;
+        let (events, remainder) = cl_types_from_bytes(remainder)?;
+        let schema = ContractSchema {
+            entry_points,
+            named_keys,
+            dictionaries,
+            events,
+        };
+        Ok((schema, remainder))
+    }
+}
+
+fn serialized_length_of_cl_types(cl_types: &BTreeMap<String, CLType>) -> usize {
+    U32_SERIALIZED_LENGTH
+        + cl_types
+            .iter()
+            .map(|(name, cl_type)| name.serialized_length() + cl_type.serialized_length())
+            .sum::<usize>()
+}
+
+fn write_cl_types(
+    cl_types: &BTreeMap<String, CLType>,
+    writer: &mut Vec<u8>,
+) -> Result<(), bytesrepr::Error> {
+    let length: u32 = cl_types
+        .len()
+        .try_into()
+        .map_err(|_| bytesrepr::Error::NotRepresentable)?;
+    length.write_bytes(writer)?;
+    for (name, cl_type) in cl_types {
+        name.write_bytes(writer)?;
+        cl_type.append_bytes(writer)?;
+    }
+    Ok(())
+}
+
+fn cl_types_from_bytes(
+    bytes: &[u8],
+) -> Result<(BTreeMap<String, CLType>, &[u8]), bytesrepr::Error> {
+    let (length, mut remainder) = u32::from_bytes(bytes)?;
+    let mut cl_types = BTreeMap::new();
+    for _ in 0..length {
+        let (name, rem) = String::from_bytes(remainder)?;
+        let (cl_type, rem) = CLType::from_bytes(rem)?;
+        cl_types.insert(name, cl_type);
+        remainder = rem;
+    }
+    Ok((cl_types, remainder))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::{bytesrepr, EntryPoint, EntryPointAccess, EntryPointType, NamedCLType, Parameter};
+
+    fn make_entry_points() -> EntryPoints {
+        let mut entry_points = EntryPoints::new();
+        entry_points.add_entry_point(EntryPoint::new(
+            "transfer",
+            vec![
+                Parameter::new("recipient", CLType::Key),
+                Parameter::new("amount", CLType::U256),
+            ],
+            CLType::Unit,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+        entry_points.add_entry_point(EntryPoint::new(
+            "total_supply",
+            vec![],
+            CLType::U256,
+            EntryPointAccess::Public,
+            EntryPointType::Contract,
+        ));
+        entry_points
+    }
+
+    fn make_schema() -> ContractSchema {
+        let mut schema = ContractSchema::from(&make_entry_points());
+        schema.add_named_key("total_supply", CLType::U256);
+        schema.add_dictionary("balances", CLType::U256);
+        schema.add_event(
+            "transfer",
+            CLType::Struct {
+                name: String::from("Transfer"),
+                fields: vec![
+                    NamedCLType::new("recipient", CLType::Key),
+                    NamedCLType::new("amount", CLType::U256),
+                ],
+            },
+        );
+        schema
+    }
+
+    #[test]
+    fn bytesrepr_roundtrip() {
+        bytesrepr::test_serialization_roundtrip(&ContractSchema::new());
+        bytesrepr::test_serialization_roundtrip(&make_schema());
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let schema = make_schema();
+        let json_string = serde_json::to_string_pretty(&schema).unwrap();
+        let decoded: ContractSchema = serde_json::from_str(&json_string).unwrap();
+        assert_eq!(decoded, schema);
+    }
+
+    #[test]
+    fn should_validate_matching_entry_points() {
+        assert_eq!(make_schema().validate(&make_entry_points()), Ok(()));
+    }
+
+    #[test]
+    fn should_not_validate_mismatched_entry_points() {
+        let entry_points = make_entry_points();
+
+        let mut schema = make_schema();
+        schema.add_entry_point("mint", vec![], CLType::Unit);
+        assert_eq!(
+            schema.validate(&entry_points),
+            Err(contracts::Error::ContractSchemaMismatch)
+        );
+
+        let mut schema = make_schema();
+        schema.add_entry_point("total_supply", vec![], CLType::U512);
+        assert_eq!(
+            schema.validate(&entry_points),
+            Err(contracts::Error::ContractSchemaMismatch)
+        );
+
+        let mut schema = make_schema();
+        schema.add_entry_point(
+            "transfer",
+            vec![
+                Parameter::new("amount", CLType::U256),
+                Parameter::new("recipient", CLType::Key),
+            ],
+            CLType::Unit,
+        );
+        assert_eq!(
+            schema.validate(&entry_points),
+            Err(contracts::Error::ContractSchemaMismatch)
+        );
+
+        assert_eq!(
+            ContractSchema::new().validate(&entry_points),
+            Err(contracts::Error::ContractSchemaMismatch)
+        );
+    }
+}
@@ -106,6 +106,12 @@ pub enum Error {
     /// assert_eq!(9, Error::URefAlreadyExists as u8);
     /// ```
     URefAlreadyExists = 9,
+    /// The schema provided for a new contract version does not match its entry points.
+    /// ```
+    /// # use casper_types::contracts::Error;
+    /// assert_eq!(10, Error::ContractSchemaMismatch as u8);
+    /// ```
+    ContractSchemaMismatch = 10,
 }
 
 impl TryFrom<u8> for Error {
@@ -122,6 +128,7 @@ impl TryFrom<u8> for Error {
             v if v == Self::UnableToRemoveURef as u8 => Self::UnableToRemoveURef,
             v if v == Self::GroupInUse as u8 => Self::GroupInUse,
             v if v == Self::URefAlreadyExists as u8 => Self::URefAlreadyExists,
+            v if v == Self::ContractSchemaMismatch as u8 => Self::ContractSchemaMismatch,
             _ => return Err(()),
         };
         Ok(error)
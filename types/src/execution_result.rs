@@ -30,7 +30,7 @@ use rand::{
 };
 #[cfg(feature = "json-schema")]
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[cfg(feature = "json-schema")]
 use crate::KEY_HASH_LENGTH;
@@ -38,7 +38,7 @@ use crate::{
     account::AccountHash,
     bytesrepr::{self, FromBytes, ToBytes, U8_SERIALIZED_LENGTH},
     system::auction::{Bid, EraInfo, UnbondingPurse, WithdrawPurse},
-    CLValue, DeployInfo, NamedKey, Transfer, TransferAddr, U128, U256, U512,
+    CLValue, DeployInfo, Key, NamedKey, Transfer, TransferAddr, U128, U256, U512,
 };
 
 #[derive(FromPrimitive, ToPrimitive, Debug)]
@@ -46,6 +46,9 @@ use crate::{
 enum ExecutionResultTag {
     Failure = 0,
     Success = 1,
+    /// A successful execution which emitted contract events.  Successes without events are still
+    /// encoded with the `Success` tag, so that their encoding is unchanged.
+    SuccessWithEvents = 2,
 }
 
 impl TryFrom<u8> for ExecutionResultTag {
@@ -147,14 +150,15 @@ static EXECUTION_RESULT: Lazy<ExecutionResult> = Lazy::new(|| {
         effect,
         transfers,
         cost: U512::from(123_456),
+        events: vec![],
     }
 });
 
 /// The result of executing a single deploy.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
 #[cfg_attr(feature = "datasize", derive(DataSize))]
 #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(feature = "json-schema", schemars(deny_unknown_fields))]
 pub enum ExecutionResult {
     /// The result of a failed execution.
     Failure {
@@ -175,7 +179,216 @@ pub enum ExecutionResult {
         transfers: Vec<TransferAddr>,
         /// The cost of executing the deploy.
         cost: U512,
+        /// The events emitted by contracts while executing the deploy, in the order they were
+        /// emitted.
+        #[cfg_attr(
+            feature = "json-schema",
+            schemars(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        events: Vec<ContractEvent>,
+    },
+}
+
+impl Serialize for ExecutionResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            HumanReadableExecutionResult::from(self.clone()).serialize(serializer)
+        } else {
+            BinaryExecutionResult::from(self.clone()).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ExecutionResult {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            HumanReadableExecutionResult::deserialize(deserializer).map(ExecutionResult::from)
+        } else {
+            BinaryExecutionResult::deserialize(deserializer).map(ExecutionResult::from)
+        }
+    }
+}
+
+/// The representation of an [`ExecutionResult`] in human-readable formats, where the `events` of
+/// a success are omitted if empty.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "ExecutionResult", deny_unknown_fields)]
+enum HumanReadableExecutionResult {
+    Failure {
+        effect: ExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+        error_message: String,
     },
+    Success {
+        effect: ExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        events: Vec<ContractEvent>,
+    },
+}
+
+impl From<ExecutionResult> for HumanReadableExecutionResult {
+    fn from(execution_result: ExecutionResult) -> Self {
+        match execution_result {
+            ExecutionResult::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            } => HumanReadableExecutionResult::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            },
+            ExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+                events,
+            } => HumanReadableExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+                events,
+            },
+        }
+    }
+}
+
+impl From<HumanReadableExecutionResult> for ExecutionResult {
+    fn from(execution_result: HumanReadableExecutionResult) -> Self {
+        match execution_result {
+            HumanReadableExecutionResult::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            } => ExecutionResult::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            },
+            HumanReadableExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+                events,
+            } => ExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+                events,
+            },
+        }
+    }
+}
+
+/// The representation of an [`ExecutionResult`] in binary formats, which can't omit fields.
+///
+/// As with the `bytesrepr` encoding, a success without events keeps the layout it had before
+/// events were introduced, so that previously-serialized execution results can still be read.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "ExecutionResult")]
+enum BinaryExecutionResult {
+    Failure {
+        effect: ExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+        error_message: String,
+    },
+    Success {
+        effect: ExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+    },
+    SuccessWithEvents {
+        effect: ExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+        events: Vec<ContractEvent>,
+    },
+}
+
+impl From<ExecutionResult> for BinaryExecutionResult {
+    fn from(execution_result: ExecutionResult) -> Self {
+        match execution_result {
+            ExecutionResult::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            } => BinaryExecutionResult::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            },
+            ExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+                events,
+            } if events.is_empty() => BinaryExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+            },
+            ExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+                events,
+            } => BinaryExecutionResult::SuccessWithEvents {
+                effect,
+                transfers,
+                cost,
+                events,
+            },
+        }
+    }
+}
+
+impl From<BinaryExecutionResult> for ExecutionResult {
+    fn from(execution_result: BinaryExecutionResult) -> Self {
+        match execution_result {
+            BinaryExecutionResult::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            } => ExecutionResult::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            },
+            BinaryExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+            } => ExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+                events: vec![],
+            },
+            BinaryExecutionResult::SuccessWithEvents {
+                effect,
+                transfers,
+                cost,
+                events,
+            } => ExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+                events,
+            },
+        }
+    }
 }
 
 impl ExecutionResult {
@@ -194,11 +407,15 @@ impl ExecutionResult {
                 cost: _,
                 error_message: _,
             } => ExecutionResultTag::Failure,
+            ExecutionResult::Success { events, .. } if events.is_empty() => {
+                ExecutionResultTag::Success
+            }
             ExecutionResult::Success {
                 effect: _,
                 transfers: _,
                 cost: _,
-            } => ExecutionResultTag::Success,
+                events: _,
+            } => ExecutionResultTag::SuccessWithEvents,
         }
     }
 }
@@ -234,6 +451,12 @@ impl Distribution<ExecutionResult> for Standard {
             transfers.push(TransferAddr::new(rng.gen()))
         }
 
+        let event_count = rng.gen_range(0..3);
+        let mut events = vec![];
+        for _ in 0..event_count {
+            events.push(rng.gen())
+        }
+
         if rng.gen() {
             ExecutionResult::Failure {
                 effect: execution_effect,
@@ -246,6 +469,7 @@ impl Distribution<ExecutionResult> for Standard {
                 effect: execution_effect,
                 transfers,
                 cost: rng.gen::<u64>().into(),
+                events,
             }
         }
     }
@@ -273,10 +497,14 @@ impl ToBytes for ExecutionResult {
                 effect,
                 transfers,
                 cost,
+                events,
             } => {
                 buffer.extend(effect.to_bytes()?);
                 buffer.extend(transfers.to_bytes()?);
                 buffer.extend(cost.to_bytes()?);
+                if !events.is_empty() {
+                    buffer.extend(events.to_bytes()?);
+                }
             }
         }
         Ok(buffer)
@@ -300,10 +528,17 @@ impl ToBytes for ExecutionResult {
                     effect: execution_effect,
                     transfers,
                     cost,
+                    events,
                 } => {
+                    let events_length = if events.is_empty() {
+                        0
+                    } else {
+                        events.serialized_length()
+                    };
                     execution_effect.serialized_length()
                         + transfers.serialized_length()
                         + cost.serialized_length()
+                        + events_length
                 }
             }
     }
@@ -334,9 +569,115 @@ impl FromBytes for ExecutionResult {
                     effect: execution_effect,
                     transfers,
                     cost,
+                    events: vec![],
                 };
                 Ok((execution_result, remainder))
             }
+            ExecutionResultTag::SuccessWithEvents => {
+                let (execution_effect, remainder) = ExecutionEffect::from_bytes(remainder)?;
+                let (transfers, remainder) = Vec::<TransferAddr>::from_bytes(remainder)?;
+                let (cost, remainder) = U512::from_bytes(remainder)?;
+                let (events, remainder) = Vec::<ContractEvent>::from_bytes(remainder)?;
+                if events.is_empty() {
+                    return Err(bytesrepr::Error::Formatting);
+                }
+                let execution_result = ExecutionResult::Success {
+                    effect: execution_effect,
+                    transfers,
+                    cost,
+                    events,
+                };
+                Ok((execution_result, remainder))
+            }
+        }
+    }
+}
+
+/// An event emitted by a contract while executing a deploy.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "datasize", derive(DataSize))]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ContractEvent {
+    /// The key of the account or contract which emitted the event, as its formatted string in
+    /// human-readable formats.
+    #[serde(with = "formatted_key")]
+    #[cfg_attr(feature = "json-schema", schemars(with = "String"))]
+    pub emitter: Key,
+    /// The topic of the event.
+    pub topic: String,
+    /// The payload of the event.
+    pub payload: CLValue,
+}
+
+impl ToBytes for ContractEvent {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        self.write_bytes(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.emitter.serialized_length()
+            + self.topic.serialized_length()
+            + self.payload.serialized_length()
+    }
+
+    fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
+        self.emitter.write_bytes(writer)?;
+        self.topic.write_bytes(writer)?;
+        self.payload.write_bytes(writer)?;
+        Ok(())
+    }
+}
+
+impl FromBytes for ContractEvent {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (emitter, remainder) = Key::from_bytes(bytes)?;
+        let (topic, remainder) = String::from_bytes(remainder)?;
+        let (payload, remainder) = CLValue::from_bytes(remainder)?;
+        let contract_event = ContractEvent {
+            emitter,
+            topic,
+            payload,
+        };
+        Ok((contract_event, remainder))
+    }
+}
+
+impl Distribution<ContractEvent> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ContractEvent {
+        ContractEvent {
+            emitter: rng.gen(),
+            topic: rng.gen::<u64>().to_string(),
+            payload: CLValue::from_t(rng.gen::<u64>()).unwrap(),
+        }
+    }
+}
+
+/// Serializes a [`Key`] as its formatted string in human-readable formats, like the keys of
+/// [`TransformEntry`]s.
+mod formatted_key {
+    use alloc::string::String;
+
+    use serde::{de::Error as SerdeError, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::Key;
+
+    pub(super) fn serialize<S: Serializer>(key: &Key, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            key.to_formatted_string().serialize(serializer)
+        } else {
+            key.serialize(serializer)
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Key, D::Error> {
+        if deserializer.is_human_readable() {
+            let formatted_key = String::deserialize(deserializer)?;
+            Key::from_formatted_str(&formatted_key).map_err(SerdeError::custom)
+        } else {
+            Key::deserialize(deserializer)
         }
     }
 }
@@ -798,4 +1139,66 @@ mod tests {
         let execution_result: ExecutionResult = rng.gen();
         bytesrepr::test_serialization_roundtrip(&execution_result);
     }
+
+    #[test]
+    fn should_encode_success_without_events_as_before() {
+        let mut rng = get_rng();
+        let event: ContractEvent = rng.gen();
+        let mut success = |events| ExecutionResult::Success {
+            effect: ExecutionEffect::default(),
+            transfers: vec![TransferAddr::new(rng.gen())],
+            cost: U512::from(rng.gen::<u64>()),
+            events,
+        };
+
+        let without_events = success(vec![]);
+        let bytes = without_events.to_bytes().unwrap();
+        assert_eq!(bytes[0], ExecutionResultTag::Success as u8);
+        assert_eq!(bytes.len(), without_events.serialized_length());
+        bytesrepr::test_serialization_roundtrip(&without_events);
+
+        let with_events = success(vec![event]);
+        let bytes = with_events.to_bytes().unwrap();
+        assert_eq!(bytes[0], ExecutionResultTag::SuccessWithEvents as u8);
+        bytesrepr::test_serialization_roundtrip(&with_events);
+    }
+
+    #[test]
+    fn serde_roundtrip_execution_result() {
+        let mut rng = get_rng();
+        for _ in 0..10 {
+            let execution_result: ExecutionResult = rng.gen();
+
+            let json = serde_json::to_string(&execution_result).unwrap();
+            assert_eq!(
+                serde_json::from_str::<ExecutionResult>(&json).unwrap(),
+                execution_result
+            );
+
+            let serialized = bincode::serialize(&execution_result).unwrap();
+            assert_eq!(
+                bincode::deserialize::<ExecutionResult>(&serialized).unwrap(),
+                execution_result
+            );
+        }
+    }
+
+    #[test]
+    fn should_omit_empty_events_from_json() {
+        let mut rng = get_rng();
+        let mut execution_result = ExecutionResult::Success {
+            effect: ExecutionEffect::default(),
+            transfers: vec![],
+            cost: U512::from(rng.gen::<u64>()),
+            events: vec![],
+        };
+        let json = serde_json::to_value(&execution_result).unwrap();
+        assert!(json["Success"].get("events").is_none());
+
+        if let ExecutionResult::Success { events, .. } = &mut execution_result {
+            events.push(rng.gen());
+        }
+        let json = serde_json::to_value(&execution_result).unwrap();
+        assert_eq!(json["Success"]["events"].as_array().unwrap().len(), 1);
+    }
 }
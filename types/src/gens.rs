@@ -14,6 +14,7 @@ use proptest::{
 
 use crate::{
     account::{gens::account_arb, AccountHash, Weight},
+    bytesrepr::ToBytes,
     contracts::{
         ContractPackageStatus, ContractVersions, DisabledVersions, Groups, NamedKeys, Parameters,
     },
@@ -179,6 +180,11 @@ pub fn cl_type_arb() -> impl Strategy<Value = CLType> {
                 key: Box::new(key),
                 value: Box::new(value)
             }),
+            // Structs and enums with a few named fields and variants
+            (".*", collection::vec((".*", element.clone()), 0..4))
+                .prop_map(|(name, fields)| CLType::new_struct(name, fields)),
+            (".*", collection::vec((".*", element.clone()), 1..4))
+                .prop_map(|(name, variants)| CLType::new_enum(name, variants)),
             // Various tuples
             element
                 .clone()
@@ -226,7 +232,9 @@ pub fn cl_value_arb() -> impl Strategy<Value = CLValue> {
             | CLType::Tuple1(_)
             | CLType::Tuple2(_)
             | CLType::Tuple3(_)
-            | CLType::Any => (),
+            | CLType::Any
+            | CLType::Struct { .. }
+            | CLType::Enum { .. } => (),
         }
     };
 
@@ -258,6 +266,18 @@ pub fn cl_value_arb() -> impl Strategy<Value = CLValue> {
             .prop_map(|x| CLValue::from_t(x).expect("should create CLValue")),
         // Fixed lists of any size
         any::<u8>().prop_map(|len| CLValue::from_t([len; 32]).expect("should create CLValue")),
+        // Structs are serialized as their fields in order, i.e. as tuples
+        (any::<u64>(), ".*").prop_map(|x: (u64, String)| {
+            let cl_type = CLType::new_struct("Struct", [("x", CLType::U64), ("y", CLType::String)]);
+            let bytes = x.to_bytes().expect("should serialize");
+            CLValue::from_components(cl_type, bytes)
+        }),
+        // Enums are serialized as a `u8` variant index and a payload, i.e. as options here
+        option::of(any::<u64>()).prop_map(|x| {
+            let cl_type = CLType::new_enum("Enum", [("None", CLType::Unit), ("Some", CLType::U64)]);
+            let bytes = x.to_bytes().expect("should serialize");
+            CLValue::from_components(cl_type, bytes)
+        }),
     ]
 }
 
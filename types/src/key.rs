@@ -47,6 +47,7 @@ const SYSTEM_CONTRACT_REGISTRY_PREFIX: &str = "system-contract-registry-";
 const ERA_SUMMARY_PREFIX: &str = "era-summary-";
 const CHAINSPEC_REGISTRY_PREFIX: &str = "chainspec-registry-";
 const CHECKSUM_REGISTRY_PREFIX: &str = "checksum-registry-";
+const CONTRACT_SCHEMA_PREFIX: &str = "contract-schema-";
 
 /// The number of bytes in a Blake2b hash
 pub const BLAKE2B_DIGEST_LENGTH: usize = 32;
@@ -80,6 +81,7 @@ const KEY_CHAINSPEC_REGISTRY_SERIALIZED_LENGTH: usize =
     KEY_ID_SERIALIZED_LENGTH + PADDING_BYTES.len();
 const KEY_CHECKSUM_REGISTRY_SERIALIZED_LENGTH: usize =
     KEY_ID_SERIALIZED_LENGTH + PADDING_BYTES.len();
+const KEY_CONTRACT_SCHEMA_SERIALIZED_LENGTH: usize = KEY_ID_SERIALIZED_LENGTH + KEY_HASH_LENGTH;
 
 /// An alias for [`Key`]s hash variant.
 pub type HashAddr = [u8; KEY_HASH_LENGTH];
@@ -106,6 +108,7 @@ pub enum KeyTag {
     Unbond = 12,
     ChainspecRegistry = 13,
     ChecksumRegistry = 14,
+    ContractSchema = 15,
 }
 
 /// The type under which data (e.g. [`CLValue`](crate::CLValue)s, smart contracts, user accounts)
@@ -145,6 +148,8 @@ pub enum Key {
     ChainspecRegistry,
     /// A `Key` variant under which we store a registry of checksums.
     ChecksumRegistry,
+    /// A `Key` under which we store the schema of the contract version with the given hash.
+    ContractSchema(HashAddr),
 }
 
 /// Errors produced when converting a `String` into a `Key`.
@@ -181,6 +186,8 @@ pub enum FromStrError {
     ChainspecRegistry(String),
     /// Checksum registry error.
     ChecksumRegistry(String),
+    /// Contract schema parse error.
+    ContractSchema(String),
     /// Unknown prefix.
     UnknownPrefix,
 }
@@ -239,6 +246,9 @@ impl Display for FromStrError {
             FromStrError::ChecksumRegistry(error) => {
                 write!(f, "checksum-registry-key from string error: {}", error)
             }
+            FromStrError::ContractSchema(error) => {
+                write!(f, "contract-schema-key from string error: {}", error)
+            }
             FromStrError::UnknownPrefix => write!(f, "unknown prefix for key"),
         }
     }
@@ -264,6 +274,7 @@ impl Key {
             Key::Unbond(_) => String::from("Key::Unbond"),
             Key::ChainspecRegistry => String::from("Key::ChainspecRegistry"),
             Key::ChecksumRegistry => String::from("Key::ChecksumRegistry"),
+            Key::ContractSchema(_) => String::from("Key::ContractSchema"),
         }
     }
 
@@ -347,6 +358,9 @@ impl Key {
                     base16::encode_lower(&PADDING_BYTES)
                 )
             }
+            Key::ContractSchema(addr) => {
+                format!("{}{}", CONTRACT_SCHEMA_PREFIX, base16::encode_lower(&addr))
+            }
         }
     }
 
@@ -474,6 +488,14 @@ impl Key {
             return Ok(Key::ChecksumRegistry);
         }
 
+        if let Some(hex) = input.strip_prefix(CONTRACT_SCHEMA_PREFIX) {
+            let addr = checksummed_hex::decode(hex)
+                .map_err(|error| FromStrError::ContractSchema(error.to_string()))?;
+            let hash_addr = HashAddr::try_from(addr.as_ref())
+                .map_err(|error| FromStrError::ContractSchema(error.to_string()))?;
+            return Ok(Key::ContractSchema(hash_addr));
+        }
+
         Err(FromStrError::UnknownPrefix)
     }
 
@@ -568,6 +590,12 @@ impl Key {
         Key::Dictionary(addr)
     }
 
+    /// Creates a new [`Key::ContractSchema`] variant under which the schema of the contract version
+    /// with the given hash is stored.
+    pub fn contract_schema(contract_hash: ContractHash) -> Key {
+        Key::ContractSchema(contract_hash.value())
+    }
+
     /// Returns true if the key is of type [`Key::Dictionary`].
     pub fn is_dictionary_key(&self) -> bool {
         if let Key::Dictionary(_) = self {
@@ -621,6 +649,9 @@ impl Display for Key {
                     base16::encode_lower(&PADDING_BYTES)
                 )
             }
+            Key::ContractSchema(addr) => {
+                write!(f, "Key::ContractSchema({})", base16::encode_lower(addr))
+            }
         }
     }
 }
@@ -649,6 +680,7 @@ impl Tagged<KeyTag> for Key {
             Key::Unbond(_) => KeyTag::Unbond,
             Key::ChainspecRegistry => KeyTag::ChainspecRegistry,
             Key::ChecksumRegistry => KeyTag::ChecksumRegistry,
+            Key::ContractSchema(_) => KeyTag::ContractSchema,
         }
     }
 }
@@ -722,6 +754,7 @@ impl ToBytes for Key {
             Key::Unbond(_) => KEY_UNBOND_SERIALIZED_LENGTH,
             Key::ChainspecRegistry => KEY_CHAINSPEC_REGISTRY_SERIALIZED_LENGTH,
             Key::ChecksumRegistry => KEY_CHECKSUM_REGISTRY_SERIALIZED_LENGTH,
+            Key::ContractSchema(_) => KEY_CONTRACT_SCHEMA_SERIALIZED_LENGTH,
         }
     }
 
@@ -739,6 +772,7 @@ impl ToBytes for Key {
             Key::Withdraw(account_hash) => account_hash.write_bytes(writer),
             Key::Dictionary(addr) => addr.write_bytes(writer),
             Key::Unbond(account_hash) => account_hash.write_bytes(writer),
+            Key::ContractSchema(addr) => addr.write_bytes(writer),
             Key::SystemContractRegistry
             | Key::EraSummary
             | Key::ChainspecRegistry
@@ -811,6 +845,10 @@ impl FromBytes for Key {
                 let (_, rem) = <[u8; 32]>::from_bytes(remainder)?;
                 Ok((Key::ChecksumRegistry, rem))
             }
+            tag if tag == KeyTag::ContractSchema as u8 => {
+                let (addr, rem) = HashAddr::from_bytes(remainder)?;
+                Ok((Key::ContractSchema(addr), rem))
+            }
             _ => Err(Error::Formatting),
         }
     }
@@ -836,12 +874,13 @@ fn please_add_to_distribution_impl(key: Key) {
         Key::Unbond(_) => unimplemented!(),
         Key::ChainspecRegistry => unimplemented!(),
         Key::ChecksumRegistry => unimplemented!(),
+        Key::ContractSchema(_) => unimplemented!(),
     }
 }
 
 impl Distribution<Key> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Key {
-        match rng.gen_range(0..=14) {
+        match rng.gen_range(0..=15) {
             0 => Key::Account(rng.gen()),
             1 => Key::Hash(rng.gen()),
             2 => Key::URef(rng.gen()),
@@ -857,6 +896,7 @@ impl Distribution<Key> for Standard {
             12 => Key::Unbond(rng.gen()),
             13 => Key::ChainspecRegistry,
             14 => Key::ChecksumRegistry,
+            15 => Key::ContractSchema(rng.gen()),
             _ => unreachable!(),
         }
     }
@@ -882,6 +922,7 @@ mod serde_helpers {
         Unbond(String),
         ChainspecRegistry(String),
         ChecksumRegistry(String),
+        ContractSchema(String),
     }
 
     impl From<&Key> for HumanReadable {
@@ -905,6 +946,7 @@ mod serde_helpers {
                 Key::Unbond(_) => HumanReadable::Unbond(formatted_string),
                 Key::ChainspecRegistry => HumanReadable::ChainspecRegistry(formatted_string),
                 Key::ChecksumRegistry => HumanReadable::ChecksumRegistry(formatted_string),
+                Key::ContractSchema(_) => HumanReadable::ContractSchema(formatted_string),
             }
         }
     }
@@ -928,7 +970,8 @@ mod serde_helpers {
                 | HumanReadable::EraSummary(formatted_string)
                 | HumanReadable::Unbond(formatted_string)
                 | HumanReadable::ChainspecRegistry(formatted_string)
-                | HumanReadable::ChecksumRegistry(formatted_string) => {
+                | HumanReadable::ChecksumRegistry(formatted_string)
+                | HumanReadable::ContractSchema(formatted_string) => {
                     Key::from_formatted_str(&formatted_string)
                 }
             }
@@ -952,6 +995,7 @@ mod serde_helpers {
         Unbond(&'a AccountHash),
         ChainspecRegistry,
         ChecksumRegistry,
+        ContractSchema(&'a HashAddr),
     }
 
     impl<'a> From<&'a Key> for BinarySerHelper<'a> {
@@ -972,6 +1016,7 @@ mod serde_helpers {
                 Key::Unbond(account_hash) => BinarySerHelper::Unbond(account_hash),
                 Key::ChainspecRegistry => BinarySerHelper::ChainspecRegistry,
                 Key::ChecksumRegistry => BinarySerHelper::ChecksumRegistry,
+                Key::ContractSchema(addr) => BinarySerHelper::ContractSchema(addr),
             }
         }
     }
@@ -993,6 +1038,7 @@ mod serde_helpers {
         Unbond(AccountHash),
         ChainspecRegistry,
         ChecksumRegistry,
+        ContractSchema(HashAddr),
     }
 
     impl From<BinaryDeserHelper> for Key {
@@ -1013,6 +1059,7 @@ mod serde_helpers {
                 BinaryDeserHelper::Unbond(account_hash) => Key::Unbond(account_hash),
                 BinaryDeserHelper::ChainspecRegistry => Key::ChainspecRegistry,
                 BinaryDeserHelper::ChecksumRegistry => Key::ChecksumRegistry,
+                BinaryDeserHelper::ContractSchema(addr) => Key::ContractSchema(addr),
             }
         }
     }
@@ -1070,6 +1117,7 @@ mod tests {
     const UNBOND_KEY: Key = Key::Unbond(AccountHash::new([42; 32]));
     const CHAINSPEC_REGISTRY_KEY: Key = Key::ChainspecRegistry;
     const CHECKSUM_REGISTRY_KEY: Key = Key::ChecksumRegistry;
+    const CONTRACT_SCHEMA_KEY: Key = Key::ContractSchema([42; 32]);
     const KEYS: &[Key] = &[
         ACCOUNT_KEY,
         HASH_KEY,
@@ -1086,6 +1134,7 @@ mod tests {
         UNBOND_KEY,
         CHAINSPEC_REGISTRY_KEY,
         CHECKSUM_REGISTRY_KEY,
+        CONTRACT_SCHEMA_KEY,
     ];
     const HEX_STRING: &str = "2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a";
 
@@ -1202,6 +1251,10 @@ mod tests {
                 base16::encode_lower(&PADDING_BYTES),
             )
         );
+        assert_eq!(
+            format!("{}", CONTRACT_SCHEMA_KEY),
+            format!("Key::ContractSchema({})", HEX_STRING)
+        );
     }
 
     #[test]
@@ -1334,6 +1387,10 @@ mod tests {
             .unwrap_err()
             .to_string()
             .starts_with("checksum-registry-key from string error: "));
+        assert!(Key::from_formatted_str(CONTRACT_SCHEMA_PREFIX)
+            .unwrap_err()
+            .to_string()
+            .starts_with("contract-schema-key from string error: "));
         let invalid_prefix = "a-0000000000000000000000000000000000000000000000000000000000000000";
         assert_eq!(
             Key::from_formatted_str(invalid_prefix)
@@ -1393,6 +1450,7 @@ mod tests {
                 "ChecksumRegistry":
                     format!("checksum-registry-{}", base16::encode_lower(&PADDING_BYTES))
             }),
+            json!({ "ContractSchema": format!("contract-schema-{}", HEX_STRING) }),
         ];
 
         assert_eq!(
@@ -1444,5 +1502,6 @@ mod tests {
         round_trip(&Key::Unbond(AccountHash::new(zeros)));
         round_trip(&Key::ChainspecRegistry);
         round_trip(&Key::ChecksumRegistry);
+        round_trip(&Key::ContractSchema(zeros));
     }
 }
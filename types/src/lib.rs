@@ -29,6 +29,7 @@ pub mod bytesrepr;
 pub mod checksummed_hex;
 mod cl_type;
 mod cl_value;
+mod contract_schema;
 mod contract_wasm;
 pub mod contracts;
 pub mod crypto;
@@ -65,8 +66,9 @@ pub use access_rights::{
 #[doc(inline)]
 pub use api_error::ApiError;
 pub use block_time::{BlockTime, BLOCKTIME_SERIALIZED_LENGTH};
-pub use cl_type::{named_key_type, CLType, CLTyped};
+pub use cl_type::{named_key_type, CLType, CLTyped, NamedCLType};
 pub use cl_value::{CLTypeMismatch, CLValue, CLValueError};
+pub use contract_schema::{ContractSchema, EntryPointSchema};
 pub use contract_wasm::{ContractWasm, ContractWasmHash};
 #[doc(inline)]
 pub use contracts::{
@@ -77,7 +79,7 @@ pub use contracts::{
 pub use crypto::*;
 pub use deploy_info::DeployInfo;
 pub use execution_result::{
-    ExecutionEffect, ExecutionResult, OpKind, Operation, Transform, TransformEntry,
+    ContractEvent, ExecutionEffect, ExecutionResult, OpKind, Operation, Transform, TransformEntry,
 };
 pub use gas::Gas;
 pub use json_pretty_printer::json_pretty_print;